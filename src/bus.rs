@@ -0,0 +1,250 @@
+//! Radio bus abstraction
+//!
+//! The SX126x is driven through five framing patterns: commands, register reads and writes, and
+//! data buffer reads and writes. [`RadioBus`] and [`AsyncRadioBus`] capture those patterns so the
+//! [`Device`](crate::Device) can run over any transport that speaks them.
+//!
+//! Both traits are implemented for every `embedded_hal::spi::SpiDevice` and
+//! `embedded_hal_async::spi::SpiDevice` respectively (with the `sync` and `async` features),
+//! which covers discrete SX126x parts.
+//! Radios reached through other transports, such as the SUBGHZSPI peripheral of the STM32WL,
+//! can be supported by implementing the traits directly.
+//!
+//! # Framing
+//! | Operation      | Written                               | Read     |
+//! |----------------|---------------------------------------|----------|
+//! | Command        | opcode, parameters                    | response |
+//! | Read register  | 0x1D, address (big endian), NOP       | value    |
+//! | Write register | 0x0D, address (big endian), value     |          |
+//! | Read buffer    | 0x1E, offset, NOP                     | data     |
+//! | Write buffer   | 0x0E, offset, data                    |          |
+//!
+//! # Shared buses
+//! Every framing is issued as a single `SpiDevice::transaction`, so chip select stays asserted
+//! for exactly one operation and nothing is assumed about the bus between two of them. The
+//! [`Device`](crate::Device) waits for BUSY before starting a transaction, never inside one,
+//! so another device can use the bus while the radio is busy. This makes the radio safe to
+//! share a bus through adapters such as the `RefCellDevice` and `CriticalSectionDevice` of
+//! `embedded-hal-bus`.
+//!
+//! Two radios sharing a bus, each behind its own chip select:
+//! ```no_run
+//! use embedded_hal::spi::SpiDevice;
+//! use sx1262::Device;
+//!
+//! # #[cfg(feature = "sync")]
+//! fn create<A: SpiDevice, B: SpiDevice>(a: A, b: B) {
+//!     let mut a = Device::new(a);
+//!     let mut b = Device::new(b);
+//!
+//!     a.write_buffer(0, &[0xAA, 0xBB]).unwrap();
+//!     let mut sync_word = [0; 2];
+//!     b.read_register_raw(0x0740, &mut sync_word).unwrap();
+//! }
+//! ```
+
+/// Opcode of the ReadRegister framing
+pub const READ_REGISTER: u8 = 0x1D;
+/// Opcode of the WriteRegister framing
+pub const WRITE_REGISTER: u8 = 0x0D;
+/// Opcode of the ReadBuffer framing
+pub const READ_BUFFER: u8 = 0x1E;
+/// Opcode of the WriteBuffer framing
+pub const WRITE_BUFFER: u8 = 0x0E;
+
+/// Blocking transport to the radio
+pub trait RadioBus {
+    /// Error type returned by the transport
+    type Error;
+
+    /// Sends a command and reads its response.
+    ///
+    /// # Arguments
+    /// * `opcode` - The command opcode
+    /// * `params` - The serialized command parameters
+    /// * `response` - Buffer filled with the response parameters
+    fn command(
+        &mut self,
+        opcode: u8,
+        params: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Reads consecutive registers starting at an address.
+    ///
+    /// # Arguments
+    /// * `address` - The first register address
+    /// * `value` - Buffer filled with the register contents
+    fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes consecutive registers starting at an address.
+    ///
+    /// # Arguments
+    /// * `address` - The first register address
+    /// * `value` - The register contents to write
+    fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), Self::Error>;
+
+    /// Reads the data buffer starting at an offset.
+    ///
+    /// # Arguments
+    /// * `offset` - Starting position in the buffer
+    /// * `bytes` - Buffer filled with the data
+    fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes the data buffer starting at an offset.
+    ///
+    /// # Arguments
+    /// * `offset` - Starting position in the buffer
+    /// * `bytes` - Data to write
+    fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous transport to the radio
+///
+/// This is the async version of [`RadioBus`].
+#[allow(async_fn_in_trait)]
+pub trait AsyncRadioBus {
+    /// Error type returned by the transport
+    type Error;
+
+    /// Sends a command and reads its response.
+    ///
+    /// This is the async version of [`RadioBus::command`].
+    async fn command(
+        &mut self,
+        opcode: u8,
+        params: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Reads consecutive registers starting at an address.
+    ///
+    /// This is the async version of [`RadioBus::read_register`].
+    async fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes consecutive registers starting at an address.
+    ///
+    /// This is the async version of [`RadioBus::write_register`].
+    async fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), Self::Error>;
+
+    /// Reads the data buffer starting at an offset.
+    ///
+    /// This is the async version of [`RadioBus::read_buffer`].
+    async fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes the data buffer starting at an offset.
+    ///
+    /// This is the async version of [`RadioBus::write_buffer`].
+    async fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+#[cfg(feature = "sync")]
+impl<SPI> RadioBus for SPI
+where
+    SPI: embedded_hal::spi::SpiDevice,
+{
+    type Error = SPI::Error;
+
+    fn command(
+        &mut self,
+        opcode: u8,
+        params: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(&mut [
+            embedded_hal::spi::Operation::Write(&[opcode]),
+            embedded_hal::spi::Operation::Write(params),
+            embedded_hal::spi::Operation::Read(response),
+        ])
+    }
+
+    fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), Self::Error> {
+        let [high, low] = address.to_be_bytes();
+
+        self.transaction(&mut [
+            embedded_hal::spi::Operation::Write(&[READ_REGISTER, high, low, 0x00]),
+            embedded_hal::spi::Operation::Read(value),
+        ])
+    }
+
+    fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), Self::Error> {
+        let [high, low] = address.to_be_bytes();
+
+        self.transaction(&mut [
+            embedded_hal::spi::Operation::Write(&[WRITE_REGISTER, high, low]),
+            embedded_hal::spi::Operation::Write(value),
+        ])
+    }
+
+    fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(&mut [
+            embedded_hal::spi::Operation::Write(&[READ_BUFFER, offset, 0x00]),
+            embedded_hal::spi::Operation::Read(bytes),
+        ])
+    }
+
+    fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(&mut [
+            embedded_hal::spi::Operation::Write(&[WRITE_BUFFER, offset]),
+            embedded_hal::spi::Operation::Write(bytes),
+        ])
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI> AsyncRadioBus for SPI
+where
+    SPI: embedded_hal_async::spi::SpiDevice,
+{
+    type Error = SPI::Error;
+
+    async fn command(
+        &mut self,
+        opcode: u8,
+        params: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(&mut [
+            embedded_hal_async::spi::Operation::Write(&[opcode]),
+            embedded_hal_async::spi::Operation::Write(params),
+            embedded_hal_async::spi::Operation::Read(response),
+        ])
+        .await
+    }
+
+    async fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), Self::Error> {
+        let [high, low] = address.to_be_bytes();
+
+        self.transaction(&mut [
+            embedded_hal_async::spi::Operation::Write(&[READ_REGISTER, high, low, 0x00]),
+            embedded_hal_async::spi::Operation::Read(value),
+        ])
+        .await
+    }
+
+    async fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), Self::Error> {
+        let [high, low] = address.to_be_bytes();
+
+        self.transaction(&mut [
+            embedded_hal_async::spi::Operation::Write(&[WRITE_REGISTER, high, low]),
+            embedded_hal_async::spi::Operation::Write(value),
+        ])
+        .await
+    }
+
+    async fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(&mut [
+            embedded_hal_async::spi::Operation::Write(&[READ_BUFFER, offset, 0x00]),
+            embedded_hal_async::spi::Operation::Read(bytes),
+        ])
+        .await
+    }
+
+    async fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(&mut [
+            embedded_hal_async::spi::Operation::Write(&[WRITE_BUFFER, offset]),
+            embedded_hal_async::spi::Operation::Write(bytes),
+        ])
+        .await
+    }
+}
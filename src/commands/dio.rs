@@ -12,12 +12,14 @@
 
 use core::convert::Infallible;
 
-use crate::commands::status::Status;
+use crate::commands::opcode;
+use crate::commands::status::{Status, StatusError};
 use crate::{Command, FromByteArray, NoParameters, ToByteArray};
 
 bitflags::bitflags! {
     /// IRQ flags for the SX126x radio
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct IrqMask: u16 {
         /// Packet transmission completed
         const TX_DONE = 1 << 0;
@@ -40,12 +42,34 @@ bitflags::bitflags! {
     }
 }
 
+impl IrqMask {
+    /// Returns the bits sent to the radio, without the bits the chip does not define.
+    ///
+    /// A mask built with [`from_bits_retain`](IrqMask::from_bits_retain) may hold reserved
+    /// bits, some of which enable internal test modes on some silicon revisions. Every
+    /// serialization goes through this method so they never reach the wire. Use
+    /// [`SetDioIrqParamsUnchecked`] to send them deliberately.
+    ///
+    /// # Example
+    /// ```
+    /// use regiface::ToByteArray;
+    /// use sx1262::IrqMask;
+    ///
+    /// let mask = IrqMask::from_bits_retain(0xFE01);
+    /// assert_eq!(mask.wire_bits(), 0x0001);
+    /// assert_eq!(mask.to_bytes(), Ok([0x00, 0x01]));
+    /// ```
+    pub const fn wire_bits(self) -> u16 {
+        self.bits() & Self::all().bits()
+    }
+}
+
 impl ToByteArray for IrqMask {
     type Error = Infallible;
     type Array = [u8; 2];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        Ok(self.bits().to_be_bytes())
+        Ok(self.wire_bits().to_be_bytes())
     }
 }
 
@@ -62,7 +86,34 @@ impl FromByteArray for IrqMask {
 ///
 /// Used to configure which interrupts are enabled and how they
 /// are mapped to DIO pins.
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] has every mask empty, matching the chip after power-up where all
+/// interrupts are disabled.
+///
+/// Bits the chip does not define are dropped from every mask when serialized, see
+/// [`IrqMask::wire_bits`].
+///
+/// # Example
+/// ```
+/// use regiface::{Command, ToByteArray};
+/// use sx1262::{ClearIrqStatus, DioIrqConfig, IrqMask, SetDioIrqParams};
+///
+/// // Reserved bits, e.g. from a configuration file
+/// let reserved = IrqMask::from_bits_retain(0xFC00);
+/// let config = DioIrqConfig {
+///     irq_mask: IrqMask::TX_DONE | reserved,
+///     dio1_mask: IrqMask::TX_DONE | reserved,
+///     dio2_mask: reserved,
+///     dio3_mask: IrqMask::from_bits_retain(0xFFFF),
+/// };
+/// let params = SetDioIrqParams { config }.invoking_parameters();
+/// assert_eq!(params.to_bytes(), Ok([0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0xFF]));
+///
+/// let clear = ClearIrqStatus { irq_mask: IrqMask::from_bits_retain(0xFFFF) };
+/// assert_eq!(clear.invoking_parameters().to_bytes(), Ok([0x01, 0xFF]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DioIrqConfig {
     /// IRQ enable mask
     pub irq_mask: IrqMask,
@@ -87,15 +138,84 @@ impl ToByteArray for DioIrqConfig {
     type Array = [u8; 8];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        let mut bytes = [0u8; 8];
-        bytes[0..2].copy_from_slice(&self.irq_mask.bits().to_be_bytes());
-        bytes[2..4].copy_from_slice(&self.dio1_mask.bits().to_be_bytes());
-        bytes[4..6].copy_from_slice(&self.dio2_mask.bits().to_be_bytes());
-        bytes[6..8].copy_from_slice(&self.dio3_mask.bits().to_be_bytes());
-        Ok(bytes)
+        UncheckedDioIrqConfig {
+            irq_mask: self.irq_mask.wire_bits(),
+            dio1_mask: self.dio1_mask.wire_bits(),
+            dio2_mask: self.dio2_mask.wire_bits(),
+            dio3_mask: self.dio3_mask.wire_bits(),
+        }
+        .to_bytes()
     }
 }
 
+/// DIO and IRQ configuration with raw masks, sent as is
+///
+/// Unlike [`DioIrqConfig`], the masks keep the bits the chip does not define. Only meant for
+/// experiments guided by Semtech, as some reserved bits enable internal test modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct UncheckedDioIrqConfig {
+    /// Raw IRQ enable mask
+    pub irq_mask: u16,
+    /// Raw DIO1 interrupt mapping mask
+    pub dio1_mask: u16,
+    /// Raw DIO2 interrupt mapping mask
+    pub dio2_mask: u16,
+    /// Raw DIO3 interrupt mapping mask
+    pub dio3_mask: u16,
+}
+
+impl ToByteArray for UncheckedDioIrqConfig {
+    type Error = Infallible;
+    type Array = [u8; 8];
+
+    fn to_bytes(self) -> Result<Self::Array, Self::Error> {
+        let [irq0, irq1] = self.irq_mask.to_be_bytes();
+        let [dio1_0, dio1_1] = self.dio1_mask.to_be_bytes();
+        let [dio2_0, dio2_1] = self.dio2_mask.to_be_bytes();
+        let [dio3_0, dio3_1] = self.dio3_mask.to_be_bytes();
+        Ok([irq0, irq1, dio1_0, dio1_1, dio2_0, dio2_1, dio3_0, dio3_1])
+    }
+}
+
+/// Returns the IRQ routing a command sets, from its serialized parameters.
+pub(crate) fn observed_irq_routing(opcode: u8, request: &[u8]) -> Option<DioIrqConfig> {
+    if opcode != SetDioIrqParams::id() {
+        return None;
+    }
+    let mask = |index: usize| match request.get(index..)? {
+        [msb, lsb, ..] => Some(IrqMask::from_bits_retain(u16::from_be_bytes([*msb, *lsb]))),
+        _ => None,
+    };
+    Some(DioIrqConfig {
+        irq_mask: mask(0)?,
+        dio1_mask: mask(2)?,
+        dio2_mask: mask(4)?,
+        dio3_mask: mask(6)?,
+    })
+}
+
+/// Returns the IRQs a command clears, from its serialized parameters.
+pub(crate) fn observed_irq_clear(opcode: u8, request: &[u8]) -> Option<IrqMask> {
+    if opcode != ClearIrqStatus::id() {
+        return None;
+    }
+    let [msb, lsb, ..] = *request else {
+        return None;
+    };
+    Some(IrqMask::from_bits_retain(u16::from_be_bytes([msb, lsb])))
+}
+
+/// Returns the IRQs a command reports, from its response.
+pub(crate) fn observed_irq_status(opcode: u8, response: &[u8]) -> Option<IrqMask> {
+    if opcode != GetIrqStatus::id() {
+        return None;
+    }
+    let [_, msb, lsb] = *response else {
+        return None;
+    };
+    Some(IrqMask::from_bits_retain(u16::from_be_bytes([msb, lsb])))
+}
+
 /// SetDioIrqParams command (0x08)
 ///
 /// Configures the mapping between interrupt sources and DIO pins.
@@ -117,7 +237,41 @@ impl Command for SetDioIrqParams {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x08
+        opcode::SET_DIO_IRQ_PARAMS
+    }
+
+    fn invoking_parameters(self) -> Self::CommandParameters {
+        self.config
+    }
+}
+
+/// SetDioIrqParams command (0x08) with raw masks
+///
+/// Sends the masks of an [`UncheckedDioIrqConfig`] without dropping the bits the chip does not
+/// define. Prefer [`SetDioIrqParams`] outside of experiments guided by Semtech.
+///
+/// # Example
+/// ```
+/// use regiface::{Command, ToByteArray};
+/// use sx1262::{SetDioIrqParamsUnchecked, UncheckedDioIrqConfig};
+///
+/// let config = UncheckedDioIrqConfig { irq_mask: 0x8001, ..Default::default() };
+/// let params = SetDioIrqParamsUnchecked { config }.invoking_parameters();
+/// assert_eq!(params.to_bytes().unwrap()[..2], [0x80, 0x01]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SetDioIrqParamsUnchecked {
+    /// Raw DIO and IRQ configuration parameters
+    pub config: UncheckedDioIrqConfig,
+}
+
+impl Command for SetDioIrqParamsUnchecked {
+    type IdType = u8;
+    type CommandParameters = UncheckedDioIrqConfig;
+    type ResponseParameters = NoParameters;
+
+    fn id() -> Self::IdType {
+        opcode::SET_DIO_IRQ_PARAMS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -137,13 +291,15 @@ pub struct GetIrqStatusResponse {
 }
 
 impl FromByteArray for GetIrqStatusResponse {
-    type Error = Infallible;
+    type Error = StatusError;
     type Array = [u8; 3]; // 1 status byte + 2 IRQ bytes
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [status, irq0, irq1] = bytes;
+        let Ok(irq_mask) = IrqMask::from_bytes([irq0, irq1]);
         Ok(Self {
-            status: Status::from_bytes([bytes[0]]).unwrap(),
-            irq_mask: IrqMask::from_bytes([bytes[1], bytes[2]]).unwrap(),
+            status: Status::from_bytes([status])?,
+            irq_mask,
         })
     }
 }
@@ -167,7 +323,7 @@ impl Command for GetIrqStatus {
     type ResponseParameters = GetIrqStatusResponse;
 
     fn id() -> Self::IdType {
-        0x12
+        opcode::GET_IRQ_STATUS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -195,7 +351,7 @@ impl Command for ClearIrqStatus {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x02
+        opcode::CLEAR_IRQ_STATUS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -204,7 +360,9 @@ impl Command for ClearIrqStatus {
 }
 
 /// RF switch control configuration
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] leaves DIO2 free for IRQ mapping, as on the chip after power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct RfSwitchConfig {
     /// Enable RF switch control on DIO2
     /// - true = DIO2 controls RF switch
@@ -241,7 +399,7 @@ impl Command for SetDio2AsRfSwitchCtrl {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x9D
+        opcode::SET_DIO2_AS_RF_SWITCH_CTRL
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -253,7 +411,8 @@ impl Command for SetDio2AsRfSwitchCtrl {
 ///
 /// Available voltage options for TCXO power supply.
 /// VBAT must be at least 200mV higher than selected voltage.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TcxoVoltage {
     /// 1.6V (min VBAT = 1.8V)
     V1_6 = 0x00,
@@ -274,7 +433,8 @@ pub enum TcxoVoltage {
 }
 
 /// TCXO control configuration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TcxoConfig {
     /// TCXO supply voltage
     pub voltage: TcxoVoltage,
@@ -286,15 +446,31 @@ pub struct TcxoConfig {
     pub delay: u32,
 }
 
+impl TcxoConfig {
+    /// Returns the delay programmed into the radio in microseconds, from the 24 bits sent.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{TcxoConfig, TcxoVoltage};
+    ///
+    /// let config = |delay| TcxoConfig { voltage: TcxoVoltage::V1_8, delay };
+    /// assert_eq!(config(64).effective_delay_us(), 1_000.0);
+    /// assert_eq!(config(5).effective_delay_us(), 78.125);
+    /// // Bits above the 24 sent are dropped
+    /// assert_eq!(config(0x0100_0040).effective_delay_us(), 1_000.0);
+    /// ```
+    pub fn effective_delay_us(&self) -> f64 {
+        f64::from(self.delay & 0xFF_FFFF) * 15.625
+    }
+}
+
 impl ToByteArray for TcxoConfig {
     type Error = Infallible;
     type Array = [u8; 4];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        let mut bytes = [0u8; 4];
-        bytes[0] = self.voltage as u8;
-        bytes[1..4].copy_from_slice(&self.delay.to_be_bytes()[1..]);
-        Ok(bytes)
+        let [_, delay0, delay1, delay2] = self.delay.to_be_bytes();
+        Ok([self.voltage as u8, delay0, delay1, delay2])
     }
 }
 
@@ -323,7 +499,7 @@ impl Command for SetDio3AsTcxoCtrl {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x97
+        opcode::SET_DIO3_AS_TCXO_CTRL
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -27,6 +27,10 @@
 //!   - Track packet statistics
 //!   - Handle error conditions
 //!
+//! - [`PreparedCommand`]: Commands serialized once for repeated issue on hot paths
+//!
+//! - [`opcode`]: Opcode of every command, described with its lengths in [`COMMANDS`]
+//!
 //! # Command Execution
 //! Most commands have specific requirements for execution:
 //! - Operating mode (usually STDBY_RC)
@@ -51,13 +55,247 @@
 //! - Parameter ranges depend on operating conditions
 //! - Error checking is recommended for critical commands
 //! - BUSY must be monitored for reliable operation
+//!
+//! # Panic Freedom
+//! Serializing parameters and parsing responses or register values never panics, whatever
+//! the bytes read from the radio. An unparsable status byte is reported as an error.
+//!
+//! ```
+//! use regiface::{FromByteArray, ToByteArray};
+//! use sx1262::*;
+//!
+//! // Every byte value in every position, over backgrounds of 0x00, 0xFF and alternating bits
+//! macro_rules! parse_all {
+//!     ($($ty:ty),* $(,)?) => {$(
+//!         for background in [0x00, 0xFF, 0x55, 0xAA] {
+//!             let empty: <$ty as FromByteArray>::Array = Default::default();
+//!             for position in 0..empty.len() {
+//!                 for value in 0..=u8::MAX {
+//!                     let mut bytes = empty;
+//!                     bytes.fill(background);
+//!                     bytes[position] = value;
+//!                     let _ = <$ty>::from_bytes(bytes);
+//!                 }
+//!             }
+//!         }
+//!     )*};
+//! }
+//!
+//! // Parsed values serialized back
+//! macro_rules! round_trip_all {
+//!     ($($ty:ty),* $(,)?) => {$(
+//!         for value in 0..=u8::MAX {
+//!             let mut bytes: <$ty as FromByteArray>::Array = Default::default();
+//!             bytes.fill(value);
+//!             if let Ok(parsed) = <$ty>::from_bytes(bytes) {
+//!                 let _ = parsed.to_bytes();
+//!             }
+//!         }
+//!     )*};
+//! }
+//!
+//! parse_all!(
+//!     Status, GetRssiInstResponse, RxBufferStatus, GetRxBufferStatusResponse, PacketStatus,
+//!     GetPacketStatusResponse, DeviceErrors, GetDeviceErrorsResponse, ClearDeviceErrorsResponse,
+//!     Stats, GetStatsResponse, IrqMask, GetIrqStatusResponse, PacketType, GetPacketTypeResponse,
+//!     RetentionList, RandomNumber,
+//! );
+//! round_trip_all!(
+//!     BroadcastAddress, CrcInitialValue, CrcPolynomial, Dio3OutputVoltage, DioInputEnable,
+//!     DioOutputEnable, DioPullDownControl, DioPullUpControl, EventMask, IqPolaritySetup,
+//!     IrqMask, LoraSyncWord, NodeAddress, OcpConfiguration, PacketType, RetentionList,
+//!     RtcControl, RxGain, SyncWord, TxClampConfig, TxModulation, WhiteningInitialValue,
+//!     XtaTrim, XtbTrim,
+//! );
+//!
+//! // Parameters at the extremes of their types
+//! for word in [0, u32::MAX] {
+//!     let gfsk = GfskModParams {
+//!         bit_rate: word,
+//!         pulse_shape: GfskPulseShape::NoFilter,
+//!         bandwidth: GfskBandwidth::Bw4670,
+//!         freq_deviation: word,
+//!     };
+//!     let _ = ModulationParams::Gfsk(gfsk).to_bytes();
+//!     let _ = ModulationParams::LoRa(LoRaModParams::default()).to_bytes();
+//!     let _ = RfFrequencyConfig { frequency: word }.to_bytes();
+//!     let _ = Timeout(word).to_bytes();
+//!     let _ = RxDutyCycleConfig { rx_period: word, sleep_period: word }.to_bytes();
+//!     let _ = CadParams { cad_timeout: word, ..Default::default() }.to_bytes();
+//!     let _ = TcxoConfig { voltage: TcxoVoltage::V3_3, delay: word }.to_bytes();
+//! }
+//! let gfsk = GFSKPacketParams {
+//!     sync_word_length: u8::MAX,
+//!     payload_length: u8::MAX,
+//!     ..Default::default()
+//! };
+//! let _ = PacketParams::GFSK(gfsk).to_bytes();
+//! let _ = PacketParams::LoRa(LoRaPacketParams::default()).to_bytes();
+//! let irq = DioIrqConfig {
+//!     irq_mask: IrqMask::all(),
+//!     dio1_mask: IrqMask::all(),
+//!     dio2_mask: IrqMask::all(),
+//!     dio3_mask: IrqMask::all(),
+//! };
+//! let _ = irq.to_bytes();
+//! let _ = TxParams { power: i8::MIN, ramp_time: RampTime::Micros3400 }.to_bytes();
+//! let _ = LoRaSymbNumTimeout { symb_num: u8::MAX }.to_bytes();
+//!
+//! // A bus stuck low or high yields an error, not a panic
+//! assert!(GetIrqStatusResponse::from_bytes([0x00; 3]).is_err());
+//! assert!(GetRssiInstResponse::from_bytes([0xFF; 2]).is_err());
+//! ```
+
+// Serialization runs on every bus transaction, where a panic is a hard fault on no_std
+// targets: lengths must be proven by the types, failures propagated
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic
+)]
 
 mod dio;
+pub mod opcode;
 mod operational;
+mod prepared;
 mod rf;
 mod status;
 
 pub use dio::*;
 pub use operational::*;
+pub use prepared::*;
 pub use rf::*;
 pub use status::*;
+
+/// Description of a command defined in this module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommandInfo {
+    /// Name of the command type
+    pub name: &'static str,
+    /// Opcode of the command
+    pub opcode: u8,
+    /// Length of the parameters written after the opcode, in bytes
+    pub params_len: usize,
+    /// Length of the response read after the opcode, status byte included, in bytes
+    pub response_len: usize,
+}
+
+const fn info(
+    name: &'static str,
+    opcode: u8,
+    params_len: usize,
+    response_len: usize,
+) -> CommandInfo {
+    CommandInfo {
+        name,
+        opcode,
+        params_len,
+        response_len,
+    }
+}
+
+/// Every command defined in this module, in opcode order
+///
+/// The lengths are those of the typed parameters and responses, e.g. for decoding captured
+/// bus traffic.
+///
+/// ```
+/// use regiface::{ByteArray, Command, FromByteArray, ToByteArray};
+/// use sx1262::*;
+///
+/// // Every typed command is described with its opcode and lengths
+/// macro_rules! check {
+///     ($($ty:ty),* $(,)?) => {$({
+///         let info = describe_opcode(<$ty>::id()).unwrap();
+///         let params = <<$ty as Command>::CommandParameters as ToByteArray>::Array::new();
+///         let response = <<$ty as Command>::ResponseParameters as FromByteArray>::Array::new();
+///         assert_eq!(info.name, stringify!($ty));
+///         assert_eq!(info.params_len, ByteArray::as_ref(&params).len());
+///         assert_eq!(info.response_len, ByteArray::as_ref(&response).len());
+///     })*
+///     assert_eq!([$(stringify!($ty)),*].len(), COMMANDS.len());
+///     };
+/// }
+///
+/// check!(
+///     SetDioIrqParams, GetIrqStatus, ClearIrqStatus, SetDio2AsRfSwitchCtrl, SetDio3AsTcxoCtrl,
+///     SetSleep, SetStandby, SetFs, SetTx, SetRx, StopTimerOnPreamble, SetRxDutyCycle, SetCad,
+///     SetTxContinuousWave, SetTxInfinitePreamble, SetRegulatorMode, Calibrate, CalibrateImage,
+///     SetPaConfig, SetRxTxFallbackMode, SetRfFrequency, SetPacketType, GetPacketType,
+///     SetTxParams, SetModulationParams, SetPacketParams, SetCadParams, SetBufferBaseAddress,
+///     SetLoRaSymbNumTimeout, GetStatus, GetRssiInst, GetRxBufferStatus, GetPacketStatus,
+///     GetDeviceErrors, ClearDeviceErrors, GetStats, ResetStats,
+/// );
+///
+/// // In opcode order, without duplicates
+/// assert!(COMMANDS.windows(2).all(|pair| pair[0].opcode < pair[1].opcode));
+/// assert_eq!(describe_opcode(opcode::SET_STANDBY).unwrap().name, "SetStandby");
+/// assert!(describe_opcode(0xFF).is_none());
+/// ```
+pub const COMMANDS: &[CommandInfo] = &[
+    info("ResetStats", opcode::RESET_STATS, 0, 0),
+    info("ClearIrqStatus", opcode::CLEAR_IRQ_STATUS, 2, 0),
+    info("ClearDeviceErrors", opcode::CLEAR_DEVICE_ERRORS, 0, 2),
+    info("SetDioIrqParams", opcode::SET_DIO_IRQ_PARAMS, 8, 0),
+    info("GetStats", opcode::GET_STATS, 0, 7),
+    info("GetPacketType", opcode::GET_PACKET_TYPE, 0, 2),
+    info("GetIrqStatus", opcode::GET_IRQ_STATUS, 0, 3),
+    info("GetRxBufferStatus", opcode::GET_RX_BUFFER_STATUS, 0, 3),
+    info("GetPacketStatus", opcode::GET_PACKET_STATUS, 0, 4),
+    info("GetRssiInst", opcode::GET_RSSI_INST, 0, 2),
+    info("GetDeviceErrors", opcode::GET_DEVICE_ERRORS, 0, 3),
+    info("SetStandby", opcode::SET_STANDBY, 1, 0),
+    info("SetRx", opcode::SET_RX, 3, 0),
+    info("SetTx", opcode::SET_TX, 3, 0),
+    info("SetSleep", opcode::SET_SLEEP, 1, 0),
+    info("SetRfFrequency", opcode::SET_RF_FREQUENCY, 4, 0),
+    info("SetCadParams", opcode::SET_CAD_PARAMS, 8, 0),
+    info("Calibrate", opcode::CALIBRATE, 1, 0),
+    info("SetPacketType", opcode::SET_PACKET_TYPE, 1, 0),
+    info("SetModulationParams", opcode::SET_MODULATION_PARAMS, 8, 0),
+    info("SetPacketParams", opcode::SET_PACKET_PARAMS, 9, 0),
+    info("SetTxParams", opcode::SET_TX_PARAMS, 2, 0),
+    info(
+        "SetBufferBaseAddress",
+        opcode::SET_BUFFER_BASE_ADDRESS,
+        2,
+        0,
+    ),
+    info("SetRxTxFallbackMode", opcode::SET_RX_TX_FALLBACK_MODE, 1, 0),
+    info("SetRxDutyCycle", opcode::SET_RX_DUTY_CYCLE, 8, 0),
+    info("SetPaConfig", opcode::SET_PA_CONFIG, 4, 0),
+    info("SetRegulatorMode", opcode::SET_REGULATOR_MODE, 1, 0),
+    info("SetDio3AsTcxoCtrl", opcode::SET_DIO3_AS_TCXO_CTRL, 4, 0),
+    info("CalibrateImage", opcode::CALIBRATE_IMAGE, 2, 0),
+    info(
+        "SetDio2AsRfSwitchCtrl",
+        opcode::SET_DIO2_AS_RF_SWITCH_CTRL,
+        1,
+        0,
+    ),
+    info("StopTimerOnPreamble", opcode::STOP_TIMER_ON_PREAMBLE, 1, 0),
+    info(
+        "SetLoRaSymbNumTimeout",
+        opcode::SET_LORA_SYMB_NUM_TIMEOUT,
+        1,
+        0,
+    ),
+    info("GetStatus", opcode::GET_STATUS, 0, 1),
+    info("SetFs", opcode::SET_FS, 0, 0),
+    info("SetCad", opcode::SET_CAD, 0, 0),
+    info("SetTxContinuousWave", opcode::SET_TX_CONTINUOUS_WAVE, 0, 0),
+    info(
+        "SetTxInfinitePreamble",
+        opcode::SET_TX_INFINITE_PREAMBLE,
+        0,
+        0,
+    ),
+];
+
+/// Returns the description of the command with an opcode, if defined in this module.
+///
+/// The register and buffer access framings are not commands, see [`bus`](crate::bus).
+pub fn describe_opcode(opcode: u8) -> Option<&'static CommandInfo> {
+    COMMANDS.iter().find(|info| info.opcode == opcode)
+}
@@ -0,0 +1,93 @@
+//! Command opcodes
+//!
+//! The opcode of every command in [`commands`](super), as returned by its [`Command::id`]
+//! implementation. The framings of register and buffer accesses have their opcodes in
+//! [`bus`](crate::bus).
+//!
+//! [`Command::id`]: crate::Command::id
+
+// DIO and IRQ control
+
+/// Opcode of [`SetDioIrqParams`](super::SetDioIrqParams)
+pub const SET_DIO_IRQ_PARAMS: u8 = 0x08;
+/// Opcode of [`GetIrqStatus`](super::GetIrqStatus)
+pub const GET_IRQ_STATUS: u8 = 0x12;
+/// Opcode of [`ClearIrqStatus`](super::ClearIrqStatus)
+pub const CLEAR_IRQ_STATUS: u8 = 0x02;
+/// Opcode of [`SetDio2AsRfSwitchCtrl`](super::SetDio2AsRfSwitchCtrl)
+pub const SET_DIO2_AS_RF_SWITCH_CTRL: u8 = 0x9D;
+/// Opcode of [`SetDio3AsTcxoCtrl`](super::SetDio3AsTcxoCtrl)
+pub const SET_DIO3_AS_TCXO_CTRL: u8 = 0x97;
+
+// Operating modes
+
+/// Opcode of [`SetSleep`](super::SetSleep)
+pub const SET_SLEEP: u8 = 0x84;
+/// Opcode of [`SetStandby`](super::SetStandby)
+pub const SET_STANDBY: u8 = 0x80;
+/// Opcode of [`SetFs`](super::SetFs)
+pub const SET_FS: u8 = 0xC1;
+/// Opcode of [`SetTx`](super::SetTx)
+pub const SET_TX: u8 = 0x83;
+/// Opcode of [`SetRx`](super::SetRx)
+pub const SET_RX: u8 = 0x82;
+/// Opcode of [`StopTimerOnPreamble`](super::StopTimerOnPreamble)
+pub const STOP_TIMER_ON_PREAMBLE: u8 = 0x9F;
+/// Opcode of [`SetRxDutyCycle`](super::SetRxDutyCycle)
+pub const SET_RX_DUTY_CYCLE: u8 = 0x94;
+/// Opcode of [`SetCad`](super::SetCad)
+pub const SET_CAD: u8 = 0xC5;
+/// Opcode of [`SetTxContinuousWave`](super::SetTxContinuousWave)
+pub const SET_TX_CONTINUOUS_WAVE: u8 = 0xD1;
+/// Opcode of [`SetTxInfinitePreamble`](super::SetTxInfinitePreamble)
+pub const SET_TX_INFINITE_PREAMBLE: u8 = 0xD2;
+/// Opcode of [`SetRegulatorMode`](super::SetRegulatorMode)
+pub const SET_REGULATOR_MODE: u8 = 0x96;
+/// Opcode of [`Calibrate`](super::Calibrate)
+pub const CALIBRATE: u8 = 0x89;
+/// Opcode of [`CalibrateImage`](super::CalibrateImage)
+pub const CALIBRATE_IMAGE: u8 = 0x98;
+/// Opcode of [`SetPaConfig`](super::SetPaConfig)
+pub const SET_PA_CONFIG: u8 = 0x95;
+/// Opcode of [`SetRxTxFallbackMode`](super::SetRxTxFallbackMode)
+pub const SET_RX_TX_FALLBACK_MODE: u8 = 0x93;
+
+// RF and packet configuration
+
+/// Opcode of [`SetRfFrequency`](super::SetRfFrequency)
+pub const SET_RF_FREQUENCY: u8 = 0x86;
+/// Opcode of [`SetPacketType`](super::SetPacketType)
+pub const SET_PACKET_TYPE: u8 = 0x8A;
+/// Opcode of [`GetPacketType`](super::GetPacketType)
+pub const GET_PACKET_TYPE: u8 = 0x11;
+/// Opcode of [`SetTxParams`](super::SetTxParams)
+pub const SET_TX_PARAMS: u8 = 0x8E;
+/// Opcode of [`SetModulationParams`](super::SetModulationParams)
+pub const SET_MODULATION_PARAMS: u8 = 0x8B;
+/// Opcode of [`SetPacketParams`](super::SetPacketParams)
+pub const SET_PACKET_PARAMS: u8 = 0x8C;
+/// Opcode of [`SetCadParams`](super::SetCadParams)
+pub const SET_CAD_PARAMS: u8 = 0x88;
+/// Opcode of [`SetBufferBaseAddress`](super::SetBufferBaseAddress)
+pub const SET_BUFFER_BASE_ADDRESS: u8 = 0x8F;
+/// Opcode of [`SetLoRaSymbNumTimeout`](super::SetLoRaSymbNumTimeout)
+pub const SET_LORA_SYMB_NUM_TIMEOUT: u8 = 0xA0;
+
+// Status and monitoring
+
+/// Opcode of [`GetStatus`](super::GetStatus)
+pub const GET_STATUS: u8 = 0xC0;
+/// Opcode of [`GetRssiInst`](super::GetRssiInst)
+pub const GET_RSSI_INST: u8 = 0x15;
+/// Opcode of [`GetRxBufferStatus`](super::GetRxBufferStatus)
+pub const GET_RX_BUFFER_STATUS: u8 = 0x13;
+/// Opcode of [`GetPacketStatus`](super::GetPacketStatus)
+pub const GET_PACKET_STATUS: u8 = 0x14;
+/// Opcode of [`GetDeviceErrors`](super::GetDeviceErrors)
+pub const GET_DEVICE_ERRORS: u8 = 0x17;
+/// Opcode of [`ClearDeviceErrors`](super::ClearDeviceErrors)
+pub const CLEAR_DEVICE_ERRORS: u8 = 0x07;
+/// Opcode of [`GetStats`](super::GetStats)
+pub const GET_STATS: u8 = 0x10;
+/// Opcode of [`ResetStats`](super::ResetStats)
+pub const RESET_STATS: u8 = 0x00;
@@ -14,16 +14,17 @@
 //! the documentation for each command.
 
 use bitflags::bitflags;
-use core::convert::Infallible;
+use core::{convert::Infallible, ops::RangeInclusive, time::Duration};
 
-use crate::{Command, NoParameters, ToByteArray};
+use crate::commands::opcode;
+use crate::{Command, NoParameters, OperatingMode, RampTime, ToByteArray, TxParams};
 
 bitflags! {
     /// Sleep configuration options
     ///
     /// Controls behavior during sleep mode, including configuration
     /// retention and wake-up sources.
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct SleepConfig: u8 {
         /// When set, configuration is retained in sleep mode (warm start)
         /// When clear, cold start - all registers reset to defaults
@@ -65,7 +66,7 @@ impl Command for SetSleep {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x84
+        opcode::SET_SLEEP
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -76,10 +77,13 @@ impl Command for SetSleep {
 /// Standby mode configuration
 ///
 /// Selects which oscillator to use in standby mode.
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] is STDBY_RC, the mode the chip enters after power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum StandbyConfig {
     /// Device running on RC13M (~0.6mA)
     /// Used for configuration and lower power
+    #[default]
     Rc = 0,
 
     /// Device running on XTAL 32MHz (~0.8mA)
@@ -117,7 +121,7 @@ impl Command for SetStandby {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x80
+        opcode::SET_STANDBY
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -144,7 +148,7 @@ impl Command for SetFs {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0xC1
+        opcode::SET_FS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -156,16 +160,59 @@ impl Command for SetFs {
 ///
 /// Used to automatically terminate TX/RX operations
 /// after specified period.
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] of 0 disables the timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct Timeout(pub u32);
 
+impl Timeout {
+    /// Longest timeout, 0xFFFFFF is reserved for continuous RX
+    pub const MAX: Self = Self(0xFF_FFFE);
+
+    /// Returns the timeout for a duration, rounded up to the next 15.625 µs step.
+    ///
+    /// Durations beyond [`MAX`](Self::MAX) (about 262 s) saturate, and a zero duration yields
+    /// a single step so the timeout stays enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use core::time::Duration;
+    /// use sx1262::Timeout;
+    ///
+    /// assert_eq!(Timeout::from_duration(Duration::from_millis(1)), Timeout(64));
+    /// assert_eq!(Timeout::from_duration(Duration::from_micros(16)), Timeout(2));
+    /// assert_eq!(Timeout::from_duration(Duration::from_secs(3600)), Timeout::MAX);
+    /// ```
+    pub fn from_duration(duration: Duration) -> Self {
+        let steps = (duration.as_nanos() * 64).div_ceil(1_000_000);
+        Self(steps.clamp(1, Self::MAX.0 as u128) as u32)
+    }
+
+    /// Returns the timeout programmed into the radio in microseconds, from the 24 bits sent.
+    ///
+    /// 0 disables the timeout, and 0xFFFFFF selects continuous RX instead.
+    ///
+    /// # Example
+    /// ```
+    /// use core::time::Duration;
+    /// use sx1262::Timeout;
+    ///
+    /// // 16 µs is rounded up to two steps
+    /// let timeout = Timeout::from_duration(Duration::from_micros(16));
+    /// assert_eq!(timeout.effective_micros(), 31.25);
+    /// assert_eq!(Timeout::MAX.effective_micros(), 262_143_968.75);
+    /// ```
+    pub fn effective_micros(&self) -> f64 {
+        f64::from(self.0 & 0xFF_FFFF) * 15.625
+    }
+}
+
 impl ToByteArray for Timeout {
     type Error = Infallible;
     type Array = [u8; 3];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        let mut bytes = [0u8; 3];
-        bytes.copy_from_slice(&self.0.to_be_bytes()[1..4]);
+        let [_, bytes @ ..] = self.0.to_be_bytes();
         Ok(bytes)
     }
 }
@@ -194,7 +241,7 @@ impl Command for SetTx {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x83
+        opcode::SET_TX
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -203,7 +250,7 @@ impl Command for SetTx {
 }
 
 /// RX operation mode
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RxMode {
     /// Return after receiving a single packet
     Single,
@@ -246,7 +293,7 @@ impl Command for SetRx {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x82
+        opcode::SET_RX
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -258,7 +305,9 @@ bitflags! {
     /// StopTimerOnPreamble configuration
     ///
     /// Controls when RX timeout timer is stopped.
-    #[derive(Debug, Clone, Copy)]
+    ///
+    /// The [`Default`] (empty) mirrors the chip's reset behaviour of stopping on Sync/Header.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
     pub struct StopTimerOnPreambleConfig: u8 {
         /// When set, stop timer on preamble detection
         /// When clear, stop on Sync/Header (default)
@@ -295,7 +344,7 @@ impl Command for StopTimerOnPreamble {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x9F
+        opcode::STOP_TIMER_ON_PREAMBLE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -306,7 +355,7 @@ impl Command for StopTimerOnPreamble {
 /// RxDutyCycle configuration
 ///
 /// Controls periodic wake-up for packet reception.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RxDutyCycleConfig {
     /// RX period in steps of 15.625 μs
     /// Time radio spends in RX mode
@@ -322,10 +371,9 @@ impl ToByteArray for RxDutyCycleConfig {
     type Array = [u8; 8];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        let mut bytes = [0u8; 8];
-        bytes[0..4].copy_from_slice(&self.rx_period.to_be_bytes());
-        bytes[4..8].copy_from_slice(&self.sleep_period.to_be_bytes());
-        Ok(bytes)
+        let [rx0, rx1, rx2, rx3] = self.rx_period.to_be_bytes();
+        let [sleep0, sleep1, sleep2, sleep3] = self.sleep_period.to_be_bytes();
+        Ok([rx0, rx1, rx2, rx3, sleep0, sleep1, sleep2, sleep3])
     }
 }
 
@@ -352,7 +400,7 @@ impl Command for SetRxDutyCycle {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x94
+        opcode::SET_RX_DUTY_CYCLE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -367,7 +415,8 @@ impl Command for SetRxDutyCycle {
 ///
 /// # Important Notes
 /// - Detects LoRa preamble or data symbols
-/// - Returns to STDBY_RC after detection
+/// - Returns to STDBY_RC after detection, whatever the mode set with SetRxTxFallbackMode,
+///   unless the CAD_RX exit mode stays in RX on activity
 /// - Triggers CADDone and optionally CADDetected IRQs
 /// - Parameters set by SetCadParams command
 #[derive(Debug, Clone)]
@@ -379,7 +428,7 @@ impl Command for SetCad {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0xC5
+        opcode::SET_CAD
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -404,7 +453,7 @@ impl Command for SetTxContinuousWave {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0xD1
+        opcode::SET_TX_CONTINUOUS_WAVE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -430,7 +479,7 @@ impl Command for SetTxInfinitePreamble {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0xD2
+        opcode::SET_TX_INFINITE_PREAMBLE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -441,15 +490,20 @@ impl Command for SetTxInfinitePreamble {
 /// Regulator mode configuration
 ///
 /// Selects voltage regulator configuration.
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] is LDO only, which is what the chip uses after power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegulatorMode {
     /// Only LDO used for all modes
     /// - Lower cost (no inductor needed)
     /// - Higher power consumption
+    #[default]
     LdoOnly = 0,
 
     /// DC-DC+LDO used for STBY_XOSC, FS, RX and TX
-    /// - Requires external inductor
+    /// - Requires the 15 µH inductor on the DCC_SW pin. Without it the radio still answers
+    ///   commands but RF performance collapses
     /// - ~50% lower power consumption
     /// - LDO remains active as backup
     DcDcLdo = 1,
@@ -485,7 +539,7 @@ impl Command for SetRegulatorMode {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x96
+        opcode::SET_REGULATOR_MODE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -497,7 +551,7 @@ bitflags! {
     /// Calibration configuration
     ///
     /// Selects which blocks to calibrate.
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct CalibrationConfig: u8 {
         /// RC64k oscillator calibration
         const RC64K = 1 << 0;
@@ -516,6 +570,40 @@ bitflags! {
     }
 }
 
+impl CalibrationConfig {
+    /// Returns the longest time the radio may stay busy calibrating the selected blocks, in
+    /// microseconds.
+    ///
+    /// The datasheet only gives 3.5ms for a full calibration, each block is budgeted a share
+    /// of it so that the selected blocks never take longer than their sum.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::CalibrationConfig;
+    ///
+    /// assert_eq!(CalibrationConfig::all().worst_case_duration_us(), 3_500);
+    /// assert_eq!(CalibrationConfig::RC64K.worst_case_duration_us(), 500);
+    /// assert_eq!(CalibrationConfig::empty().worst_case_duration_us(), 0);
+    /// ```
+    pub fn worst_case_duration_us(self) -> u32 {
+        const BUDGET_US: [(CalibrationConfig, u32); 7] = [
+            (CalibrationConfig::RC64K, 500),
+            (CalibrationConfig::RC13M, 100),
+            (CalibrationConfig::PLL, 700),
+            (CalibrationConfig::ADC_PULSE, 400),
+            (CalibrationConfig::ADC_BULK_N, 400),
+            (CalibrationConfig::ADC_BULK_P, 400),
+            (CalibrationConfig::IMAGE, 1_000),
+        ];
+
+        BUDGET_US
+            .iter()
+            .filter(|(block, _)| self.contains(*block))
+            .map(|(_, us)| us)
+            .sum()
+    }
+}
+
 impl ToByteArray for CalibrationConfig {
     type Error = Infallible;
     type Array = [u8; 1];
@@ -531,8 +619,10 @@ impl ToByteArray for CalibrationConfig {
 ///
 /// # Important Notes
 /// - Must be called in STDBY_RC mode
-/// - Takes up to 3.5ms for full calibration
-/// - BUSY high during calibration
+/// - Takes up to 3.5ms for full calibration, see [`CalibrationConfig::worst_case_duration_us`]
+/// - BUSY high during calibration. The [`Device`](crate::Device) waits for BUSY to be released
+///   before returning from the command, without a BUSY line its owned delay waits for the
+///   worst-case duration
 /// - Automatically performed at power-up
 /// - Required after configuration changes
 #[derive(Debug, Clone)]
@@ -547,7 +637,7 @@ impl Command for Calibrate {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x89
+        opcode::CALIBRATE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -558,7 +648,7 @@ impl Command for Calibrate {
 /// Image calibration configuration
 ///
 /// Defines frequency range for image calibration.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ImageCalibConfig {
     /// Start frequency code
     pub freq1: u8,
@@ -566,6 +656,259 @@ pub struct ImageCalibConfig {
     pub freq2: u8,
 }
 
+impl Default for ImageCalibConfig {
+    /// The 902-928MHz band (0xE1, 0xE9), matching the image calibration the chip performs at
+    /// power-up.
+    fn default() -> Self {
+        Self::BAND_902_928
+    }
+}
+
+/// Image calibration band listed in the datasheet
+///
+/// # Example
+/// ```
+/// use sx1262::{Band, ImageCalibConfig};
+///
+/// let band = Band::for_frequency(868_100_000).unwrap();
+/// assert_eq!(band, Band::Mhz863_870);
+/// assert_eq!(band.code_pair(), ImageCalibConfig::BAND_863_870);
+/// assert!(!band.contains(915_000_000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Band {
+    /// 430-440MHz
+    Mhz430_440,
+    /// 470-510MHz
+    Mhz470_510,
+    /// 779-787MHz
+    Mhz779_787,
+    /// 863-870MHz
+    Mhz863_870,
+    /// 902-928MHz
+    Mhz902_928,
+}
+
+impl Band {
+    /// Every band, in increasing frequency
+    pub const ALL: [Self; 5] = [
+        Self::Mhz430_440,
+        Self::Mhz470_510,
+        Self::Mhz779_787,
+        Self::Mhz863_870,
+        Self::Mhz902_928,
+    ];
+
+    /// Returns the CalibrateImage codes of the band.
+    pub const fn code_pair(self) -> ImageCalibConfig {
+        match self {
+            Self::Mhz430_440 => ImageCalibConfig::BAND_430_440,
+            Self::Mhz470_510 => ImageCalibConfig::BAND_470_510,
+            Self::Mhz779_787 => ImageCalibConfig::BAND_779_787,
+            Self::Mhz863_870 => ImageCalibConfig::BAND_863_870,
+            Self::Mhz902_928 => ImageCalibConfig::BAND_902_928,
+        }
+    }
+
+    /// Returns the frequencies of the band in MHz.
+    pub const fn range_mhz(self) -> RangeInclusive<u32> {
+        match self {
+            Self::Mhz430_440 => 430..=440,
+            Self::Mhz470_510 => 470..=510,
+            Self::Mhz779_787 => 779..=787,
+            Self::Mhz863_870 => 863..=870,
+            Self::Mhz902_928 => 902..=928,
+        }
+    }
+
+    /// Returns whether an RF frequency is inside the band.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency in Hz, compared in whole MHz
+    pub fn contains(self, frequency: u32) -> bool {
+        self.range_mhz().contains(&(frequency / 1_000_000))
+    }
+
+    /// Returns the band containing an RF frequency, if any.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency in Hz
+    pub fn for_frequency(frequency: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|band| band.contains(frequency))
+    }
+}
+
+impl ImageCalibConfig {
+    /// Codes of the 430-440MHz band
+    ///
+    /// # Example
+    /// The codes listed in the datasheet for every band:
+    /// ```
+    /// use sx1262::ImageCalibConfig;
+    ///
+    /// let codes = |config: ImageCalibConfig| [config.freq1, config.freq2];
+    /// assert_eq!(codes(ImageCalibConfig::BAND_430_440), [0x6B, 0x6F]);
+    /// assert_eq!(codes(ImageCalibConfig::BAND_470_510), [0x75, 0x81]);
+    /// assert_eq!(codes(ImageCalibConfig::BAND_779_787), [0xC1, 0xC5]);
+    /// assert_eq!(codes(ImageCalibConfig::BAND_863_870), [0xD7, 0xDB]);
+    /// assert_eq!(codes(ImageCalibConfig::BAND_902_928), [0xE1, 0xE9]);
+    /// ```
+    pub const BAND_430_440: Self = Self {
+        freq1: 0x6B,
+        freq2: 0x6F,
+    };
+    /// Codes of the 470-510MHz band
+    pub const BAND_470_510: Self = Self {
+        freq1: 0x75,
+        freq2: 0x81,
+    };
+    /// Codes of the 779-787MHz band
+    pub const BAND_779_787: Self = Self {
+        freq1: 0xC1,
+        freq2: 0xC5,
+    };
+    /// Codes of the 863-870MHz band
+    pub const BAND_863_870: Self = Self {
+        freq1: 0xD7,
+        freq2: 0xDB,
+    };
+    /// Codes of the 902-928MHz band
+    pub const BAND_902_928: Self = Self {
+        freq1: 0xE1,
+        freq2: 0xE9,
+    };
+
+    /// Returns the image calibration covering the given RF frequency.
+    ///
+    /// Frequencies inside one of the datasheet's bands, see [`Band`], use that band's codes.
+    /// Any other frequency is covered by the 4MHz steps either side of it.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency in Hz
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::ImageCalibConfig;
+    ///
+    /// let config = ImageCalibConfig::for_frequency(433_920_000);
+    /// assert_eq!((config.freq1, config.freq2), (0x6B, 0x6F));
+    /// ```
+    pub fn for_frequency(frequency: u32) -> Self {
+        if let Some(band) = Band::for_frequency(frequency) {
+            return band.code_pair();
+        }
+
+        let step = frequency / 4_000_000;
+        Self {
+            freq1: step.min(u8::MAX as u32) as u8,
+            freq2: (step + 1).min(u8::MAX as u32) as u8,
+        }
+    }
+
+    /// Returns the span of RF frequencies covered by the calibration, in MHz.
+    ///
+    /// The codes are in steps of 4MHz, so the span may extend past the requested band.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::ImageCalibConfig;
+    ///
+    /// // A custom span over the 863-870 and 902-928MHz bands
+    /// let config = ImageCalibConfig { freq1: 0xD7, freq2: 0xE9 };
+    /// assert_eq!(config.span_mhz(), 860..=932);
+    /// assert!(config.covers(868_100_000));
+    /// assert!(config.covers(915_000_000));
+    /// assert!(!config.covers(433_920_000));
+    /// ```
+    pub fn span_mhz(&self) -> RangeInclusive<u32> {
+        u32::from(self.freq1) * 4..=u32::from(self.freq2) * 4
+    }
+
+    /// Returns whether the calibration covers an RF frequency.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency in Hz
+    pub fn covers(&self, frequency: u32) -> bool {
+        let mhz = self.span_mhz();
+        (*mhz.start() * 1_000_000..=mhz.end().saturating_mul(1_000_000)).contains(&frequency)
+    }
+}
+
+/// Updates the image calibration known to be on the radio from a command sent to it.
+///
+/// CalibrateImage sets it, while a full Calibrate and a cold-start SetSleep leave it unknown.
+pub(crate) fn track_image_calibration(
+    tracked: &mut Option<ImageCalibConfig>,
+    opcode: u8,
+    request: &[u8],
+) {
+    if opcode == CalibrateImage::id() {
+        if let [freq1, freq2] = *request {
+            *tracked = Some(ImageCalibConfig { freq1, freq2 });
+        }
+    } else if opcode == Calibrate::id() {
+        if request.first().is_some_and(|&bits| {
+            CalibrationConfig::from_bits_retain(bits).contains(CalibrationConfig::IMAGE)
+        }) {
+            *tracked = None;
+        }
+    } else if is_cold_sleep(opcode, request) {
+        *tracked = None;
+    }
+}
+
+/// Returns whether a command puts the radio to sleep with a cold start, losing its
+/// configuration.
+pub(crate) fn is_cold_sleep(opcode: u8, request: &[u8]) -> bool {
+    opcode == SetSleep::id()
+        && request.first().is_some_and(|&bits| {
+            !SleepConfig::from_bits_retain(bits).contains(SleepConfig::WARM_START)
+        })
+}
+
+/// Updates the mode the radio stays in until the next command from a command sent to it.
+///
+/// TX and RX end on their own and sleep ends with the next command, leaving the mode unknown.
+pub(crate) fn track_settled_mode(mode: &mut Option<OperatingMode>, opcode: u8, request: &[u8]) {
+    if opcode == SetStandby::id() {
+        *mode = match request.first() {
+            Some(0) => Some(OperatingMode::StandbyRc),
+            _ => Some(OperatingMode::StandbyXosc),
+        };
+    } else if opcode == SetFs::id() {
+        *mode = Some(OperatingMode::FrequencySynthesizer);
+    } else if [SetTxContinuousWave::id(), SetTxInfinitePreamble::id()].contains(&opcode) {
+        *mode = Some(OperatingMode::Transmit);
+    } else if starts_operation(opcode) || opcode == SetSleep::id() {
+        *mode = None;
+    }
+}
+
+/// Returns whether a command starts an operation raising IRQs.
+pub(crate) fn starts_operation(opcode: u8) -> bool {
+    [
+        SetTx::id(),
+        SetRx::id(),
+        SetRxDutyCycle::id(),
+        SetCad::id(),
+        SetTxContinuousWave::id(),
+        SetTxInfinitePreamble::id(),
+    ]
+    .contains(&opcode)
+}
+
+/// Returns how long the radio may stay busy after a command sent to it, in microseconds,
+/// when longer than the usual command processing.
+pub(crate) fn busy_after_us(opcode: u8, request: &[u8]) -> Option<u32> {
+    if opcode != Calibrate::id() {
+        return None;
+    }
+    request
+        .first()
+        .map(|&bits| CalibrationConfig::from_bits_truncate(bits).worst_case_duration_us())
+}
+
 impl ToByteArray for ImageCalibConfig {
     type Error = Infallible;
     type Array = [u8; 2];
@@ -591,13 +934,27 @@ pub struct CalibrateImage {
     pub config: ImageCalibConfig,
 }
 
+impl CalibrateImage {
+    /// Creates a CalibrateImage command covering the given RF frequency.
+    ///
+    /// See [`ImageCalibConfig::for_frequency`].
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency in Hz
+    pub fn for_frequency(frequency: u32) -> Self {
+        Self {
+            config: ImageCalibConfig::for_frequency(frequency),
+        }
+    }
+}
+
 impl Command for CalibrateImage {
     type IdType = u8;
     type CommandParameters = ImageCalibConfig;
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x98
+        opcode::CALIBRATE_IMAGE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -606,16 +963,66 @@ impl Command for CalibrateImage {
 }
 
 /// Device selection for PA configuration
-#[derive(Debug, Clone, Copy)]
+///
+/// The SX1268 is register-compatible with the SX1262 and selects the same high-power PA, but
+/// covers a narrower frequency range and has its own optimal PA settings. The LLCC68 is an
+/// SX1262 drop-in that only supports a subset of the LoRa spreading factors and bandwidths,
+/// see [`LLCC68_LORA_CAPABILITIES`](crate::LLCC68_LORA_CAPABILITIES).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeviceSelect {
-    /// SX1262 device (+22dBm max)
-    Sx1262 = 0,
-    /// SX1261 device (+15dBm max)
-    Sx1261 = 1,
+    /// SX1262 device (+22dBm max, 150-960MHz)
+    #[default]
+    Sx1262,
+    /// SX1261 device (+15dBm max, 150-960MHz)
+    Sx1261,
+    /// SX1268 device (+22dBm max, 410-810MHz)
+    Sx1268,
+    /// LLCC68 device (+22dBm max, 150-960MHz, reduced LoRa SF/BW support)
+    Llcc68,
+}
+
+impl DeviceSelect {
+    /// Returns the value of the device selection field of SetPaConfig.
+    pub fn bits(self) -> u8 {
+        match self {
+            Self::Sx1262 | Self::Sx1268 | Self::Llcc68 => 0x00,
+            Self::Sx1261 => 0x01,
+        }
+    }
+
+    /// Returns the RF frequency range supported by the device, in Hz.
+    pub fn frequency_range(self) -> RangeInclusive<u32> {
+        match self {
+            Self::Sx1262 | Self::Sx1261 | Self::Llcc68 => 150_000_000..=960_000_000,
+            Self::Sx1268 => 410_000_000..=810_000_000,
+        }
+    }
+
+    /// Returns the output power range of the device, in dBm.
+    ///
+    /// The SX1261 low-power PA covers -17 to +15 dBm, the high-power PA of the other devices
+    /// -9 to +22 dBm.
+    pub fn output_power_range(self) -> RangeInclusive<i8> {
+        match self {
+            Self::Sx1262 | Self::Sx1268 | Self::Llcc68 => -9..=22,
+            Self::Sx1261 => -17..=15,
+        }
+    }
+
+    /// Returns the datasheet's optimal PA settings for the device.
+    pub fn pa_presets(self) -> &'static [PaPreset] {
+        match self {
+            Self::Sx1262 | Self::Llcc68 => SX1262_PA_PRESETS,
+            Self::Sx1261 => SX1261_PA_PRESETS,
+            Self::Sx1268 => SX1268_PA_PRESETS,
+        }
+    }
 }
 
 /// PA configuration parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PaConfig {
     /// PA duty cycle (controls efficiency)
     /// See datasheet for optimal values
@@ -633,6 +1040,20 @@ pub struct PaConfig {
     pub pa_lut: u8,
 }
 
+impl Default for PaConfig {
+    /// The datasheet's optimal SX1262 settings for +14 dBm (duty cycle 0x02, hp_max 0x02),
+    /// pairing with the [`TxParams`](crate::TxParams) default. A sane starting point rather
+    /// than a silicon reset value.
+    fn default() -> Self {
+        Self {
+            duty_cycle: 0x02,
+            hp_max: 0x02,
+            device_sel: DeviceSelect::Sx1262,
+            pa_lut: 0x01,
+        }
+    }
+}
+
 impl ToByteArray for PaConfig {
     type Error = Infallible;
     type Array = [u8; 4];
@@ -641,12 +1062,180 @@ impl ToByteArray for PaConfig {
         Ok([
             self.duty_cycle,
             self.hp_max,
-            self.device_sel as u8,
+            self.device_sel.bits(),
             self.pa_lut,
         ])
     }
 }
 
+/// Optimal PA settings for a target output power
+///
+/// Each preset pairs a [`PaConfig`] with the power to program through
+/// [`SetTxParams`](crate::SetTxParams), as listed in the datasheet's optimal settings tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PaPreset {
+    /// Output power at the antenna in dBm
+    pub output_power: i8,
+    /// PA configuration
+    pub pa_config: PaConfig,
+    /// Power to program through SetTxParams, in dBm
+    pub tx_power: i8,
+}
+
+impl PaPreset {
+    /// Returns the preset for the given device and output power, if the datasheet lists one.
+    ///
+    /// # Arguments
+    /// * `device` - The device the preset is for
+    /// * `output_power` - The target output power in dBm
+    pub fn find(device: DeviceSelect, output_power: i8) -> Option<Self> {
+        device
+            .pa_presets()
+            .iter()
+            .find(|preset| preset.output_power == output_power)
+            .copied()
+    }
+
+    /// Returns the TX parameters for this preset with the given ramp time.
+    pub fn tx_params(self, ramp_time: RampTime) -> TxParams {
+        TxParams {
+            power: self.tx_power,
+            ramp_time,
+        }
+    }
+}
+
+const fn pa_preset(
+    output_power: i8,
+    duty_cycle: u8,
+    hp_max: u8,
+    device_sel: DeviceSelect,
+    tx_power: i8,
+) -> PaPreset {
+    PaPreset {
+        output_power,
+        pa_config: PaConfig {
+            duty_cycle,
+            hp_max,
+            device_sel,
+            pa_lut: 0x01,
+        },
+        tx_power,
+    }
+}
+
+/// Optimal PA settings for the SX1262, which the LLCC68 shares
+pub const SX1262_PA_PRESETS: &[PaPreset] = &[
+    pa_preset(22, 0x04, 0x07, DeviceSelect::Sx1262, 22),
+    pa_preset(20, 0x03, 0x05, DeviceSelect::Sx1262, 22),
+    pa_preset(17, 0x02, 0x03, DeviceSelect::Sx1262, 22),
+    pa_preset(14, 0x02, 0x02, DeviceSelect::Sx1262, 22),
+];
+
+/// Optimal PA settings for the SX1261
+pub const SX1261_PA_PRESETS: &[PaPreset] = &[
+    pa_preset(15, 0x06, 0x00, DeviceSelect::Sx1261, 14),
+    pa_preset(14, 0x04, 0x00, DeviceSelect::Sx1261, 14),
+    pa_preset(10, 0x01, 0x00, DeviceSelect::Sx1261, 13),
+];
+
+/// Optimal PA settings for the SX1268
+pub const SX1268_PA_PRESETS: &[PaPreset] = &[
+    pa_preset(22, 0x04, 0x06, DeviceSelect::Sx1268, 22),
+    pa_preset(20, 0x03, 0x05, DeviceSelect::Sx1268, 22),
+    pa_preset(17, 0x02, 0x03, DeviceSelect::Sx1268, 22),
+    pa_preset(14, 0x04, 0x06, DeviceSelect::Sx1268, 14),
+    pa_preset(10, 0x00, 0x03, DeviceSelect::Sx1268, 15),
+];
+
+/// PA configuration and TX parameters reaching a target output power
+///
+/// Resolved by [`TxPowerPlan::resolve`] over the whole output power range of the device. The
+/// datasheet's [`PaPreset`]s are used where one lists the power, otherwise the PA is set to its
+/// full-power configuration and the power is programmed as is through
+/// [`SetTxParams`](crate::SetTxParams). Send [`SetPaConfig`] before [`SetTxParams`](crate::SetTxParams).
+///
+/// # Example
+/// ```
+/// use sx1262::{DeviceSelect, RampTime, TxPowerPlan};
+///
+/// // (output power, duty cycle, hp_max, SetTxParams power byte)
+/// let sx1261 = [
+///     (-17, 0x04, 0x00, 0xEF),
+///     (-9, 0x04, 0x00, 0xF7),
+///     (0, 0x04, 0x00, 0x00),
+///     (14, 0x04, 0x00, 0x0E),
+/// ];
+/// let sx1262 = [
+///     (-9, 0x04, 0x07, 0xF7),
+///     (0, 0x04, 0x07, 0x00),
+///     (14, 0x02, 0x02, 0x16),
+///     (22, 0x04, 0x07, 0x16),
+/// ];
+///
+/// for (device, table) in [(DeviceSelect::Sx1261, sx1261), (DeviceSelect::Sx1262, sx1262)] {
+///     for (power, duty_cycle, hp_max, byte) in table {
+///         let plan = TxPowerPlan::resolve(device, power, RampTime::Micros40).unwrap();
+///         assert_eq!(plan.pa_config.duty_cycle, duty_cycle);
+///         assert_eq!(plan.pa_config.hp_max, hp_max);
+///         assert_eq!(plan.pa_config.device_sel, device);
+///         assert_eq!(plan.tx_params.power as u8, byte);
+///     }
+/// }
+///
+/// // Outside the range of the device
+/// assert_eq!(TxPowerPlan::resolve(DeviceSelect::Sx1261, -18, RampTime::Micros40), None);
+/// assert_eq!(TxPowerPlan::resolve(DeviceSelect::Sx1262, -10, RampTime::Micros40), None);
+/// assert_eq!(TxPowerPlan::resolve(DeviceSelect::Sx1262, 23, RampTime::Micros40), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TxPowerPlan {
+    /// PA configuration, sent through SetPaConfig
+    pub pa_config: PaConfig,
+    /// TX parameters, sent through SetTxParams after the PA configuration
+    pub tx_params: TxParams,
+}
+
+impl TxPowerPlan {
+    /// Returns the plan reaching the given output power, if the device supports it.
+    ///
+    /// # Arguments
+    /// * `device` - The device the plan is for
+    /// * `output_power` - The target output power in dBm, see
+    ///   [`DeviceSelect::output_power_range`]
+    /// * `ramp_time` - PA ramp time
+    pub fn resolve(device: DeviceSelect, output_power: i8, ramp_time: RampTime) -> Option<Self> {
+        if let Some(preset) = PaPreset::find(device, output_power) {
+            return Some(Self {
+                pa_config: preset.pa_config,
+                tx_params: preset.tx_params(ramp_time),
+            });
+        }
+        if !device.output_power_range().contains(&output_power) {
+            return None;
+        }
+
+        // The full-power configuration, with SetTxParams scaling the output down
+        let (duty_cycle, hp_max) = match device {
+            DeviceSelect::Sx1262 | DeviceSelect::Llcc68 => (0x04, 0x07),
+            DeviceSelect::Sx1261 => (0x04, 0x00),
+            DeviceSelect::Sx1268 => (0x04, 0x06),
+        };
+        Some(Self {
+            pa_config: PaConfig {
+                duty_cycle,
+                hp_max,
+                device_sel: device,
+                pa_lut: 0x01,
+            },
+            tx_params: TxParams {
+                power: output_power,
+                ramp_time,
+            },
+        })
+    }
+}
+
 /// SetPaConfig command (0x95)
 ///
 /// Configures the power amplifier.
@@ -656,7 +1245,8 @@ impl ToByteArray for PaConfig {
 /// - Different optimal settings for power levels
 /// - Affects efficiency and harmonics
 /// - SX1261: duty_cycle ≤ 0x04 below 400MHz
-/// - SX1262: duty_cycle ≤ 0x04 all frequencies
+/// - SX1262/SX1268: duty_cycle ≤ 0x04 all frequencies
+/// - See [`PaPreset`] for the datasheet's optimal settings
 #[derive(Debug, Clone)]
 pub struct SetPaConfig {
     /// PA configuration
@@ -669,7 +1259,7 @@ impl Command for SetPaConfig {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x95
+        opcode::SET_PA_CONFIG
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -680,7 +1270,10 @@ impl Command for SetPaConfig {
 /// Fallback mode after Rx/Tx
 ///
 /// Defines mode to enter after packet operation.
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] is STDBY_RC, as on the chip after power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FallbackMode {
     /// Go to FS mode
     /// Fastest transition to next TX/RX
@@ -692,6 +1285,7 @@ pub enum FallbackMode {
 
     /// Go to STDBY_RC mode (default)
     /// Lowest power, slowest transition
+    #[default]
     StdbyRc = 0x20,
 }
 
@@ -725,7 +1319,7 @@ impl Command for SetRxTxFallbackMode {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x93
+        opcode::SET_RX_TX_FALLBACK_MODE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
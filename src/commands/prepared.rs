@@ -0,0 +1,78 @@
+//! Pre-serialized commands for hot paths
+
+use core::{convert::Infallible, fmt, marker::PhantomData};
+
+use regiface::{ByteArray, Command, ToByteArray};
+
+/// A command serialized once, ready to be issued repeatedly
+///
+/// Hot-path commands such as SetRx, ClearIrqStatus and GetIrqStatus are often issued with the
+/// same parameters thousands of times. A `PreparedCommand` serializes the parameters into a
+/// fixed buffer at construction, and
+/// [`Device::execute_prepared`](crate::Device::execute_prepared) sends them without
+/// re-serializing. The bytes on the bus are identical to those sent by
+/// [`Device::execute_command`](crate::Device::execute_command) for the same command.
+///
+/// # Example
+/// ```
+/// use regiface::{Command, ToByteArray};
+/// use sx1262::{PreparedCommand, RxMode, SetRx};
+///
+/// let prepared = PreparedCommand::new(SetRx { mode: RxMode::Continuous });
+/// let params = SetRx { mode: RxMode::Continuous }
+///     .invoking_parameters()
+///     .to_bytes()
+///     .unwrap();
+///
+/// assert_eq!(PreparedCommand::<SetRx>::opcode(), 0x82);
+/// assert_eq!(prepared.params(), &params[..]);
+/// ```
+pub struct PreparedCommand<C>
+where
+    C: Command<IdType = u8>,
+    C::CommandParameters: ToByteArray<Error = Infallible>,
+{
+    params: <C::CommandParameters as ToByteArray>::Array,
+    command: PhantomData<fn() -> C>,
+}
+
+impl<C> PreparedCommand<C>
+where
+    C: Command<IdType = u8>,
+    C::CommandParameters: ToByteArray<Error = Infallible>,
+{
+    /// Serializes the parameters of a command.
+    ///
+    /// # Arguments
+    /// * `command` - The command to prepare
+    pub fn new(command: C) -> Self {
+        let Ok(params) = command.invoking_parameters().to_bytes();
+        Self {
+            params,
+            command: PhantomData,
+        }
+    }
+
+    /// Returns the opcode of the command.
+    pub fn opcode() -> u8 {
+        C::id()
+    }
+
+    /// Returns the serialized command parameters.
+    pub fn params(&self) -> &[u8] {
+        self.params.as_ref()
+    }
+}
+
+impl<C> fmt::Debug for PreparedCommand<C>
+where
+    C: Command<IdType = u8>,
+    C::CommandParameters: ToByteArray<Error = Infallible>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreparedCommand")
+            .field("opcode", &C::id())
+            .field("params", &self.params())
+            .finish()
+    }
+}
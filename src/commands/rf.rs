@@ -16,29 +16,80 @@ use core::convert::Infallible;
 
 use regiface::FromByteArray;
 
-use crate::{Command, NoParameters, ToByteArray};
+use crate::commands::opcode;
+use crate::{
+    commands::operational::is_cold_sleep, config::ConfigItems, Command, DeviceSelect, NoParameters,
+    Status, StatusError, ToByteArray,
+};
 
 /// RF frequency configuration parameters
 ///
 /// Used to set the RF frequency for both TX and RX operations.
 /// The frequency is calculated as: RF = frequency_in_hz * FXTAL / 2^25
 /// where FXTAL is typically 32MHz.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RfFrequencyConfig {
     /// RF frequency in Hz
-    /// Valid range: 150MHz to 960MHz
+    /// Valid range: 150MHz to 960MHz (410MHz to 810MHz for the SX1268)
     pub frequency: u32,
 }
 
+/// Error type for an RF frequency outside the range supported by the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrequencyOutOfRange(pub u32);
+
+impl RfFrequencyConfig {
+    /// Returns the PLL steps of the frequency, rounded to the nearest step of about 0.95Hz.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::RfFrequencyConfig;
+    ///
+    /// // 868MHz is a whole number of steps, 1Hz more is 1.048576 steps
+    /// assert_eq!(RfFrequencyConfig { frequency: 868_000_000 }.pll_steps(), 910_163_968);
+    /// assert_eq!(RfFrequencyConfig { frequency: 868_000_001 }.pll_steps(), 910_163_969);
+    /// ```
+    pub fn pll_steps(&self) -> u32 {
+        // Frequency register = (Frequency * 2^25) / FXTAL
+        ((u64::from(self.frequency) * (1_u64 << 25) + 16_000_000) / 32_000_000) as u32
+    }
+
+    /// Returns the frequency programmed into the radio in Hz, from the PLL steps sent.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::RfFrequencyConfig;
+    ///
+    /// let config = |frequency| RfFrequencyConfig { frequency };
+    /// assert_eq!(config(868_000_000).effective_frequency_hz(), 868_000_000.0);
+    /// assert_eq!(config(868_000_001).effective_frequency_hz(), 868_000_000.953_674_316_406_25);
+    /// ```
+    pub fn effective_frequency_hz(&self) -> f64 {
+        // FXTAL / 2^25 = 15625 / 16384
+        f64::from(self.pll_steps()) * 15_625.0 / 16_384.0
+    }
+
+    /// Checks that the frequency is within the range supported by the given device.
+    ///
+    /// # Errors
+    /// * `FrequencyOutOfRange` - The frequency is outside
+    ///   [`DeviceSelect::frequency_range`]
+    pub fn validate(&self, device: DeviceSelect) -> Result<(), FrequencyOutOfRange> {
+        if device.frequency_range().contains(&self.frequency) {
+            Ok(())
+        } else {
+            Err(FrequencyOutOfRange(self.frequency))
+        }
+    }
+}
+
 impl ToByteArray for RfFrequencyConfig {
     type Error = Infallible;
     type Array = [u8; 4];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        // Frequency register = (Frequency * 2^25) / FXTAL
-        let f = ((self.frequency as u64 * (1_u64 << 25)) / 32_000_000) as u32;
-
-        Ok(f.to_be_bytes())
+        Ok(self.pll_steps().to_be_bytes())
     }
 }
 
@@ -63,7 +114,7 @@ impl Command for SetRfFrequency {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x86
+        opcode::SET_RF_FREQUENCY
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -72,10 +123,15 @@ impl Command for SetRfFrequency {
 }
 
 /// Packet type options for radio configuration
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] is GFSK, matching the packet type selected by the chip at power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PacketType {
     /// GFSK packet type (0x00)
     /// Supports bit rates from 0.6 to 300kbps
+    #[default]
     Gfsk = 0x00,
 
     /// LoRa packet type (0x01)
@@ -83,16 +139,25 @@ pub enum PacketType {
     LoRa = 0x01,
 }
 
+/// Error type for invalid packet type values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PacketTypeError {
+    /// The value does not correspond to a supported packet type
+    InvalidValue(u8),
+    /// The status byte of the GetPacketType response could not be parsed
+    InvalidStatus(StatusError),
+}
+
 impl FromByteArray for PacketType {
-    type Error = Infallible;
+    type Error = PacketTypeError;
     type Array = [u8; 1];
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
-        Ok(match bytes[0] {
-            0x00 => Self::Gfsk,
-            0x01 => Self::LoRa,
-            _ => Self::LoRa,
-        })
+        match bytes[0] {
+            0x00 => Ok(Self::Gfsk),
+            0x01 => Ok(Self::LoRa),
+            value => Err(PacketTypeError::InvalidValue(value)),
+        }
     }
 }
 
@@ -114,6 +179,8 @@ impl ToByteArray for PacketType {
 /// - Must be called while in STDBY_RC mode
 /// - Parameters from previous mode are not retained
 /// - Modulation and packet parameters must be reconfigured after changing type
+/// - The [`Device`](crate::Device) forgets the configuration applied last when it sends this
+///   command, so the next [`apply_config_diff`](crate::Device::apply_config_diff) sends it all
 #[derive(Debug, Clone)]
 pub struct SetPacketType {
     /// Packet type selection
@@ -126,7 +193,7 @@ impl Command for SetPacketType {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x8A
+        opcode::SET_PACKET_TYPE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -134,19 +201,46 @@ impl Command for SetPacketType {
     }
 }
 
+/// GetPacketType response
+///
+/// Contains the device status and the packet type set on the radio.
+#[derive(Debug, Clone, Copy)]
+pub struct GetPacketTypeResponse {
+    /// Device status from the first response byte
+    pub status: Status,
+    /// Packet type set on the radio
+    pub packet_type: PacketType,
+}
+
+impl FromByteArray for GetPacketTypeResponse {
+    type Error = PacketTypeError;
+    type Array = [u8; 2]; // 1 status byte + 1 packet type byte
+
+    fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [status, packet_type] = bytes;
+        Ok(Self {
+            status: Status::from_bytes([status]).map_err(PacketTypeError::InvalidStatus)?,
+            packet_type: PacketType::from_bytes([packet_type])?,
+        })
+    }
+}
+
 /// GetPacketType command (0x11)
 ///
 /// Returns the current packet type configuration.
+///
+/// # Important Notes
+/// - Values other than GFSK and LoRa are rejected when parsing the response
 #[derive(Debug, Clone)]
 pub struct GetPacketType;
 
 impl Command for GetPacketType {
     type IdType = u8;
     type CommandParameters = NoParameters;
-    type ResponseParameters = PacketType;
+    type ResponseParameters = GetPacketTypeResponse;
 
     fn id() -> Self::IdType {
-        0x11
+        opcode::GET_PACKET_TYPE
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -155,13 +249,17 @@ impl Command for GetPacketType {
 }
 
 /// Power amplifier ramp time options
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] of 40 μs is a sane starting point, not a silicon reset value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RampTime {
     /// 10 μs ramp time
     Micros10 = 0x00,
     /// 20 μs ramp time
     Micros20 = 0x01,
     /// 40 μs ramp time
+    #[default]
     Micros40 = 0x02,
     /// 80 μs ramp time
     Micros80 = 0x03,
@@ -176,11 +274,13 @@ pub enum RampTime {
 }
 
 /// TX parameters configuration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TxParams {
     /// Output power in dBm
     /// - SX1261: -17 to +14 dBm
     /// - SX1262: -9 to +22 dBm
+    /// - SX1268: -9 to +22 dBm
     ///
     /// Power selection depends on PA configuration set by SetPaConfig
     pub power: i8,
@@ -191,6 +291,17 @@ pub struct TxParams {
     pub ramp_time: RampTime,
 }
 
+impl Default for TxParams {
+    /// A sane starting point of +14 dBm with a 40 μs ramp, which is within range for both the
+    /// SX1261 and SX1262. This is not a silicon reset value.
+    fn default() -> Self {
+        Self {
+            power: 14,
+            ramp_time: RampTime::default(),
+        }
+    }
+}
+
 impl ToByteArray for TxParams {
     type Error = Infallible;
     type Array = [u8; 2];
@@ -221,7 +332,7 @@ impl Command for SetTxParams {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x8E
+        opcode::SET_TX_PARAMS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -241,7 +352,8 @@ impl Command for SetTxParams {
 /// - Ensure bandwidth > 2 * (frequency_deviation + bit_rate/2)
 /// - Pulse shaping affects spectral efficiency and occupied bandwidth
 /// - Higher bit rates require wider bandwidths
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GfskModParams {
     /// Bit rate in bits per second
     /// Valid range: 600 bps to 300 kbps
@@ -260,9 +372,11 @@ pub struct GfskModParams {
 /// Gaussian filtering reduces spectral spreading but increases
 /// intersymbol interference. Higher BT products reduce ISI
 /// at the cost of wider bandwidth.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GfskPulseShape {
     /// No pulse shaping filter
+    #[default]
     NoFilter = 0x00,
     /// Gaussian filter, BT = 0.3
     /// Minimum bandwidth, maximum ISI
@@ -286,7 +400,8 @@ pub enum GfskPulseShape {
 /// - Expected frequency error
 ///
 /// Wider bandwidths allow higher data rates but reduce selectivity
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GfskBandwidth {
     /// 4.8 kHz Double-Side Bandwidth
     Bw48 = 0x1F,
@@ -345,7 +460,11 @@ pub enum GfskBandwidth {
 /// - Wider bandwidths increase data rate but reduce sensitivity
 /// - Enable low data rate optimization when symbol length ≥ 16.38ms
 /// - Coding rate adds redundancy at the cost of time-on-air
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] (SF7, 125 kHz, CR 4/5, no LDRO) is a sane starting point rather than a
+/// silicon reset value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LoRaModParams {
     /// Spreading Factor (chip/symbol)
     pub spreading_factor: SpreadingFactor,
@@ -367,7 +486,8 @@ pub struct LoRaModParams {
 /// - Reduce tolerance to frequency offset
 ///
 /// SF5/SF6 have restrictions on header and CRC usage
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SpreadingFactor {
     /// SF5 - 32 chips/symbol
     /// Fastest data rate, shortest range
@@ -375,6 +495,7 @@ pub enum SpreadingFactor {
     /// SF6 - 64 chips/symbol
     SF6 = 6,
     /// SF7 - 128 chips/symbol
+    #[default]
     SF7 = 7,
     /// SF8 - 256 chips/symbol
     SF8 = 8,
@@ -397,7 +518,8 @@ pub enum SpreadingFactor {
 /// - Increase tolerance to frequency offset
 ///
 /// Some bandwidths may not be available below 400MHz
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LoRaBandwidth {
     /// 7.81 kHz bandwidth
     Bw7 = 0x00,
@@ -414,6 +536,7 @@ pub enum LoRaBandwidth {
     /// 62.50 kHz bandwidth
     Bw62 = 0x03,
     /// 125 kHz bandwidth
+    #[default]
     Bw125 = 0x04,
     /// 250 kHz bandwidth
     Bw250 = 0x05,
@@ -429,10 +552,12 @@ pub enum LoRaBandwidth {
 /// - Increase reliability in noisy conditions
 /// - Increase time-on-air
 /// - Reduce effective data rate
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CodingRate {
     /// 4/5 coding rate
     /// Lowest redundancy (1.25x overhead)
+    #[default]
     Cr45 = 0x01,
     /// 4/6 coding rate
     /// 1.5x overhead
@@ -445,6 +570,52 @@ pub enum CodingRate {
     Cr48 = 0x04,
 }
 
+/// Error type for invalid spreading factor values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpreadingFactorError {
+    /// The value is not a spreading factor from 5 to 12
+    InvalidValue(u8),
+}
+
+impl TryFrom<u8> for SpreadingFactor {
+    type Error = SpreadingFactorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            5 => Ok(Self::SF5),
+            6 => Ok(Self::SF6),
+            7 => Ok(Self::SF7),
+            8 => Ok(Self::SF8),
+            9 => Ok(Self::SF9),
+            10 => Ok(Self::SF10),
+            11 => Ok(Self::SF11),
+            12 => Ok(Self::SF12),
+            invalid => Err(SpreadingFactorError::InvalidValue(invalid)),
+        }
+    }
+}
+
+/// Error type for invalid coding rate values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CodingRateError {
+    /// The value does not correspond to a coding rate
+    InvalidValue(u8),
+}
+
+impl TryFrom<u8> for CodingRate {
+    type Error = CodingRateError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(Self::Cr45),
+            0x02 => Ok(Self::Cr46),
+            0x03 => Ok(Self::Cr47),
+            0x04 => Ok(Self::Cr48),
+            invalid => Err(CodingRateError::InvalidValue(invalid)),
+        }
+    }
+}
+
 /// Modulation parameters configuration
 ///
 /// Configures the radio modulation based on the selected packet type.
@@ -468,7 +639,8 @@ pub enum CodingRate {
 /// - Parameters must match the selected packet type
 /// - Configuration affects sensitivity, range, and data rate
 /// - Some parameter combinations may be invalid or suboptimal
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ModulationParams {
     /// GFSK modulation configuration
     Gfsk(GfskModParams),
@@ -476,6 +648,407 @@ pub enum ModulationParams {
     LoRa(LoRaModParams),
 }
 
+/// Error type for a GFSK frequency deviation the radio cannot use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidDeviation {
+    /// FSK needs a non-zero deviation
+    Zero,
+    /// The deviation does not fit the 24-bit deviation register
+    TooLarge {
+        /// The requested deviation in Hz
+        freq_deviation: u32,
+    },
+    /// The modulation index is outside the range the demodulator is characterized for
+    ModulationIndex {
+        /// The modulation index (2 * deviation / bit rate) in thousandths, `u32::MAX` for a
+        /// bit rate of 0
+        permille: u32,
+    },
+}
+
+impl GfskModParams {
+    /// Largest value of the 24-bit frequency deviation register
+    pub const MAX_DEVIATION_STEPS: u32 = 0xFF_FFFF;
+
+    /// Lowest modulation index the demodulator is characterized for, in thousandths
+    pub const MIN_MODULATION_INDEX_PERMILLE: u32 = 500;
+
+    /// Highest modulation index the demodulator is characterized for, in thousandths
+    pub const MAX_MODULATION_INDEX_PERMILLE: u32 = 4_000;
+
+    /// Returns the frequency deviation register value, in PLL steps of about 0.95Hz.
+    pub fn deviation_steps(&self) -> u64 {
+        // Frequency deviation register = (Frequency deviation * 2^25) / FXTAL
+        (u64::from(self.freq_deviation) << 25) / 32_000_000
+    }
+
+    /// Returns the bit rate programmed into the radio in bits per second, from the 24-bit bit
+    /// rate register sent, or 0 for a bit rate of 0, which cannot be encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{GfskBandwidth, GfskModParams, GfskPulseShape};
+    ///
+    /// let params = |bit_rate| GfskModParams {
+    ///     bit_rate,
+    ///     pulse_shape: GfskPulseShape::NoFilter,
+    ///     bandwidth: GfskBandwidth::Bw4670,
+    ///     freq_deviation: 5_000,
+    /// };
+    /// assert_eq!(params(250_000).effective_bit_rate(), 250_000.0);
+    /// // 32 * FXTAL / 4800 is not a whole register value
+    /// assert!((params(4_800).effective_bit_rate() - 4_800.0075).abs() < 1e-4);
+    /// ```
+    pub fn effective_bit_rate(&self) -> f64 {
+        match (32 * 32_000_000_u32).checked_div(self.bit_rate) {
+            Some(register) if register & 0xFF_FFFF != 0 => {
+                1_024_000_000.0 / f64::from(register & 0xFF_FFFF)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the frequency deviation programmed into the radio in Hz, from the 24-bit
+    /// deviation register sent.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{GfskBandwidth, GfskModParams, GfskPulseShape};
+    ///
+    /// let params = |freq_deviation| GfskModParams {
+    ///     bit_rate: 4_800,
+    ///     pulse_shape: GfskPulseShape::NoFilter,
+    ///     bandwidth: GfskBandwidth::Bw4670,
+    ///     freq_deviation,
+    /// };
+    /// assert_eq!(params(15_625).effective_deviation_hz(), 15_625.0);
+    /// // Truncated to 5242 steps
+    /// assert_eq!(params(5_000).effective_deviation_hz(), 4_999.160_766_601_562_5);
+    /// ```
+    pub fn effective_deviation_hz(&self) -> f64 {
+        // FXTAL / 2^25 = 15625 / 16384
+        (self.deviation_steps() & 0xFF_FFFF) as f64 * 15_625.0 / 16_384.0
+    }
+
+    /// Returns the modulation index (2 * deviation / bit rate) in thousandths, rounded to the
+    /// nearest, or `u32::MAX` for a bit rate of 0.
+    pub fn modulation_index_permille(&self) -> u32 {
+        if self.bit_rate == 0 {
+            return u32::MAX;
+        }
+        let bit_rate = u64::from(self.bit_rate);
+        let permille = (u64::from(self.freq_deviation) * 2_000 + bit_rate / 2) / bit_rate;
+        u32::try_from(permille).unwrap_or(u32::MAX)
+    }
+
+    /// Checks that the frequency deviation can be encoded and demodulated at the bit rate.
+    ///
+    /// The modulation index must be within 0.5 to 4.0, the range over which the demodulator is
+    /// characterized. The boundaries are compared exactly, not on the rounded index.
+    ///
+    /// # Errors
+    /// * `InvalidDeviation::Zero` - The deviation is 0
+    /// * `InvalidDeviation::TooLarge` - The deviation exceeds
+    ///   [`MAX_DEVIATION_STEPS`](GfskModParams::MAX_DEVIATION_STEPS)
+    /// * `InvalidDeviation::ModulationIndex` - The modulation index is outside 0.5 to 4.0
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{GfskBandwidth, GfskModParams, GfskPulseShape, InvalidDeviation};
+    ///
+    /// let params = |bit_rate, freq_deviation| GfskModParams {
+    ///     bit_rate,
+    ///     pulse_shape: GfskPulseShape::NoFilter,
+    ///     bandwidth: GfskBandwidth::Bw4670,
+    ///     freq_deviation,
+    /// };
+    ///
+    /// // Modulation index 0.5 and 4.0 are accepted, just outside is not
+    /// assert!(params(10_000, 2_500).validate().is_ok());
+    /// assert!(params(10_000, 20_000).validate().is_ok());
+    /// assert_eq!(
+    ///     params(10_000, 2_499).validate(),
+    ///     Err(InvalidDeviation::ModulationIndex { permille: 500 })
+    /// );
+    /// assert_eq!(
+    ///     params(10_000, 20_001).validate(),
+    ///     Err(InvalidDeviation::ModulationIndex { permille: 4_000 })
+    /// );
+    /// assert_eq!(
+    ///     params(4_800, 50_000).validate(),
+    ///     Err(InvalidDeviation::ModulationIndex { permille: 20_833 })
+    /// );
+    ///
+    /// assert_eq!(params(4_800, 0).validate(), Err(InvalidDeviation::Zero));
+    ///
+    /// // 2^24 steps (16 MHz) do not fit the register, whatever the bit rate
+    /// assert_eq!(params(8_000_000, 15_999_999).deviation_steps(), 0xFF_FFFE);
+    /// assert!(params(8_000_000, 15_999_999).validate().is_ok());
+    /// assert_eq!(
+    ///     params(8_000_000, 16_000_000).validate(),
+    ///     Err(InvalidDeviation::TooLarge { freq_deviation: 16_000_000 })
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), InvalidDeviation> {
+        if self.freq_deviation == 0 {
+            return Err(InvalidDeviation::Zero);
+        }
+        if self.deviation_steps() > u64::from(Self::MAX_DEVIATION_STEPS) {
+            return Err(InvalidDeviation::TooLarge {
+                freq_deviation: self.freq_deviation,
+            });
+        }
+
+        // 0.5 <= 2 * deviation / bit rate <= 4.0
+        let deviation = u64::from(self.freq_deviation);
+        let bit_rate = u64::from(self.bit_rate);
+        if bit_rate == 0 || deviation * 4 < bit_rate || deviation > bit_rate * 2 {
+            return Err(InvalidDeviation::ModulationIndex {
+                permille: self.modulation_index_permille(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Error type for LoRa modulation parameters the device cannot demodulate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedModulation {
+    /// The requested spreading factor
+    pub spreading_factor: SpreadingFactor,
+    /// The requested bandwidth
+    pub bandwidth: LoRaBandwidth,
+}
+
+/// LoRa capability matrix of the LLCC68
+///
+/// Lists the highest spreading factor supported at each bandwidth, with SF5 as the lowest.
+/// Bandwidths not listed are not supported.
+pub const LLCC68_LORA_CAPABILITIES: &[(LoRaBandwidth, SpreadingFactor)] = &[
+    (LoRaBandwidth::Bw125, SpreadingFactor::SF9),
+    (LoRaBandwidth::Bw250, SpreadingFactor::SF10),
+    (LoRaBandwidth::Bw500, SpreadingFactor::SF11),
+];
+
+/// Shortest symbol time requiring the low data rate optimization, in microseconds
+const LDRO_SYMBOL_TIME_US: u32 = 16_380;
+
+impl LoRaBandwidth {
+    /// Returns the bandwidth in Hz.
+    pub const fn hz(self) -> u32 {
+        match self {
+            Self::Bw7 => 7_810,
+            Self::Bw10 => 10_420,
+            Self::Bw15 => 15_630,
+            Self::Bw20 => 20_830,
+            Self::Bw31 => 31_250,
+            Self::Bw41 => 41_670,
+            Self::Bw62 => 62_500,
+            Self::Bw125 => 125_000,
+            Self::Bw250 => 250_000,
+            Self::Bw500 => 500_000,
+        }
+    }
+
+    /// Returns the bandwidth of [`hz`](LoRaBandwidth::hz) Hz, if any.
+    pub const fn from_hz(hz: u32) -> Option<Self> {
+        Some(match hz {
+            7_810 => Self::Bw7,
+            10_420 => Self::Bw10,
+            15_630 => Self::Bw15,
+            20_830 => Self::Bw20,
+            31_250 => Self::Bw31,
+            41_670 => Self::Bw41,
+            62_500 => Self::Bw62,
+            125_000 => Self::Bw125,
+            250_000 => Self::Bw250,
+            500_000 => Self::Bw500,
+            _ => return None,
+        })
+    }
+}
+
+impl LoRaModParams {
+    /// Returns parameters with CR 4/5 and the low data rate optimization enabled when the
+    /// symbol time is at least 16.38 ms.
+    ///
+    /// # Arguments
+    /// * `spreading_factor` - The spreading factor
+    /// * `bandwidth` - The bandwidth
+    pub fn new(spreading_factor: SpreadingFactor, bandwidth: LoRaBandwidth) -> Self {
+        let symbol_time_us = (1_000_000u64 << spreading_factor as u8) / u64::from(bandwidth.hz());
+        Self {
+            spreading_factor,
+            bandwidth,
+            coding_rate: CodingRate::Cr45,
+            low_data_rate_opt: symbol_time_us >= u64::from(LDRO_SYMBOL_TIME_US),
+        }
+    }
+
+    /// Returns the parameters of a LoRa data rate of the LoRaWAN EU868 channel plan.
+    ///
+    /// Data rates are from the LoRaWAN Regional Parameters: DR0 to DR5 are SF12 to SF7 at
+    /// 125 kHz and DR6 is SF7 at 250 kHz, with CR 4/5.
+    ///
+    /// # Arguments
+    /// * `dr` - The data rate index
+    ///
+    /// # Returns
+    /// `None` for DR7 (FSK), the LR-FHSS data rates and undefined indices
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{LoRaBandwidth, LoRaModParams, SpreadingFactor};
+    ///
+    /// let dr0 = LoRaModParams::lorawan_eu868_dr(0).unwrap();
+    /// assert_eq!(dr0.spreading_factor, SpreadingFactor::SF12);
+    /// assert_eq!(dr0.bandwidth, LoRaBandwidth::Bw125);
+    /// assert!(dr0.low_data_rate_opt);
+    ///
+    /// let dr5 = LoRaModParams::lorawan_eu868_dr(5).unwrap();
+    /// assert_eq!(dr5.spreading_factor, SpreadingFactor::SF7);
+    /// assert_eq!(dr5.bandwidth, LoRaBandwidth::Bw125);
+    /// assert!(!dr5.low_data_rate_opt);
+    ///
+    /// // FSK
+    /// assert_eq!(LoRaModParams::lorawan_eu868_dr(7), None);
+    /// ```
+    pub fn lorawan_eu868_dr(dr: u8) -> Option<Self> {
+        let (spreading_factor, bandwidth) = match dr {
+            0 => (SpreadingFactor::SF12, LoRaBandwidth::Bw125),
+            1 => (SpreadingFactor::SF11, LoRaBandwidth::Bw125),
+            2 => (SpreadingFactor::SF10, LoRaBandwidth::Bw125),
+            3 => (SpreadingFactor::SF9, LoRaBandwidth::Bw125),
+            4 => (SpreadingFactor::SF8, LoRaBandwidth::Bw125),
+            5 => (SpreadingFactor::SF7, LoRaBandwidth::Bw125),
+            6 => (SpreadingFactor::SF7, LoRaBandwidth::Bw250),
+            _ => return None,
+        };
+        Some(Self::new(spreading_factor, bandwidth))
+    }
+
+    /// Returns the parameters of a LoRa data rate of the LoRaWAN US915 channel plan.
+    ///
+    /// Data rates are from the LoRaWAN Regional Parameters: DR0 to DR3 are SF10 to SF7 at
+    /// 125 kHz, DR4 is SF8 at 500 kHz and DR8 to DR13 are the SF12 to SF7 downlink rates at
+    /// 500 kHz, with CR 4/5. The 500 kHz rates need the
+    /// [`LoRa500kHzModulation`](crate::errata::Workaround::LoRa500kHzModulation) workaround,
+    /// which [`Device::apply_config`](crate::Device::apply_config) applies.
+    ///
+    /// # Arguments
+    /// * `dr` - The data rate index
+    ///
+    /// # Returns
+    /// `None` for the LR-FHSS data rates and undefined indices
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{
+    ///     errata::{required_workarounds, Workaround},
+    ///     LoRaBandwidth, LoRaInit, LoRaModParams, RfFrequencyConfig, SpreadingFactor,
+    /// };
+    ///
+    /// let dr3 = LoRaModParams::lorawan_us915_dr(3).unwrap();
+    /// assert_eq!(dr3.spreading_factor, SpreadingFactor::SF7);
+    /// assert_eq!(dr3.bandwidth, LoRaBandwidth::Bw125);
+    ///
+    /// let dr4 = LoRaModParams::lorawan_us915_dr(4).unwrap();
+    /// assert_eq!(dr4.spreading_factor, SpreadingFactor::SF8);
+    /// assert_eq!(dr4.bandwidth, LoRaBandwidth::Bw500);
+    /// assert!(!dr4.low_data_rate_opt);
+    ///
+    /// // Only the 500 kHz data rate needs the modulation quality workaround
+    /// let frequency = RfFrequencyConfig { frequency: 903_900_000 };
+    /// for (dr, needed) in [(dr3, false), (dr4, true)] {
+    ///     let config = LoRaInit { modulation: dr, ..LoRaInit::new(frequency) }.config().unwrap();
+    ///     let workarounds = required_workarounds(&config).collect::<Vec<_>>();
+    ///     assert_eq!(workarounds.contains(&Workaround::LoRa500kHzModulation), needed);
+    /// }
+    ///
+    /// // LR-FHSS
+    /// assert_eq!(LoRaModParams::lorawan_us915_dr(5), None);
+    /// ```
+    pub fn lorawan_us915_dr(dr: u8) -> Option<Self> {
+        let (spreading_factor, bandwidth) = match dr {
+            0 => (SpreadingFactor::SF10, LoRaBandwidth::Bw125),
+            1 => (SpreadingFactor::SF9, LoRaBandwidth::Bw125),
+            2 => (SpreadingFactor::SF8, LoRaBandwidth::Bw125),
+            3 => (SpreadingFactor::SF7, LoRaBandwidth::Bw125),
+            4 => (SpreadingFactor::SF8, LoRaBandwidth::Bw500),
+            8 => (SpreadingFactor::SF12, LoRaBandwidth::Bw500),
+            9 => (SpreadingFactor::SF11, LoRaBandwidth::Bw500),
+            10 => (SpreadingFactor::SF10, LoRaBandwidth::Bw500),
+            11 => (SpreadingFactor::SF9, LoRaBandwidth::Bw500),
+            12 => (SpreadingFactor::SF8, LoRaBandwidth::Bw500),
+            13 => (SpreadingFactor::SF7, LoRaBandwidth::Bw500),
+            _ => return None,
+        };
+        Some(Self::new(spreading_factor, bandwidth))
+    }
+
+    /// Checks that the given device supports this spreading factor and bandwidth combination.
+    ///
+    /// Only the LLCC68 restricts the combinations, see [`LLCC68_LORA_CAPABILITIES`].
+    ///
+    /// # Errors
+    /// * `UnsupportedModulation` - The device cannot demodulate this combination
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{DeviceSelect, LoRaBandwidth, LoRaModParams, SpreadingFactor};
+    ///
+    /// let params = LoRaModParams {
+    ///     spreading_factor: SpreadingFactor::SF10,
+    ///     bandwidth: LoRaBandwidth::Bw125,
+    ///     ..Default::default()
+    /// };
+    /// assert!(params.validate(DeviceSelect::Sx1262).is_ok());
+    /// assert!(params.validate(DeviceSelect::Llcc68).is_err());
+    /// ```
+    pub fn validate(&self, device: DeviceSelect) -> Result<(), UnsupportedModulation> {
+        if device != DeviceSelect::Llcc68 {
+            return Ok(());
+        }
+
+        let supported = LLCC68_LORA_CAPABILITIES
+            .iter()
+            .find(|(bandwidth, _)| *bandwidth == self.bandwidth)
+            .is_some_and(|(_, max_sf)| self.spreading_factor as u8 <= *max_sf as u8);
+
+        if supported {
+            Ok(())
+        } else {
+            Err(UnsupportedModulation {
+                spreading_factor: self.spreading_factor,
+                bandwidth: self.bandwidth,
+            })
+        }
+    }
+}
+
+impl ModulationParams {
+    /// Returns the packet type these parameters are for.
+    pub fn packet_type(&self) -> PacketType {
+        match self {
+            ModulationParams::Gfsk(_) => PacketType::Gfsk,
+            ModulationParams::LoRa(_) => PacketType::LoRa,
+        }
+    }
+
+    /// Checks that the given device supports these modulation parameters.
+    ///
+    /// GFSK parameters are supported by every device. See [`LoRaModParams::validate`].
+    ///
+    /// # Errors
+    /// * `UnsupportedModulation` - The device cannot demodulate the LoRa parameters
+    pub fn validate(&self, device: DeviceSelect) -> Result<(), UnsupportedModulation> {
+        match self {
+            ModulationParams::Gfsk(_) => Ok(()),
+            ModulationParams::LoRa(params) => params.validate(device),
+        }
+    }
+}
+
 impl ToByteArray for ModulationParams {
     type Error = Infallible;
     type Array = [u8; 8];
@@ -485,13 +1058,23 @@ impl ToByteArray for ModulationParams {
         match self {
             ModulationParams::Gfsk(params) => {
                 // Bit rate = (32 * FXTAL) / bit_rate
-                let br_val = (32 * 32_000_000) / params.bit_rate;
-                bytes[0..3].copy_from_slice(&br_val.to_be_bytes()[1..]);
-                bytes[3] = params.pulse_shape as u8;
-                bytes[4] = params.bandwidth as u8;
-                // Frequency deviation register = (Frequency deviation * 2^25) / FXTAL
-                let fdev = ((params.freq_deviation as u64 * (1_u64 << 25)) / 32_000_000) as u32;
-                bytes[5..8].copy_from_slice(&fdev.to_be_bytes()[1..]);
+                // A bit rate of 0 cannot be encoded, see GfskModParams::effective_bit_rate
+                let br_val = (32 * 32_000_000_u32)
+                    .checked_div(params.bit_rate)
+                    .unwrap_or(0);
+                let [_, br0, br1, br2] = br_val.to_be_bytes();
+                let fdev = params.deviation_steps() as u32;
+                let [_, fdev0, fdev1, fdev2] = fdev.to_be_bytes();
+                bytes = [
+                    br0,
+                    br1,
+                    br2,
+                    params.pulse_shape as u8,
+                    params.bandwidth as u8,
+                    fdev0,
+                    fdev1,
+                    fdev2,
+                ];
             }
             ModulationParams::LoRa(params) => {
                 bytes[0] = params.spreading_factor as u8;
@@ -526,7 +1109,7 @@ impl Command for SetModulationParams {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x8B
+        opcode::SET_MODULATION_PARAMS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -539,12 +1122,23 @@ impl Command for SetModulationParams {
 /// Parameters interpretation depends on the packet type.
 ///
 /// see [`GFSKPacketParams`] and [`LoRaPacketParams`]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PacketParams {
     GFSK(GFSKPacketParams),
     LoRa(LoRaPacketParams),
 }
 
+impl PacketParams {
+    /// Returns the packet type these parameters are for.
+    pub fn packet_type(&self) -> PacketType {
+        match self {
+            PacketParams::GFSK(_) => PacketType::Gfsk,
+            PacketParams::LoRa(_) => PacketType::LoRa,
+        }
+    }
+}
+
 impl ToByteArray for PacketParams {
     type Error = Infallible;
     type Array = [u8; 9];
@@ -560,11 +1154,13 @@ impl ToByteArray for PacketParams {
 /// The preamble detector acts as a gate to the packet controller, when different from 0x00
 /// (preamble detector length off) the packet controller only becomes actve if a cerain number of
 /// preamble bits have been successfully received by the radio.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PreambleDetectorLength {
     /// preamble detector length off
     Off = 0x00,
     /// preamble detector length 8 bits
+    #[default]
     Bits8 = 0x04,
     /// preamble detector length 16 bits
     Bits16 = 0x05,
@@ -576,9 +1172,11 @@ pub enum PreambleDetectorLength {
 
 /// The node address and the broadcast address are directly programmed into the device through
 /// simple register access.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddressFiltering {
     /// Address Filtering Disable
+    #[default]
     Disable = 0x00,
     /// Address Filtering activated on Node address
     Node = 0x01,
@@ -587,7 +1185,8 @@ pub enum AddressFiltering {
 }
 
 /// Packet Header Type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GFSKPacketHeaderType {
     /// The packet length is known on both sides, the size of the payload is not added to the
     /// packet
@@ -597,27 +1196,84 @@ pub enum GFSKPacketHeaderType {
     /// The packet is of variable size, the first byte of the payload is the size of the packet
     ///
     /// also called implicit
+    #[default]
     Variable = 0x01,
 }
 
 /// When the byte HeaderType is at 0x00, the payload length, coding rate and the header CRC are
 /// added to the LoRa header and transported to the receiver.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LoraPacketHeaderType {
     /// The packet length is known on both sides, the size of the payload is not added to the
     /// packet
     ///
-    /// also called explicit
+    /// also called implicit
     Fixed = 0x01, // inverse of GFSK 🙃
-    /// The packet is of variable size, the first byte of the payload is the size of the packet
+    /// The packet is of variable size, the header carries the size of the packet
     ///
-    /// also called implicit
+    /// also called explicit
+    #[default]
     Variable = 0x00,
 }
 
+/// LoRa header mode, coupling an implicit header to its payload length
+///
+/// Without a header, the receiver demodulates exactly the configured number of bytes, so the
+/// length is part of the mode rather than a separate field that may be left at its default.
+/// Select it with [`LoRaPacketParamsBuilder::header_mode`] and read it back with
+/// [`LoRaPacketParams::header_mode`].
+///
+/// # Example
+/// ```
+/// use regiface::ToByteArray;
+/// use sx1262::{LoRaHeaderMode, LoRaPacketParams, LoraPacketHeaderType};
+///
+/// let implicit = LoRaPacketParams::builder()
+///     .header_mode(LoRaHeaderMode::Implicit { payload_length: 16 })
+///     .build()
+///     .unwrap();
+/// assert_eq!(implicit.header_type, LoraPacketHeaderType::Fixed);
+/// assert_eq!(implicit.payload_length, 16);
+/// assert_eq!(implicit.header_mode(), LoRaHeaderMode::Implicit { payload_length: 16 });
+/// assert_eq!(implicit.to_bytes().unwrap()[..6], [0x00, 0x08, 0x01, 0x10, 0x01, 0x00]);
+///
+/// // The transmit helpers set the length of each packet, up to the configured one
+/// let explicit = LoRaPacketParams::builder()
+///     .header_mode(LoRaHeaderMode::Explicit)
+///     .build()
+///     .unwrap();
+/// assert_eq!(explicit.header_type, LoraPacketHeaderType::Variable);
+/// assert_eq!(explicit.header_mode(), LoRaHeaderMode::Explicit);
+/// assert_eq!(explicit.to_bytes().unwrap()[..6], [0x00, 0x08, 0x00, 0xFF, 0x01, 0x00]);
+///
+/// // Switching back to an explicit header keeps the length as the largest payload accepted
+/// let params = LoRaPacketParams::builder()
+///     .header_mode(LoRaHeaderMode::Implicit { payload_length: 16 })
+///     .header_mode(LoRaHeaderMode::Explicit)
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.payload_length, 16);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoRaHeaderMode {
+    /// The header carries the payload length, which the receive helpers take from
+    /// GetRxBufferStatus
+    #[default]
+    Explicit,
+    /// No header, both ends must use the same payload length. The receive helpers read this
+    /// length and apply the implicit header timeout workaround after an RX timeout
+    Implicit {
+        /// Length of every payload
+        payload_length: u8,
+    },
+}
+
 /// In the SX1261/2, the CRC can be fully configured and the polynomial used, and the initial
 /// values can be entered directly via register access.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CrcType {
     /// No CRC
     CrcOff = 0x01,
@@ -631,19 +1287,62 @@ pub enum CrcType {
     Crc2ByteInv = 0x06,
 }
 
+/// GFSK preamble length
+///
+/// The radio is programmed with the number of preamble bits, sent as alternating 0 and 1
+/// (0x55 for every whole byte). Construct it in either unit, the value sent on the wire is
+/// always in bits.
+///
+/// # Example
+/// ```
+/// use regiface::ToByteArray;
+/// use sx1262::{GFSKPacketParams, PreambleLength};
+///
+/// // 4 bytes of 0x55, i.e. 32 bits, is programmed as 0x0020
+/// assert_eq!(PreambleLength::bytes(4), PreambleLength::bits(32));
+///
+/// let params = GFSKPacketParams {
+///     preamble: PreambleLength::bytes(4),
+///     ..Default::default()
+/// };
+/// assert_eq!(params.to_bytes().unwrap()[..2], [0x00, 0x20]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreambleLength {
+    bits: u16,
+}
+
+impl PreambleLength {
+    /// Creates a preamble length from a number of bits.
+    pub const fn bits(bits: u16) -> Self {
+        Self { bits }
+    }
+
+    /// Creates a preamble length from a number of 0x55 bytes.
+    pub const fn bytes(bytes: u8) -> Self {
+        Self {
+            bits: bytes as u16 * 8,
+        }
+    }
+
+    /// Returns the preamble length in bits, the unit sent to the radio.
+    pub const fn as_bits(self) -> u16 {
+        self.bits
+    }
+}
+
 /// GFSK Mode Packet Parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GFSKPacketParams {
-    /// Preamble length in bits
-    ///
-    /// The preamble length is a 16-bit value which represents the number of bytes which are sent
-    /// by the radio. Each preamble byte represents an alternating 0 and 1, and each preamble byte
-    /// is coded as 0x55, so 0b01010101.
-    pub preamble_length: u16,
+    /// Preamble length, sent to the radio in bits
+    pub preamble: PreambleLength,
     /// Preamble detector length
     pub preamble_detector_length: PreambleDetectorLength,
     /// The Sync Word is directly programmed into the device through simple register acceess. This
-    /// parameter describes the Sync Word length in bits (from 0 to 8 bytes)
+    /// parameter describes the Sync Word length in bits, a multiple of 8 from 0 to 64. Use
+    /// [`GFSKPacketParams::builder`] to have it checked
     pub sync_word_length: u8,
     /// Address filtering
     pub address_filtering: AddressFiltering,
@@ -658,12 +1357,29 @@ pub struct GFSKPacketParams {
     pub whitening_enable: bool,
 }
 
+impl Default for GFSKPacketParams {
+    /// A sane starting point: 32-bit preamble with an 8-bit detector, 16-bit sync word,
+    /// variable-length packets of up to 255 bytes, 2-byte CRC and no whitening.
+    fn default() -> Self {
+        Self {
+            preamble: PreambleLength::bits(32),
+            preamble_detector_length: PreambleDetectorLength::default(),
+            sync_word_length: 16,
+            address_filtering: AddressFiltering::default(),
+            packet_type: GFSKPacketHeaderType::default(),
+            payload_length: 0xFF,
+            crc_type: CrcType::Crc2Byte,
+            whitening_enable: false,
+        }
+    }
+}
+
 impl ToByteArray for GFSKPacketParams {
     type Error = Infallible;
     type Array = [u8; 9];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        let [p0, p1] = self.preamble_length.to_bytes()?;
+        let [p0, p1] = self.preamble.as_bits().to_bytes()?;
         Ok([
             p0,
             p1,
@@ -679,12 +1395,15 @@ impl ToByteArray for GFSKPacketParams {
 }
 
 /// LoRa Mode Packet Parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LoRaPacketParams {
     /// Preamble length in symbols
     ///
     /// The preamble length is a 16-bit value which represents the number of LoRa symbols which are
-    /// sent by the radio.
+    /// sent by the radio. Reception is unreliable below
+    /// [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS), see
+    /// [`validate`](LoRaPacketParams::validate).
     pub preamble_length: u16,
     /// Header type
     pub header_type: LoraPacketHeaderType,
@@ -696,6 +1415,114 @@ pub struct LoRaPacketParams {
     pub iq_inversion_enable: bool,
 }
 
+/// Error type for a LoRa preamble too short to be received reliably
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreambleTooShort {
+    /// The spreading factor the preamble was checked against
+    pub spreading_factor: SpreadingFactor,
+    /// The requested preamble length in symbols
+    pub preamble_length: u16,
+}
+
+impl LoRaPacketParams {
+    /// Shortest preamble in symbols the receiver detects reliably
+    pub const MIN_PREAMBLE_SYMBOLS: u16 = 6;
+
+    /// Returns the header mode, with the payload length of an implicit header.
+    pub fn header_mode(&self) -> LoRaHeaderMode {
+        match self.header_type {
+            LoraPacketHeaderType::Variable => LoRaHeaderMode::Explicit,
+            LoraPacketHeaderType::Fixed => LoRaHeaderMode::Implicit {
+                payload_length: self.payload_length,
+            },
+        }
+    }
+
+    /// Creates packet parameters with the given preamble and every other field defaulted.
+    ///
+    /// # Arguments
+    /// * `spreading_factor` - Spreading factor the packets are sent with
+    /// * `preamble_length` - Preamble length in symbols
+    ///
+    /// # Errors
+    /// * `PreambleTooShort` - The preamble is shorter than
+    ///   [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS)
+    pub fn new(
+        spreading_factor: SpreadingFactor,
+        preamble_length: u16,
+    ) -> Result<Self, PreambleTooShort> {
+        let params = Self::new_unchecked(preamble_length);
+        params.validate(spreading_factor)?;
+        Ok(params)
+    }
+
+    /// Creates packet parameters with the given preamble and every other field defaulted,
+    /// without checking the preamble length.
+    ///
+    /// Intended for experiments, a preamble shorter than
+    /// [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS) is rejected by
+    /// [`RadioConfigBuilder::build`](crate::RadioConfigBuilder::build).
+    ///
+    /// # Arguments
+    /// * `preamble_length` - Preamble length in symbols
+    pub fn new_unchecked(preamble_length: u16) -> Self {
+        Self {
+            preamble_length,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the preamble length in symbols recommended for a spreading factor.
+    ///
+    /// SF5 and SF6 need 12 symbols for reliable detection, the other spreading factors 8.
+    pub fn recommended_preamble(spreading_factor: SpreadingFactor) -> u16 {
+        match spreading_factor {
+            SpreadingFactor::SF5 | SpreadingFactor::SF6 => 12,
+            _ => 8,
+        }
+    }
+
+    /// Checks that the preamble can be received reliably at the given spreading factor.
+    ///
+    /// # Errors
+    /// * `PreambleTooShort` - The preamble is shorter than
+    ///   [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS)
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{LoRaPacketParams, SpreadingFactor};
+    ///
+    /// assert_eq!(LoRaPacketParams::recommended_preamble(SpreadingFactor::SF5), 12);
+    /// assert!(LoRaPacketParams::new(SpreadingFactor::SF5, 6).is_ok());
+    /// assert!(LoRaPacketParams::new(SpreadingFactor::SF7, 5).is_err());
+    /// assert!(LoRaPacketParams::new(SpreadingFactor::SF12, 8).is_ok());
+    /// ```
+    pub fn validate(&self, spreading_factor: SpreadingFactor) -> Result<(), PreambleTooShort> {
+        if self.preamble_length < Self::MIN_PREAMBLE_SYMBOLS {
+            Err(PreambleTooShort {
+                spreading_factor,
+                preamble_length: self.preamble_length,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for LoRaPacketParams {
+    /// A sane starting point: 8 preamble symbols, explicit header, up to 255 payload bytes,
+    /// CRC enabled and standard IQ.
+    fn default() -> Self {
+        Self {
+            preamble_length: 8,
+            header_type: LoraPacketHeaderType::default(),
+            payload_length: 0xFF,
+            crc_enable: true,
+            iq_inversion_enable: false,
+        }
+    }
+}
+
 impl ToByteArray for LoRaPacketParams {
     type Error = Infallible;
     type Array = [u8; 9];
@@ -716,6 +1543,393 @@ impl ToByteArray for LoRaPacketParams {
     }
 }
 
+/// Error type for packet parameters whose fields contradict each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketParamsError {
+    /// The LoRa preamble is shorter than
+    /// [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS)
+    PreambleTooShort {
+        /// The requested preamble length in symbols
+        preamble_length: u16,
+    },
+    /// The GFSK preamble detector is longer than the preamble, so no packet is ever detected
+    PreambleDetectorTooLong {
+        /// The preamble detector length in bits
+        detector_bits: u16,
+        /// The preamble length in bits
+        preamble_bits: u16,
+    },
+    /// The GFSK sync word is longer than the 8 bytes of the sync word registers
+    SyncWordTooLong {
+        /// The requested sync word length in bytes
+        bytes: u8,
+    },
+    /// The GFSK sync word length is not a whole number of bytes
+    SyncWordNotByteMultiple {
+        /// The requested sync word length in bits
+        bits: u8,
+    },
+    /// The GFSK address byte leaves room for at most 254 payload bytes
+    PayloadTooLongForAddress {
+        /// The requested payload length
+        payload_length: u8,
+    },
+    /// A fixed-length GFSK or implicit-header LoRa packet has no payload
+    EmptyFixedPayload,
+    /// Fixed-length GFSK packets without an explicit payload length, the receiver would
+    /// silently capture the 255 bytes of the default
+    MissingFixedPayloadLength,
+    /// Variable-length GFSK packets without a CRC, where a corrupted length byte yields a
+    /// packet of the wrong size, see
+    /// [`allow_unprotected_variable_length`](GFSKPacketParamsBuilder::allow_unprotected_variable_length)
+    UnprotectedVariableLength,
+}
+
+impl PreambleDetectorLength {
+    /// Returns the preamble detector length in bits, 0 when off.
+    pub const fn bits(self) -> u16 {
+        match self {
+            Self::Off => 0,
+            Self::Bits8 => 8,
+            Self::Bits16 => 16,
+            Self::Bits24 => 24,
+            Self::Bits32 => 32,
+        }
+    }
+}
+
+/// Builder for [`GFSKPacketParams`] checking the fields against each other
+///
+/// Created with [`GFSKPacketParams::builder`], starting from the [`Default`] parameters.
+///
+/// # Example
+/// ```
+/// use sx1262::{
+///     AddressFiltering, CrcType, GFSKPacketHeaderType, GFSKPacketParams, PacketParamsError,
+///     PreambleDetectorLength, PreambleLength,
+/// };
+///
+/// let params = GFSKPacketParams::builder()
+///     .preamble(PreambleLength::bytes(4))
+///     .sync_word_bytes(4)
+///     .address_filtering(AddressFiltering::Node)
+///     .payload_length(64)
+///     .crc(CrcType::Crc2ByteInv)
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.sync_word_length, 32);
+/// assert_eq!(params.packet_type, GFSKPacketHeaderType::Variable);
+///
+/// // The sync word registers hold 8 bytes
+/// let params = GFSKPacketParams::builder().sync_word_bytes(9).build();
+/// assert_eq!(params, Err(PacketParamsError::SyncWordTooLong { bytes: 9 }));
+///
+/// // Lengths in bits must be whole bytes
+/// for bits in [0, 8, 64] {
+///     let params = GFSKPacketParams::builder().sync_word_bits(bits).build().unwrap();
+///     assert_eq!(params.sync_word_length, bits);
+/// }
+/// let params = GFSKPacketParams::builder().sync_word_bits(12).build();
+/// assert_eq!(params, Err(PacketParamsError::SyncWordNotByteMultiple { bits: 12 }));
+/// let params = GFSKPacketParams::builder().sync_word_bits(72).build();
+/// assert_eq!(params, Err(PacketParamsError::SyncWordTooLong { bytes: 9 }));
+///
+/// // A 32-bit detector never triggers on a 16-bit preamble
+/// let params = GFSKPacketParams::builder()
+///     .preamble(PreambleLength::bits(16))
+///     .preamble_detector(PreambleDetectorLength::Bits32)
+///     .build();
+/// assert!(matches!(params, Err(PacketParamsError::PreambleDetectorTooLong { .. })));
+///
+/// // The address byte takes one byte of the 255
+/// let params = GFSKPacketParams::builder()
+///     .address_filtering(AddressFiltering::NodeAndBroadcast)
+///     .payload_length(255)
+///     .build();
+/// assert!(matches!(params, Err(PacketParamsError::PayloadTooLongForAddress { .. })));
+///
+/// // Fixed-length packets need a length, given explicitly
+/// let params = GFSKPacketParams::builder().fixed_length().payload_length(0).build();
+/// assert_eq!(params, Err(PacketParamsError::EmptyFixedPayload));
+/// let params = GFSKPacketParams::builder().fixed_length().build();
+/// assert_eq!(params, Err(PacketParamsError::MissingFixedPayloadLength));
+/// let params = GFSKPacketParams::builder().payload_length(255).fixed_length().build();
+/// assert_eq!(params.unwrap().payload_length, 255);
+///
+/// // Without a CRC a corrupted length byte goes unnoticed, unless explicitly allowed
+/// let builder = GFSKPacketParams::builder().crc(CrcType::CrcOff);
+/// assert_eq!(builder.build(), Err(PacketParamsError::UnprotectedVariableLength));
+/// assert!(builder.allow_unprotected_variable_length().build().is_ok());
+/// assert!(builder.fixed_length().payload_length(16).build().is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GFSKPacketParamsBuilder {
+    params: GFSKPacketParams,
+    sync_word_bits: u16,
+    payload_length_set: bool,
+    allow_unprotected_variable_length: bool,
+}
+
+impl GFSKPacketParams {
+    /// Creates a builder starting from the [`Default`] parameters.
+    pub fn builder() -> GFSKPacketParamsBuilder {
+        let params = Self::default();
+        GFSKPacketParamsBuilder {
+            params,
+            sync_word_bits: params.sync_word_length.into(),
+            payload_length_set: false,
+            allow_unprotected_variable_length: false,
+        }
+    }
+}
+
+impl GFSKPacketParamsBuilder {
+    /// Sets the preamble length.
+    pub fn preamble(mut self, preamble: PreambleLength) -> Self {
+        self.params.preamble = preamble;
+        self
+    }
+
+    /// Sets the preamble detector length, at most the preamble length.
+    pub fn preamble_detector(mut self, length: PreambleDetectorLength) -> Self {
+        self.params.preamble_detector_length = length;
+        self
+    }
+
+    /// Sets the sync word length in bytes, at most 8.
+    pub fn sync_word_bytes(mut self, bytes: u8) -> Self {
+        self.sync_word_bits = u16::from(bytes) * 8;
+        self
+    }
+
+    /// Sets the sync word length in bits, a multiple of 8 up to 64.
+    pub fn sync_word_bits(mut self, bits: u8) -> Self {
+        self.sync_word_bits = bits.into();
+        self
+    }
+
+    /// Sets the address filtering.
+    pub fn address_filtering(mut self, address_filtering: AddressFiltering) -> Self {
+        self.params.address_filtering = address_filtering;
+        self
+    }
+
+    /// Sends packets of a known length, without a length byte.
+    ///
+    /// The length must be set with [`payload_length`](GFSKPacketParamsBuilder::payload_length),
+    /// the receiver captures exactly that many bytes.
+    pub fn fixed_length(mut self) -> Self {
+        self.params.packet_type = GFSKPacketHeaderType::Fixed;
+        self
+    }
+
+    /// Sends packets starting with a length byte.
+    pub fn variable_length(mut self) -> Self {
+        self.params.packet_type = GFSKPacketHeaderType::Variable;
+        self
+    }
+
+    /// Sets the payload length, or the largest payload accepted for variable-length packets.
+    pub fn payload_length(mut self, payload_length: u8) -> Self {
+        self.params.payload_length = payload_length;
+        self.payload_length_set = true;
+        self
+    }
+
+    /// Sets the CRC type.
+    pub fn crc(mut self, crc_type: CrcType) -> Self {
+        self.params.crc_type = crc_type;
+        self
+    }
+
+    /// Enables or disables whitening.
+    pub fn whitening(mut self, enable: bool) -> Self {
+        self.params.whitening_enable = enable;
+        self
+    }
+
+    /// Accepts variable-length packets without a CRC.
+    ///
+    /// Without a CRC a corrupted length byte is only caught when it exceeds the maximum
+    /// payload length, otherwise a packet of the wrong size is received as valid. Only allow
+    /// this when the payload carries its own integrity check.
+    pub fn allow_unprotected_variable_length(mut self) -> Self {
+        self.allow_unprotected_variable_length = true;
+        self
+    }
+
+    /// Checks the fields against each other and returns the parameters.
+    ///
+    /// # Errors
+    /// * `PacketParamsError::SyncWordTooLong` - The sync word is longer than 8 bytes
+    /// * `PacketParamsError::SyncWordNotByteMultiple` - The sync word length in bits is not a
+    ///   multiple of 8
+    /// * `PacketParamsError::PreambleDetectorTooLong` - The detector is longer than the preamble
+    /// * `PacketParamsError::PayloadTooLongForAddress` - 255 payload bytes with address filtering
+    /// * `PacketParamsError::MissingFixedPayloadLength` - Fixed-length packets without a
+    ///   payload length
+    /// * `PacketParamsError::EmptyFixedPayload` - Fixed-length packets of 0 bytes
+    /// * `PacketParamsError::UnprotectedVariableLength` - Variable-length packets without a
+    ///   CRC, unless allowed
+    pub fn build(self) -> Result<GFSKPacketParams, PacketParamsError> {
+        let mut params = self.params;
+
+        if !self.sync_word_bits.is_multiple_of(8) {
+            return Err(PacketParamsError::SyncWordNotByteMultiple {
+                bits: self.sync_word_bits as u8,
+            });
+        }
+        if self.sync_word_bits > 64 {
+            return Err(PacketParamsError::SyncWordTooLong {
+                bytes: (self.sync_word_bits / 8) as u8,
+            });
+        }
+        params.sync_word_length = self.sync_word_bits as u8;
+
+        let detector_bits = params.preamble_detector_length.bits();
+        if detector_bits > params.preamble.as_bits() {
+            return Err(PacketParamsError::PreambleDetectorTooLong {
+                detector_bits,
+                preamble_bits: params.preamble.as_bits(),
+            });
+        }
+        if params.address_filtering != AddressFiltering::Disable && params.payload_length == 0xFF {
+            return Err(PacketParamsError::PayloadTooLongForAddress {
+                payload_length: params.payload_length,
+            });
+        }
+        if params.packet_type == GFSKPacketHeaderType::Fixed && !self.payload_length_set {
+            return Err(PacketParamsError::MissingFixedPayloadLength);
+        }
+        if params.packet_type == GFSKPacketHeaderType::Fixed && params.payload_length == 0 {
+            return Err(PacketParamsError::EmptyFixedPayload);
+        }
+        if params.packet_type == GFSKPacketHeaderType::Variable
+            && params.crc_type == CrcType::CrcOff
+            && !self.allow_unprotected_variable_length
+        {
+            return Err(PacketParamsError::UnprotectedVariableLength);
+        }
+
+        Ok(params)
+    }
+}
+
+/// Builder for [`LoRaPacketParams`] checking the fields against each other
+///
+/// Created with [`LoRaPacketParams::builder`], starting from the [`Default`] parameters.
+///
+/// # Example
+/// ```
+/// use sx1262::{LoRaPacketParams, LoraPacketHeaderType, PacketParamsError};
+///
+/// let params = LoRaPacketParams::builder()
+///     .preamble_symbols(8)
+///     .explicit_header()
+///     .payload_length(32)
+///     .crc(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.header_type, LoraPacketHeaderType::Variable);
+///
+/// let params = LoRaPacketParams::builder().preamble_symbols(4).build();
+/// assert_eq!(params, Err(PacketParamsError::PreambleTooShort { preamble_length: 4 }));
+///
+/// // Without a header the receiver must know the length
+/// let params = LoRaPacketParams::builder().implicit_header().payload_length(0).build();
+/// assert_eq!(params, Err(PacketParamsError::EmptyFixedPayload));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoRaPacketParamsBuilder {
+    params: LoRaPacketParams,
+}
+
+impl LoRaPacketParams {
+    /// Creates a builder starting from the [`Default`] parameters.
+    pub fn builder() -> LoRaPacketParamsBuilder {
+        LoRaPacketParamsBuilder {
+            params: Self::default(),
+        }
+    }
+}
+
+impl LoRaPacketParamsBuilder {
+    /// Sets the preamble length in symbols, at least
+    /// [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS).
+    pub fn preamble_symbols(mut self, symbols: u16) -> Self {
+        self.params.preamble_length = symbols;
+        self
+    }
+
+    /// Sends the payload length, coding rate and header CRC in a header.
+    pub fn explicit_header(mut self) -> Self {
+        self.params.header_type = LoraPacketHeaderType::Variable;
+        self
+    }
+
+    /// Sends no header, the receiver must be configured with the same length.
+    pub fn implicit_header(mut self) -> Self {
+        self.params.header_type = LoraPacketHeaderType::Fixed;
+        self
+    }
+
+    /// Selects the header mode, setting the payload length of an implicit header.
+    ///
+    /// With [`LoRaHeaderMode::Explicit`] the payload length is kept, as the largest payload
+    /// accepted.
+    pub fn header_mode(mut self, mode: LoRaHeaderMode) -> Self {
+        match mode {
+            LoRaHeaderMode::Explicit => {
+                self.params.header_type = LoraPacketHeaderType::Variable;
+            }
+            LoRaHeaderMode::Implicit { payload_length } => {
+                self.params.header_type = LoraPacketHeaderType::Fixed;
+                self.params.payload_length = payload_length;
+            }
+        }
+        self
+    }
+
+    /// Sets the payload length, or the largest payload accepted with an explicit header.
+    pub fn payload_length(mut self, payload_length: u8) -> Self {
+        self.params.payload_length = payload_length;
+        self
+    }
+
+    /// Enables or disables the payload CRC.
+    pub fn crc(mut self, enable: bool) -> Self {
+        self.params.crc_enable = enable;
+        self
+    }
+
+    /// Enables or disables IQ inversion.
+    pub fn invert_iq(mut self, enable: bool) -> Self {
+        self.params.iq_inversion_enable = enable;
+        self
+    }
+
+    /// Checks the fields against each other and returns the parameters.
+    ///
+    /// # Errors
+    /// * `PacketParamsError::PreambleTooShort` - The preamble is shorter than
+    ///   [`MIN_PREAMBLE_SYMBOLS`](LoRaPacketParams::MIN_PREAMBLE_SYMBOLS)
+    /// * `PacketParamsError::EmptyFixedPayload` - Implicit header with a payload of 0 bytes
+    pub fn build(self) -> Result<LoRaPacketParams, PacketParamsError> {
+        let params = self.params;
+
+        if params.preamble_length < LoRaPacketParams::MIN_PREAMBLE_SYMBOLS {
+            return Err(PacketParamsError::PreambleTooShort {
+                preamble_length: params.preamble_length,
+            });
+        }
+        if params.header_type == LoraPacketHeaderType::Fixed && params.payload_length == 0 {
+            return Err(PacketParamsError::EmptyFixedPayload);
+        }
+
+        Ok(params)
+    }
+}
+
 /// SetPacketParams command (0x8C)
 ///
 /// Configures the packet parameters for the selected packet type.
@@ -738,7 +1952,7 @@ impl Command for SetPacketParams {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x8C
+        opcode::SET_PACKET_PARAMS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -746,9 +1960,78 @@ impl Command for SetPacketParams {
     }
 }
 
+/// Returns the packet type the serialized parameters of a command are for, when the bytes can
+/// only be for one.
+///
+/// GFSK modulation parameters end with a bandwidth, which is never zero, where LoRa ones end
+/// after the fourth byte. LoRa packet parameters end after the sixth byte and hold flags in the
+/// third, fifth and sixth ones, so anything else is GFSK. They are told apart from GFSK ones by
+/// an implicit header, where GFSK has a preamble detector length that is never 1, or by a
+/// payload length beyond the 64 bits of the longest GFSK sync word. Other packet parameters,
+/// such as GFSK ones without preamble detector, payload or whitening and with a 1 byte CRC,
+/// could be either and yield `None`.
+pub(crate) fn requested_packet_type(opcode: u8, request: &[u8]) -> Option<PacketType> {
+    if opcode == SetModulationParams::id() {
+        let lora = request.get(4..)?.iter().all(|&byte| byte == 0);
+        return Some(if lora {
+            PacketType::LoRa
+        } else {
+            PacketType::Gfsk
+        });
+    }
+    if opcode != SetPacketParams::id() {
+        return None;
+    }
+    let flags: [u8; 4] = request.get(2..6)?.try_into().ok()?;
+    let [header, length, crc, iq] = flags;
+    let tail = request.get(6..)?;
+    if tail.iter().any(|&byte| byte != 0) || [header, crc, iq].iter().any(|&byte| byte > 1) {
+        Some(PacketType::Gfsk)
+    } else if header == 1 || length > 64 {
+        Some(PacketType::LoRa)
+    } else {
+        None
+    }
+}
+
+/// Updates the configuration items known to be applied on the radio from a command sent to it.
+///
+/// SetRfFrequency, SetModulationParams and SetPacketParams apply their item. SetPacketType
+/// invalidates the modulation and packet parameters, and a cold-start SetSleep all of them.
+pub(crate) fn track_config_items(tracked: &mut ConfigItems, opcode: u8, request: &[u8]) {
+    if opcode == SetRfFrequency::id() {
+        *tracked |= ConfigItems::FREQUENCY;
+    } else if opcode == SetModulationParams::id() {
+        *tracked |= ConfigItems::MODULATION;
+    } else if opcode == SetPacketParams::id() {
+        *tracked |= ConfigItems::PACKET;
+    } else if opcode == SetPacketType::id() {
+        tracked.remove(ConfigItems::MODULATION | ConfigItems::PACKET);
+    } else if is_cold_sleep(opcode, request) {
+        *tracked = ConfigItems::empty();
+    }
+}
+
+/// Returns the packet type a command sets or reads back, from its serialized parameters and
+/// response.
+pub(crate) fn observed_packet_type(
+    opcode: u8,
+    request: &[u8],
+    response: &[u8],
+) -> Option<PacketType> {
+    let byte = if opcode == SetPacketType::id() {
+        request.first()
+    } else if opcode == GetPacketType::id() {
+        response.get(1)
+    } else {
+        None
+    };
+    byte.and_then(|&byte| PacketType::from_bytes([byte]).ok())
+}
+
 /// Channel Activity Detection (CAD) parameters
 /// LoRa mode only
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CadParams {
     /// Number of symbols for CAD detection (0=1, 1=2, 2=4, 3=8, 4=16)
     pub cad_symbol_num: u8,
@@ -762,18 +2045,36 @@ pub struct CadParams {
     pub cad_timeout: u32,
 }
 
+impl Default for CadParams {
+    /// Semtech's recommended CAD settings for SF7/125 kHz (2 symbols, peak 22, min 10) with
+    /// CAD-only exit. A sane starting point rather than a silicon reset value.
+    fn default() -> Self {
+        Self {
+            cad_symbol_num: 0x01,
+            cad_detect_peak: 22,
+            cad_detect_min: 10,
+            cad_exit_mode: 0x00,
+            cad_timeout: 0,
+        }
+    }
+}
+
 impl ToByteArray for CadParams {
     type Error = Infallible;
     type Array = [u8; 8];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        let mut bytes = [0u8; 8];
-        bytes[0] = self.cad_symbol_num;
-        bytes[1] = self.cad_detect_peak;
-        bytes[2] = self.cad_detect_min;
-        bytes[3] = self.cad_exit_mode;
-        bytes[4..8].copy_from_slice(&self.cad_timeout.to_be_bytes());
-        Ok(bytes)
+        let [timeout0, timeout1, timeout2, timeout3] = self.cad_timeout.to_be_bytes();
+        Ok([
+            self.cad_symbol_num,
+            self.cad_detect_peak,
+            self.cad_detect_min,
+            self.cad_exit_mode,
+            timeout0,
+            timeout1,
+            timeout2,
+            timeout3,
+        ])
     }
 }
 
@@ -798,7 +2099,7 @@ impl Command for SetCadParams {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x88
+        opcode::SET_CAD_PARAMS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -807,7 +2108,10 @@ impl Command for SetCadParams {
 }
 
 /// Buffer base addresses configuration
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] places both base addresses at 0x00, as the chip does at power-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BufferBaseAddressConfig {
     /// TX base address in data buffer (0-255)
     pub tx_base_addr: u8,
@@ -844,7 +2148,7 @@ impl Command for SetBufferBaseAddress {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x8F
+        opcode::SET_BUFFER_BASE_ADDRESS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -853,7 +2157,9 @@ impl Command for SetBufferBaseAddress {
 }
 
 /// LoRa symbol number timeout configuration
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Default`] of 0 mirrors the chip's reset behaviour of validating on the first symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct LoRaSymbNumTimeout {
     /// Number of symbols to validate reception
     /// 0 = Validate on first symbol
@@ -892,7 +2198,7 @@ impl Command for SetLoRaSymbNumTimeout {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0xA0
+        opcode::SET_LORA_SYMB_NUM_TIMEOUT
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
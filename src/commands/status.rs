@@ -14,6 +14,7 @@ use core::convert::Infallible;
 
 use regiface::FromByteArray;
 
+use crate::commands::opcode;
 use crate::{Command, NoParameters};
 
 /// Error type for invalid operating mode values
@@ -43,7 +44,8 @@ pub enum StatusError {
 ///
 /// Represents the current state of the radio's state machine.
 /// Extracted from status byte bits 6:4.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum OperatingMode {
     /// STDBY_RC mode: RC oscillator running
     StandbyRc = 0x2,
@@ -76,8 +78,12 @@ impl TryFrom<u8> for OperatingMode {
 ///
 /// Indicates the result of the last command execution.
 /// Extracted from status byte bits 3:1.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum CommandStatus {
+    /// Reserved or RFU value (0x0, 0x1 or 0x7), nothing to report
+    /// The radio reports it after a reset, see [`Status::raw`] for the exact value
+    Reserved = 0x0,
     /// Data is available to be read from the radio
     DataAvailable = 0x2,
     /// Command timed out during execution
@@ -95,6 +101,7 @@ impl TryFrom<u8> for CommandStatus {
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
+            0x0 | 0x1 | 0x7 => Ok(Self::Reserved),
             0x2 => Ok(Self::DataAvailable),
             0x3 => Ok(Self::Timeout),
             0x4 => Ok(Self::ProcessingError),
@@ -121,6 +128,54 @@ pub struct Status {
     pub mode: OperatingMode,
     /// Status of the last command execution
     pub cmd_status: CommandStatus,
+    raw: u8,
+}
+
+impl Status {
+    /// Returns the status byte as read from the radio, reserved bits included.
+    pub fn raw(&self) -> u8 {
+        self.raw
+    }
+
+    /// Returns whether the status suggests the radio reset behind the driver's back, e.g.
+    /// after a brown-out.
+    ///
+    /// A radio coming out of reset is in STDBY_RC with a reserved command status. Seen while
+    /// the radio should be in another mode, this is a strong hint of an unexpected reset. Only
+    /// use it with modes the radio stays in until the next command: TX and RX end on their
+    /// own, possibly in STDBY_RC.
+    ///
+    /// # Arguments
+    /// * `expected_mode` - The mode the radio was put in
+    ///
+    /// # Example
+    /// ```
+    /// use regiface::FromByteArray;
+    /// use sx1262::{CommandStatus, OperatingMode, Status};
+    ///
+    /// let status = |byte| Status::from_bytes([byte]).unwrap();
+    ///
+    /// // Normal operation
+    /// assert!(!status(0x24).looks_like_unexpected_reset(OperatingMode::StandbyRc));
+    /// assert!(!status(0x32).looks_like_unexpected_reset(OperatingMode::StandbyXosc));
+    /// assert!(!status(0x42).looks_like_unexpected_reset(OperatingMode::FrequencySynthesizer));
+    /// assert!(!status(0x22).looks_like_unexpected_reset(OperatingMode::StandbyRc));
+    /// // A failed command does not move the radio to STDBY_RC
+    /// assert!(!status(0x3A).looks_like_unexpected_reset(OperatingMode::StandbyXosc));
+    ///
+    /// // Back in STDBY_RC with nothing to report
+    /// let reset = status(0x22);
+    /// assert_eq!(reset.raw(), 0x22);
+    /// assert_eq!(reset.cmd_status, CommandStatus::Reserved);
+    /// assert!(reset.looks_like_unexpected_reset(OperatingMode::StandbyXosc));
+    /// assert!(status(0x20).looks_like_unexpected_reset(OperatingMode::FrequencySynthesizer));
+    /// assert!(status(0xAF).looks_like_unexpected_reset(OperatingMode::Transmit));
+    /// ```
+    pub fn looks_like_unexpected_reset(&self, expected_mode: OperatingMode) -> bool {
+        expected_mode != OperatingMode::StandbyRc
+            && self.mode == OperatingMode::StandbyRc
+            && self.cmd_status == CommandStatus::Reserved
+    }
 }
 
 impl FromByteArray for Status {
@@ -134,6 +189,7 @@ impl FromByteArray for Status {
         Ok(Self {
             mode: OperatingMode::try_from(mode).map_err(StatusError::InvalidMode)?,
             cmd_status: CommandStatus::try_from(cmd).map_err(StatusError::InvalidCommandStatus)?,
+            raw: bytes[0],
         })
     }
 }
@@ -156,7 +212,7 @@ impl Command for GetStatus {
     type ResponseParameters = Status;
 
     fn id() -> Self::IdType {
-        0xC0
+        opcode::GET_STATUS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -176,13 +232,23 @@ pub struct GetRssiInstResponse {
     pub rssi: u8,
 }
 
+impl GetRssiInstResponse {
+    /// Returns the instantaneous RSSI in dBm, corrected by an offset.
+    ///
+    /// # Arguments
+    /// * `offset_db` - Correction added to the converted value, 0 for none
+    pub fn rssi_dbm(&self, offset_db: i8) -> f32 {
+        -f32::from(self.rssi) / 2.0 + f32::from(offset_db)
+    }
+}
+
 impl FromByteArray for GetRssiInstResponse {
-    type Error = Infallible;
+    type Error = StatusError;
     type Array = [u8; 2]; // 1 status byte + 1 RSSI byte
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
         Ok(Self {
-            status: Status::from_bytes([bytes[0]]).unwrap(),
+            status: Status::from_bytes([bytes[0]])?,
             rssi: bytes[1],
         })
     }
@@ -208,7 +274,7 @@ impl Command for GetRssiInst {
     type ResponseParameters = GetRssiInstResponse;
 
     fn id() -> Self::IdType {
-        0x15
+        opcode::GET_RSSI_INST
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -253,13 +319,15 @@ pub struct GetRxBufferStatusResponse {
 }
 
 impl FromByteArray for GetRxBufferStatusResponse {
-    type Error = Infallible;
+    type Error = StatusError;
     type Array = [u8; 3]; // 1 status byte + 2 buffer bytes
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [status, buffer0, buffer1] = bytes;
+        let Ok(buffer_status) = RxBufferStatus::from_bytes([buffer0, buffer1]);
         Ok(Self {
-            status: Status::from_bytes([bytes[0]]).unwrap(),
-            buffer_status: RxBufferStatus::from_bytes([bytes[1], bytes[2]]).unwrap(),
+            status: Status::from_bytes([status])?,
+            buffer_status,
         })
     }
 }
@@ -282,7 +350,7 @@ impl Command for GetRxBufferStatus {
     type ResponseParameters = GetRxBufferStatusResponse;
 
     fn id() -> Self::IdType {
-        0x13
+        opcode::GET_RX_BUFFER_STATUS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -326,6 +394,70 @@ impl FromByteArray for PacketStatus {
     }
 }
 
+impl PacketStatus {
+    /// Decodes the status of a GFSK packet.
+    pub fn gfsk(&self) -> GfskPacketStatus {
+        self.gfsk_with_offset(0)
+    }
+
+    /// Decodes the status of a GFSK packet, adding an offset to both RSSI values.
+    ///
+    /// # Arguments
+    /// * `offset_db` - Correction added to the converted RSSI values, 0 for none
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::PacketStatus;
+    ///
+    /// let status = PacketStatus { status: [0x02, 0xA0, 0xA4] };
+    /// assert_eq!(status.gfsk().rssi_sync, -80.0);
+    /// assert_eq!(status.gfsk_with_offset(3).rssi_sync, -77.0);
+    /// assert_eq!(status.gfsk_with_offset(3).rssi_avg, -79.0);
+    /// ```
+    pub fn gfsk_with_offset(&self, offset_db: i8) -> GfskPacketStatus {
+        let offset = f32::from(offset_db);
+        GfskPacketStatus {
+            rx_status: GfskRxStatus::from_bits_retain(self.status[0]),
+            rssi_sync: -f32::from(self.status[1]) / 2.0 + offset,
+            rssi_avg: -f32::from(self.status[2]) / 2.0 + offset,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// RxStatus byte of a GFSK packet status
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct GfskRxStatus: u8 {
+        /// A packet was sent
+        const PACKET_SENT = 1 << 0;
+        /// A packet was received
+        const PACKET_RECEIVED = 1 << 1;
+        /// The reception was aborted
+        const ABORT_ERROR = 1 << 2;
+        /// The received length exceeded the maximum payload length
+        const LENGTH_ERROR = 1 << 3;
+        /// The payload CRC check failed
+        const CRC_ERROR = 1 << 4;
+        /// The address did not pass address filtering
+        const ADDRESS_ERROR = 1 << 5;
+        /// No sync word was detected
+        const SYNC_ERROR = 1 << 6;
+        /// No preamble was detected
+        const PREAMBLE_ERROR = 1 << 7;
+    }
+}
+
+/// Decoded status of a received GFSK packet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GfskPacketStatus {
+    /// Reception flags
+    pub rx_status: GfskRxStatus,
+    /// RSSI latched when the sync word was detected, in dBm
+    pub rssi_sync: f32,
+    /// RSSI averaged over the payload, in dBm
+    pub rssi_avg: f32,
+}
+
 /// GetPacketStatus response
 ///
 /// Contains the device status and packet status information.
@@ -338,13 +470,15 @@ pub struct GetPacketStatusResponse {
 }
 
 impl FromByteArray for GetPacketStatusResponse {
-    type Error = Infallible;
+    type Error = StatusError;
     type Array = [u8; 4]; // 1 status byte + 3 packet status bytes
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [status, packet_status @ ..] = bytes;
+        let Ok(packet_status) = PacketStatus::from_bytes(packet_status);
         Ok(Self {
-            status: Status::from_bytes([bytes[0]]).unwrap(),
-            packet_status: PacketStatus::from_bytes([bytes[1], bytes[2], bytes[3]]).unwrap(),
+            status: Status::from_bytes([status])?,
+            packet_status,
         })
     }
 }
@@ -368,7 +502,7 @@ impl Command for GetPacketStatus {
     type ResponseParameters = GetPacketStatusResponse;
 
     fn id() -> Self::IdType {
-        0x14
+        opcode::GET_PACKET_STATUS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -430,13 +564,15 @@ pub struct GetDeviceErrorsResponse {
 }
 
 impl FromByteArray for GetDeviceErrorsResponse {
-    type Error = Infallible;
+    type Error = StatusError;
     type Array = [u8; 3]; // 1 status byte + 2 error bytes
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [status, errors @ ..] = bytes;
+        let Ok(errors) = DeviceErrors::from_bytes(errors);
         Ok(Self {
-            status: Status::from_bytes([bytes[0]]).unwrap(),
-            errors: DeviceErrors::from_bytes([bytes[1], bytes[2]]).unwrap(),
+            status: Status::from_bytes([status])?,
+            errors,
         })
     }
 }
@@ -459,7 +595,7 @@ impl Command for GetDeviceErrors {
     type ResponseParameters = GetDeviceErrorsResponse;
 
     fn id() -> Self::IdType {
-        0x17
+        opcode::GET_DEVICE_ERRORS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -507,7 +643,7 @@ impl Command for ClearDeviceErrors {
     type ResponseParameters = ClearDeviceErrorsResponse;
 
     fn id() -> Self::IdType {
-        0x07
+        opcode::CLEAR_DEVICE_ERRORS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -518,7 +654,11 @@ impl Command for ClearDeviceErrors {
 /// Statistics response
 ///
 /// Contains packet reception statistics.
-#[derive(Debug, Clone, Copy)]
+///
+/// The header error counter has a different meaning per packet type, see
+/// [`Device::stats`](crate::Device::stats) for counters tagged with the packet type they were
+/// accumulated under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct Stats {
     /// Number of packets received
     /// Increments for all received packets
@@ -534,15 +674,55 @@ pub struct Stats {
     pub packets_header_error: u16,
 }
 
+impl Stats {
+    /// Number of counter bytes in the GetStats response, as documented in the datasheet
+    pub const SIZE: usize = 6;
+
+    /// Returns the share of received packets with a CRC error, in thousandths.
+    ///
+    /// Returns 0 when no packet has been received.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::Stats;
+    ///
+    /// let stats = Stats {
+    ///     packets_received: 400,
+    ///     packets_crc_error: 3,
+    ///     packets_header_error: 0,
+    /// };
+    /// assert_eq!(stats.crc_error_ratio_permille(), 7);
+    /// ```
+    pub fn crc_error_ratio_permille(&self) -> u16 {
+        ratio_permille(self.packets_crc_error, self.packets_received)
+    }
+
+    /// Returns the share of received packets with a header error, in thousandths.
+    ///
+    /// Returns 0 when no packet has been received.
+    pub fn header_error_ratio_permille(&self) -> u16 {
+        ratio_permille(self.packets_header_error, self.packets_received)
+    }
+}
+
+/// Returns `count / total` in thousandths, 0 when `total` is 0 and at most 1000.
+fn ratio_permille(count: u16, total: u16) -> u16 {
+    if total == 0 {
+        return 0;
+    }
+    (u32::from(count) * 1000 / u32::from(total)).min(1000) as u16
+}
+
 impl FromByteArray for Stats {
     type Error = Infallible;
     type Array = [u8; 6];
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [received0, received1, crc0, crc1, header0, header1] = bytes;
         Ok(Self {
-            packets_received: u16::from_be_bytes(bytes[0..2].try_into().unwrap()),
-            packets_crc_error: u16::from_be_bytes(bytes[2..4].try_into().unwrap()),
-            packets_header_error: u16::from_be_bytes(bytes[4..6].try_into().unwrap()),
+            packets_received: u16::from_be_bytes([received0, received1]),
+            packets_crc_error: u16::from_be_bytes([crc0, crc1]),
+            packets_header_error: u16::from_be_bytes([header0, header1]),
         })
     }
 }
@@ -558,15 +738,62 @@ pub struct GetStatsResponse {
     pub stats: Stats,
 }
 
+impl GetStatsResponse {
+    /// Number of bytes in the GetStats response, as documented in the datasheet
+    pub const SIZE: usize = 1 + Stats::SIZE;
+
+    /// Parses a GetStats response that may be longer than documented.
+    ///
+    /// Only the documented bytes are decoded, so the counters stay aligned whatever follows
+    /// them. Bytes beyond [`SIZE`](GetStatsResponse::SIZE) are returned untouched.
+    ///
+    /// # Arguments
+    /// * `bytes` - The response, status byte first
+    ///
+    /// # Returns
+    /// The decoded response and the trailing bytes, or `None` when `bytes` is shorter than
+    /// [`SIZE`](GetStatsResponse::SIZE)
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::GetStatsResponse;
+    ///
+    /// // Status, 3 counters and 2 reserved trailing bytes
+    /// let bytes = [0x24, 0x01, 0x2C, 0x00, 0x05, 0x00, 0x02, 0xAA, 0x55];
+    /// let (response, trailing) = GetStatsResponse::from_extended_bytes(&bytes).unwrap();
+    /// assert_eq!(response.stats.packets_received, 300);
+    /// assert_eq!(response.stats.packets_crc_error, 5);
+    /// assert_eq!(response.stats.packets_header_error, 2);
+    /// assert_eq!(trailing, [0xAA, 0x55]);
+    ///
+    /// assert!(GetStatsResponse::from_extended_bytes(&bytes[..6]).is_none());
+    /// ```
+    pub fn from_extended_bytes(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        if bytes.len() < Self::SIZE {
+            return None;
+        }
+        let (documented, trailing) = bytes.split_at(Self::SIZE);
+        let response = Self::from_bytes(documented.try_into().ok()?).ok()?;
+        Some((response, trailing))
+    }
+}
+
+// The response arrays must match the documented layout of GetStats
+const _: () = assert!(core::mem::size_of::<<Stats as FromByteArray>::Array>() == Stats::SIZE);
+const _: () = assert!(
+    core::mem::size_of::<<GetStatsResponse as FromByteArray>::Array>() == GetStatsResponse::SIZE
+);
+
 impl FromByteArray for GetStatsResponse {
-    type Error = Infallible;
+    type Error = StatusError;
     type Array = [u8; 7]; // 1 status byte + 6 stats bytes
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
+        let [status, stats @ ..] = bytes;
+        let Ok(stats) = Stats::from_bytes(stats);
         Ok(Self {
-            status: Status::from_bytes([bytes[0]]).unwrap(),
-            stats: Stats::from_bytes([bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6]])
-                .unwrap(),
+            status: Status::from_bytes([status])?,
+            stats,
         })
     }
 }
@@ -589,7 +816,7 @@ impl Command for GetStats {
     type ResponseParameters = GetStatsResponse;
 
     fn id() -> Self::IdType {
-        0x10
+        opcode::GET_STATS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
@@ -614,7 +841,7 @@ impl Command for ResetStats {
     type ResponseParameters = NoParameters;
 
     fn id() -> Self::IdType {
-        0x00
+        opcode::RESET_STATS
     }
 
     fn invoking_parameters(self) -> Self::CommandParameters {
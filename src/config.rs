@@ -0,0 +1,680 @@
+//! Complete radio configuration
+//!
+//! This module contains [`RadioConfig`], a plain description of everything the driver needs to
+//! bring the radio from STDBY_RC to a state where it can transmit and receive. It is consumed by
+//! [`Device::apply_config`](crate::Device::apply_config), which issues the commands in the order
+//! required by the datasheet:
+//!
+//! 1. Standby (STDBY_RC), regulator mode and image calibration
+//! 2. Packet type
+//! 3. RF frequency
+//! 4. PA configuration and TX parameters
+//! 5. Modulation parameters
+//! 6. Packet parameters
+//! 7. Buffer base addresses and fallback mode
+//! 8. DIO and IRQ mapping
+//! 9. RX gain, sync words and any errata workarounds the configuration requires
+//!
+//! A [`RadioConfig`] is a plain value: the one last applied can be read back with
+//! [`Device::snapshot_config`](crate::Device::snapshot_config), stashed (e.g. in backup RAM, or
+//! serialized with the `serde` feature), and later re-applied to a freshly reset radio.
+//! [`Device::apply_config_diff`](crate::Device::apply_config_diff) applies a configuration over
+//! the one last applied, sending only the [`ConfigChanges`], e.g. when hopping.
+//!
+//! [`RadioConfigBuilder`] builds a [`RadioConfig`] from sane defaults and checks it for
+//! consistency, including against the capabilities of the device when it is known.
+//!
+//! [`LoRaInit`] and [`GfskInit`] describe a complete bring-up from power-on, including the
+//! TCXO and PA preset selection, for [`Device::init_lora`](crate::Device::init_lora) and
+//! [`Device::init_gfsk`](crate::Device::init_gfsk).
+
+use crate::{
+    BufferBaseAddressConfig, DeviceSelect, DioIrqConfig, FallbackMode, FrequencyOutOfRange,
+    GFSKPacketHeaderType, GFSKPacketParams, GfskBandwidth, GfskModParams, GfskPulseShape,
+    InvalidDeviation, LoRaModParams, LoRaPacketParams, LoraPacketHeaderType, LoraSyncWord,
+    ModulationParams, PaConfig, PacketParams, PacketType, PreambleTooShort, RampTime,
+    RegulatorMode, RfFrequencyConfig, RxGain, SyncWord, TcxoConfig, TxParams, TxPowerPlan,
+    UnsupportedModulation,
+};
+
+bitflags::bitflags! {
+    /// Configuration items the radio needs before it can transmit or receive
+    ///
+    /// The [`Device`](crate::Device) tracks the items applied since the last reset or
+    /// cold-start sleep, see [`Device::assume_configured`](crate::Device::assume_configured).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ConfigItems: u8 {
+        /// RF frequency, set with SetRfFrequency
+        const FREQUENCY = 1 << 0;
+        /// Modulation parameters, set with SetModulationParams
+        const MODULATION = 1 << 1;
+        /// Packet parameters, set with SetPacketParams
+        const PACKET = 1 << 2;
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConfigItems {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "ConfigItems({=u8:#x})", self.bits())
+    }
+}
+
+bitflags::bitflags! {
+    /// Parts of a [`RadioConfig`] sent to the radio
+    ///
+    /// Returned by [`Device::apply_config_diff`](crate::Device::apply_config_diff). Each flag
+    /// stands for the command or register writes of the corresponding step of
+    /// [`Device::apply_config`](crate::Device::apply_config).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ConfigChanges: u16 {
+        /// SetStandby, SetPacketType and the packet type readback, only sent by a full apply
+        const PACKET_TYPE = 1 << 0;
+        /// SetRegulatorMode
+        const REGULATOR_MODE = 1 << 1;
+        /// CalibrateImage
+        const IMAGE_CALIBRATION = 1 << 2;
+        /// SetRfFrequency
+        const FREQUENCY = 1 << 3;
+        /// SetPaConfig
+        const PA_CONFIG = 1 << 4;
+        /// SetTxParams
+        const TX_PARAMS = 1 << 5;
+        /// SetModulationParams
+        const MODULATION = 1 << 6;
+        /// SetPacketParams
+        const PACKET = 1 << 7;
+        /// SetBufferBaseAddress
+        const BUFFER_BASE = 1 << 8;
+        /// SetRxTxFallbackMode
+        const FALLBACK_MODE = 1 << 9;
+        /// SetDioIrqParams
+        const IRQ = 1 << 10;
+        /// RX gain register
+        const RX_GAIN = 1 << 11;
+        /// LoRa sync word register
+        const LORA_SYNC_WORD = 1 << 12;
+        /// GFSK sync word register
+        const GFSK_SYNC_WORD = 1 << 13;
+        /// Errata workarounds
+        const WORKAROUNDS = 1 << 14;
+        /// SetDio2AsRfSwitchCtrl, and SetDio3AsTcxoCtrl with the calibration following it
+        const DIO_CONTROL = 1 << 15;
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConfigChanges {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "ConfigChanges({=u16:#x})", self.bits())
+    }
+}
+
+/// Error type for an inconsistent [`RadioConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The frequency is outside the range supported by the device
+    FrequencyOutOfRange(FrequencyOutOfRange),
+    /// The device cannot demodulate the modulation parameters
+    UnsupportedModulation(UnsupportedModulation),
+    /// The packet parameters do not match the packet type of the modulation parameters
+    PacketTypeMismatch,
+    /// The output power in dBm is outside the range of the device
+    UnsupportedOutputPower(i8),
+    /// The LoRa preamble is too short to be received reliably
+    PreambleTooShort(PreambleTooShort),
+    /// The GFSK frequency deviation cannot be encoded or demodulated at the bit rate
+    InvalidDeviation(InvalidDeviation),
+    /// Fixed-length GFSK packets of 0 bytes, the receiver would capture nothing
+    EmptyFixedPayload,
+}
+
+impl From<InvalidDeviation> for ConfigError {
+    fn from(err: InvalidDeviation) -> Self {
+        Self::InvalidDeviation(err)
+    }
+}
+
+impl From<PreambleTooShort> for ConfigError {
+    fn from(err: PreambleTooShort) -> Self {
+        Self::PreambleTooShort(err)
+    }
+}
+
+impl From<FrequencyOutOfRange> for ConfigError {
+    fn from(err: FrequencyOutOfRange) -> Self {
+        Self::FrequencyOutOfRange(err)
+    }
+}
+
+impl From<UnsupportedModulation> for ConfigError {
+    fn from(err: UnsupportedModulation) -> Self {
+        Self::UnsupportedModulation(err)
+    }
+}
+
+/// Complete radio configuration
+///
+/// Describes the full set of parameters applied by
+/// [`Device::apply_config`](crate::Device::apply_config).
+///
+/// # Important Notes
+/// - The packet type is derived from the [`ModulationParams`] variant
+/// - [`PacketParams`] must use the same variant as the modulation parameters
+/// - The frequency has no sensible default, so this type has no [`Default`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RadioConfig {
+    /// Voltage regulator selection
+    pub regulator_mode: RegulatorMode,
+    /// DIO2 drives the RF switch, high in TX, see
+    /// [`SetDio2AsRfSwitchCtrl`](crate::SetDio2AsRfSwitchCtrl)
+    pub dio2_rf_switch: bool,
+    /// TCXO supplied through DIO3, `None` for a crystal
+    pub tcxo: Option<TcxoConfig>,
+    /// RF frequency for both TX and RX
+    pub frequency: RfFrequencyConfig,
+    /// Modulation parameters, which also select the packet type
+    pub modulation: ModulationParams,
+    /// Packet format parameters
+    pub packet: PacketParams,
+    /// Power amplifier configuration
+    pub pa_config: PaConfig,
+    /// TX output power and ramp time
+    pub tx_params: TxParams,
+    /// TX and RX base addresses in the data buffer
+    pub buffer_base: BufferBaseAddressConfig,
+    /// Mode entered after TX or RX completes
+    pub fallback_mode: FallbackMode,
+    /// IRQ enable and DIO mapping
+    pub irq: DioIrqConfig,
+    /// Receiver gain
+    pub rx_gain: RxGain,
+    /// LoRa sync word, left unchanged when `None`
+    pub lora_sync_word: Option<LoraSyncWord>,
+    /// GFSK sync word, left unchanged when `None`
+    pub gfsk_sync_word: Option<SyncWord>,
+    /// Correction added to every RSSI converted to dBm by the driver, see
+    /// [`Device::set_rssi_offset_db`](crate::Device::set_rssi_offset_db)
+    pub rssi_offset_db: i8,
+}
+
+impl RadioConfig {
+    /// Creates a builder for a configuration on the given frequency.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency for both TX and RX
+    pub fn builder(frequency: RfFrequencyConfig) -> RadioConfigBuilder {
+        RadioConfigBuilder::new(frequency)
+    }
+
+    /// Returns the packet type selected by the modulation parameters.
+    pub fn packet_type(&self) -> PacketType {
+        self.modulation.packet_type()
+    }
+
+    /// Returns the parts that differ from a previous configuration, as sent by
+    /// [`Device::apply_config_diff`](crate::Device::apply_config_diff).
+    ///
+    /// A sync word set to `None` leaves the register unchanged, so it is never a change. The
+    /// TX parameters are included whenever the PA configuration changes, as SetTxParams must
+    /// follow SetPaConfig. The packet type, image calibration and workarounds depend on the
+    /// state of the radio and are never included.
+    ///
+    /// # Arguments
+    /// * `previous` - The configuration on the radio
+    pub fn changes(&self, previous: &RadioConfig) -> ConfigChanges {
+        let mut changes = ConfigChanges::empty();
+        let fields = [
+            (
+                self.regulator_mode != previous.regulator_mode,
+                ConfigChanges::REGULATOR_MODE,
+            ),
+            (
+                self.dio2_rf_switch != previous.dio2_rf_switch || self.tcxo != previous.tcxo,
+                ConfigChanges::DIO_CONTROL,
+            ),
+            (
+                self.frequency != previous.frequency,
+                ConfigChanges::FREQUENCY,
+            ),
+            (
+                self.pa_config != previous.pa_config,
+                ConfigChanges::PA_CONFIG | ConfigChanges::TX_PARAMS,
+            ),
+            (
+                self.tx_params != previous.tx_params,
+                ConfigChanges::TX_PARAMS,
+            ),
+            (
+                self.modulation != previous.modulation,
+                ConfigChanges::MODULATION,
+            ),
+            (self.packet != previous.packet, ConfigChanges::PACKET),
+            (
+                self.buffer_base != previous.buffer_base,
+                ConfigChanges::BUFFER_BASE,
+            ),
+            (
+                self.fallback_mode != previous.fallback_mode,
+                ConfigChanges::FALLBACK_MODE,
+            ),
+            (self.irq != previous.irq, ConfigChanges::IRQ),
+            (self.rx_gain != previous.rx_gain, ConfigChanges::RX_GAIN),
+            (
+                self.lora_sync_word.is_some() && self.lora_sync_word != previous.lora_sync_word,
+                ConfigChanges::LORA_SYNC_WORD,
+            ),
+            (
+                self.gfsk_sync_word.is_some() && self.gfsk_sync_word != previous.gfsk_sync_word,
+                ConfigChanges::GFSK_SYNC_WORD,
+            ),
+        ];
+        for (changed, flags) in fields {
+            if changed {
+                changes |= flags;
+            }
+        }
+        changes
+    }
+
+    /// Returns the payload length of every packet when the packet format is fixed-length.
+    ///
+    /// Returns `None` for variable-length packets, whose length is carried in the packet.
+    pub fn fixed_payload_length(&self) -> Option<u8> {
+        match self.packet {
+            PacketParams::LoRa(params) if params.header_type == LoraPacketHeaderType::Fixed => {
+                Some(params.payload_length)
+            }
+            PacketParams::GFSK(params) if params.packet_type == GFSKPacketHeaderType::Fixed => {
+                Some(params.payload_length)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Builder for a [`RadioConfig`]
+///
+/// Every field except the frequency starts from its [`Default`], with LoRa modulation and
+/// packet parameters. [`build`](RadioConfigBuilder::build) checks that the packet parameters
+/// match the modulation and, when a device has been set with
+/// [`device`](RadioConfigBuilder::device), that the device supports the frequency and
+/// modulation.
+///
+/// # Example
+/// ```
+/// use sx1262::{DeviceSelect, RadioConfig, RfFrequencyConfig};
+///
+/// let config = RadioConfig::builder(RfFrequencyConfig { frequency: 868_100_000 })
+///     .device(DeviceSelect::Llcc68)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RadioConfigBuilder {
+    config: RadioConfig,
+    device: Option<DeviceSelect>,
+}
+
+impl RadioConfigBuilder {
+    /// Creates a builder for a configuration on the given frequency.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency for both TX and RX
+    pub fn new(frequency: RfFrequencyConfig) -> Self {
+        Self {
+            config: RadioConfig {
+                regulator_mode: RegulatorMode::default(),
+                dio2_rf_switch: false,
+                tcxo: None,
+                frequency,
+                modulation: ModulationParams::LoRa(LoRaModParams::default()),
+                packet: PacketParams::LoRa(LoRaPacketParams::default()),
+                pa_config: PaConfig::default(),
+                tx_params: TxParams::default(),
+                buffer_base: BufferBaseAddressConfig::default(),
+                fallback_mode: FallbackMode::default(),
+                irq: DioIrqConfig::default(),
+                rx_gain: RxGain::default(),
+                lora_sync_word: None,
+                gfsk_sync_word: None,
+                rssi_offset_db: 0,
+            },
+            device: None,
+        }
+    }
+
+    /// Sets the device the configuration is for, enabling capability checks.
+    ///
+    /// This does not change the PA configuration, see [`TxPowerPlan`](crate::TxPowerPlan) for
+    /// device-specific PA settings.
+    pub fn device(mut self, device: DeviceSelect) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    /// Sets the voltage regulator selection, LDO only by default.
+    ///
+    /// Only select [`RegulatorMode::DcDcLdo`] on boards fitted with the 15 µH inductor
+    /// between DCC_SW and VREG. Without it the radio keeps answering commands while its RF
+    /// performance collapses, which is easily mistaken for a software issue.
+    pub fn regulator_mode(mut self, regulator_mode: RegulatorMode) -> Self {
+        self.config.regulator_mode = regulator_mode;
+        self
+    }
+
+    /// Sets whether DIO2 drives the RF switch, disabled by default.
+    ///
+    /// Boards whose antenna switch is wired to DIO2 transmit nothing without it.
+    pub fn dio2_rf_switch(mut self, enabled: bool) -> Self {
+        self.config.dio2_rf_switch = enabled;
+        self
+    }
+
+    /// Sets the TCXO supplied through DIO3, a crystal by default.
+    pub fn tcxo(mut self, tcxo: Option<TcxoConfig>) -> Self {
+        self.config.tcxo = tcxo;
+        self
+    }
+
+    /// Sets the modulation parameters, which also select the packet type.
+    pub fn modulation(mut self, modulation: ModulationParams) -> Self {
+        self.config.modulation = modulation;
+        self
+    }
+
+    /// Sets the packet format parameters.
+    pub fn packet(mut self, packet: PacketParams) -> Self {
+        self.config.packet = packet;
+        self
+    }
+
+    /// Sets the power amplifier configuration.
+    pub fn pa_config(mut self, pa_config: PaConfig) -> Self {
+        self.config.pa_config = pa_config;
+        self
+    }
+
+    /// Sets the TX output power and ramp time.
+    pub fn tx_params(mut self, tx_params: TxParams) -> Self {
+        self.config.tx_params = tx_params;
+        self
+    }
+
+    /// Sets the TX and RX base addresses in the data buffer.
+    pub fn buffer_base(mut self, buffer_base: BufferBaseAddressConfig) -> Self {
+        self.config.buffer_base = buffer_base;
+        self
+    }
+
+    /// Sets the mode entered after TX or RX completes.
+    pub fn fallback_mode(mut self, fallback_mode: FallbackMode) -> Self {
+        self.config.fallback_mode = fallback_mode;
+        self
+    }
+
+    /// Sets the IRQ enable and DIO mapping.
+    pub fn irq(mut self, irq: DioIrqConfig) -> Self {
+        self.config.irq = irq;
+        self
+    }
+
+    /// Sets the receiver gain.
+    pub fn rx_gain(mut self, rx_gain: RxGain) -> Self {
+        self.config.rx_gain = rx_gain;
+        self
+    }
+
+    /// Sets the LoRa sync word.
+    pub fn lora_sync_word(mut self, sync_word: LoraSyncWord) -> Self {
+        self.config.lora_sync_word = Some(sync_word);
+        self
+    }
+
+    /// Sets the GFSK sync word.
+    pub fn gfsk_sync_word(mut self, sync_word: SyncWord) -> Self {
+        self.config.gfsk_sync_word = Some(sync_word);
+        self
+    }
+
+    /// Sets the correction added to every RSSI converted to dBm by the driver.
+    pub fn rssi_offset_db(mut self, offset_db: i8) -> Self {
+        self.config.rssi_offset_db = offset_db;
+        self
+    }
+
+    /// Checks the configuration for consistency and returns it.
+    ///
+    /// # Errors
+    /// * `ConfigError::PacketTypeMismatch` - The packet parameters do not match the modulation
+    /// * `ConfigError::PreambleTooShort` - The LoRa preamble is too short for reliable reception
+    /// * `ConfigError::InvalidDeviation` - The GFSK deviation is 0, too large for the register
+    ///   or outside modulation index 0.5 to 4.0
+    /// * `ConfigError::EmptyFixedPayload` - Fixed-length GFSK packets have a length of 0
+    /// * `ConfigError::FrequencyOutOfRange` - The device does not support the frequency
+    /// * `ConfigError::UnsupportedModulation` - The device cannot demodulate the modulation
+    pub fn build(self) -> Result<RadioConfig, ConfigError> {
+        let config = self.config;
+
+        if config.packet.packet_type() != config.packet_type() {
+            return Err(ConfigError::PacketTypeMismatch);
+        }
+        if let (ModulationParams::LoRa(modulation), PacketParams::LoRa(packet)) =
+            (config.modulation, config.packet)
+        {
+            packet.validate(modulation.spreading_factor)?;
+        }
+        if let ModulationParams::Gfsk(modulation) = config.modulation {
+            modulation.validate()?;
+        }
+        if let PacketParams::GFSK(packet) = config.packet {
+            if packet.packet_type == GFSKPacketHeaderType::Fixed && packet.payload_length == 0 {
+                return Err(ConfigError::EmptyFixedPayload);
+            }
+        }
+
+        if let Some(device) = self.device {
+            config.frequency.validate(device)?;
+            config.modulation.validate(device)?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Bring-up parameters for LoRa operation
+///
+/// Every field except the frequency starts from a sensible default: an SX1262 at +14 dBm with
+/// a 40 µs ramp, the LDO regulator, no TCXO, and the [`Default`] LoRa modulation, packet and
+/// IRQ parameters. Consumed by [`Device::init_lora`](crate::Device::init_lora).
+///
+/// # Example
+/// ```
+/// use sx1262::{config::LoRaInit, RfFrequencyConfig, TcxoConfig, TcxoVoltage};
+///
+/// let mut init = LoRaInit::new(RfFrequencyConfig { frequency: 868_100_000 });
+/// init.output_power = 22;
+/// init.tcxo = Some(TcxoConfig { voltage: TcxoVoltage::V1_8, delay: 320 });
+///
+/// let config = init.config().unwrap();
+/// assert_eq!(config.tx_params.power, 22);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoRaInit {
+    /// RF frequency for both TX and RX
+    pub frequency: RfFrequencyConfig,
+    /// The device being brought up, selecting the PA configuration
+    pub device: DeviceSelect,
+    /// Output power at the antenna in dBm, resolved by [`TxPowerPlan::resolve`]
+    pub output_power: i8,
+    /// PA ramp time
+    pub ramp_time: RampTime,
+    /// Voltage regulator selection
+    pub regulator_mode: RegulatorMode,
+    /// TCXO supplied through DIO3, `None` for a crystal
+    pub tcxo: Option<TcxoConfig>,
+    /// DIO2 drives the RF switch
+    pub dio2_rf_switch: bool,
+    /// LoRa modulation parameters
+    pub modulation: LoRaModParams,
+    /// LoRa packet parameters
+    pub packet: LoRaPacketParams,
+    /// LoRa sync word, `None` to keep the radio's (private network) default
+    pub sync_word: Option<LoraSyncWord>,
+    /// IRQ enable and DIO mapping
+    pub irq: DioIrqConfig,
+}
+
+impl LoRaInit {
+    /// Creates bring-up parameters for the given frequency with every other field defaulted.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency for both TX and RX
+    pub fn new(frequency: RfFrequencyConfig) -> Self {
+        Self {
+            frequency,
+            device: DeviceSelect::default(),
+            output_power: TxParams::default().power,
+            ramp_time: RampTime::default(),
+            regulator_mode: RegulatorMode::default(),
+            tcxo: None,
+            dio2_rf_switch: false,
+            modulation: LoRaModParams::default(),
+            packet: LoRaPacketParams::default(),
+            sync_word: None,
+            irq: DioIrqConfig::default(),
+        }
+    }
+
+    /// Returns the configuration applied after the calibration step, TCXO and RF switch
+    /// included.
+    ///
+    /// # Errors
+    /// * `ConfigError::UnsupportedOutputPower` - The output power is outside the range of the
+    ///   device
+    /// * `ConfigError::FrequencyOutOfRange` - The device does not support the frequency
+    /// * `ConfigError::UnsupportedModulation` - The device cannot demodulate the modulation
+    /// * `ConfigError::PreambleTooShort` - The preamble is too short for reliable reception
+    pub fn config(&self) -> Result<RadioConfig, ConfigError> {
+        let mut builder = init_builder(
+            self.frequency,
+            self.device,
+            self.output_power,
+            self.ramp_time,
+        )?
+        .regulator_mode(self.regulator_mode)
+        .dio2_rf_switch(self.dio2_rf_switch)
+        .tcxo(self.tcxo)
+        .modulation(ModulationParams::LoRa(self.modulation))
+        .packet(PacketParams::LoRa(self.packet))
+        .irq(self.irq);
+        if let Some(sync_word) = self.sync_word {
+            builder = builder.lora_sync_word(sync_word);
+        }
+        builder.build()
+    }
+}
+
+/// Bring-up parameters for GFSK operation
+///
+/// Every field except the frequency starts from a sensible default: an SX1262 at +14 dBm with
+/// a 40 µs ramp, the LDO regulator, no TCXO, 4.8 kbps with a 5 kHz deviation in a 19.5 kHz
+/// receiver bandwidth, and the [`Default`] GFSK packet and IRQ parameters. Consumed by
+/// [`Device::init_gfsk`](crate::Device::init_gfsk).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GfskInit {
+    /// RF frequency for both TX and RX
+    pub frequency: RfFrequencyConfig,
+    /// The device being brought up, selecting the PA configuration
+    pub device: DeviceSelect,
+    /// Output power at the antenna in dBm, resolved by [`TxPowerPlan::resolve`]
+    pub output_power: i8,
+    /// PA ramp time
+    pub ramp_time: RampTime,
+    /// Voltage regulator selection
+    pub regulator_mode: RegulatorMode,
+    /// TCXO supplied through DIO3, `None` for a crystal
+    pub tcxo: Option<TcxoConfig>,
+    /// DIO2 drives the RF switch
+    pub dio2_rf_switch: bool,
+    /// GFSK modulation parameters
+    pub modulation: GfskModParams,
+    /// GFSK packet parameters
+    pub packet: GFSKPacketParams,
+    /// GFSK sync word, `None` to keep the radio's current sync word
+    pub sync_word: Option<SyncWord>,
+    /// IRQ enable and DIO mapping
+    pub irq: DioIrqConfig,
+}
+
+impl GfskInit {
+    /// Creates bring-up parameters for the given frequency with every other field defaulted.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency for both TX and RX
+    pub fn new(frequency: RfFrequencyConfig) -> Self {
+        Self {
+            frequency,
+            device: DeviceSelect::default(),
+            output_power: TxParams::default().power,
+            ramp_time: RampTime::default(),
+            regulator_mode: RegulatorMode::default(),
+            tcxo: None,
+            dio2_rf_switch: false,
+            modulation: GfskModParams {
+                bit_rate: 4_800,
+                pulse_shape: GfskPulseShape::NoFilter,
+                bandwidth: GfskBandwidth::Bw195,
+                freq_deviation: 5_000,
+            },
+            packet: GFSKPacketParams::default(),
+            sync_word: None,
+            irq: DioIrqConfig::default(),
+        }
+    }
+
+    /// Returns the configuration applied after the calibration step, TCXO and RF switch
+    /// included.
+    ///
+    /// # Errors
+    /// * `ConfigError::UnsupportedOutputPower` - The output power is outside the range of the
+    ///   device
+    /// * `ConfigError::InvalidDeviation` - The deviation is 0, too large for the register or
+    ///   outside modulation index 0.5 to 4.0
+    /// * `ConfigError::FrequencyOutOfRange` - The device does not support the frequency
+    /// * `ConfigError::UnsupportedModulation` - The device cannot demodulate the modulation
+    pub fn config(&self) -> Result<RadioConfig, ConfigError> {
+        let mut builder = init_builder(
+            self.frequency,
+            self.device,
+            self.output_power,
+            self.ramp_time,
+        )?
+        .regulator_mode(self.regulator_mode)
+        .dio2_rf_switch(self.dio2_rf_switch)
+        .tcxo(self.tcxo)
+        .modulation(ModulationParams::Gfsk(self.modulation))
+        .packet(PacketParams::GFSK(self.packet))
+        .irq(self.irq);
+        if let Some(sync_word) = self.sync_word {
+            builder = builder.gfsk_sync_word(sync_word);
+        }
+        builder.build()
+    }
+}
+
+/// Starts a builder with the PA configuration and TX parameters for the output power.
+fn init_builder(
+    frequency: RfFrequencyConfig,
+    device: DeviceSelect,
+    output_power: i8,
+    ramp_time: RampTime,
+) -> Result<RadioConfigBuilder, ConfigError> {
+    let plan = TxPowerPlan::resolve(device, output_power, ramp_time)
+        .ok_or(ConfigError::UnsupportedOutputPower(output_power))?;
+    Ok(RadioConfig::builder(frequency)
+        .device(device)
+        .pa_config(plan.pa_config)
+        .tx_params(plan.tx_params))
+}
@@ -0,0 +1,306 @@
+//! Text configuration for field provisioning
+//!
+//! Parses `KEY=VALUE` lines, as typed over a UART, into updates to a [`RadioConfig`], and
+//! renders a configuration back to the same format for audit. Blank lines and lines starting
+//! with `#` are ignored, keys are case-insensitive and spaces around keys and values are
+//! trimmed.
+//!
+//! | Key         | Value                                                   | Packet type |
+//! |-------------|---------------------------------------------------------|-------------|
+//! | `FREQ`      | RF frequency in Hz                                      | Both        |
+//! | `POWER`     | Output power in dBm, one of the device's [`PaPreset`]s  | Both        |
+//! | `RXGAIN`    | `BOOSTED` or `SAVING`                                   | Both        |
+//! | `SF`        | Spreading factor, 5 to 12                               | LoRa        |
+//! | `BW`        | Bandwidth in Hz, one of [`LoRaBandwidth::hz`]           | LoRa        |
+//! | `CR`        | Coding rate denominator, 5 to 8 for 4/5 to 4/8          | LoRa        |
+//! | `LDRO`      | Low data rate optimization, `0` or `1`                  | LoRa        |
+//! | `PREAMBLE`  | Preamble length in symbols                              | LoRa        |
+//! | `CRC`       | Payload CRC, `0` or `1`                                 | LoRa        |
+//! | `IQ`        | IQ inversion, `0` or `1`                                | LoRa        |
+//! | `SYNC`      | LoRa sync word in hex, such as `0x3444`                 | LoRa        |
+//! | `BITRATE`   | Bit rate in bits per second                             | GFSK        |
+//! | `FDEV`      | Frequency deviation in Hz                               | GFSK        |
+//!
+//! Values are only checked against their type. Validating the resulting configuration, such
+//! as the frequency range of the device, is left to
+//! [`RadioConfigBuilder`](crate::RadioConfigBuilder) and [`Device`](crate::Device).
+//!
+//! # Example
+//! ```
+//! use sx1262::{config_text, LoRaInit, RfFrequencyConfig};
+//!
+//! let mut config = LoRaInit::new(RfFrequencyConfig { frequency: 868_100_000 })
+//!     .config()
+//!     .unwrap();
+//! config_text::apply_text(&mut config, "FREQ=869525000\nSF=9\n# EU868 RX2\nPOWER=14").unwrap();
+//! assert_eq!(config.frequency.frequency, 869_525_000);
+//!
+//! let mut audit = String::new();
+//! config_text::render(&config, &mut audit).unwrap();
+//! assert!(audit.contains("SF=9\n"));
+//!
+//! // The rendered text restores the configuration
+//! let mut restored = LoRaInit::new(RfFrequencyConfig { frequency: 433_000_000 })
+//!     .config()
+//!     .unwrap();
+//! config_text::apply_text(&mut restored, &audit).unwrap();
+//! assert_eq!(restored, config);
+//! ```
+//!
+//! Errors carry the line and key they happened on, and leave the configuration unchanged:
+//! ```
+//! use sx1262::{
+//!     config_text::{self, TextErrorKind},
+//!     GfskInit, LoRaInit, RfFrequencyConfig,
+//! };
+//!
+//! let lora = LoRaInit::new(RfFrequencyConfig { frequency: 868_100_000 })
+//!     .config()
+//!     .unwrap();
+//! let mut config = lora;
+//!
+//! let err = config_text::apply_text(&mut config, "FREQ=869525000\nSF=13").unwrap_err();
+//! assert_eq!((err.line, err.key, err.kind), (2, "SF", TextErrorKind::InvalidValue));
+//! assert_eq!(config, lora);
+//!
+//! let err = config_text::apply_text(&mut config, "\nCHANNEL=3").unwrap_err();
+//! assert_eq!((err.line, err.kind), (2, TextErrorKind::UnknownKey));
+//!
+//! let err = config_text::apply_line(&mut config, "SF 9").unwrap_err();
+//! assert_eq!((err.line, err.kind), (1, TextErrorKind::MissingValue));
+//!
+//! let err = config_text::apply_line(&mut config, "POWER=23").unwrap_err();
+//! assert_eq!(err.kind, TextErrorKind::UnsupportedPower);
+//!
+//! let mut gfsk = GfskInit::new(RfFrequencyConfig { frequency: 868_100_000 })
+//!     .config()
+//!     .unwrap();
+//! let err = config_text::apply_line(&mut gfsk, "sf=9").unwrap_err();
+//! assert_eq!((err.key, err.kind), ("sf", TextErrorKind::WrongPacketType));
+//! ```
+
+use core::fmt;
+
+use crate::{
+    config::RadioConfig, CodingRate, LoRaBandwidth, LoraSyncWord, ModulationParams, PaPreset,
+    PacketParams, RxGain, SpreadingFactor,
+};
+
+/// The cause of a [`TextError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TextErrorKind {
+    /// The line has no `=`
+    MissingValue,
+    /// The key is not one of the supported keys
+    UnknownKey,
+    /// The value cannot be parsed or is out of range for the key
+    InvalidValue,
+    /// The key only applies to the other packet type
+    WrongPacketType,
+    /// The device has no PA preset for the output power
+    UnsupportedPower,
+}
+
+/// Error returned when a line of text cannot be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TextError<'a> {
+    /// Line number within the text, starting at 1
+    pub line: usize,
+    /// Key of the line, the whole line when it has no `=`
+    pub key: &'a str,
+    /// The underlying cause
+    pub kind: TextErrorKind,
+}
+
+impl fmt::Display for TextError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cause = match self.kind {
+            TextErrorKind::MissingValue => "missing '='",
+            TextErrorKind::UnknownKey => "unknown key",
+            TextErrorKind::InvalidValue => "invalid value",
+            TextErrorKind::WrongPacketType => "not available for the packet type",
+            TextErrorKind::UnsupportedPower => "no PA preset for the output power",
+        };
+        write!(f, "line {}: {}: {cause}", self.line, self.key)
+    }
+}
+
+/// Applies every line of `text` to `config`, in order.
+///
+/// # Errors
+/// The first line that cannot be applied, with `config` left unchanged.
+pub fn apply_text<'a>(config: &mut RadioConfig, text: &'a str) -> Result<(), TextError<'a>> {
+    let mut updated = *config;
+    for (index, line) in text.lines().enumerate() {
+        apply_numbered(&mut updated, index + 1, line)?;
+    }
+    *config = updated;
+    Ok(())
+}
+
+/// Applies a single line to `config`, for text received one line at a time.
+///
+/// # Errors
+/// Why the line cannot be applied, reported as line 1, with `config` left unchanged.
+pub fn apply_line<'a>(config: &mut RadioConfig, line: &'a str) -> Result<(), TextError<'a>> {
+    apply_numbered(config, 1, line)
+}
+
+/// Writes `config` as lines that [`apply_text`] turns back into it.
+///
+/// Only the keys of the configuration's packet type are written. `POWER` is written as a
+/// comment when the PA configuration is not one of the device's [`PaPreset`]s.
+pub fn render(config: &RadioConfig, out: &mut impl fmt::Write) -> fmt::Result {
+    writeln!(out, "FREQ={}", config.frequency.frequency)?;
+    match output_power(config) {
+        Some(power) => writeln!(out, "POWER={power}")?,
+        None => writeln!(out, "# POWER=custom PA configuration")?,
+    }
+    let rx_gain = match config.rx_gain {
+        RxGain::Boosted => "BOOSTED",
+        RxGain::PowerSaving => "SAVING",
+    };
+    writeln!(out, "RXGAIN={rx_gain}")?;
+
+    match config.modulation {
+        ModulationParams::LoRa(params) => {
+            writeln!(out, "SF={}", params.spreading_factor as u8)?;
+            writeln!(out, "BW={}", params.bandwidth.hz())?;
+            writeln!(out, "CR={}", params.coding_rate as u8 + 4)?;
+            writeln!(out, "LDRO={}", u8::from(params.low_data_rate_opt))?;
+        }
+        ModulationParams::Gfsk(params) => {
+            writeln!(out, "BITRATE={}", params.bit_rate)?;
+            writeln!(out, "FDEV={}", params.freq_deviation)?;
+        }
+    }
+    if let PacketParams::LoRa(params) = config.packet {
+        writeln!(out, "PREAMBLE={}", params.preamble_length)?;
+        writeln!(out, "CRC={}", u8::from(params.crc_enable))?;
+        writeln!(out, "IQ={}", u8::from(params.iq_inversion_enable))?;
+    }
+    if let (ModulationParams::LoRa(_), Some(sync_word)) = (config.modulation, config.lora_sync_word)
+    {
+        writeln!(out, "SYNC=0x{:04X}", sync_word.value)?;
+    }
+    Ok(())
+}
+
+/// Returns the output power of the PA preset the configuration uses, if any.
+fn output_power(config: &RadioConfig) -> Option<i8> {
+    config
+        .pa_config
+        .device_sel
+        .pa_presets()
+        .iter()
+        .find(|preset| {
+            preset.pa_config == config.pa_config && preset.tx_power == config.tx_params.power
+        })
+        .map(|preset| preset.output_power)
+}
+
+fn apply_numbered<'a>(
+    config: &mut RadioConfig,
+    line: usize,
+    text: &'a str,
+) -> Result<(), TextError<'a>> {
+    let text = text.trim();
+    if text.is_empty() || text.starts_with('#') {
+        return Ok(());
+    }
+    let Some((key, value)) = text.split_once('=') else {
+        return Err(TextError {
+            line,
+            key: text,
+            kind: TextErrorKind::MissingValue,
+        });
+    };
+    let key = key.trim();
+    apply_entry(config, key, value.trim()).map_err(|kind| TextError { line, key, kind })
+}
+
+/// Applies a value, leaving `config` unchanged on error.
+fn apply_entry(config: &mut RadioConfig, key: &str, value: &str) -> Result<(), TextErrorKind> {
+    let is = |name: &str| key.eq_ignore_ascii_case(name);
+
+    if is("FREQ") {
+        config.frequency.frequency = parse(value)?;
+    } else if is("POWER") {
+        let preset = PaPreset::find(config.pa_config.device_sel, parse(value)?)
+            .ok_or(TextErrorKind::UnsupportedPower)?;
+        config.pa_config = preset.pa_config;
+        config.tx_params = preset.tx_params(config.tx_params.ramp_time);
+    } else if is("RXGAIN") {
+        config.rx_gain = if value.eq_ignore_ascii_case("BOOSTED") {
+            RxGain::Boosted
+        } else if value.eq_ignore_ascii_case("SAVING") {
+            RxGain::PowerSaving
+        } else {
+            return Err(TextErrorKind::InvalidValue);
+        };
+    } else if is("SF") || is("BW") || is("CR") || is("LDRO") {
+        let ModulationParams::LoRa(params) = &mut config.modulation else {
+            return Err(TextErrorKind::WrongPacketType);
+        };
+        if is("SF") {
+            params.spreading_factor = SpreadingFactor::try_from(parse::<u8>(value)?)
+                .or(Err(TextErrorKind::InvalidValue))?;
+        } else if is("BW") {
+            params.bandwidth =
+                LoRaBandwidth::from_hz(parse(value)?).ok_or(TextErrorKind::InvalidValue)?;
+        } else if is("CR") {
+            let denominator = parse::<u8>(value)?;
+            params.coding_rate = CodingRate::try_from(denominator.wrapping_sub(4))
+                .or(Err(TextErrorKind::InvalidValue))?;
+        } else {
+            params.low_data_rate_opt = parse_flag(value)?;
+        }
+    } else if is("PREAMBLE") || is("CRC") || is("IQ") {
+        let PacketParams::LoRa(params) = &mut config.packet else {
+            return Err(TextErrorKind::WrongPacketType);
+        };
+        if is("PREAMBLE") {
+            params.preamble_length = parse(value)?;
+        } else if is("CRC") {
+            params.crc_enable = parse_flag(value)?;
+        } else {
+            params.iq_inversion_enable = parse_flag(value)?;
+        }
+    } else if is("SYNC") {
+        if !matches!(config.modulation, ModulationParams::LoRa(_)) {
+            return Err(TextErrorKind::WrongPacketType);
+        }
+        let digits = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .unwrap_or(value);
+        let value = u16::from_str_radix(digits, 16).or(Err(TextErrorKind::InvalidValue))?;
+        config.lora_sync_word = Some(LoraSyncWord { value });
+    } else if is("BITRATE") || is("FDEV") {
+        let ModulationParams::Gfsk(params) = &mut config.modulation else {
+            return Err(TextErrorKind::WrongPacketType);
+        };
+        if is("BITRATE") {
+            params.bit_rate = parse(value)?;
+        } else {
+            params.freq_deviation = parse(value)?;
+        }
+    } else {
+        return Err(TextErrorKind::UnknownKey);
+    }
+    Ok(())
+}
+
+fn parse<T: core::str::FromStr>(value: &str) -> Result<T, TextErrorKind> {
+    value.parse().or(Err(TextErrorKind::InvalidValue))
+}
+
+fn parse_flag(value: &str) -> Result<bool, TextErrorKind> {
+    match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(TextErrorKind::InvalidValue),
+    }
+}
@@ -0,0 +1,290 @@
+//! Decoding of captured bus traffic
+//!
+//! Turns the bytes written to the radio during one chip select frame, as exported by a logic
+//! analyzer, back into the operation that produced them. Commands are recognized from
+//! [`COMMANDS`](crate::COMMANDS) and registers from [`REGISTERS`](crate::REGISTERS), so a
+//! capture from any host can be read against the definitions of this crate.
+//!
+//! The bytes clocked out while reading, the NOP after a read header and the NOPs while the
+//! radio returns a response or data, may be included or left out: they are only counted.
+//!
+//! # Example
+//! ```
+//! use sx1262::{
+//!     decode::{decode_transaction, DecodeError, DecodedOperation, DecodedParams},
+//!     IrqMask, PacketType, StandbyConfig,
+//! };
+//!
+//! // Commands, with their parameters parsed where the type is known
+//! let op = decode_transaction(&[0x80, 0x00]).unwrap();
+//! let DecodedOperation::Command { info, params, typed, read_len } = op else { panic!() };
+//! assert_eq!((info.name, params, read_len), ("SetStandby", &[0x00][..], 0));
+//! assert_eq!(typed, Some(DecodedParams::Standby(StandbyConfig::Rc)));
+//!
+//! let op = decode_transaction(&[0x8A, 0x01]).unwrap();
+//! let DecodedOperation::Command { typed, .. } = op else { panic!() };
+//! assert_eq!(typed, Some(DecodedParams::PacketType(PacketType::LoRa)));
+//!
+//! let op = decode_transaction(&[0x86, 0x36, 0x40, 0x00, 0x00]).unwrap();
+//! let DecodedOperation::Command { typed, .. } = op else { panic!() };
+//! let Some(DecodedParams::Frequency(frequency)) = typed else { panic!() };
+//! assert_eq!(frequency.frequency, 868_000_000);
+//!
+//! // GetIrqStatus with the NOPs clocked while the status and flags are read
+//! let op = decode_transaction(&[0x12, 0x00, 0x00, 0x00]).unwrap();
+//! let DecodedOperation::Command { info, params, typed, read_len } = op else { panic!() };
+//! assert_eq!((info.name, params.len(), typed, read_len), ("GetIrqStatus", 0, None, 3));
+//!
+//! let op = decode_transaction(&[0x02, 0x01, 0x03]).unwrap();
+//! let DecodedOperation::Command { typed, .. } = op else { panic!() };
+//! let mask = IrqMask::TIMEOUT | IrqMask::RX_DONE | IrqMask::TX_DONE;
+//! assert_eq!(typed, Some(DecodedParams::IrqMask(mask)));
+//!
+//! // Register accesses, with the register when its address is known
+//! let op = decode_transaction(&[0x0D, 0x07, 0x40, 0x34, 0x44]).unwrap();
+//! let DecodedOperation::WriteRegister { address, value, register } = op else { panic!() };
+//! assert_eq!((address, value), (0x0740, &[0x34, 0x44][..]));
+//! assert_eq!(register.unwrap().name, "LoraSyncWord");
+//!
+//! let op = decode_transaction(&[0x1D, 0x08, 0xAC, 0x00, 0x00]).unwrap();
+//! let DecodedOperation::ReadRegister { address, len, register } = op else { panic!() };
+//! assert_eq!((address, len, register.unwrap().name), (0x08AC, 1, "RxGain"));
+//!
+//! // Buffer accesses
+//! assert_eq!(
+//!     decode_transaction(&[0x0E, 0x80, 0xAA, 0xBB]),
+//!     Ok(DecodedOperation::WriteBuffer { offset: 0x80, data: &[0xAA, 0xBB] })
+//! );
+//! assert_eq!(
+//!     decode_transaction(&[0x1E, 0x00, 0x00, 0x00, 0x00, 0x00]),
+//!     Ok(DecodedOperation::ReadBuffer { offset: 0x00, len: 3 })
+//! );
+//!
+//! // Truncated or unknown frames are reported, not guessed
+//! assert_eq!(decode_transaction(&[]), Err(DecodeError::Empty));
+//! assert_eq!(
+//!     decode_transaction(&[0x86, 0x36, 0x40]),
+//!     Err(DecodeError::Truncated { opcode: 0x86, expected: 5, found: 3 })
+//! );
+//! assert_eq!(
+//!     decode_transaction(&[0x1D, 0x07]),
+//!     Err(DecodeError::Truncated { opcode: 0x1D, expected: 4, found: 2 })
+//! );
+//! assert_eq!(decode_transaction(&[0xFF]), Err(DecodeError::UnknownOpcode { opcode: 0xFF }));
+//! ```
+
+use core::fmt;
+
+use crate::{
+    bus::{READ_BUFFER, READ_REGISTER, WRITE_BUFFER, WRITE_REGISTER},
+    describe_opcode, opcode,
+    registers::{RegisterInfo, REGISTERS},
+    CommandInfo, DioIrqConfig, FromByteArray, IrqMask, PacketType, RfFrequencyConfig,
+    StandbyConfig, Timeout,
+};
+
+/// Command parameters parsed into their type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodedParams {
+    /// Parameters of SetStandby
+    Standby(StandbyConfig),
+    /// Parameters of SetRfFrequency, rounded to the nearest Hz
+    Frequency(RfFrequencyConfig),
+    /// Parameters of SetTx and SetRx
+    Timeout(Timeout),
+    /// Parameters of SetPacketType
+    PacketType(PacketType),
+    /// Parameters of ClearIrqStatus
+    IrqMask(IrqMask),
+    /// Parameters of SetDioIrqParams
+    DioIrq(DioIrqConfig),
+}
+
+/// Operation recognized in the bytes of a chip select frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodedOperation<'a> {
+    /// A command
+    Command {
+        /// Description of the command
+        info: &'static CommandInfo,
+        /// Raw parameters
+        params: &'a [u8],
+        /// Parameters parsed into their type, when supported and valid
+        typed: Option<DecodedParams>,
+        /// Number of bytes clocked after the parameters, while reading the response
+        read_len: usize,
+    },
+    /// A ReadRegister framing
+    ReadRegister {
+        /// Address of the first byte read
+        address: u16,
+        /// Number of bytes read
+        len: usize,
+        /// The register at the address, if defined in [`registers`](crate::registers)
+        register: Option<&'static RegisterInfo>,
+    },
+    /// A WriteRegister framing
+    WriteRegister {
+        /// Address of the first byte written
+        address: u16,
+        /// Bytes written
+        value: &'a [u8],
+        /// The register at the address, if defined in [`registers`](crate::registers)
+        register: Option<&'static RegisterInfo>,
+    },
+    /// A ReadBuffer framing
+    ReadBuffer {
+        /// Offset into the data buffer
+        offset: u8,
+        /// Number of bytes read
+        len: usize,
+    },
+    /// A WriteBuffer framing
+    WriteBuffer {
+        /// Offset into the data buffer
+        offset: u8,
+        /// Bytes written
+        data: &'a [u8],
+    },
+}
+
+/// Error returned when a frame cannot be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DecodeError {
+    /// The frame has no bytes
+    Empty,
+    /// The first byte is neither a command defined in this crate nor a framing opcode
+    UnknownOpcode {
+        /// The first byte of the frame
+        opcode: u8,
+    },
+    /// The frame ends before the parameters or header of its opcode
+    Truncated {
+        /// The first byte of the frame
+        opcode: u8,
+        /// Minimum length of the frame, opcode included
+        expected: usize,
+        /// Length of the frame
+        found: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty frame"),
+            Self::UnknownOpcode { opcode } => write!(f, "unknown opcode {opcode:#04X}"),
+            Self::Truncated {
+                opcode,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{opcode:#04X} frame of {found} bytes, at least {expected} expected"
+            ),
+        }
+    }
+}
+
+/// Decodes the bytes written to the radio during one chip select frame.
+///
+/// # Arguments
+/// * `bytes` - The bytes on MOSI, opcode first
+///
+/// # Errors
+/// * `DecodeError::Empty` - No bytes
+/// * `DecodeError::UnknownOpcode` - The opcode is not known
+/// * `DecodeError::Truncated` - The frame ends within the parameters or framing header
+pub fn decode_transaction(bytes: &[u8]) -> Result<DecodedOperation<'_>, DecodeError> {
+    let (&opcode, rest) = bytes.split_first().ok_or(DecodeError::Empty)?;
+    let truncated = |header_len: usize| DecodeError::Truncated {
+        opcode,
+        expected: 1 + header_len,
+        found: bytes.len(),
+    };
+
+    match opcode {
+        READ_REGISTER => {
+            let ([high, low, _nop], value) = rest.split_first_chunk().ok_or(truncated(3))?;
+            let address = u16::from_be_bytes([*high, *low]);
+            Ok(DecodedOperation::ReadRegister {
+                address,
+                len: value.len(),
+                register: register_at(address),
+            })
+        }
+        WRITE_REGISTER => {
+            let ([high, low], value) = rest.split_first_chunk().ok_or(truncated(2))?;
+            let address = u16::from_be_bytes([*high, *low]);
+            Ok(DecodedOperation::WriteRegister {
+                address,
+                value,
+                register: register_at(address),
+            })
+        }
+        READ_BUFFER => {
+            let ([offset, _nop], data) = rest.split_first_chunk().ok_or(truncated(2))?;
+            Ok(DecodedOperation::ReadBuffer {
+                offset: *offset,
+                len: data.len(),
+            })
+        }
+        WRITE_BUFFER => {
+            let (&offset, data) = rest.split_first().ok_or(truncated(1))?;
+            Ok(DecodedOperation::WriteBuffer { offset, data })
+        }
+        _ => {
+            let info = describe_opcode(opcode).ok_or(DecodeError::UnknownOpcode { opcode })?;
+            let params = rest
+                .get(..info.params_len)
+                .ok_or(truncated(info.params_len))?;
+            Ok(DecodedOperation::Command {
+                info,
+                params,
+                typed: typed_params(opcode, params),
+                read_len: rest.len() - params.len(),
+            })
+        }
+    }
+}
+
+/// Returns the register starting at an address.
+fn register_at(address: u16) -> Option<&'static RegisterInfo> {
+    REGISTERS.iter().find(|info| info.address == address)
+}
+
+/// Parses the parameters of the commands with a known parameter type.
+fn typed_params(opcode: u8, params: &[u8]) -> Option<DecodedParams> {
+    match (opcode, params) {
+        (opcode::SET_STANDBY, [0]) => Some(DecodedParams::Standby(StandbyConfig::Rc)),
+        (opcode::SET_STANDBY, [1]) => Some(DecodedParams::Standby(StandbyConfig::Xosc)),
+        (opcode::SET_RF_FREQUENCY, &[a, b, c, d]) => {
+            // Frequency = steps * FXTAL / 2^25, FXTAL / 2^25 = 15625 / 16384
+            let steps = u64::from(u32::from_be_bytes([a, b, c, d]));
+            let frequency = u32::try_from((steps * 15_625 + 8_192) / 16_384).ok()?;
+            Some(DecodedParams::Frequency(RfFrequencyConfig { frequency }))
+        }
+        (opcode::SET_TX | opcode::SET_RX, &[a, b, c]) => {
+            Some(DecodedParams::Timeout(Timeout(u32::from_be_bytes([
+                0, a, b, c,
+            ]))))
+        }
+        (opcode::SET_PACKET_TYPE, &[a]) => PacketType::from_bytes([a])
+            .ok()
+            .map(DecodedParams::PacketType),
+        (opcode::CLEAR_IRQ_STATUS, &[a, b]) => {
+            IrqMask::from_bytes([a, b]).ok().map(DecodedParams::IrqMask)
+        }
+        (opcode::SET_DIO_IRQ_PARAMS, &[a, b, c, d, e, f, g, h]) => {
+            let mask = |high, low| IrqMask::from_bits_truncate(u16::from_be_bytes([high, low]));
+            Some(DecodedParams::DioIrq(DioIrqConfig {
+                irq_mask: mask(a, b),
+                dio1_mask: mask(c, d),
+                dio2_mask: mask(e, f),
+                dio3_mask: mask(g, h),
+            }))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,159 @@
+//! Device construction from any subset of its optional parts
+
+use super::Device;
+use crate::{
+    frontend::NoFrontend,
+    pins::{NoDelay, NoPin, Pins},
+};
+
+/// Builder for a [`Device`] with any subset of its optional parts
+///
+/// Created with [`Device::builder`]. Every part starts as its placeholder ([`NoFrontend`],
+/// [`NoPin`], [`NoDelay`]), and each setter replaces one of them, changing the type of the
+/// built [`Device`]. Methods that need a part are only available when it is supplied, e.g.
+/// [`irq_pending`](Device::irq_pending) needs DIO1.
+///
+/// # Example
+/// ```no_run
+/// use embedded_hal::{delay::DelayNs, digital::{InputPin, OutputPin}, spi::SpiDevice};
+/// use sx1262::{pins::BusyPin, Device};
+///
+/// // No pins
+/// fn bare<SPI: SpiDevice>(spi: SPI) {
+///     let device = Device::builder(spi).build();
+/// }
+///
+/// // BUSY only
+/// fn busy_only<SPI: SpiDevice, B: InputPin, D: DelayNs>(spi: SPI, busy: B, delay: D) {
+///     let device = Device::builder(spi).busy(BusyPin::new(busy, delay)).build();
+/// }
+///
+/// // Every line and an owned delay
+/// # #[cfg(feature = "sync")]
+/// fn full<SPI, B, R, I, D>(spi: SPI, busy: B, reset: R, dio1: I, delay: D, busy_delay: D)
+/// where
+///     SPI: SpiDevice,
+///     B: InputPin,
+///     R: OutputPin,
+///     I: InputPin,
+///     D: DelayNs,
+/// {
+///     let mut device = Device::builder(spi)
+///         .busy(BusyPin::new(busy, busy_delay))
+///         .reset(reset)
+///         .dio1(dio1)
+///         .delay(delay)
+///         .build();
+///     device.recover().unwrap();
+///     let pending = device.irq_pending().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DeviceBuilder<
+    BUS,
+    FE = NoFrontend,
+    BUSY = NoPin,
+    RESET = NoPin,
+    DIO1 = NoPin,
+    DELAY = NoDelay,
+> {
+    bus: BUS,
+    frontend: FE,
+    pins: Pins<BUSY, RESET, DIO1>,
+    delay: DELAY,
+}
+
+impl<BUS> Device<BUS> {
+    /// Starts building a Device around the provided SPI interface or radio bus.
+    ///
+    /// # Arguments
+    /// * `bus` - An SPI interface implementing the required embedded-hal traits, or any other
+    ///   radio bus implementation
+    pub fn builder(bus: BUS) -> DeviceBuilder<BUS> {
+        DeviceBuilder {
+            bus,
+            frontend: NoFrontend,
+            pins: Pins::default(),
+            delay: NoDelay,
+        }
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> DeviceBuilder<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Sets the RF front-end, see [`Device::with_frontend`].
+    pub fn frontend<F>(self, frontend: F) -> DeviceBuilder<BUS, F, BUSY, RESET, DIO1, DELAY> {
+        DeviceBuilder {
+            bus: self.bus,
+            frontend,
+            pins: self.pins,
+            delay: self.delay,
+        }
+    }
+
+    /// Sets the BUSY line, see [`Device::with_busy`].
+    pub fn busy<B>(self, busy: B) -> DeviceBuilder<BUS, FE, B, RESET, DIO1, DELAY> {
+        DeviceBuilder {
+            bus: self.bus,
+            frontend: self.frontend,
+            pins: Pins {
+                busy,
+                reset: self.pins.reset,
+                dio1: self.pins.dio1,
+            },
+            delay: self.delay,
+        }
+    }
+
+    /// Sets the NRESET line, see [`Device::with_reset`].
+    pub fn reset<R>(self, reset: R) -> DeviceBuilder<BUS, FE, BUSY, R, DIO1, DELAY> {
+        DeviceBuilder {
+            bus: self.bus,
+            frontend: self.frontend,
+            pins: Pins {
+                busy: self.pins.busy,
+                reset,
+                dio1: self.pins.dio1,
+            },
+            delay: self.delay,
+        }
+    }
+
+    /// Sets the DIO1 interrupt line.
+    pub fn dio1<I>(self, dio1: I) -> DeviceBuilder<BUS, FE, BUSY, RESET, I, DELAY> {
+        DeviceBuilder {
+            bus: self.bus,
+            frontend: self.frontend,
+            pins: Pins {
+                busy: self.pins.busy,
+                reset: self.pins.reset,
+                dio1,
+            },
+            delay: self.delay,
+        }
+    }
+
+    /// Sets all control lines at once.
+    pub fn pins<B, R, I>(self, pins: Pins<B, R, I>) -> DeviceBuilder<BUS, FE, B, R, I, DELAY> {
+        DeviceBuilder {
+            bus: self.bus,
+            frontend: self.frontend,
+            pins,
+            delay: self.delay,
+        }
+    }
+
+    /// Sets the owned delay, see [`Device::with_delay`].
+    pub fn delay<D>(self, delay: D) -> DeviceBuilder<BUS, FE, BUSY, RESET, DIO1, D> {
+        DeviceBuilder {
+            bus: self.bus,
+            frontend: self.frontend,
+            pins: self.pins,
+            delay,
+        }
+    }
+
+    /// Builds the Device.
+    pub fn build(self) -> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+        Device::new(self.bus).with_parts(self.frontend, self.pins, self.delay)
+    }
+}
@@ -0,0 +1,418 @@
+//! CAD-assisted reception
+//!
+//! Low-power LoRa listening: the radio runs short Channel Activity Detections between sleeps
+//! of the MCU and only stays in RX when a preamble is detected, and listen-before-talk
+//! transmission.
+//!
+//! A CAD ends in STDBY_RC on CAD_DONE whatever the fallback mode set with
+//! SetRxTxFallbackMode, which only applies to TX and RX. The Device tracks the radio in
+//! STDBY_RC from the GetIrqStatus reporting CAD_DONE, unless the CAD + RX exit mode kept it in
+//! RX after a detection, and the helpers here send the transition the next operation expects.
+
+use regiface::Command;
+
+use super::transceive::{RxOptions, RxPacket, TxDone, IRQ_POLL_US, RX_END};
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    commands::{observed_irq_status, starts_operation},
+    error::{Error, RxError, TxError},
+    CadParams, ClearIrqStatus, FallbackMode, GetIrqStatus, IrqMask, OperatingMode, PacketType,
+    SetCad, SetCadParams, SetFs, SetSleep, SetStandby, StandbyConfig, Timeout,
+};
+
+/// IRQs ending a CAD
+const CAD_IRQS: IrqMask = IrqMask::CAD_DONE.union(IrqMask::CAD_DETECTED);
+
+/// IRQs needed by a CAD-assisted receive
+const CAD_RX_IRQS: IrqMask = CAD_IRQS.union(RX_END).union(IrqMask::CRC_ERROR);
+
+/// CAD_ONLY exit mode of SetCadParams
+const CAD_ONLY: u8 = 0x00;
+
+/// CAD_RX exit mode of SetCadParams
+const CAD_RX: u8 = 0x01;
+
+/// Returns the CAD parameters with the CAD + RX exit mode.
+fn cad_then_rx(mut params: CadParams) -> CadParams {
+    params.cad_exit_mode = CAD_RX;
+    params
+}
+
+/// Returns the CAD parameters with the CAD only exit mode.
+fn cad_only(mut params: CadParams) -> CadParams {
+    params.cad_exit_mode = CAD_ONLY;
+    params
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Follows the end of a CAD from a command sent to the radio.
+    ///
+    /// The radio is in STDBY_RC once GetIrqStatus reports CAD_DONE, except after a detection
+    /// with the CAD + RX exit mode, which keeps it in RX.
+    pub(super) fn track_cad_end(&mut self, opcode: u8, request: &[u8], response: &[u8]) {
+        if opcode == SetCadParams::id() {
+            if let Some(&exit_mode) = request.get(3) {
+                self.state.cad_exit_mode = exit_mode;
+            }
+        } else if opcode == SetCad::id() {
+            self.state.cad_running = true;
+        } else if starts_operation(opcode)
+            || [SetStandby::id(), SetFs::id(), SetSleep::id()].contains(&opcode)
+        {
+            self.state.cad_running = false;
+        } else if let Some(irq) = observed_irq_status(opcode, response) {
+            if self.state.cad_running && irq.contains(IrqMask::CAD_DONE) {
+                self.state.cad_running = false;
+                if !(self.state.cad_exit_mode == CAD_RX && irq.contains(IrqMask::CAD_DETECTED)) {
+                    self.state.settled_mode = Some(OperatingMode::StandbyRc);
+                }
+            }
+        }
+    }
+
+    /// Returns the mode the radio falls back to after TX and RX, from the applied
+    /// configuration or else the turnaround profile.
+    fn fallback_mode(&self) -> FallbackMode {
+        self.state
+            .config
+            .map_or(self.settings.turnaround.fallback_mode(), |config| {
+                config.fallback_mode
+            })
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Listens for a LoRa packet with repeated CADs, sleeping between them.
+    ///
+    /// Each cycle clears the IRQs and starts a CAD in the CAD + RX exit mode. When no
+    /// activity is detected the CAD IRQs are cleared and `delay` sleeps for `idle_sleep_ms`
+    /// before the next cycle. When activity is detected the radio stays in RX until a packet
+    /// ends it or `cad.cad_timeout` elapses. A timeout counts as a false detection and
+    /// listening goes on. `stop` is called before every cycle and ends listening by
+    /// returning `true`, for example once a deadline passed.
+    ///
+    /// Packets are handled as by [`receive`](Device::receive): a bad payload CRC or header
+    /// ends listening with an error. The `cad_exit_mode` of `cad` is ignored.
+    ///
+    /// Every CAD starts from STDBY_RC, where the previous CAD without detection left the
+    /// radio. An RX timeout after a false detection leaves it in the fallback mode, from which
+    /// SetCad is accepted too.
+    ///
+    /// # Returns
+    /// The received packet, or `None` when `stop` ended listening
+    ///
+    /// # Errors
+    /// * `RxError::Crc` - A packet was received with a bad payload CRC
+    /// * `RxError::Header` - A packet was received with a bad header
+    /// * `RxError::Device` - Bus communication failed, the radio is not configured or
+    ///   is set to GFSK
+    /// * `RxError::Device` with `ErrorKind::IrqTimeout` - CAD_DONE was not read within the
+    ///   [busy timeout](Device::set_busy_timeout_us), or nothing ended RX within `cad_timeout`
+    ///   plus the busy timeout. The radio is put in STDBY_RC
+    ///
+    /// # Example
+    /// ```no_run
+    /// use embedded_hal::delay::DelayNs;
+    /// use sx1262::{bus::RadioBus, CadParams, Device};
+    ///
+    /// fn listen(device: &mut Device<impl RadioBus>, delay: &mut impl DelayNs) {
+    ///     let mut bytes = [0; 255];
+    ///     // Sleeps 50 ms between CADs, until a packet is received
+    ///     let packet = device
+    ///         .receive_cad_assisted(&mut bytes, CadParams::default(), 50, delay, || false)
+    ///         .unwrap()
+    ///         .unwrap();
+    ///     let payload = &bytes[..packet.len];
+    /// }
+    /// ```
+    pub fn receive_cad_assisted(
+        &mut self,
+        bytes: &mut [u8],
+        cad: CadParams,
+        idle_sleep_ms: u32,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+        mut stop: impl FnMut() -> bool,
+    ) -> Result<Option<RxPacket>, RxError> {
+        self.check_configured(SetCad::id())?;
+        self.check_packet_type(SetCad::id(), Some(PacketType::LoRa))?;
+        self.set_payload_length(u8::MAX)?;
+        self.ensure_irq_routes(CAD_RX_IRQS)?;
+        self.execute_command(SetCadParams {
+            params: cad_then_rx(cad),
+        })?;
+
+        while !stop() {
+            self.execute_command(ClearIrqStatus {
+                irq_mask: IrqMask::all(),
+            })?;
+            self.expect_irqs_cleared(CAD_RX_IRQS);
+            self.execute_command(SetCad)?;
+
+            // RX IRQs raised by the time CAD_DONE is read are left for the RX poll
+            let cad_irq = self.poll_irqs(IrqMask::CAD_DONE, Timeout(0))? & CAD_IRQS;
+            self.record_irqs(cad_irq);
+            self.execute_command(ClearIrqStatus { irq_mask: cad_irq })?;
+            if cad_irq.contains(IrqMask::CAD_DETECTED) {
+                let irq = self.poll_irqs(RX_END, Timeout(cad.cad_timeout))?;
+                let timestamp = self.timestamp();
+                self.record_irqs(irq);
+                self.execute_command(ClearIrqStatus { irq_mask: irq })?;
+                if !irq.contains(IrqMask::TIMEOUT) {
+                    return self
+                        .finish_receive(bytes, irq, timestamp, RxOptions::default())
+                        .map(Some);
+                }
+            }
+            delay.delay_ms(idle_sleep_ms);
+        }
+        Ok(None)
+    }
+
+    /// Transmits a LoRa packet if a CAD finds the channel free, listen-before-talk.
+    ///
+    /// The CAD runs in the CAD only exit mode, the `cad_exit_mode` of `cad` is ignored. The
+    /// radio ends the CAD in STDBY_RC whatever the fallback mode, so before SetTx it is put
+    /// in the fallback mode of the applied configuration or
+    /// [turnaround profile](Device::set_turnaround_profile): FS or STDBY_XOSC are entered
+    /// again, the transmit then starts as it does after any other TX or RX.
+    ///
+    /// # Returns
+    /// The time the packet was sent, or `None` when the CAD detected activity and nothing was
+    /// sent
+    ///
+    /// # Errors
+    /// As [`transmit`](Device::transmit), and `TxError::Device` when the radio is set to
+    /// GFSK
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, CadParams, Device, Timeout};
+    ///
+    /// fn send(device: &mut Device<impl RadioBus>) {
+    ///     let sent = device.transmit_after_cad(&[1, 2], CadParams::default(), Timeout::default());
+    ///     if sent.unwrap().is_none() {
+    ///         // The channel is busy, try again later
+    ///     }
+    /// }
+    /// ```
+    pub fn transmit_after_cad(
+        &mut self,
+        payload: &[u8],
+        cad: CadParams,
+        timeout: Timeout,
+    ) -> Result<Option<TxDone>, TxError> {
+        self.check_configured(SetCad::id())?;
+        self.check_packet_type(SetCad::id(), Some(PacketType::LoRa))?;
+        self.ensure_irq_routes(CAD_IRQS)?;
+        self.execute_command(SetCadParams {
+            params: cad_only(cad),
+        })?;
+        self.execute_command(ClearIrqStatus { irq_mask: CAD_IRQS })?;
+        self.expect_irqs_cleared(CAD_IRQS);
+        self.execute_command(SetCad)?;
+
+        let cad_irq = self.poll_irqs(IrqMask::CAD_DONE, Timeout(0))? & CAD_IRQS;
+        self.record_irqs(cad_irq);
+        self.execute_command(ClearIrqStatus { irq_mask: cad_irq })?;
+        if cad_irq.contains(IrqMask::CAD_DETECTED) {
+            return Ok(None);
+        }
+        self.leave_cad_standby()?;
+        self.transmit(payload, timeout).map(Some)
+    }
+
+    /// Puts the radio from STDBY_RC, where a CAD leaves it, in the mode it falls back to
+    /// after TX and RX.
+    fn leave_cad_standby(&mut self) -> Result<(), Error> {
+        match self.fallback_mode() {
+            FallbackMode::Fs => {
+                self.execute_command(SetFs)?;
+            }
+            FallbackMode::StdbyXosc => {
+                self.execute_command(SetStandby {
+                    config: StandbyConfig::Xosc,
+                })?;
+            }
+            FallbackMode::StdbyRc => {}
+        }
+        Ok(())
+    }
+
+    /// Polls GetIrqStatus until one of `any` is raised, for at most `timeout` plus the busy
+    /// timeout.
+    fn poll_irqs(&mut self, any: IrqMask, timeout: Timeout) -> Result<IrqMask, Error> {
+        let limit_us = self.irq_poll_limit_us(timeout);
+        let mut waited_us = 0;
+        loop {
+            let irq = self.execute_command(GetIrqStatus)?.irq_mask;
+            if irq.intersects(any) {
+                return Ok(irq);
+            }
+            waited_us += IRQ_POLL_US;
+            if waited_us >= limit_us {
+                return Err(self.abandon_poll(waited_us));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously listens for a LoRa packet with repeated CADs, sleeping between them.
+    ///
+    /// This is the async version of [`receive_cad_assisted`](Device::receive_cad_assisted).
+    /// The sleeps between CADs await `delay`, leaving the executor free for other tasks.
+    ///
+    /// # Cancellation
+    /// When the future is dropped before completion the radio may be left in CAD or RX. The
+    /// next async transmit or receive first calls [`resync_async`](Device::resync_async).
+    pub async fn receive_cad_assisted_async(
+        &mut self,
+        bytes: &mut [u8],
+        cad: CadParams,
+        idle_sleep_ms: u32,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        mut stop: impl FnMut() -> bool,
+    ) -> Result<Option<RxPacket>, RxError> {
+        self.check_configured(SetCad::id())?;
+        self.check_packet_type(SetCad::id(), Some(PacketType::LoRa))?;
+        self.begin_operation_async().await?;
+        self.set_payload_length_async(u8::MAX).await?;
+        self.ensure_irq_routes_async(CAD_RX_IRQS).await?;
+        self.execute_command_async(SetCadParams {
+            params: cad_then_rx(cad),
+        })
+        .await?;
+
+        while !stop() {
+            self.execute_command_async(ClearIrqStatus {
+                irq_mask: IrqMask::all(),
+            })
+            .await?;
+            self.expect_irqs_cleared(CAD_RX_IRQS);
+            self.execute_command_async(SetCad).await?;
+
+            let cad_irq = self.poll_irqs_async(IrqMask::CAD_DONE, Timeout(0)).await? & CAD_IRQS;
+            self.record_irqs(cad_irq);
+            self.execute_command_async(ClearIrqStatus { irq_mask: cad_irq })
+                .await?;
+            if cad_irq.contains(IrqMask::CAD_DETECTED) {
+                let irq = self
+                    .poll_irqs_async(RX_END, Timeout(cad.cad_timeout))
+                    .await?;
+                let timestamp = self.timestamp();
+                self.record_irqs(irq);
+                self.execute_command_async(ClearIrqStatus { irq_mask: irq })
+                    .await?;
+                if !irq.contains(IrqMask::TIMEOUT) {
+                    self.end_operation();
+                    return self
+                        .finish_receive_async(bytes, irq, timestamp, RxOptions::default())
+                        .await
+                        .map(Some);
+                }
+            }
+            delay.delay_ms(idle_sleep_ms).await;
+        }
+        self.end_operation();
+        Ok(None)
+    }
+
+    /// Asynchronously transmits a LoRa packet if a CAD finds the channel free.
+    ///
+    /// This is the async version of [`transmit_after_cad`](Device::transmit_after_cad).
+    ///
+    /// # Cancellation
+    /// When the future is dropped before completion the radio may be left in CAD or TX. The
+    /// next async transmit or receive first calls [`resync_async`](Device::resync_async).
+    pub async fn transmit_after_cad_async(
+        &mut self,
+        payload: &[u8],
+        cad: CadParams,
+        timeout: Timeout,
+    ) -> Result<Option<TxDone>, TxError> {
+        self.check_configured(SetCad::id())?;
+        self.check_packet_type(SetCad::id(), Some(PacketType::LoRa))?;
+        self.begin_operation_async().await?;
+        self.ensure_irq_routes_async(CAD_IRQS).await?;
+        self.execute_command_async(SetCadParams {
+            params: cad_only(cad),
+        })
+        .await?;
+        self.execute_command_async(ClearIrqStatus { irq_mask: CAD_IRQS })
+            .await?;
+        self.expect_irqs_cleared(CAD_IRQS);
+        self.execute_command_async(SetCad).await?;
+
+        let cad_irq = self.poll_irqs_async(IrqMask::CAD_DONE, Timeout(0)).await? & CAD_IRQS;
+        self.record_irqs(cad_irq);
+        self.execute_command_async(ClearIrqStatus { irq_mask: cad_irq })
+            .await?;
+        self.end_operation();
+        if cad_irq.contains(IrqMask::CAD_DETECTED) {
+            return Ok(None);
+        }
+        self.leave_cad_standby_async().await?;
+        self.transmit_async(payload, timeout).await.map(Some)
+    }
+
+    /// Asynchronously puts the radio from STDBY_RC, where a CAD leaves it, in the mode it
+    /// falls back to after TX and RX.
+    async fn leave_cad_standby_async(&mut self) -> Result<(), Error> {
+        match self.fallback_mode() {
+            FallbackMode::Fs => {
+                self.execute_command_async(SetFs).await?;
+            }
+            FallbackMode::StdbyXosc => {
+                self.execute_command_async(SetStandby {
+                    config: StandbyConfig::Xosc,
+                })
+                .await?;
+            }
+            FallbackMode::StdbyRc => {}
+        }
+        Ok(())
+    }
+
+    /// Asynchronously polls GetIrqStatus until one of `any` is raised, for at most `timeout`
+    /// plus the busy timeout.
+    async fn poll_irqs_async(&mut self, any: IrqMask, timeout: Timeout) -> Result<IrqMask, Error> {
+        let limit_us = self.irq_poll_limit_us(timeout);
+        let mut waited_us = 0;
+        loop {
+            let irq = self.execute_command_async(GetIrqStatus).await?.irq_mask;
+            if irq.intersects(any) {
+                return Ok(irq);
+            }
+            waited_us += IRQ_POLL_US;
+            if waited_us >= limit_us {
+                return Err(self.abandon_poll_async(waited_us).await);
+            }
+        }
+    }
+}
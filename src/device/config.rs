@@ -0,0 +1,1320 @@
+//! Configuration and errata helpers
+//!
+//! Composite operations that apply a complete [`RadioConfig`] and the silicon workarounds
+//! it requires.
+
+use regiface::{Command, Register};
+
+use super::{check_packet_type_readback, Device, IqMode};
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::{ConfigChanges, ConfigItems, RadioConfig},
+    errata::{self, Workaround},
+    error::{DioPin, Error, ErrorKind, PinOwner, StepExt},
+    Calibrate, CalibrateImage, CalibrationConfig, ClearDeviceErrors, EventMask, GetPacketType,
+    GetStatus, ImageCalibConfig, IqPolaritySetup, LoRaPacketParams, OperatingMode, PacketParams,
+    PacketType, RegulatorMode, ResetStats, RetentionList, RfSwitchConfig, RtcControl, RxGain,
+    SetBufferBaseAddress, SetDio2AsRfSwitchCtrl, SetDio3AsTcxoCtrl, SetDioIrqParams,
+    SetModulationParams, SetPaConfig, SetPacketParams, SetPacketType, SetRegulatorMode,
+    SetRfFrequency, SetRxTxFallbackMode, SetStandby, SetTxParams, StandbyConfig, TcxoConfig,
+    TxClampConfig, TxModulation,
+};
+
+/// Checks a retention list read back against the one written.
+fn check_retention_readback(
+    written: &RetentionList,
+    readback: &RetentionList,
+) -> Result<(), Error> {
+    if written.get_entries() == readback.get_entries() {
+        Ok(())
+    } else {
+        Err(Error::register(
+            RetentionList::id(),
+            ErrorKind::VerificationFailed,
+        ))
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Returns the LoRa packet parameters of the cached configuration.
+    pub(super) fn cached_lora_packet(&self) -> Result<LoRaPacketParams, Error> {
+        let Some(config) = self.state.config else {
+            return Err(Error::command(
+                SetPacketParams::id(),
+                ErrorKind::NotConfigured {
+                    missing: ConfigItems::PACKET,
+                },
+            ));
+        };
+        match config.packet {
+            PacketParams::LoRa(params) => Ok(params),
+            PacketParams::GFSK(_) => Err(Error::command(
+                SetPacketParams::id(),
+                ErrorKind::PacketTypeMismatch {
+                    configured: PacketType::Gfsk,
+                    provided: PacketType::LoRa,
+                },
+            )),
+        }
+    }
+
+    /// Rejects SetDio3AsTcxoCtrl while IRQs are routed to DIO3.
+    fn check_dio3_for_tcxo(&self) -> Result<(), Error> {
+        match self.state.irq_routing {
+            Some(routing) if !routing.dio3_mask.is_empty() => Err(Error::command(
+                SetDio3AsTcxoCtrl::id(),
+                ErrorKind::PinInUse {
+                    pin: DioPin::Dio3,
+                    owner: PinOwner::IrqMapping,
+                },
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records LoRa packet parameters sent to the radio in the cached configuration.
+    fn cache_lora_packet(&mut self, params: LoRaPacketParams) {
+        if let Some(config) = &mut self.state.config {
+            config.packet = PacketParams::LoRa(params);
+        }
+    }
+
+    /// Returns a register the applied configuration needs retained across warm-start sleep
+    /// that is missing from a retention list, if any.
+    ///
+    /// Only the boosted [`RxGain`] is known to be lost in warm-start sleep without retention
+    /// (datasheet 9.6). Check the list read from the radio before
+    /// [`sleep_for`](Device::sleep_for) or a SetSleep with warm start.
+    ///
+    /// # Arguments
+    /// * `list` - The retention list on the radio
+    pub fn missing_retention(&self, list: &RetentionList) -> Option<u16> {
+        let config = self.state.config?;
+        (config.rx_gain == RxGain::Boosted && !list.contains::<RxGain>()).then(RxGain::id)
+    }
+
+    /// Checks that a raw status byte reports standby, from which SetRegulatorMode can be
+    /// sent. Returns whether the radio must go through STDBY_RC, as it is in STDBY_XOSC.
+    fn check_regulator_mode_allowed(status: u8) -> Result<bool, Error> {
+        let mode = OperatingMode::try_from((status >> 4) & 0x7).ok();
+        match mode {
+            Some(OperatingMode::StandbyRc) => return Ok(false),
+            Some(OperatingMode::StandbyXosc) => return Ok(true),
+            _ => {}
+        }
+        Err(Error::command(
+            SetRegulatorMode::id(),
+            ErrorKind::WrongMode { found: mode },
+        ))
+    }
+
+    /// Records a regulator selection in the cached configuration.
+    fn cache_regulator_mode(&mut self, mode: RegulatorMode) {
+        if let Some(config) = &mut self.state.config {
+            config.regulator_mode = mode;
+        }
+    }
+
+    /// Checks that a raw status byte reports STDBY_RC or STDBY_XOSC, where the RX gain is
+    /// changed without disturbing a reception.
+    fn check_rx_gain_allowed(status: u8) -> Result<(), Error> {
+        let mode = OperatingMode::try_from((status >> 4) & 0x7).ok();
+        if matches!(
+            mode,
+            Some(OperatingMode::StandbyRc | OperatingMode::StandbyXosc)
+        ) {
+            return Ok(());
+        }
+        Err(Error::register(
+            RxGain::id(),
+            ErrorKind::WrongMode { found: mode },
+        ))
+    }
+
+    /// Records an RX gain in the cached configuration.
+    fn cache_rx_gain(&mut self, gain: RxGain) {
+        if let Some(config) = &mut self.state.config {
+            config.rx_gain = gain;
+        }
+    }
+
+    /// Returns whether the image calibration on the radio covers the configured frequency.
+    fn image_calibrated_for(&self, config: &RadioConfig) -> bool {
+        self.state
+            .image_calibration
+            .is_some_and(|calibration| calibration.covers(config.frequency.frequency))
+    }
+
+    /// Returns the configuration on the radio a configuration can be applied over with only
+    /// its changes, if any.
+    ///
+    /// A packet type change, or items lost since the configuration was applied (e.g. in
+    /// cold-start sleep), need a full apply.
+    fn diff_base(&self, config: &RadioConfig) -> Option<RadioConfig> {
+        self.state.config.filter(|previous| {
+            previous.packet_type() == config.packet_type() && self.state.configured.is_all()
+        })
+    }
+
+    /// Returns the parts of a configuration sent by a full apply.
+    fn full_apply_changes(&self, config: &RadioConfig) -> ConfigChanges {
+        let mut changes = ConfigChanges::all();
+        changes.set(
+            ConfigChanges::IMAGE_CALIBRATION,
+            !self.image_calibrated_for(config),
+        );
+        changes.set(
+            ConfigChanges::LORA_SYNC_WORD,
+            config.lora_sync_word.is_some(),
+        );
+        changes.set(
+            ConfigChanges::GFSK_SYNC_WORD,
+            config.gfsk_sync_word.is_some(),
+        );
+        changes.set(
+            ConfigChanges::WORKAROUNDS,
+            errata::workaround_states(config).next().is_some(),
+        );
+        changes
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Applies a complete radio configuration.
+    ///
+    /// The radio is placed in STDBY_RC and configured in the order required by the
+    /// datasheet, including image calibration for the configured frequency and finishing with
+    /// the errata workarounds, applying those the configuration requires and undoing the
+    /// reversible ones it does not. The image calibration is skipped when the calibration on
+    /// the radio already covers the frequency, see
+    /// [`image_calibration_span`](Device::image_calibration_span). On success the configuration is
+    /// cached and returned by [`snapshot_config`](Device::snapshot_config).
+    ///
+    /// The packet statistics are reset when the packet type changes, as the counters of one
+    /// packet type are meaningless under the other.
+    ///
+    /// Applying a snapshot to a freshly reset radio issues exactly the same commands as the
+    /// original call, which restores the configuration after the radio has been power-gated.
+    ///
+    /// The packet type is read back after SetPacketType, as every later command depends on
+    /// it, and a mismatch fails with `ErrorKind::ConfigVerificationFailed` before any of them
+    /// is sent.
+    ///
+    /// # Errors
+    /// Errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | SetStandby (STDBY_RC)             |
+    /// | 1    | SetRegulatorMode,                 |
+    /// |      | SetDio2AsRfSwitchCtrl             |
+    /// | 2    | SetDio3AsTcxoCtrl,                |
+    /// |      | ClearDeviceErrors, Calibrate,     |
+    /// |      | CalibrateImage                    |
+    /// | 3    | SetPacketType, GetPacketType,     |
+    /// |      | ResetStats                        |
+    /// | 4    | SetRfFrequency                    |
+    /// | 5    | SetPaConfig                       |
+    /// | 6    | SetTxParams                       |
+    /// | 7    | SetModulationParams               |
+    /// | 8    | SetPacketParams                   |
+    /// | 9    | SetBufferBaseAddress              |
+    /// | 10   | SetRxTxFallbackMode               |
+    /// | 11   | SetDioIrqParams                   |
+    /// | 12   | RX gain register                  |
+    /// | 13   | LoRa sync word register           |
+    /// | 14   | GFSK sync word register           |
+    /// | 15   | Errata workarounds                |
+    ///
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::Serialization` - The retention list has no free entry
+    /// * `ErrorKind::ConfigVerificationFailed` - The packet type read back differs
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, LoRaInit, RfFrequencyConfig};
+    ///
+    /// fn configure(device: &mut Device<impl RadioBus>) {
+    ///     let frequency = RfFrequencyConfig { frequency: 868_100_000 };
+    ///     let config = LoRaInit::new(frequency).config().unwrap();
+    ///     if let Err(err) = device.apply_config(&config) {
+    ///         // The step tells how far the configuration went
+    ///         let step = err.step();
+    ///     }
+    /// }
+    /// ```
+    pub fn apply_config(&mut self, config: &RadioConfig) -> Result<(), Error> {
+        self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .at_step(0)?;
+        self.execute_command(SetRegulatorMode {
+            mode: config.regulator_mode,
+        })
+        .at_step(1)?;
+        self.apply_rf_switch(config).at_step(1)?;
+        if let Some(tcxo) = config.tcxo {
+            self.apply_tcxo(tcxo).at_step(2)?;
+        }
+        if !self.image_calibrated_for(config) {
+            self.execute_command(CalibrateImage::for_frequency(config.frequency.frequency))
+                .at_step(2)?;
+        }
+        self.execute_command(SetPacketType {
+            packet_type: config.packet_type(),
+        })
+        .at_step(3)?;
+        if !self.settings.paranoid_checks {
+            // Paranoid checks already read the packet type back
+            let mut readback = [0; 2];
+            self.execute_raw(GetPacketType::id(), &[], &mut readback)
+                .at_step(3)?;
+            check_packet_type_readback(SetPacketType::id(), config.packet_type(), readback)
+                .at_step(3)?;
+        }
+        if self.state.stats_packet_type != Some(config.packet_type()) {
+            self.execute_command(ResetStats).at_step(3)?;
+            self.stats_reset(Some(config.packet_type()));
+        }
+        self.execute_command(SetRfFrequency {
+            config: self.corrected_frequency(config.frequency),
+        })
+        .at_step(4)?;
+        self.execute_command(SetPaConfig {
+            config: config.pa_config,
+        })
+        .at_step(5)?;
+        self.execute_command(SetTxParams {
+            params: config.tx_params,
+        })
+        .at_step(6)?;
+        self.execute_command(SetModulationParams {
+            params: config.modulation,
+        })
+        .at_step(7)?;
+        self.execute_command(SetPacketParams {
+            params: config.packet,
+        })
+        .at_step(8)?;
+        self.execute_command(SetBufferBaseAddress {
+            config: config.buffer_base,
+        })
+        .at_step(9)?;
+        self.execute_command(SetRxTxFallbackMode {
+            mode: config.fallback_mode,
+        })
+        .at_step(10)?;
+        self.execute_command(SetDioIrqParams { config: config.irq })
+            .at_step(11)?;
+        self.write_register(config.rx_gain).at_step(12)?;
+        if let Some(sync_word) = config.lora_sync_word {
+            self.write_register(sync_word).at_step(13)?;
+        }
+        if let Some(sync_word) = config.gfsk_sync_word {
+            self.write_register(sync_word).at_step(14)?;
+        }
+
+        self.apply_errata_workarounds(config).at_step(15)?;
+        self.settings.rssi_offset_db = config.rssi_offset_db;
+        self.state.config = Some(*config);
+        Ok(())
+    }
+
+    /// Applies a radio configuration, sending only what differs from the configuration
+    /// applied last.
+    ///
+    /// Meant for frequency hopping and adaptive data rate, where the configuration changes
+    /// every packet but usually in a single field. The parts that differ, see
+    /// [`RadioConfig::changes`], are sent in the order and with the step indices of
+    /// [`apply_config`](Device::apply_config), followed by the image calibration when the new
+    /// frequency is not covered and the errata workarounds depending on the changed parts. A
+    /// changed modulation or packet re-evaluates the reversible workarounds, undoing those no
+    /// longer required, e.g. when leaving the 500 kHz bandwidth.
+    ///
+    /// The commands are sent in the current mode, which must accept them: usually a standby
+    /// mode or FS, and STDBY_RC for a regulator mode change. A full
+    /// [`apply_config`](Device::apply_config) is done instead when no configuration has been
+    /// applied, the packet type changes, or the radio lost its configuration since, e.g. in
+    /// cold-start sleep or after a SetPacketType sent with
+    /// [`execute_command`](Device::execute_command). A changed [frequency correction](Device::set_frequency_correction_ppb)
+    /// is only sent with the next frequency change.
+    ///
+    /// # Returns
+    /// The parts of the configuration sent, with
+    /// [`PACKET_TYPE`](ConfigChanges::PACKET_TYPE) for a full apply
+    ///
+    /// # Errors
+    /// Errors carry the index of the failing step, see [`apply_config`](Device::apply_config).
+    /// After an error the configuration last applied is unknown, and the next call does a
+    /// full apply.
+    ///
+    /// # Example
+    /// Frequency hopping, where only SetRfFrequency is sent as long as the frequency stays within
+    /// the calibrated band:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, RadioConfig};
+    ///
+    /// fn hop(device: &mut Device<impl RadioBus>, config: &mut RadioConfig, frequency: u32) {
+    ///     config.frequency.frequency = frequency;
+    ///     let changes = device.apply_config_diff(config).unwrap();
+    /// }
+    /// ```
+    pub fn apply_config_diff(&mut self, config: &RadioConfig) -> Result<ConfigChanges, Error> {
+        let Some(previous) = self.diff_base(config) else {
+            let changes = self.full_apply_changes(config);
+            self.apply_config(config)?;
+            return Ok(changes);
+        };
+        let mut changes = config.changes(&previous);
+        // Unknown until every change is sent
+        self.state.config = None;
+
+        if changes.contains(ConfigChanges::REGULATOR_MODE) {
+            self.execute_command(SetRegulatorMode {
+                mode: config.regulator_mode,
+            })
+            .at_step(1)?;
+        }
+        if changes.contains(ConfigChanges::DIO_CONTROL) {
+            self.apply_rf_switch(config).at_step(1)?;
+            if let Some(tcxo) = config.tcxo {
+                self.apply_tcxo(tcxo).at_step(2)?;
+            }
+        }
+        if !self.image_calibrated_for(config) {
+            self.execute_command(CalibrateImage::for_frequency(config.frequency.frequency))
+                .at_step(2)?;
+            changes |= ConfigChanges::IMAGE_CALIBRATION;
+        }
+        if changes.contains(ConfigChanges::FREQUENCY) {
+            self.execute_command(SetRfFrequency {
+                config: self.corrected_frequency(config.frequency),
+            })
+            .at_step(4)?;
+        }
+        if changes.contains(ConfigChanges::PA_CONFIG) {
+            self.execute_command(SetPaConfig {
+                config: config.pa_config,
+            })
+            .at_step(5)?;
+        }
+        if changes.contains(ConfigChanges::TX_PARAMS) {
+            self.execute_command(SetTxParams {
+                params: config.tx_params,
+            })
+            .at_step(6)?;
+        }
+        if changes.contains(ConfigChanges::MODULATION) {
+            self.execute_command(SetModulationParams {
+                params: config.modulation,
+            })
+            .at_step(7)?;
+        }
+        if changes.contains(ConfigChanges::PACKET) {
+            self.execute_command(SetPacketParams {
+                params: config.packet,
+            })
+            .at_step(8)?;
+        }
+        if changes.contains(ConfigChanges::BUFFER_BASE) {
+            self.execute_command(SetBufferBaseAddress {
+                config: config.buffer_base,
+            })
+            .at_step(9)?;
+        }
+        if changes.contains(ConfigChanges::FALLBACK_MODE) {
+            self.execute_command(SetRxTxFallbackMode {
+                mode: config.fallback_mode,
+            })
+            .at_step(10)?;
+        }
+        if changes.contains(ConfigChanges::IRQ) {
+            self.execute_command(SetDioIrqParams { config: config.irq })
+                .at_step(11)?;
+        }
+        if changes.contains(ConfigChanges::RX_GAIN) {
+            self.write_register(config.rx_gain).at_step(12)?;
+        }
+        if let (true, Some(sync_word)) = (
+            changes.contains(ConfigChanges::LORA_SYNC_WORD),
+            config.lora_sync_word,
+        ) {
+            self.write_register(sync_word).at_step(13)?;
+        }
+        if let (true, Some(sync_word)) = (
+            changes.contains(ConfigChanges::GFSK_SYNC_WORD),
+            config.gfsk_sync_word,
+        ) {
+            self.write_register(sync_word).at_step(14)?;
+        }
+
+        for (workaround, required) in errata::workaround_states(config) {
+            if changes.intersects(workaround.inputs()) {
+                self.apply_workaround(workaround, required).at_step(15)?;
+                changes |= ConfigChanges::WORKAROUNDS;
+            }
+        }
+        self.settings.rssi_offset_db = config.rssi_offset_db;
+        self.state.config = Some(*config);
+        Ok(changes)
+    }
+
+    /// Applies every errata workaround required by the given configuration, and undoes the
+    /// reversible ones it does not require.
+    ///
+    /// See [`errata::ERRATA`] for the conditions under which each workaround applies, and
+    /// [`Workaround::is_reversible`] for those written back to their reset value.
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::Serialization` - The retention list has no free entry
+    pub fn apply_errata_workarounds(&mut self, config: &RadioConfig) -> Result<(), Error> {
+        for (workaround, required) in errata::workaround_states(config) {
+            self.apply_workaround(workaround, required)?;
+        }
+        Ok(())
+    }
+
+    /// Sends the DIO2 RF switch control of a configuration.
+    fn apply_rf_switch(&mut self, config: &RadioConfig) -> Result<(), Error> {
+        self.execute_command(SetDio2AsRfSwitchCtrl {
+            config: RfSwitchConfig {
+                enable: config.dio2_rf_switch,
+            },
+        })?;
+        Ok(())
+    }
+
+    /// Powers a TCXO through DIO3 and calibrates every block with it.
+    ///
+    /// DIO3 stops raising the IRQs routed to it, so this fails while
+    /// [`irq_routing`](Device::irq_routing) routes any IRQ to DIO3, and the routing helpers
+    /// refuse new DIO3 routes afterwards. Only a reset gives DIO3 back to the IRQs.
+    ///
+    /// # Arguments
+    /// * `tcxo` - TCXO supply voltage and start-up delay
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::PinInUse` - IRQs are routed to DIO3, see
+    ///   [`force_dio3_as_tcxo_ctrl`](Device::force_dio3_as_tcxo_ctrl)
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, TcxoConfig, TcxoVoltage};
+    ///
+    /// fn power_tcxo(device: &mut Device<impl RadioBus>) {
+    ///     // 1.8 V with a 5 ms start-up, in steps of 15.625 µs
+    ///     let tcxo = TcxoConfig { voltage: TcxoVoltage::V1_8, delay: 320 };
+    ///     device.set_dio3_as_tcxo_ctrl(tcxo).unwrap();
+    /// }
+    /// ```
+    pub fn set_dio3_as_tcxo_ctrl(&mut self, tcxo: TcxoConfig) -> Result<(), Error> {
+        self.check_dio3_for_tcxo()?;
+        self.apply_tcxo(tcxo)
+    }
+
+    /// Powers a TCXO through DIO3 like [`set_dio3_as_tcxo_ctrl`](Device::set_dio3_as_tcxo_ctrl),
+    /// even while IRQs are routed to DIO3.
+    ///
+    /// The IRQs routed to DIO3 stop arriving.
+    pub fn force_dio3_as_tcxo_ctrl(&mut self, tcxo: TcxoConfig) -> Result<(), Error> {
+        self.apply_tcxo(tcxo)
+    }
+
+    /// Powers a TCXO through DIO3 and calibrates every block with it.
+    fn apply_tcxo(&mut self, tcxo: TcxoConfig) -> Result<(), Error> {
+        self.execute_command(SetDio3AsTcxoCtrl { config: tcxo })?;
+        // The start-up calibration ran before the TCXO was powered and flagged
+        // XOSC_START_ERR
+        self.execute_command(ClearDeviceErrors)?;
+        self.execute_command(Calibrate {
+            config: CalibrationConfig::all(),
+        })?;
+        Ok(())
+    }
+
+    /// Applies a single errata workaround, or undoes it when not `required` and reversible.
+    fn apply_workaround(&mut self, workaround: Workaround, required: bool) -> Result<(), Error> {
+        match workaround {
+            Workaround::LoRa500kHzModulation => self.apply_lora_500khz_workaround(required),
+            Workaround::TxClamp => self.apply_tx_clamp_workaround(),
+            Workaround::InvertedIq => self.apply_inverted_iq_workaround(required),
+            Workaround::RxGainRetention => self.apply_rx_gain_retention_workaround(),
+        }
+    }
+
+    /// Configures the TX modulation register for LoRa 500 kHz operation (datasheet 15.1).
+    ///
+    /// # Arguments
+    /// * `bw_500khz` - True when using LoRa with 500 kHz bandwidth, false otherwise
+    pub fn apply_lora_500khz_workaround(&mut self, bw_500khz: bool) -> Result<(), Error> {
+        let mut reg: TxModulation = self.read_register()?;
+        reg.apply_lora_500khz_optimization(bw_500khz);
+        self.write_register(reg)
+    }
+
+    /// Configures the TX clamp register of the SX1262 and SX1268 (datasheet 15.2).
+    ///
+    /// Must be applied after power-on reset or wake from cold start.
+    pub fn apply_tx_clamp_workaround(&mut self) -> Result<(), Error> {
+        let mut reg: TxClampConfig = self.read_register()?;
+        reg.apply_sx1262_workaround();
+        self.write_register(reg)
+    }
+
+    /// Configures the IQ polarity register for inverted or standard IQ (datasheet 15.4).
+    ///
+    /// # Arguments
+    /// * `inverted` - True when the packet parameters enable IQ inversion
+    pub fn apply_inverted_iq_workaround(&mut self, inverted: bool) -> Result<(), Error> {
+        let mut reg: IqPolaritySetup = self.read_register()?;
+        reg.optimize_for_inverted_iq(inverted);
+        self.write_register(reg)
+    }
+
+    /// Sets the LoRa IQ polarity.
+    ///
+    /// Inverting the IQ takes both the IQ bit of the packet parameters and the IQ polarity
+    /// register workaround (datasheet 15.4), which this sends together. The packet parameters
+    /// are those of the cached configuration, which is updated. See
+    /// [`TxOptions::iq`](crate::TxOptions::iq) and [`RxOptions::iq`](crate::RxOptions::iq)
+    /// to change the polarity for a single operation.
+    ///
+    /// # Arguments
+    /// * `iq` - The IQ polarity
+    ///
+    /// # Errors
+    /// `ErrorKind::NotConfigured` is returned at step 0 when no configuration was applied, and
+    /// `ErrorKind::PacketTypeMismatch` when it is not LoRa, without any command sent. Other
+    /// errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | SetPacketParams                   |
+    /// | 1    | IQ polarity register              |
+    pub fn set_iq_mode(&mut self, iq: IqMode) -> Result<(), Error> {
+        let params = self.write_iq_mode(iq)?;
+        self.cache_lora_packet(params);
+        Ok(())
+    }
+
+    /// Sends the cached LoRa packet parameters and the IQ polarity register for an IQ
+    /// polarity, leaving the cached configuration as it is.
+    pub(super) fn write_iq_mode(&mut self, iq: IqMode) -> Result<LoRaPacketParams, Error> {
+        let mut params = self.cached_lora_packet().at_step(0)?;
+        params.iq_inversion_enable = iq.is_inverted();
+        self.execute_command(SetPacketParams {
+            params: PacketParams::LoRa(params),
+        })
+        .at_step(0)?;
+        self.apply_inverted_iq_workaround(iq.is_inverted())
+            .at_step(1)?;
+        Ok(params)
+    }
+
+    /// Adds the RX gain register to the retention list so a boosted gain survives
+    /// warm-start sleep (datasheet 9.6).
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::Serialization` - The retention list has no free entry
+    pub fn apply_rx_gain_retention_workaround(&mut self) -> Result<(), Error> {
+        let mut list: RetentionList = self.read_register()?;
+        list.add_entry(RxGain::id())
+            .map_err(|_| Error::register(RetentionList::id(), ErrorKind::Serialization))?;
+        self.write_register(list)
+    }
+
+    /// Writes a retention list and reads it back.
+    ///
+    /// # Arguments
+    /// * `list` - The registers to retain across warm-start sleep
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::VerificationFailed` - The list read back differs from the one written
+    ///
+    /// # Example
+    /// ```no_run
+    /// use regiface::Register;
+    /// use sx1262::{bus::RadioBus, Device, RetentionList, RxGain};
+    ///
+    /// fn retain_rx_gain(device: &mut Device<impl RadioBus>) {
+    ///     let list = RetentionList::from_registers(&[RxGain::id()]).unwrap();
+    ///     device.apply_retention(&list).unwrap();
+    /// }
+    /// ```
+    pub fn apply_retention(&mut self, list: &RetentionList) -> Result<(), Error> {
+        self.write_register(*list)?;
+        let readback: RetentionList = self.read_register()?;
+        check_retention_readback(list, &readback)
+    }
+
+    /// Stops the RTC and clears the timeout event after an RX timeout with implicit
+    /// header (datasheet 15.3).
+    ///
+    /// Without this, the RTC keeps running after the timeout and can generate spurious
+    /// timeout interrupts. Must be called after each RX timeout in LoRa implicit header mode,
+    /// which [`receive`](Device::receive) does.
+    pub fn apply_implicit_header_timeout_workaround(&mut self) -> Result<(), Error> {
+        self.write_register(RtcControl { enabled: false })?;
+        let mut mask: EventMask = self.read_register()?;
+        mask.timeout_event = true;
+        self.write_register(mask)
+    }
+
+    /// Calibrates the image rejection over a span of frequencies.
+    ///
+    /// The span is tracked, so that [`apply_config`](Device::apply_config) does not
+    /// recalibrate for frequencies inside it, e.g. after a single calibration over several
+    /// bands. The radio must be in STDBY_RC.
+    ///
+    /// # Arguments
+    /// * `config` - The span to calibrate
+    ///
+    /// # Example
+    /// One calibration over the 863-870 and 902-928MHz bands, kept by later configurations in
+    /// either band:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, ImageCalibConfig};
+    ///
+    /// fn calibrate(device: &mut Device<impl RadioBus>) {
+    ///     device
+    ///         .calibrate_image(ImageCalibConfig { freq1: 0xD7, freq2: 0xE9 })
+    ///         .unwrap();
+    ///     assert_eq!(device.image_calibration_span(), Some(860..=932));
+    /// }
+    /// ```
+    pub fn calibrate_image(&mut self, config: ImageCalibConfig) -> Result<(), Error> {
+        self.execute_command(CalibrateImage { config })?;
+        Ok(())
+    }
+
+    /// Calibrates the selected blocks and waits for the calibration to complete.
+    ///
+    /// With a BUSY line the command itself waits for BUSY to be released. Without one, the
+    /// owned delay waits for [`CalibrationConfig::worst_case_duration_us`], as any command sent
+    /// while the radio is still calibrating is silently dropped. Every Calibrate sent by the
+    /// [`Device`], including those of [`apply_config`](Device::apply_config), waits the same
+    /// way. The radio must be in STDBY_RC.
+    ///
+    /// # Arguments
+    /// * `config` - The blocks to calibrate
+    ///
+    /// # Example
+    /// ```no_run
+    /// use embedded_hal::{delay::DelayNs, spi::SpiDevice};
+    /// use sx1262::{CalibrationConfig, Device};
+    ///
+    /// fn create(spi: impl SpiDevice, delay: impl DelayNs) {
+    ///     // Without BUSY, the delay waits for the worst case duration of the calibration
+    ///     let mut device = Device::new(spi).with_delay(delay);
+    ///     device.calibrate(CalibrationConfig::all()).unwrap();
+    /// }
+    /// ```
+    pub fn calibrate(&mut self, config: CalibrationConfig) -> Result<(), Error> {
+        self.execute_command(Calibrate { config })?;
+        Ok(())
+    }
+
+    /// Selects the voltage regulator.
+    ///
+    /// SetRegulatorMode is only accepted in STDBY_RC, so the operating mode is read with
+    /// GetStatus first. In STDBY_XOSC, the idle mode of
+    /// [`TurnaroundProfile::LowLatency`](crate::TurnaroundProfile::LowLatency), the radio is
+    /// placed in STDBY_RC for the command and returned to STDBY_XOSC after it. The command is
+    /// not sent in any other mode. The selection is recorded in the cached configuration, if
+    /// any.
+    ///
+    /// # Arguments
+    /// * `mode` - The regulator selection. Only select [`RegulatorMode::DcDcLdo`] on boards
+    ///   fitted with the 15 µH inductor on DCC_SW, without it RF performance collapses
+    ///
+    /// # Errors
+    /// `ErrorKind::WrongMode` is returned at step 0 when the radio is not in standby. Other
+    /// errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                                        |
+    /// |------|--------------------------------------------------|
+    /// | 0    | GetStatus, SetStandby (STDBY_RC) from STDBY_XOSC |
+    /// | 1    | SetRegulatorMode                                 |
+    /// | 2    | SetStandby (STDBY_XOSC) from STDBY_XOSC          |
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, RegulatorMode};
+    ///
+    /// fn use_dcdc(device: &mut Device<impl RadioBus>) {
+    ///     device.set_regulator_mode(RegulatorMode::DcDcLdo).unwrap();
+    /// }
+    /// ```
+    pub fn set_regulator_mode(&mut self, mode: RegulatorMode) -> Result<(), Error> {
+        // The raw status byte, as Status rejects some command statuses left by earlier commands
+        let mut status = [0];
+        self.execute_raw(GetStatus::id(), &[], &mut status)
+            .at_step(0)?;
+        let from_xosc = Self::check_regulator_mode_allowed(status[0]).at_step(0)?;
+        if from_xosc {
+            self.execute_command(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .at_step(0)?;
+        }
+        self.execute_command(SetRegulatorMode { mode }).at_step(1)?;
+        if from_xosc {
+            self.execute_command(SetStandby {
+                config: StandbyConfig::Xosc,
+            })
+            .at_step(2)?;
+        }
+        self.cache_regulator_mode(mode);
+        Ok(())
+    }
+
+    /// Sets the RX gain, optionally keeping it across warm-start sleep.
+    ///
+    /// The gain is only changed in standby, so the operating mode is read with GetStatus
+    /// first and nothing is written in any other mode. The radio resets the gain to power
+    /// saving when it wakes from sleep, unless the RX gain register is in the retention list
+    /// (datasheet 9.6), see
+    /// [`apply_rx_gain_retention_workaround`](Device::apply_rx_gain_retention_workaround).
+    /// Without `retain_across_sleep` the retention list is left as it is. The gain is
+    /// recorded in the cached configuration, if any.
+    ///
+    /// # Arguments
+    /// * `gain` - The RX gain
+    /// * `retain_across_sleep` - Whether to add the RX gain register to the retention list
+    ///
+    /// # Errors
+    /// `ErrorKind::WrongMode` is returned at step 0 when the radio is not in STDBY_RC or
+    /// STDBY_XOSC. Other errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                                          |
+    /// |------|----------------------------------------------------|
+    /// | 0    | GetStatus                                          |
+    /// | 1    | RX gain register write                             |
+    /// | 2    | Retention list read and write, when retaining      |
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, RxGain};
+    ///
+    /// fn boost(device: &mut Device<impl RadioBus>) {
+    ///     // Kept across warm-start sleep
+    ///     device.set_rx_gain(RxGain::Boosted, true).unwrap();
+    /// }
+    /// ```
+    pub fn set_rx_gain(&mut self, gain: RxGain, retain_across_sleep: bool) -> Result<(), Error> {
+        // The raw status byte, as Status rejects some command statuses left by earlier commands
+        let mut status = [0];
+        self.execute_raw(GetStatus::id(), &[], &mut status)
+            .at_step(0)?;
+        Self::check_rx_gain_allowed(status[0]).at_step(0)?;
+        self.write_register(gain).at_step(1)?;
+        self.cache_rx_gain(gain);
+        if retain_across_sleep {
+            self.apply_rx_gain_retention_workaround().at_step(2)?;
+        }
+        Ok(())
+    }
+
+    /// Calibrates the selected blocks and waits for the calibration to complete, using the
+    /// provided delay.
+    ///
+    /// See [`calibrate`](Device::calibrate), the provided delay is used instead of the owned
+    /// one.
+    pub fn calibrate_with_delay(
+        &mut self,
+        config: CalibrationConfig,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<(), Error> {
+        self.execute_raw_with_delay(Calibrate::id(), &[config.bits()], &mut [], Some(delay))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously applies a complete radio configuration.
+    ///
+    /// This is the async version of [`apply_config`](Device::apply_config).
+    pub async fn apply_config_async(&mut self, config: &RadioConfig) -> Result<(), Error> {
+        self.execute_command_async(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .await
+        .at_step(0)?;
+        self.execute_command_async(SetRegulatorMode {
+            mode: config.regulator_mode,
+        })
+        .await
+        .at_step(1)?;
+        self.apply_rf_switch_async(config).await.at_step(1)?;
+        if let Some(tcxo) = config.tcxo {
+            self.apply_tcxo_async(tcxo).await.at_step(2)?;
+        }
+        if !self.image_calibrated_for(config) {
+            self.execute_command_async(CalibrateImage::for_frequency(config.frequency.frequency))
+                .await
+                .at_step(2)?;
+        }
+        self.execute_command_async(SetPacketType {
+            packet_type: config.packet_type(),
+        })
+        .await
+        .at_step(3)?;
+        if !self.settings.paranoid_checks {
+            let mut readback = [0; 2];
+            self.execute_raw_async(GetPacketType::id(), &[], &mut readback)
+                .await
+                .at_step(3)?;
+            check_packet_type_readback(SetPacketType::id(), config.packet_type(), readback)
+                .at_step(3)?;
+        }
+        if self.state.stats_packet_type != Some(config.packet_type()) {
+            self.execute_command_async(ResetStats).await.at_step(3)?;
+            self.stats_reset(Some(config.packet_type()));
+        }
+        self.execute_command_async(SetRfFrequency {
+            config: self.corrected_frequency(config.frequency),
+        })
+        .await
+        .at_step(4)?;
+        self.execute_command_async(SetPaConfig {
+            config: config.pa_config,
+        })
+        .await
+        .at_step(5)?;
+        self.execute_command_async(SetTxParams {
+            params: config.tx_params,
+        })
+        .await
+        .at_step(6)?;
+        self.execute_command_async(SetModulationParams {
+            params: config.modulation,
+        })
+        .await
+        .at_step(7)?;
+        self.execute_command_async(SetPacketParams {
+            params: config.packet,
+        })
+        .await
+        .at_step(8)?;
+        self.execute_command_async(SetBufferBaseAddress {
+            config: config.buffer_base,
+        })
+        .await
+        .at_step(9)?;
+        self.execute_command_async(SetRxTxFallbackMode {
+            mode: config.fallback_mode,
+        })
+        .await
+        .at_step(10)?;
+        self.execute_command_async(SetDioIrqParams { config: config.irq })
+            .await
+            .at_step(11)?;
+        self.write_register_async(config.rx_gain)
+            .await
+            .at_step(12)?;
+        if let Some(sync_word) = config.lora_sync_word {
+            self.write_register_async(sync_word).await.at_step(13)?;
+        }
+        if let Some(sync_word) = config.gfsk_sync_word {
+            self.write_register_async(sync_word).await.at_step(14)?;
+        }
+
+        self.apply_errata_workarounds_async(config)
+            .await
+            .at_step(15)?;
+        self.settings.rssi_offset_db = config.rssi_offset_db;
+        self.state.config = Some(*config);
+        Ok(())
+    }
+
+    /// Asynchronously applies a radio configuration, sending only what differs from the
+    /// configuration applied last.
+    ///
+    /// This is the async version of [`apply_config_diff`](Device::apply_config_diff).
+    pub async fn apply_config_diff_async(
+        &mut self,
+        config: &RadioConfig,
+    ) -> Result<ConfigChanges, Error> {
+        let Some(previous) = self.diff_base(config) else {
+            let changes = self.full_apply_changes(config);
+            self.apply_config_async(config).await?;
+            return Ok(changes);
+        };
+        let mut changes = config.changes(&previous);
+        self.state.config = None;
+
+        if changes.contains(ConfigChanges::REGULATOR_MODE) {
+            self.execute_command_async(SetRegulatorMode {
+                mode: config.regulator_mode,
+            })
+            .await
+            .at_step(1)?;
+        }
+        if changes.contains(ConfigChanges::DIO_CONTROL) {
+            self.apply_rf_switch_async(config).await.at_step(1)?;
+            if let Some(tcxo) = config.tcxo {
+                self.apply_tcxo_async(tcxo).await.at_step(2)?;
+            }
+        }
+        if !self.image_calibrated_for(config) {
+            self.execute_command_async(CalibrateImage::for_frequency(config.frequency.frequency))
+                .await
+                .at_step(2)?;
+            changes |= ConfigChanges::IMAGE_CALIBRATION;
+        }
+        if changes.contains(ConfigChanges::FREQUENCY) {
+            self.execute_command_async(SetRfFrequency {
+                config: self.corrected_frequency(config.frequency),
+            })
+            .await
+            .at_step(4)?;
+        }
+        if changes.contains(ConfigChanges::PA_CONFIG) {
+            self.execute_command_async(SetPaConfig {
+                config: config.pa_config,
+            })
+            .await
+            .at_step(5)?;
+        }
+        if changes.contains(ConfigChanges::TX_PARAMS) {
+            self.execute_command_async(SetTxParams {
+                params: config.tx_params,
+            })
+            .await
+            .at_step(6)?;
+        }
+        if changes.contains(ConfigChanges::MODULATION) {
+            self.execute_command_async(SetModulationParams {
+                params: config.modulation,
+            })
+            .await
+            .at_step(7)?;
+        }
+        if changes.contains(ConfigChanges::PACKET) {
+            self.execute_command_async(SetPacketParams {
+                params: config.packet,
+            })
+            .await
+            .at_step(8)?;
+        }
+        if changes.contains(ConfigChanges::BUFFER_BASE) {
+            self.execute_command_async(SetBufferBaseAddress {
+                config: config.buffer_base,
+            })
+            .await
+            .at_step(9)?;
+        }
+        if changes.contains(ConfigChanges::FALLBACK_MODE) {
+            self.execute_command_async(SetRxTxFallbackMode {
+                mode: config.fallback_mode,
+            })
+            .await
+            .at_step(10)?;
+        }
+        if changes.contains(ConfigChanges::IRQ) {
+            self.execute_command_async(SetDioIrqParams { config: config.irq })
+                .await
+                .at_step(11)?;
+        }
+        if changes.contains(ConfigChanges::RX_GAIN) {
+            self.write_register_async(config.rx_gain)
+                .await
+                .at_step(12)?;
+        }
+        if let (true, Some(sync_word)) = (
+            changes.contains(ConfigChanges::LORA_SYNC_WORD),
+            config.lora_sync_word,
+        ) {
+            self.write_register_async(sync_word).await.at_step(13)?;
+        }
+        if let (true, Some(sync_word)) = (
+            changes.contains(ConfigChanges::GFSK_SYNC_WORD),
+            config.gfsk_sync_word,
+        ) {
+            self.write_register_async(sync_word).await.at_step(14)?;
+        }
+
+        for (workaround, required) in errata::workaround_states(config) {
+            if changes.intersects(workaround.inputs()) {
+                self.apply_workaround_async(workaround, required)
+                    .await
+                    .at_step(15)?;
+                changes |= ConfigChanges::WORKAROUNDS;
+            }
+        }
+        self.settings.rssi_offset_db = config.rssi_offset_db;
+        self.state.config = Some(*config);
+        Ok(changes)
+    }
+
+    /// Asynchronously applies every errata workaround required by the given configuration,
+    /// and undoes the reversible ones it does not require.
+    ///
+    /// This is the async version of
+    /// [`apply_errata_workarounds`](Device::apply_errata_workarounds).
+    pub async fn apply_errata_workarounds_async(
+        &mut self,
+        config: &RadioConfig,
+    ) -> Result<(), Error> {
+        for (workaround, required) in errata::workaround_states(config) {
+            self.apply_workaround_async(workaround, required).await?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously sends the DIO2 RF switch control of a configuration.
+    async fn apply_rf_switch_async(&mut self, config: &RadioConfig) -> Result<(), Error> {
+        self.execute_command_async(SetDio2AsRfSwitchCtrl {
+            config: RfSwitchConfig {
+                enable: config.dio2_rf_switch,
+            },
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Asynchronously powers a TCXO through DIO3 and calibrates every block with it.
+    ///
+    /// This is the async version of [`set_dio3_as_tcxo_ctrl`](Device::set_dio3_as_tcxo_ctrl).
+    pub async fn set_dio3_as_tcxo_ctrl_async(&mut self, tcxo: TcxoConfig) -> Result<(), Error> {
+        self.check_dio3_for_tcxo()?;
+        self.apply_tcxo_async(tcxo).await
+    }
+
+    /// Asynchronously powers a TCXO through DIO3, even while IRQs are routed to DIO3.
+    ///
+    /// This is the async version of
+    /// [`force_dio3_as_tcxo_ctrl`](Device::force_dio3_as_tcxo_ctrl).
+    pub async fn force_dio3_as_tcxo_ctrl_async(&mut self, tcxo: TcxoConfig) -> Result<(), Error> {
+        self.apply_tcxo_async(tcxo).await
+    }
+
+    /// Asynchronously powers a TCXO through DIO3 and calibrates every block with it.
+    async fn apply_tcxo_async(&mut self, tcxo: TcxoConfig) -> Result<(), Error> {
+        self.execute_command_async(SetDio3AsTcxoCtrl { config: tcxo })
+            .await?;
+        // The start-up calibration ran before the TCXO was powered and flagged
+        // XOSC_START_ERR
+        self.execute_command_async(ClearDeviceErrors).await?;
+        self.execute_command_async(Calibrate {
+            config: CalibrationConfig::all(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Asynchronously applies a single errata workaround, or undoes it when not `required` and
+    /// reversible.
+    async fn apply_workaround_async(
+        &mut self,
+        workaround: Workaround,
+        required: bool,
+    ) -> Result<(), Error> {
+        match workaround {
+            Workaround::LoRa500kHzModulation => {
+                self.apply_lora_500khz_workaround_async(required).await
+            }
+            Workaround::TxClamp => self.apply_tx_clamp_workaround_async().await,
+            Workaround::InvertedIq => self.apply_inverted_iq_workaround_async(required).await,
+            Workaround::RxGainRetention => self.apply_rx_gain_retention_workaround_async().await,
+        }
+    }
+
+    /// This is the async version of
+    /// [`apply_lora_500khz_workaround`](Device::apply_lora_500khz_workaround).
+    pub async fn apply_lora_500khz_workaround_async(
+        &mut self,
+        bw_500khz: bool,
+    ) -> Result<(), Error> {
+        let mut reg: TxModulation = self.read_register_async().await?;
+        reg.apply_lora_500khz_optimization(bw_500khz);
+        self.write_register_async(reg).await
+    }
+
+    /// This is the async version of
+    /// [`apply_tx_clamp_workaround`](Device::apply_tx_clamp_workaround).
+    pub async fn apply_tx_clamp_workaround_async(&mut self) -> Result<(), Error> {
+        let mut reg: TxClampConfig = self.read_register_async().await?;
+        reg.apply_sx1262_workaround();
+        self.write_register_async(reg).await
+    }
+
+    /// This is the async version of
+    /// [`apply_inverted_iq_workaround`](Device::apply_inverted_iq_workaround).
+    pub async fn apply_inverted_iq_workaround_async(
+        &mut self,
+        inverted: bool,
+    ) -> Result<(), Error> {
+        let mut reg: IqPolaritySetup = self.read_register_async().await?;
+        reg.optimize_for_inverted_iq(inverted);
+        self.write_register_async(reg).await
+    }
+
+    /// Asynchronously sets the LoRa IQ polarity.
+    ///
+    /// This is the async version of [`set_iq_mode`](Device::set_iq_mode).
+    pub async fn set_iq_mode_async(&mut self, iq: IqMode) -> Result<(), Error> {
+        let params = self.write_iq_mode_async(iq).await?;
+        self.cache_lora_packet(params);
+        Ok(())
+    }
+
+    /// Asynchronously sends the cached LoRa packet parameters and the IQ polarity register
+    /// for an IQ polarity, leaving the cached configuration as it is.
+    pub(super) async fn write_iq_mode_async(
+        &mut self,
+        iq: IqMode,
+    ) -> Result<LoRaPacketParams, Error> {
+        let mut params = self.cached_lora_packet().at_step(0)?;
+        params.iq_inversion_enable = iq.is_inverted();
+        self.execute_command_async(SetPacketParams {
+            params: PacketParams::LoRa(params),
+        })
+        .await
+        .at_step(0)?;
+        self.apply_inverted_iq_workaround_async(iq.is_inverted())
+            .await
+            .at_step(1)?;
+        Ok(params)
+    }
+
+    /// This is the async version of
+    /// [`apply_rx_gain_retention_workaround`](Device::apply_rx_gain_retention_workaround).
+    pub async fn apply_rx_gain_retention_workaround_async(&mut self) -> Result<(), Error> {
+        let mut list: RetentionList = self.read_register_async().await?;
+        list.add_entry(RxGain::id())
+            .map_err(|_| Error::register(RetentionList::id(), ErrorKind::Serialization))?;
+        self.write_register_async(list).await
+    }
+
+    /// This is the async version of
+    /// [`apply_implicit_header_timeout_workaround`](Device::apply_implicit_header_timeout_workaround).
+    pub async fn apply_implicit_header_timeout_workaround_async(&mut self) -> Result<(), Error> {
+        self.write_register_async(RtcControl { enabled: false })
+            .await?;
+        let mut mask: EventMask = self.read_register_async().await?;
+        mask.timeout_event = true;
+        self.write_register_async(mask).await
+    }
+
+    /// Asynchronously writes a retention list and reads it back.
+    ///
+    /// This is the async version of [`apply_retention`](Device::apply_retention).
+    pub async fn apply_retention_async(&mut self, list: &RetentionList) -> Result<(), Error> {
+        self.write_register_async(*list).await?;
+        let readback: RetentionList = self.read_register_async().await?;
+        check_retention_readback(list, &readback)
+    }
+
+    /// Asynchronously calibrates the image rejection over a span of frequencies.
+    ///
+    /// This is the async version of [`calibrate_image`](Device::calibrate_image).
+    pub async fn calibrate_image_async(&mut self, config: ImageCalibConfig) -> Result<(), Error> {
+        self.execute_command_async(CalibrateImage { config })
+            .await?;
+        Ok(())
+    }
+
+    /// Asynchronously calibrates the selected blocks and waits for the calibration to
+    /// complete.
+    ///
+    /// This is the async version of [`calibrate`](Device::calibrate).
+    pub async fn calibrate_async(&mut self, config: CalibrationConfig) -> Result<(), Error> {
+        self.execute_command_async(Calibrate { config }).await?;
+        Ok(())
+    }
+
+    /// Asynchronously selects the voltage regulator.
+    ///
+    /// This is the async version of [`set_regulator_mode`](Device::set_regulator_mode).
+    pub async fn set_regulator_mode_async(&mut self, mode: RegulatorMode) -> Result<(), Error> {
+        let mut status = [0];
+        self.execute_raw_async(GetStatus::id(), &[], &mut status)
+            .await
+            .at_step(0)?;
+        let from_xosc = Self::check_regulator_mode_allowed(status[0]).at_step(0)?;
+        if from_xosc {
+            self.execute_command_async(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .await
+            .at_step(0)?;
+        }
+        self.execute_command_async(SetRegulatorMode { mode })
+            .await
+            .at_step(1)?;
+        if from_xosc {
+            self.execute_command_async(SetStandby {
+                config: StandbyConfig::Xosc,
+            })
+            .await
+            .at_step(2)?;
+        }
+        self.cache_regulator_mode(mode);
+        Ok(())
+    }
+
+    /// Asynchronously sets the RX gain, optionally keeping it across warm-start sleep.
+    ///
+    /// This is the async version of [`set_rx_gain`](Device::set_rx_gain).
+    pub async fn set_rx_gain_async(
+        &mut self,
+        gain: RxGain,
+        retain_across_sleep: bool,
+    ) -> Result<(), Error> {
+        let mut status = [0];
+        self.execute_raw_async(GetStatus::id(), &[], &mut status)
+            .await
+            .at_step(0)?;
+        Self::check_rx_gain_allowed(status[0]).at_step(0)?;
+        self.write_register_async(gain).await.at_step(1)?;
+        self.cache_rx_gain(gain);
+        if retain_across_sleep {
+            self.apply_rx_gain_retention_workaround_async()
+                .await
+                .at_step(2)?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously calibrates the selected blocks and waits for the calibration to
+    /// complete, using the provided delay.
+    ///
+    /// This is the async version of [`calibrate_with_delay`](Device::calibrate_with_delay).
+    pub async fn calibrate_with_delay_async(
+        &mut self,
+        config: CalibrationConfig,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error> {
+        self.execute_raw_with_delay_async(Calibrate::id(), &[config.bits()], &mut [], Some(delay))
+            .await
+    }
+}
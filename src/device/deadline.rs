@@ -0,0 +1,206 @@
+//! Transmit, receive and sleep bounded by a [`Duration`]
+//!
+//! The TX and RX bounds are enforced by the radio's own timer, so expiry is reported as
+//! [`TxError::Timeout`] and [`RxError::Timeout`] without a timer on the host. Sleeping is timed
+//! with the owned delay, which can be any `DelayNs` implementation such as
+//! `embassy_time::Delay`.
+
+use core::time::Duration;
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    device::{RxPacket, TxDone},
+    error::{Error, ErrorKind, RxError, StepExt, TxError},
+    GetStatus, RxMode, SetSleep, SleepConfig, Timeout,
+};
+
+/// Time the radio needs to save its configuration after SetSleep, during which the bus must
+/// stay idle
+pub(super) const SLEEP_SETTLE_US: u32 = 500;
+
+/// Maximum time for the radio to answer after a wake-up from warm sleep
+pub(super) const WAKE_TIMEOUT_US: u32 = 10_000;
+
+/// Returns the time to sleep in microseconds, saturating at `u32::MAX`.
+fn sleep_us(duration: Duration) -> u32 {
+    u32::try_from(duration.as_micros())
+        .unwrap_or(u32::MAX)
+        .max(SLEEP_SETTLE_US)
+}
+
+/// Returns the error of a failed wake-up transaction
+fn wake_error<E>(_: E) -> Error {
+    Error::command(GetStatus::id(), ErrorKind::Bus)
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Transmits a packet, giving up once a duration has elapsed.
+    ///
+    /// The duration is converted with [`Timeout::from_duration`] and passed to
+    /// [`transmit`](Device::transmit).
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to send, at most 255
+    /// * `timeout` - Maximum time in TX, at most about 262 s
+    ///
+    /// # Errors
+    /// See [`transmit`](Device::transmit), `TxError::Timeout` is returned once `timeout` has
+    /// elapsed.
+    pub fn transmit_timeout(
+        &mut self,
+        payload: &[u8],
+        timeout: Duration,
+    ) -> Result<TxDone, TxError> {
+        self.transmit(payload, Timeout::from_duration(timeout))
+    }
+
+    /// Receives a packet, giving up once a duration has elapsed.
+    ///
+    /// The duration is converted with [`Timeout::from_duration`] and passed to
+    /// [`receive`](Device::receive) as [`RxMode::Timed`].
+    ///
+    /// # Arguments
+    /// * `bytes` - Buffer to store the payload
+    /// * `timeout` - Maximum time in RX, at most about 262 s
+    ///
+    /// # Errors
+    /// See [`receive`](Device::receive), `RxError::Timeout` is returned once `timeout` has
+    /// elapsed.
+    pub fn receive_timeout(
+        &mut self,
+        bytes: &mut [u8],
+        timeout: Duration,
+    ) -> Result<RxPacket, RxError> {
+        self.receive(bytes, RxMode::Timed(Timeout::from_duration(timeout).0))
+    }
+
+    /// Puts the radio to sleep for a duration, then wakes it up.
+    ///
+    /// The radio sleeps with a warm start so its configuration is retained, and the wait is
+    /// timed with the owned delay. It is woken by a GetStatus transaction, sent without
+    /// waiting for BUSY, which stays high during sleep, then polled with
+    /// [`wait_until_responsive`](Device::wait_until_responsive). The radio is in STDBY_RC on
+    /// return. Registers outside the retention list are lost, see
+    /// [`missing_retention`](Device::missing_retention).
+    ///
+    /// # Arguments
+    /// * `duration` - Time to sleep, at least 500 µs and at most about 71 minutes
+    ///
+    /// # Errors
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | SetSleep                          |
+    /// | 1    | Wake-up transaction               |
+    /// | 2    | Wait until responsive (GetStatus) |
+    pub fn sleep_for(&mut self, duration: Duration) -> Result<(), Error>
+    where
+        DELAY: embedded_hal::delay::DelayNs,
+    {
+        self.execute_command(SetSleep {
+            config: SleepConfig::WARM_START,
+        })
+        .at_step(0)?;
+        self.delay.delay_us(sleep_us(duration));
+
+        self.bus
+            .command(GetStatus::id(), &[], &mut [0])
+            .map_err(wake_error)
+            .at_step(1)?;
+        self.wait_until_responsive(WAKE_TIMEOUT_US).at_step(2)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously transmits a packet, giving up once a duration has elapsed.
+    ///
+    /// This is the async version of [`transmit_timeout`](Device::transmit_timeout).
+    pub async fn transmit_timeout_async(
+        &mut self,
+        payload: &[u8],
+        timeout: Duration,
+    ) -> Result<TxDone, TxError> {
+        self.transmit_async(payload, Timeout::from_duration(timeout))
+            .await
+    }
+
+    /// Asynchronously receives a packet, giving up once a duration has elapsed.
+    ///
+    /// This is the async version of [`receive_timeout`](Device::receive_timeout).
+    ///
+    /// With the `embassy` feature, `EmbassyRadioExt` adds a host-side watchdog over the
+    /// radio's timer.
+    pub async fn receive_timeout_async(
+        &mut self,
+        bytes: &mut [u8],
+        timeout: Duration,
+    ) -> Result<RxPacket, RxError> {
+        self.receive_async(bytes, RxMode::Timed(Timeout::from_duration(timeout).0))
+            .await
+    }
+
+    /// Asynchronously puts the radio to sleep for a duration, then wakes it up.
+    ///
+    /// This is the async version of [`sleep_for`](Device::sleep_for).
+    pub async fn sleep_for_async(&mut self, duration: Duration) -> Result<(), Error>
+    where
+        DELAY: embedded_hal_async::delay::DelayNs,
+    {
+        self.enter_warm_sleep_async().await?;
+        self.delay.delay_us(sleep_us(duration)).await;
+        self.wake_async().await?;
+        self.wait_until_responsive_async(WAKE_TIMEOUT_US)
+            .await
+            .at_step(2)?;
+        Ok(())
+    }
+
+    /// Asynchronously puts the radio to sleep with a warm start, step 0 of
+    /// [`sleep_for`](Device::sleep_for).
+    pub(super) async fn enter_warm_sleep_async(&mut self) -> Result<(), Error> {
+        self.execute_command_async(SetSleep {
+            config: SleepConfig::WARM_START,
+        })
+        .await
+        .at_step(0)?;
+        Ok(())
+    }
+
+    /// Asynchronously sends the wake-up transaction, step 1 of
+    /// [`sleep_for`](Device::sleep_for).
+    pub(super) async fn wake_async(&mut self) -> Result<(), Error> {
+        self.bus
+            .command(GetStatus::id(), &[], &mut [0])
+            .await
+            .map_err(wake_error)
+            .at_step(1)
+    }
+}
@@ -0,0 +1,158 @@
+//! Configuration discovery
+//!
+//! Reads the configuration left in the radio by a bootloader or a co-processor, for products
+//! where the application adopts the existing configuration rather than applying its own. This
+//! is the read side of [`Device::validate_current_config`], which compares the same registers
+//! with a known configuration.
+
+use regiface::Register;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    error::Error, BroadcastAddress, CrcInitialValue, CrcPolynomial, GetPacketType, IqPolaritySetup,
+    LoraSyncWord, ModulationParams, NodeAddress, OcpConfiguration, PacketParams, PacketType,
+    RetentionList, RfFrequencyConfig, RxGain, SyncWord, WhiteningInitialValue,
+};
+
+/// Configuration read from the radio by [`Device::discover_config`]
+///
+/// The packet type and the registers are read from the radio. The RF frequency, modulation
+/// and packet parameters have no read-back path: they are taken from the configuration this
+/// Device applied last, and are `None` when it applied none, e.g. when a bootloader configured
+/// the radio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialRadioConfig {
+    /// Packet type, from GetPacketType
+    pub packet_type: PacketType,
+    /// LoRa sync word register
+    pub lora_sync_word: LoraSyncWord,
+    /// GFSK sync word register, all 8 bytes whatever the sync word length
+    pub gfsk_sync_word: SyncWord,
+    /// GFSK node address register
+    pub node_address: NodeAddress,
+    /// GFSK broadcast address register
+    pub broadcast_address: BroadcastAddress,
+    /// IQ polarity setup register, with the inverted IQ workaround in bit 2
+    pub iq_polarity: IqPolaritySetup,
+    /// RX gain, `None` when the register holds neither gain setting
+    pub rx_gain: Option<RxGain>,
+    /// Over-current protection threshold register
+    pub ocp: OcpConfiguration,
+    /// GFSK whitening seed register, with its reserved bits
+    pub whitening: WhiteningInitialValue,
+    /// GFSK CRC initial value register
+    pub crc_initial_value: CrcInitialValue,
+    /// GFSK CRC polynomial register
+    pub crc_polynomial: CrcPolynomial,
+    /// Registers retained in warm-start sleep
+    pub retention_list: RetentionList,
+    /// RF frequency, only known when applied by this Device
+    pub frequency: Option<RfFrequencyConfig>,
+    /// Modulation parameters, only known when applied by this Device
+    pub modulation: Option<ModulationParams>,
+    /// Packet parameters, only known when applied by this Device
+    pub packet: Option<PacketParams>,
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Reads every readable part of the configuration from the radio.
+    ///
+    /// Nothing is written to the radio, and the configuration cached by the Device is left
+    /// as is. See [`PartialRadioConfig`] for the parts that cannot be read back.
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::Deserialization` - GetPacketType returned an unknown packet type
+    ///
+    /// # Example
+    /// A radio left configured by a bootloader:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, PacketType};
+    ///
+    /// fn inspect(device: &mut Device<impl RadioBus>) {
+    ///     let config = device.discover_config().unwrap();
+    ///     if config.packet_type == PacketType::LoRa {
+    ///         println!("LoRa sync word 0x{:04X}", config.lora_sync_word.value);
+    ///     }
+    /// }
+    /// ```
+    pub fn discover_config(&mut self) -> Result<PartialRadioConfig, Error> {
+        let packet_type = self.execute_command(GetPacketType)?.packet_type;
+        let mut rx_gain = [0];
+        self.read_register_raw(RxGain::id(), &mut rx_gain)?;
+
+        Ok(PartialRadioConfig {
+            packet_type,
+            lora_sync_word: self.read_register()?,
+            gfsk_sync_word: self.read_register()?,
+            node_address: self.read_register()?,
+            broadcast_address: self.read_register()?,
+            iq_polarity: self.read_register()?,
+            rx_gain: RxGain::from_byte(rx_gain[0]).ok(),
+            ocp: self.read_register()?,
+            whitening: self.read_register()?,
+            crc_initial_value: self.read_register()?,
+            crc_polynomial: self.read_register()?,
+            retention_list: self.read_register()?,
+            frequency: self.state.config.map(|config| config.frequency),
+            modulation: self.state.config.map(|config| config.modulation),
+            packet: self.state.config.map(|config| config.packet),
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously reads every readable part of the configuration from the radio.
+    ///
+    /// This is the async version of [`discover_config`](Device::discover_config).
+    pub async fn discover_config_async(&mut self) -> Result<PartialRadioConfig, Error> {
+        let packet_type = self.execute_command_async(GetPacketType).await?.packet_type;
+        let mut rx_gain = [0];
+        self.read_register_raw_async(RxGain::id(), &mut rx_gain)
+            .await?;
+
+        Ok(PartialRadioConfig {
+            packet_type,
+            lora_sync_word: self.read_register_async().await?,
+            gfsk_sync_word: self.read_register_async().await?,
+            node_address: self.read_register_async().await?,
+            broadcast_address: self.read_register_async().await?,
+            iq_polarity: self.read_register_async().await?,
+            rx_gain: RxGain::from_byte(rx_gain[0]).ok(),
+            ocp: self.read_register_async().await?,
+            whitening: self.read_register_async().await?,
+            crc_initial_value: self.read_register_async().await?,
+            crc_polynomial: self.read_register_async().await?,
+            retention_list: self.read_register_async().await?,
+            frequency: self.state.config.map(|config| config.frequency),
+            modulation: self.state.config.map(|config| config.modulation),
+            packet: self.state.config.map(|config| config.packet),
+        })
+    }
+}
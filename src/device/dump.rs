@@ -0,0 +1,134 @@
+//! Register snapshot helpers
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    error::Error,
+    registers::{RegisterInfo, REGISTERS},
+};
+
+/// Longest run of consecutive registers read in one transaction by the dump
+const MAX_SPAN_LEN: usize = 16;
+
+/// Splits the register table into runs of consecutive registers, each read in one transaction.
+fn spans(registers: &[RegisterInfo]) -> impl Iterator<Item = &[RegisterInfo]> {
+    let mut rest = registers;
+    core::iter::from_fn(move || {
+        let first = rest.first()?;
+        let mut end = first.address + first.len as u16;
+        let mut count = 1;
+        while let Some(next) = rest.get(count) {
+            let len = usize::from(end - first.address) + next.len;
+            if next.address != end || len > MAX_SPAN_LEN {
+                break;
+            }
+            end += next.len as u16;
+            count += 1;
+        }
+        let (span, tail) = rest.split_at(count);
+        rest = tail;
+        Some(span)
+    })
+}
+
+/// Returns the length in bytes of a run of consecutive registers.
+fn span_len(span: &[RegisterInfo]) -> usize {
+    span.iter().map(|info| info.len).sum()
+}
+
+/// Reports every register of a span read from `raw`, or the error reading the span.
+fn report_span<F>(span: &[RegisterInfo], raw: &[u8], result: Result<(), Error>, f: &mut F)
+where
+    F: FnMut(&RegisterInfo, Result<&[u8], Error>),
+{
+    let mut offset = 0;
+    for info in span {
+        match result {
+            Ok(()) => f(info, Ok(&raw[offset..offset + info.len])),
+            Err(err) => f(info, Err(err)),
+        }
+        offset += info.len;
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Reads every register in [`REGISTERS`] and reports its raw value.
+    ///
+    /// The callback is invoked once per table entry, in table order, with the register's
+    /// description and either its raw bytes or the error reading it. A failed read does not
+    /// abort the dump. Registers at consecutive addresses are read together in a single
+    /// transaction, see [`read_register_raw`](Device::read_register_raw), and a failed read
+    /// is reported for each of them.
+    ///
+    /// # Important Notes
+    /// - Registers with a [`mode_note`](RegisterInfo::mode_note) may read differently
+    ///   depending on the operating mode
+    /// - Reading the registers does not change the radio's configuration
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn dump(device: &mut Device<impl RadioBus>) {
+    ///     device.dump_registers(|info, value| match value {
+    ///         Ok(bytes) => println!("{} @ 0x{:04X}: {:02X?}", info.name, info.address, bytes),
+    ///         Err(err) => println!("{} @ 0x{:04X}: {}", info.name, info.address, err),
+    ///     });
+    /// }
+    /// ```
+    pub fn dump_registers<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&RegisterInfo, Result<&[u8], Error>),
+    {
+        let mut raw = [0u8; MAX_SPAN_LEN];
+
+        for span in spans(REGISTERS) {
+            let raw = &mut raw[..span_len(span)];
+            let result = self.read_register_raw(span[0].address, raw);
+            report_span(span, raw, result, &mut f);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously reads every register in [`REGISTERS`] and reports its raw value.
+    ///
+    /// This is the async version of [`dump_registers`](Device::dump_registers).
+    pub async fn dump_registers_async<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&RegisterInfo, Result<&[u8], Error>),
+    {
+        let mut raw = [0u8; MAX_SPAN_LEN];
+
+        for span in spans(REGISTERS) {
+            let raw = &mut raw[..span_len(span)];
+            let result = self.read_register_raw_async(span[0].address, raw).await;
+            report_span(span, raw, result, &mut f);
+        }
+    }
+}
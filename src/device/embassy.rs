@@ -0,0 +1,154 @@
+//! Timeouts and sleeps driven by embassy-time
+//!
+//! [`EmbassyRadioExt`] bounds the async transmit and receive twice: the radio's own timer ends
+//! the operation as usual, and [`with_timeout`] is a watchdog on the host in case the radio
+//! never raises its timeout IRQ. Sleeping is timed with [`Timer`], leaving the owned delay
+//! untouched. Nothing of embassy-time is used outside this module.
+
+use embassy_time::{with_timeout, Delay, Duration, Timer};
+
+use super::{
+    deadline::{SLEEP_SETTLE_US, WAKE_TIMEOUT_US},
+    Device,
+};
+use crate::{
+    bus::AsyncRadioBus,
+    device::{RxPacket, TxDone},
+    error::{Error, RxError, StepExt, TxError},
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+    RxMode, Timeout,
+};
+
+/// Time the watchdog leaves the radio beyond its own timeout, covering the commands around
+/// the operation
+const WATCHDOG_MARGIN: Duration = Duration::from_millis(50);
+
+/// Converts an embassy-time duration into a radio timeout.
+fn radio_timeout(duration: Duration) -> Timeout {
+    Timeout::from_duration(core::time::Duration::from_micros(duration.as_micros()))
+}
+
+/// Async [`Device`] operations timed with embassy-time
+///
+/// The names differ from [`transmit_timeout`](Device::transmit_timeout) and friends so that
+/// the inherent methods never shadow them.
+///
+/// # Example
+/// An RX loop, sleeping for a second whenever nothing was received for five:
+/// ```no_run
+/// use embassy_time::Duration;
+/// use sx1262::{bus::AsyncRadioBus, error::RxError, Device, EmbassyRadioExt};
+///
+/// async fn rx_loop(device: &mut Device<impl AsyncRadioBus>) {
+///     let mut buf = [0; 255];
+///     loop {
+///         match device.receive_with_timeout(&mut buf, Duration::from_secs(5)).await {
+///             Ok(packet) => handle(&buf[..packet.len]),
+///             Err(RxError::Timeout) => device.sleep_with_timer(Duration::from_secs(1)).await.unwrap(),
+///             Err(_) => device.resync_async().await.map(drop).unwrap(),
+///         }
+///     }
+/// }
+/// # fn handle(_: &[u8]) {}
+/// ```
+#[allow(async_fn_in_trait)]
+pub trait EmbassyRadioExt {
+    /// Transmits a packet, giving up once a duration has elapsed.
+    ///
+    /// The radio's TX timeout is set to `timeout`, and the operation is abandoned on the
+    /// host if it has not completed `timeout` plus 50 ms later. An abandoned transmit is
+    /// cleaned up with [`resync_async`](Device::resync_async) before returning.
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to send, at most 255
+    /// * `timeout` - Maximum time in TX, at most about 262 s
+    ///
+    /// # Errors
+    /// See [`transmit_async`](Device::transmit_async), `TxError::Timeout` is returned once
+    /// either timer has expired.
+    async fn transmit_with_timeout(
+        &mut self,
+        payload: &[u8],
+        timeout: Duration,
+    ) -> Result<TxDone, TxError>;
+
+    /// Receives a packet, giving up once a duration has elapsed.
+    ///
+    /// The radio's RX timeout is set to `timeout`, with the same host watchdog as
+    /// [`transmit_with_timeout`](EmbassyRadioExt::transmit_with_timeout).
+    ///
+    /// # Arguments
+    /// * `bytes` - Buffer to store the payload
+    /// * `timeout` - Maximum time in RX, at most about 262 s
+    ///
+    /// # Errors
+    /// See [`receive_async`](Device::receive_async), `RxError::Timeout` is returned once
+    /// either timer has expired.
+    async fn receive_with_timeout(
+        &mut self,
+        bytes: &mut [u8],
+        timeout: Duration,
+    ) -> Result<RxPacket, RxError>;
+
+    /// Puts the radio to sleep for a duration with [`Timer`], then wakes it up.
+    ///
+    /// This is [`sleep_for_async`](Device::sleep_for_async) timed with embassy-time, polling
+    /// the radio after wake-up with [`Delay`].
+    ///
+    /// # Arguments
+    /// * `duration` - Time to sleep, at least 500 µs
+    ///
+    /// # Errors
+    /// See [`sleep_for`](Device::sleep_for)
+    async fn sleep_with_timer(&mut self, duration: Duration) -> Result<(), Error>;
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> EmbassyRadioExt
+    for Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    async fn transmit_with_timeout(
+        &mut self,
+        payload: &[u8],
+        timeout: Duration,
+    ) -> Result<TxDone, TxError> {
+        let transmit = self.transmit_async(payload, radio_timeout(timeout));
+        match with_timeout(timeout + WATCHDOG_MARGIN, transmit).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.resync_async().await?;
+                Err(TxError::Timeout)
+            }
+        }
+    }
+
+    async fn receive_with_timeout(
+        &mut self,
+        bytes: &mut [u8],
+        timeout: Duration,
+    ) -> Result<RxPacket, RxError> {
+        let receive = self.receive_async(bytes, RxMode::Timed(radio_timeout(timeout).0));
+        match with_timeout(timeout + WATCHDOG_MARGIN, receive).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.resync_async().await?;
+                Err(RxError::Timeout)
+            }
+        }
+    }
+
+    async fn sleep_with_timer(&mut self, duration: Duration) -> Result<(), Error> {
+        self.enter_warm_sleep_async().await?;
+        Timer::after(duration.max(Duration::from_micros(SLEEP_SETTLE_US.into()))).await;
+        self.wake_async().await?;
+        self.wait_until_responsive_with_delay_async(WAKE_TIMEOUT_US, &mut Delay)
+            .await
+            .at_step(2)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+//! RF frequency changes
+//!
+//! [`Device::set_rf_frequency`] composes what a frequency change needs on top of the raw
+//! SetRfFrequency command: range validation, crystal error correction and image calibration.
+//! [`Device::lock_pll`] holds the synthesizer at a frequency and checks that it locked.
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    error::{Error, ErrorKind, StepExt},
+    CalibrateImage, ClearDeviceErrors, DeviceSelect, GetDeviceErrors, GetStatus, OperatingMode,
+    RfFrequencyConfig, SetFs, SetRfFrequency, SetStandby, StandbyConfig,
+};
+
+/// Checks that a raw status byte reports FS, as expected after SetFs.
+fn check_synthesizing(status: u8) -> Result<(), Error> {
+    let mode = OperatingMode::try_from((status >> 4) & 0x7).ok();
+    if mode == Some(OperatingMode::FrequencySynthesizer) {
+        return Ok(());
+    }
+    Err(Error::command(
+        SetFs::id(),
+        ErrorKind::WrongMode { found: mode },
+    ))
+}
+
+/// Returns the error of a PLL that did not lock at a frequency.
+fn pll_lock_failed(frequency: RfFrequencyConfig) -> Error {
+    Error::command(
+        GetDeviceErrors::id(),
+        ErrorKind::PllLockFailed {
+            frequency: frequency.frequency,
+        },
+    )
+    .at_step(6)
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Checks that a frequency is supported by the device variant of the cached
+    /// configuration, or by the SX1262 when no configuration was applied.
+    fn validate_frequency(&self, frequency: RfFrequencyConfig) -> Result<(), Error> {
+        let device = self.state.config.map_or(DeviceSelect::default(), |config| {
+            config.pa_config.device_sel
+        });
+        frequency.validate(device).map_err(|_| {
+            Error::command(
+                SetRfFrequency::id(),
+                ErrorKind::FrequencyOutOfRange {
+                    frequency: frequency.frequency,
+                },
+            )
+        })
+    }
+
+    /// Returns whether a frequency change must be preceded by an image calibration.
+    fn needs_image_calibration(&self, frequency: RfFrequencyConfig) -> bool {
+        self.settings.auto_image_calibration
+            && !self
+                .state
+                .image_calibration
+                .is_some_and(|calibration| calibration.covers(frequency.frequency))
+    }
+
+    /// Records a frequency set on the radio in the cached configuration.
+    fn cache_frequency(&mut self, frequency: RfFrequencyConfig) {
+        if let Some(config) = &mut self.state.config {
+            config.frequency = frequency;
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Changes the RF frequency.
+    ///
+    /// The frequency is checked against the range of the device variant in the cached
+    /// configuration, corrected for the crystal error set with
+    /// [`set_frequency_correction_ppb`](Device::set_frequency_correction_ppb) and sent rounded
+    /// to the nearest PLL step. When the image calibration on the radio does not cover the new
+    /// frequency, the radio is placed in STDBY_RC and the image is calibrated first, unless
+    /// disabled with [`set_auto_image_calibration`](Device::set_auto_image_calibration). The
+    /// cached configuration, if any, is updated on success.
+    ///
+    /// # Arguments
+    /// * `frequency` - The RF frequency
+    ///
+    /// # Errors
+    /// `ErrorKind::FrequencyOutOfRange` is returned at step 0 without any command sent.
+    /// Other errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | Validation                        |
+    /// | 1    | SetStandby (STDBY_RC)             |
+    /// | 2    | CalibrateImage                    |
+    /// | 3    | SetRfFrequency                    |
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, RfFrequencyConfig};
+    ///
+    /// fn hop(device: &mut Device<impl RadioBus>) {
+    ///     // Another band is calibrated first
+    ///     device.set_rf_frequency(RfFrequencyConfig { frequency: 915_000_000 }).unwrap();
+    /// }
+    /// ```
+    pub fn set_rf_frequency(&mut self, frequency: RfFrequencyConfig) -> Result<(), Error> {
+        self.validate_frequency(frequency).at_step(0)?;
+
+        if self.needs_image_calibration(frequency) {
+            self.execute_command(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .at_step(1)?;
+            self.execute_command(CalibrateImage::for_frequency(frequency.frequency))
+                .at_step(2)?;
+        }
+
+        self.execute_command(SetRfFrequency {
+            config: self.corrected_frequency(frequency),
+        })
+        .at_step(3)?;
+        self.cache_frequency(frequency);
+        Ok(())
+    }
+
+    /// Tunes the PLL to a frequency and holds it there in FS.
+    ///
+    /// The frequency is set as with [`set_rf_frequency`](Device::set_rf_frequency), the radio
+    /// enters FS and the status and device errors are read back to check that the PLL locked.
+    /// A lock error is cleared on the radio before being returned. The radio stays in FS until
+    /// [`unlock_pll`](Device::unlock_pll) or another mode change, e.g. for spur hunting or
+    /// crystal trimming.
+    ///
+    /// # Arguments
+    /// * `frequency` - The RF frequency
+    ///
+    /// # Errors
+    /// Errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                                                      |
+    /// |------|----------------------------------------------------------------|
+    /// | 0-3  | As [`set_rf_frequency`](Device::set_rf_frequency)              |
+    /// | 4    | SetFs                                                          |
+    /// | 5    | GetStatus, `ErrorKind::WrongMode` when the radio is not in FS  |
+    /// | 6    | GetDeviceErrors, `ErrorKind::PllLockFailed` on a lock error    |
+    /// | 7    | ClearDeviceErrors, after a lock error                          |
+    ///
+    /// # Example
+    /// Holding a carrier frequency while trimming the crystal:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, RfFrequencyConfig};
+    ///
+    /// fn hold(device: &mut Device<impl RadioBus>) {
+    ///     device.lock_pll(RfFrequencyConfig { frequency: 868_100_000 }).unwrap();
+    ///     // ...
+    ///     device.unlock_pll().unwrap();
+    /// }
+    /// ```
+    pub fn lock_pll(&mut self, frequency: RfFrequencyConfig) -> Result<(), Error> {
+        self.set_rf_frequency(frequency)?;
+        self.execute_command(SetFs).at_step(4)?;
+
+        let mut status = [0];
+        self.execute_raw(GetStatus::id(), &[], &mut status)
+            .at_step(5)?;
+        check_synthesizing(status[0]).at_step(5)?;
+
+        let errors = self.execute_command(GetDeviceErrors).at_step(6)?.errors;
+        if errors.pll_lock_err {
+            self.execute_command(ClearDeviceErrors).at_step(7)?;
+            return Err(pll_lock_failed(frequency));
+        }
+        Ok(())
+    }
+
+    /// Releases the PLL held by [`lock_pll`](Device::lock_pll), returning to STDBY_RC.
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    pub fn unlock_pll(&mut self) -> Result<(), Error> {
+        self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously changes the RF frequency.
+    ///
+    /// This is the async version of [`set_rf_frequency`](Device::set_rf_frequency).
+    pub async fn set_rf_frequency_async(
+        &mut self,
+        frequency: RfFrequencyConfig,
+    ) -> Result<(), Error> {
+        self.validate_frequency(frequency).at_step(0)?;
+
+        if self.needs_image_calibration(frequency) {
+            self.execute_command_async(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .await
+            .at_step(1)?;
+            self.execute_command_async(CalibrateImage::for_frequency(frequency.frequency))
+                .await
+                .at_step(2)?;
+        }
+
+        self.execute_command_async(SetRfFrequency {
+            config: self.corrected_frequency(frequency),
+        })
+        .await
+        .at_step(3)?;
+        self.cache_frequency(frequency);
+        Ok(())
+    }
+
+    /// Asynchronously tunes the PLL to a frequency and holds it there in FS.
+    ///
+    /// This is the async version of [`lock_pll`](Device::lock_pll).
+    pub async fn lock_pll_async(&mut self, frequency: RfFrequencyConfig) -> Result<(), Error> {
+        self.set_rf_frequency_async(frequency).await?;
+        self.execute_command_async(SetFs).await.at_step(4)?;
+
+        let mut status = [0];
+        self.execute_raw_async(GetStatus::id(), &[], &mut status)
+            .await
+            .at_step(5)?;
+        check_synthesizing(status[0]).at_step(5)?;
+
+        let errors = self
+            .execute_command_async(GetDeviceErrors)
+            .await
+            .at_step(6)?
+            .errors;
+        if errors.pll_lock_err {
+            self.execute_command_async(ClearDeviceErrors)
+                .await
+                .at_step(7)?;
+            return Err(pll_lock_failed(frequency));
+        }
+        Ok(())
+    }
+
+    /// Asynchronously releases the PLL held by [`lock_pll_async`](Device::lock_pll_async).
+    ///
+    /// This is the async version of [`unlock_pll`](Device::unlock_pll).
+    pub async fn unlock_pll_async(&mut self) -> Result<(), Error> {
+        self.execute_command_async(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+//! Scoped radio modes and IRQ suspension
+//!
+//! [`ModeGuard`] enters RX, continuous wave TX or CAD and puts the radio back into the mode
+//! it was in when the guard ends, on early returns too. [`IrqSuspendGuard`] keeps the DIO
+//! lines quiet and restores the IRQ routing when it ends. Cleanup on drop cannot report errors
+//! or await, so guards are only available for the blocking interface.
+
+use core::{
+    convert::Infallible,
+    ops::{Deref, DerefMut},
+};
+
+use regiface::{Command, ToByteArray};
+
+use super::Device;
+use crate::{
+    bus::RadioBus,
+    error::{Error, ErrorKind, StepExt},
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+    ClearIrqStatus, DioIrqConfig, GetStatus, IrqMask, OperatingMode, RxMode, SetCad,
+    SetDioIrqParams, SetFs, SetRx, SetStandby, SetTxContinuousWave, StandbyConfig,
+};
+
+/// IRQs cleared before entering RX
+const GUARDED_RX_IRQS: IrqMask = IrqMask::RX_DONE
+    .union(IrqMask::TIMEOUT)
+    .union(IrqMask::CRC_ERROR)
+    .union(IrqMask::HEADER_ERROR)
+    .union(IrqMask::PREAMBLE_DETECTED)
+    .union(IrqMask::SYNC_WORD_VALID);
+
+/// IRQs cleared before starting CAD
+const GUARDED_CAD_IRQS: IrqMask = IrqMask::CAD_DONE.union(IrqMask::CAD_DETECTED);
+
+/// Mode the radio is put back into when a guard ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PriorMode {
+    Standby(StandbyConfig),
+    Fs,
+}
+
+impl PriorMode {
+    /// Returns the mode to restore from a raw status byte.
+    ///
+    /// Modes that cannot be restored, RX and TX, and unknown modes fall back to STDBY_RC.
+    fn from_status(status: u8) -> Self {
+        match OperatingMode::try_from((status >> 4) & 0x7) {
+            Ok(OperatingMode::StandbyXosc) => Self::Standby(StandbyConfig::Xosc),
+            Ok(OperatingMode::FrequencySynthesizer) => Self::Fs,
+            _ => Self::Standby(StandbyConfig::Rc),
+        }
+    }
+}
+
+/// Guard keeping the radio in RX, continuous wave TX or CAD for a scope
+///
+/// Created with [`Device::rx_guard`], [`Device::tx_cw_guard`] or [`Device::cad_guard`]. The
+/// guard dereferences to the [`Device`], so IRQs can be waited for and packets read while it
+/// is held.
+///
+/// End the guard with [`finish`](ModeGuard::finish) to see cleanup errors. When the guard is
+/// dropped instead, e.g. on an early return with `?`, the same cleanup runs and its first
+/// error is kept for [`Device::take_deferred_error`].
+///
+/// # Cleanup
+/// | Step | Operation                               |
+/// |------|-----------------------------------------|
+/// | 0    | SetStandby or SetFs to the prior mode   |
+/// | 1    | ClearIrqStatus for every IRQ            |
+///
+/// # Example
+/// ```no_run
+/// use sx1262::{bus::RadioBus, Device, Error, RxMode};
+///
+/// fn listen(device: &mut Device<impl RadioBus>) -> Result<(), Error> {
+///     let mut rx = device.rx_guard(RxMode::Continuous)?;
+///     // The guard restores the prior mode on an early return
+///     rx.read_buffer(0, &mut [0; 4])?;
+///     rx.finish()
+/// }
+/// ```
+pub struct ModeGuard<'a, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    device: &'a mut Device<BUS, FE, BUSY, RESET, DIO1, DELAY>,
+    prior: PriorMode,
+    finished: bool,
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Enters RX until the returned guard ends.
+    ///
+    /// # Arguments
+    /// * `mode` - The receive mode
+    ///
+    /// # Errors
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | GetStatus, ClearIrqStatus         |
+    /// | 1    | SetRx                             |
+    pub fn rx_guard(
+        &mut self,
+        mode: RxMode,
+    ) -> Result<ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>, Error> {
+        self.enter_guarded(SetRx { mode }, GUARDED_RX_IRQS)
+    }
+
+    /// Transmits a continuous wave until the returned guard ends.
+    ///
+    /// # Errors
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | GetStatus                         |
+    /// | 1    | SetTxContinuousWave               |
+    pub fn tx_cw_guard(
+        &mut self,
+    ) -> Result<ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>, Error> {
+        self.enter_guarded(SetTxContinuousWave, IrqMask::empty())
+    }
+
+    /// Starts CAD until the returned guard ends.
+    ///
+    /// The CAD exit mode set with SetCadParams applies, with CAD_RX the radio stays in RX
+    /// after a detection until the guard ends. CAD_DONE and CAD_DETECTED are enabled and
+    /// routed to DIO1 when the IRQ routing is known, see
+    /// [`modify_irq_routing`](Device::modify_irq_routing).
+    ///
+    /// # Errors
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | SetDioIrqParams, GetStatus,       |
+    /// |      | ClearIrqStatus                    |
+    /// | 1    | SetCad                            |
+    pub fn cad_guard(&mut self) -> Result<ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>, Error> {
+        self.ensure_irq_routes(IrqMask::CAD_DONE | IrqMask::CAD_DETECTED)
+            .at_step(0)?;
+        self.enter_guarded(SetCad, GUARDED_CAD_IRQS)
+    }
+
+    /// Reads the current mode, clears the IRQs the guarded mode raises and sends the command
+    /// entering it.
+    fn enter_guarded<C>(
+        &mut self,
+        command: C,
+        irq_mask: IrqMask,
+    ) -> Result<ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>, Error>
+    where
+        C: Command<IdType = u8>,
+        C::CommandParameters: ToByteArray<Error = Infallible>,
+    {
+        // The raw status byte, as Status rejects some command statuses left by earlier commands
+        let mut status = [0];
+        self.execute_raw(GetStatus::id(), &[], &mut status)
+            .at_step(0)?;
+        if !irq_mask.is_empty() {
+            self.execute_command(ClearIrqStatus { irq_mask })
+                .at_step(0)?;
+        }
+        self.expect_irqs_cleared(irq_mask);
+        self.execute_command(command).at_step(1)?;
+
+        Ok(ModeGuard {
+            device: self,
+            prior: PriorMode::from_status(status[0]),
+            finished: false,
+        })
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Puts the radio back into the prior mode and clears the IRQs raised meanwhile.
+    ///
+    /// # Errors
+    /// See the [cleanup steps](ModeGuard#cleanup)
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.finished = true;
+        self.restore()
+    }
+
+    fn restore(&mut self) -> Result<(), Error> {
+        match self.prior {
+            PriorMode::Standby(config) => self.device.execute_command(SetStandby { config }),
+            PriorMode::Fs => self.device.execute_command(SetFs),
+        }
+        .at_step(0)?;
+        self.device
+            .execute_command(ClearIrqStatus {
+                irq_mask: IrqMask::all(),
+            })
+            .at_step(1)?;
+        Ok(())
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Deref for ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    type Target = Device<BUS, FE, BUSY, RESET, DIO1, DELAY>;
+
+    fn deref(&self) -> &Self::Target {
+        self.device
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> DerefMut
+    for ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.device
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Drop for ModeGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Err(err) = self.restore() {
+            self.device.state.deferred_error = Some(err);
+        }
+    }
+}
+
+/// Guard keeping DIO1, DIO2 and DIO3 from signalling IRQs for a critical section
+///
+/// Created with [`Device::suspend_irqs`]. The guard dereferences to the [`Device`]. The IRQ
+/// enable is kept, so IRQs raised meanwhile accumulate in the IRQ status as usual.
+///
+/// End the guard with [`resume`](IrqSuspendGuard::resume) to see errors restoring the routing.
+/// When the guard is dropped instead, the routing is restored the same way and the error is
+/// kept for [`Device::take_deferred_error`].
+///
+/// # Example
+/// ```no_run
+/// use sx1262::{bus::RadioBus, Device};
+///
+/// fn critical_section(device: &mut Device<impl RadioBus>) {
+///     let suspended = device.suspend_irqs().unwrap();
+///     // IRQs raised here stay pending in the IRQ status
+///     suspended.resume().unwrap();
+/// }
+/// ```
+pub struct IrqSuspendGuard<'a, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    device: &'a mut Device<BUS, FE, BUSY, RESET, DIO1, DELAY>,
+    routing: DioIrqConfig,
+    resumed: bool,
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Stops the radio signalling IRQs on the DIO lines until the returned guard ends.
+    ///
+    /// SetDioIrqParams is sent with the IRQ enable of [`irq_routing`](Device::irq_routing)
+    /// and every DIO mask cleared. When the guard ends the routing is sent again unchanged,
+    /// and IRQs raised meanwhile raise DIO1 and are read and cleared as usual, e.g. with
+    /// [`wait_irq`](Device::wait_irq). Helpers run through the guard route the IRQs they wait
+    /// for to DIO1 again.
+    ///
+    /// # Errors
+    /// * `ErrorKind::IrqRoutingUnknown` - The IRQ routing was not set through the driver
+    /// * `ErrorKind::Bus` - Bus communication failed
+    pub fn suspend_irqs(
+        &mut self,
+    ) -> Result<IrqSuspendGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>, Error> {
+        let routing = self.state.irq_routing.ok_or(Error::command(
+            SetDioIrqParams::id(),
+            ErrorKind::IrqRoutingUnknown,
+        ))?;
+        self.execute_command(SetDioIrqParams {
+            config: DioIrqConfig {
+                irq_mask: routing.irq_mask,
+                ..DioIrqConfig::default()
+            },
+        })?;
+
+        Ok(IrqSuspendGuard {
+            device: self,
+            routing,
+            resumed: false,
+        })
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> IrqSuspendGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Restores the IRQ routing set before the suspension.
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    pub fn resume(mut self) -> Result<(), Error> {
+        self.resumed = true;
+        self.restore()
+    }
+
+    fn restore(&mut self) -> Result<(), Error> {
+        self.device.execute_command(SetDioIrqParams {
+            config: self.routing,
+        })?;
+        Ok(())
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Deref
+    for IrqSuspendGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    type Target = Device<BUS, FE, BUSY, RESET, DIO1, DELAY>;
+
+    fn deref(&self) -> &Self::Target {
+        self.device
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> DerefMut
+    for IrqSuspendGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.device
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Drop
+    for IrqSuspendGuard<'_, BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    fn drop(&mut self) {
+        if self.resumed {
+            return;
+        }
+        if let Err(err) = self.restore() {
+            self.device.state.deferred_error = Some(err);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! One-call bring-up
+//!
+//! Composes the TCXO and calibration steps with
+//! [`apply_config`](Device::apply_config) into the full bring-up sequence of the datasheet.
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::{GfskInit, LoRaInit, RadioConfig},
+    error::{Error, InitError, InitStep},
+    opcode, Calibrate, CalibrationConfig, RegulatorMode, SetRegulatorMode, SetStandby,
+    StandbyConfig,
+};
+
+/// Attaches a bring-up step to a device error
+fn at(step: InitStep) -> impl FnOnce(Error) -> InitError {
+    move |source| InitError::Device { step, source }
+}
+
+/// Names the bring-up step of an [`apply_config`](Device::apply_config) error
+///
+/// Step 2 of [`apply_config`](Device::apply_config) also powers the TCXO and calibrates, which
+/// are told apart by the opcode.
+fn config_step(source: Error) -> InitError {
+    let step = match source {
+        Error::Command {
+            opcode: opcode::SET_DIO3_AS_TCXO_CTRL | opcode::CLEAR_DEVICE_ERRORS,
+            step: Some(2),
+            ..
+        } => InitStep::Tcxo,
+        Error::Command {
+            opcode: opcode::CALIBRATE,
+            step: Some(2),
+            ..
+        } => InitStep::Calibrate,
+        _ => InitStep::from_config_step(source.step()),
+    };
+    InitError::Device { step, source }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Brings the radio up for LoRa operation.
+    ///
+    /// Performs the full bring-up sequence of the datasheet: standby, regulator, the optional
+    /// TCXO, calibration of every block, then the rest of
+    /// [`apply_config`](Device::apply_config) with the configuration returned by
+    /// [`LoRaInit::config`]. The TCXO and the calibration following it are sent by
+    /// [`apply_config`](Device::apply_config), so that they are part of the cached
+    /// configuration restored by [`recover`](Device::recover). The PA configuration and TX power are
+    /// resolved by [`TxPowerPlan`](crate::TxPowerPlan) for the device and output power.
+    ///
+    /// # Arguments
+    /// * `init` - Bring-up parameters
+    ///
+    /// # Errors
+    /// * `InitError::Config` - The bring-up parameters are inconsistent, see
+    ///   [`LoRaInit::config`]
+    /// * `InitError::Device` - A bus transaction failed, with the failing [`InitStep`]
+    pub fn init_lora(&mut self, init: LoRaInit) -> Result<(), InitError> {
+        let config = init.config()?;
+        self.init(init.regulator_mode, &config)
+    }
+
+    /// Brings the radio up for GFSK operation.
+    ///
+    /// Performs the same sequence as [`init_lora`](Device::init_lora) with the configuration
+    /// returned by [`GfskInit::config`].
+    ///
+    /// # Arguments
+    /// * `init` - Bring-up parameters
+    ///
+    /// # Errors
+    /// * `InitError::Config` - The bring-up parameters are inconsistent, see
+    ///   [`GfskInit::config`]
+    /// * `InitError::Device` - A bus transaction failed, with the failing [`InitStep`]
+    pub fn init_gfsk(&mut self, init: GfskInit) -> Result<(), InitError> {
+        let config = init.config()?;
+        self.init(init.regulator_mode, &config)
+    }
+
+    /// Runs the steps preceding the configuration, then applies it.
+    fn init(
+        &mut self,
+        regulator_mode: RegulatorMode,
+        config: &RadioConfig,
+    ) -> Result<(), InitError> {
+        self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .map_err(at(InitStep::Standby))?;
+        self.execute_command(SetRegulatorMode {
+            mode: regulator_mode,
+        })
+        .map_err(at(InitStep::RegulatorMode))?;
+        // With a TCXO, apply_config powers it and calibrates
+        if config.tcxo.is_none() {
+            self.execute_command(Calibrate {
+                config: CalibrationConfig::all(),
+            })
+            .map_err(at(InitStep::Calibrate))?;
+        }
+
+        self.apply_config(config).map_err(config_step)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously brings the radio up for LoRa operation.
+    ///
+    /// This is the async version of [`init_lora`](Device::init_lora).
+    pub async fn init_lora_async(&mut self, init: LoRaInit) -> Result<(), InitError> {
+        let config = init.config()?;
+        self.init_async(init.regulator_mode, &config).await
+    }
+
+    /// Asynchronously brings the radio up for GFSK operation.
+    ///
+    /// This is the async version of [`init_gfsk`](Device::init_gfsk).
+    pub async fn init_gfsk_async(&mut self, init: GfskInit) -> Result<(), InitError> {
+        let config = init.config()?;
+        self.init_async(init.regulator_mode, &config).await
+    }
+
+    /// Asynchronously runs the steps preceding the configuration, then applies it.
+    async fn init_async(
+        &mut self,
+        regulator_mode: RegulatorMode,
+        config: &RadioConfig,
+    ) -> Result<(), InitError> {
+        self.execute_command_async(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .await
+        .map_err(at(InitStep::Standby))?;
+        self.execute_command_async(SetRegulatorMode {
+            mode: regulator_mode,
+        })
+        .await
+        .map_err(at(InitStep::RegulatorMode))?;
+        // With a TCXO, apply_config_async powers it and calibrates
+        if config.tcxo.is_none() {
+            self.execute_command_async(Calibrate {
+                config: CalibrationConfig::all(),
+            })
+            .await
+            .map_err(at(InitStep::Calibrate))?;
+        }
+
+        self.apply_config_async(config).await.map_err(config_step)
+    }
+}
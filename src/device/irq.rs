@@ -0,0 +1,476 @@
+//! DIO1 interrupt line helpers
+
+use embedded_hal::digital::InputPin;
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    error::{DioPin, Error, ErrorKind, PinOwner},
+    ClearIrqStatus, DioIrqConfig, GetIrqStatus, IrqMask, SetDioIrqParams,
+};
+
+/// IRQs read by [`Device::wait_irq`] or [`Device::poll_rx`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IrqEvent {
+    /// The IRQs that were pending
+    pub irq_mask: IrqMask,
+    /// Time the IRQs were observed, when a clock is installed with
+    /// [`set_clock`](Device::set_clock)
+    pub at: Option<u32>,
+}
+
+/// Handle reading and clearing the IRQ status from an interrupt context
+///
+/// Created with [`Device::split_irq`] around its own bus, usually a second `SpiDevice` on the
+/// bus shared with the [`Device`], so that a DIO1 interrupt handler can acknowledge IRQs while
+/// another task owns the Device. The handle is `Send` whenever its bus is.
+///
+/// # Concurrent access
+/// - Each command is a single bus transaction, so the radio only ever sees whole commands
+///   from either side. The shared-bus adapter must be safe to use from every context involved,
+///   e.g. the `CriticalSectionDevice` of `embedded-hal-bus` when one of them is an interrupt
+/// - The handle does not wait for BUSY. Only use it once the radio has raised DIO1, which it
+///   does after finishing the operation that raised the IRQ, and not while the Device has a
+///   command in flight
+/// - [`take_irq`](IrqHandle::take_irq) is two transactions, a Device command may run between
+///   them without affecting either
+/// - Leave the IRQs read here to the handle, the Device helpers that poll GetIrqStatus would
+///   otherwise find them already cleared
+/// - The Device does not see RX_DONE read here, call
+///   [`mark_packet_pending`](Device::mark_packet_pending) before reading the payload
+///
+/// # Example
+/// An interrupt reading the IRQs through a second device on a shared bus:
+/// ```no_run
+/// use embedded_hal::spi::SpiDevice;
+/// use sx1262::Device;
+///
+/// # #[cfg(feature = "sync")]
+/// fn create<A: SpiDevice, B: SpiDevice>(main: A, interrupt: B) {
+///     let (mut device, mut irq) = Device::new(main).split_irq(interrupt);
+///
+///     // In the DIO1 interrupt handler
+///     let irq_mask = irq.take_irq().unwrap();
+/// }
+/// ```
+pub struct IrqHandle<BUS> {
+    device: Device<BUS>,
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Splits off an [`IrqHandle`] for reading and clearing the IRQ status elsewhere.
+    ///
+    /// # Arguments
+    /// * `irq_bus` - Bus used by the handle, usually a second device on a shared SPI bus
+    ///
+    /// # Returns
+    /// The Device and the handle
+    pub fn split_irq<IRQBUS>(self, irq_bus: IRQBUS) -> (Self, IrqHandle<IRQBUS>) {
+        (
+            self,
+            IrqHandle {
+                device: Device::new(irq_bus),
+            },
+        )
+    }
+}
+
+impl<BUS> IrqHandle<BUS> {
+    /// Releases the bus of the handle.
+    pub fn release(self) -> BUS {
+        self.device.release()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Changes the IRQ enable and DIO mapping from the one last set on the radio.
+    ///
+    /// The closure is applied to [`irq_routing`](Device::irq_routing), or to the empty
+    /// [`Default`] when it is not known, and SetDioIrqParams is only sent when the routing
+    /// changed. Helpers add the IRQs they wait for the same way, e.g.
+    /// [`transmit`](Device::transmit) enables and routes TX_DONE and TIMEOUT to DIO1, so the
+    /// routes of the application are kept.
+    ///
+    /// # Arguments
+    /// * `modify` - Closure changing the routing
+    ///
+    /// # Returns
+    /// The routing now set on the radio
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Command` with `ErrorKind::PinInUse` - A new route goes to DIO3 while it powers
+    ///   a TCXO, see [`force_irq_routing`](Device::force_irq_routing)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, IrqMask};
+    ///
+    /// fn route_rx(device: &mut Device<impl RadioBus>) {
+    ///     // Helpers add their own routes to this one, e.g. TX_DONE for transmit
+    ///     device
+    ///         .modify_irq_routing(|routing| {
+    ///             routing.irq_mask |= IrqMask::RX_DONE;
+    ///             routing.dio1_mask |= IrqMask::RX_DONE;
+    ///         })
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn modify_irq_routing(
+        &mut self,
+        modify: impl FnOnce(&mut DioIrqConfig),
+    ) -> Result<DioIrqConfig, Error> {
+        self.route_irqs(modify, false)
+    }
+
+    /// Changes the IRQ enable and DIO mapping like
+    /// [`modify_irq_routing`](Device::modify_irq_routing), routing IRQs to DIO3 even while it
+    /// powers a TCXO.
+    ///
+    /// The radio ignores IRQ routes to DIO3 once it drives a TCXO, so they never arrive.
+    pub fn force_irq_routing(
+        &mut self,
+        modify: impl FnOnce(&mut DioIrqConfig),
+    ) -> Result<DioIrqConfig, Error> {
+        self.route_irqs(modify, true)
+    }
+
+    /// Changes the IRQ routing, rejecting new DIO3 routes while it powers a TCXO unless
+    /// `force` is set.
+    fn route_irqs(
+        &mut self,
+        modify: impl FnOnce(&mut DioIrqConfig),
+        force: bool,
+    ) -> Result<DioIrqConfig, Error> {
+        let current = self.state.irq_routing;
+        let mut routing = current.unwrap_or_default();
+        modify(&mut routing);
+        if !force {
+            self.check_dio3_for_irqs(current, &routing)?;
+        }
+
+        if current != Some(routing) {
+            self.execute_command(SetDioIrqParams { config: routing })?;
+            if let Some(config) = &mut self.state.config {
+                config.irq = routing;
+            }
+        }
+        Ok(routing)
+    }
+
+    /// Enables the given IRQs and routes them to DIO1, when the routing is known.
+    ///
+    /// An unknown routing is left to the application, which may have set it before the
+    /// Device was created.
+    pub(super) fn ensure_irq_routes(&mut self, required: IrqMask) -> Result<(), Error> {
+        if self.state.irq_routing.is_some() {
+            self.modify_irq_routing(|routing| add_routes(routing, required))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously changes the IRQ enable and DIO mapping from the one last set on the
+    /// radio.
+    ///
+    /// This is the async version of [`modify_irq_routing`](Device::modify_irq_routing).
+    pub async fn modify_irq_routing_async(
+        &mut self,
+        modify: impl FnOnce(&mut DioIrqConfig),
+    ) -> Result<DioIrqConfig, Error> {
+        self.route_irqs_async(modify, false).await
+    }
+
+    /// Asynchronously changes the IRQ enable and DIO mapping, routing IRQs to DIO3 even while
+    /// it powers a TCXO.
+    ///
+    /// This is the async version of [`force_irq_routing`](Device::force_irq_routing).
+    pub async fn force_irq_routing_async(
+        &mut self,
+        modify: impl FnOnce(&mut DioIrqConfig),
+    ) -> Result<DioIrqConfig, Error> {
+        self.route_irqs_async(modify, true).await
+    }
+
+    /// Asynchronously changes the IRQ routing, rejecting new DIO3 routes while it powers a
+    /// TCXO unless `force` is set.
+    async fn route_irqs_async(
+        &mut self,
+        modify: impl FnOnce(&mut DioIrqConfig),
+        force: bool,
+    ) -> Result<DioIrqConfig, Error> {
+        let current = self.state.irq_routing;
+        let mut routing = current.unwrap_or_default();
+        modify(&mut routing);
+        if !force {
+            self.check_dio3_for_irqs(current, &routing)?;
+        }
+
+        if current != Some(routing) {
+            self.execute_command_async(SetDioIrqParams { config: routing })
+                .await?;
+            if let Some(config) = &mut self.state.config {
+                config.irq = routing;
+            }
+        }
+        Ok(routing)
+    }
+
+    /// Asynchronously enables the given IRQs and routes them to DIO1, when the routing is
+    /// known.
+    pub(super) async fn ensure_irq_routes_async(&mut self, required: IrqMask) -> Result<(), Error> {
+        if self.state.irq_routing.is_some() {
+            self.modify_irq_routing_async(|routing| add_routes(routing, required))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Rejects IRQ routes to DIO3 that `routing` adds to `current` while DIO3 powers a TCXO.
+    fn check_dio3_for_irqs(
+        &self,
+        current: Option<DioIrqConfig>,
+        routing: &DioIrqConfig,
+    ) -> Result<(), Error> {
+        let routed = current.map_or(IrqMask::empty(), |current| current.dio3_mask);
+        if self.state.dio3_tcxo && !routing.dio3_mask.difference(routed).is_empty() {
+            return Err(Error::command(
+                SetDioIrqParams::id(),
+                ErrorKind::PinInUse {
+                    pin: DioPin::Dio3,
+                    owner: PinOwner::TcxoControl,
+                },
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Enables IRQs and routes them to DIO1.
+fn add_routes(routing: &mut DioIrqConfig, irq: IrqMask) {
+    routing.irq_mask |= irq;
+    routing.dio1_mask |= irq;
+}
+
+#[cfg(feature = "sync")]
+impl<BUS> IrqHandle<BUS>
+where
+    BUS: RadioBus,
+{
+    /// Reads the pending IRQs.
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn irq_status(&mut self) -> Result<IrqMask, Error> {
+        Ok(self.device.execute_command(GetIrqStatus)?.irq_mask)
+    }
+
+    /// Clears IRQs.
+    ///
+    /// # Arguments
+    /// * `irq_mask` - The IRQs to clear
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn clear_irq_status(&mut self, irq_mask: IrqMask) -> Result<(), Error> {
+        self.device.execute_command(ClearIrqStatus { irq_mask })?;
+        Ok(())
+    }
+
+    /// Reads and clears the pending IRQs.
+    ///
+    /// # Returns
+    /// The IRQs that were pending
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn take_irq(&mut self) -> Result<IrqMask, Error> {
+        let irq_mask = self.irq_status()?;
+        self.clear_irq_status(irq_mask)?;
+        Ok(irq_mask)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS> IrqHandle<BUS>
+where
+    BUS: AsyncRadioBus,
+{
+    /// Asynchronously reads the pending IRQs.
+    ///
+    /// This is the async version of [`irq_status`](IrqHandle::irq_status).
+    pub async fn irq_status_async(&mut self) -> Result<IrqMask, Error> {
+        Ok(self
+            .device
+            .execute_command_async(GetIrqStatus)
+            .await?
+            .irq_mask)
+    }
+
+    /// Asynchronously clears IRQs.
+    ///
+    /// This is the async version of [`clear_irq_status`](IrqHandle::clear_irq_status).
+    pub async fn clear_irq_status_async(&mut self, irq_mask: IrqMask) -> Result<(), Error> {
+        self.device
+            .execute_command_async(ClearIrqStatus { irq_mask })
+            .await?;
+        Ok(())
+    }
+
+    /// Asynchronously reads and clears the pending IRQs.
+    ///
+    /// This is the async version of [`take_irq`](IrqHandle::take_irq).
+    pub async fn take_irq_async(&mut self) -> Result<IrqMask, Error> {
+        let irq_mask = self.irq_status_async().await?;
+        self.clear_irq_status_async(irq_mask).await?;
+        Ok(irq_mask)
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    DIO1: InputPin,
+{
+    /// Returns whether DIO1 signals a pending interrupt.
+    ///
+    /// Only the IRQs mapped to DIO1 with SetDioIrqParams raise the line.
+    ///
+    /// # Errors
+    /// * `Error::Control` with `ErrorKind::Pin` - DIO1 could not be read
+    pub fn irq_pending(&mut self) -> Result<bool, Error> {
+        self.dio1
+            .is_high()
+            .map_err(|_| Error::control(ErrorKind::Pin))
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+    DIO1: InputPin,
+{
+    /// Waits for DIO1 to go high, then reads and clears the pending IRQs.
+    ///
+    /// DIO1 is polled without delay, prefer the async version where possible, or
+    /// [`wait_irq_polled`](Device::wait_irq_polled) on a hosted OS. The installed
+    /// clock, if any, is read as soon as DIO1 is seen high.
+    ///
+    /// # Returns
+    /// The IRQs that were pending and the time DIO1 rose
+    ///
+    /// # Errors
+    /// * `Error::Control` with `ErrorKind::Pin` - DIO1 could not be read
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn wait_irq(&mut self) -> Result<IrqEvent, Error> {
+        self.poll_irq(|_| {})
+    }
+
+    /// Waits for DIO1 to go high, reading it every `poll_interval_us`, then reads and clears
+    /// the pending IRQs.
+    ///
+    /// This is [`wait_irq`](Device::wait_irq) sleeping with the owned delay between reads, for
+    /// hosted platforms where DIO1 is read through a system call. The IRQs are seen up to
+    /// `poll_interval_us` late, plus the oversleep of the OS.
+    ///
+    /// # Arguments
+    /// * `poll_interval_us` - Interval between two reads of DIO1 in microseconds
+    ///
+    /// # Errors
+    /// As [`wait_irq`](Device::wait_irq)
+    pub fn wait_irq_polled(&mut self, poll_interval_us: u32) -> Result<IrqEvent, Error>
+    where
+        DELAY: embedded_hal::delay::DelayNs,
+    {
+        self.poll_irq(|device| device.delay.delay_us(poll_interval_us))
+    }
+
+    /// Waits for DIO1 to go high, reading it every `poll_interval_us` timed with the given
+    /// delay, then reads and clears the pending IRQs.
+    ///
+    /// See [`wait_irq_polled`](Device::wait_irq_polled).
+    ///
+    /// # Arguments
+    /// * `poll_interval_us` - Interval between two reads of DIO1 in microseconds
+    /// * `delay` - Delay used between reads
+    pub fn wait_irq_polled_with_delay(
+        &mut self,
+        poll_interval_us: u32,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<IrqEvent, Error> {
+        self.poll_irq(|_| delay.delay_us(poll_interval_us))
+    }
+
+    /// Reads DIO1 until it is high, calling `idle` between reads, then reads and clears the
+    /// pending IRQs.
+    fn poll_irq(&mut self, mut idle: impl FnMut(&mut Self)) -> Result<IrqEvent, Error> {
+        while !self.irq_pending()? {
+            idle(self);
+        }
+        let at = self.timestamp();
+
+        let irq_mask = self.execute_command(GetIrqStatus)?.irq_mask;
+        self.record_irqs(irq_mask);
+        self.execute_command(ClearIrqStatus { irq_mask })?;
+        Ok(IrqEvent { irq_mask, at })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+    DIO1: embedded_hal_async::digital::Wait,
+{
+    /// Asynchronously waits for DIO1 to go high, then reads and clears the pending IRQs.
+    ///
+    /// This is the async version of [`wait_irq`](Device::wait_irq).
+    pub async fn wait_irq_async(&mut self) -> Result<IrqEvent, Error> {
+        self.dio1
+            .wait_for_high()
+            .await
+            .map_err(|_| Error::control(ErrorKind::Pin))?;
+        let at = self.timestamp();
+
+        let irq_mask = self.execute_command_async(GetIrqStatus).await?.irq_mask;
+        self.record_irqs(irq_mask);
+        self.execute_command_async(ClearIrqStatus { irq_mask })
+            .await?;
+        Ok(IrqEvent { irq_mask, at })
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-IRQ counters for diagnosing interrupt storms
+//!
+//! A source raised far more often than expected, e.g. PREAMBLE_DETECTED routed to DIO1 in a
+//! noisy band, can starve the application with interrupts. The Device counts every IRQ it
+//! reads and clears, and [`Device::detect_irq_storm`] reports the sources whose rate exceeds a
+//! threshold so that they can be removed from the DIO mapping.
+
+use super::Device;
+use crate::IrqMask;
+
+/// Number of times each IRQ was read and cleared by the [`Device`]
+///
+/// IRQs read by an [`IrqHandle`](crate::IrqHandle) are not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct IrqCounts {
+    counts: [u32; 16],
+    window_start: Option<u32>,
+    window_counts: [u32; 16],
+}
+
+impl IrqCounts {
+    /// Counts the IRQs of a read.
+    pub(crate) fn record(&mut self, irq: IrqMask) {
+        for (bit, count) in self.counts.iter_mut().enumerate() {
+            if irq.bits() & (1 << bit) != 0 {
+                *count = count.saturating_add(1);
+            }
+        }
+    }
+
+    /// Returns the number of reads that had any of the given IRQs, per IRQ.
+    ///
+    /// # Arguments
+    /// * `irq` - A single IRQ flag, for a mask the counts of its flags are summed
+    pub fn get(&self, irq: IrqMask) -> u32 {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| irq.bits() & (1 << bit) != 0)
+            .fold(0, |sum, (_, count)| sum.saturating_add(*count))
+    }
+
+    /// Returns the counts of the IRQs that were raised at least once.
+    pub fn iter(&self) -> impl Iterator<Item = (IrqMask, u32)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(bit, count)| (IrqMask::from_bits_retain(1 << bit), *count))
+    }
+
+    /// Returns the IRQs raised more often than a rate since the previous call.
+    ///
+    /// The first call only starts the measurement window.
+    fn detect_storm(&mut self, threshold_per_s: u32, now_ms: u32) -> IrqMask {
+        let Some(start) = self.window_start.replace(now_ms) else {
+            self.window_counts = self.counts;
+            return IrqMask::empty();
+        };
+        let elapsed_ms = now_ms.wrapping_sub(start);
+        let window_counts = core::mem::replace(&mut self.window_counts, self.counts);
+        if elapsed_ms == 0 {
+            return IrqMask::empty();
+        }
+
+        let mut storm = IrqMask::empty();
+        for (bit, (count, previous)) in self.counts.iter().zip(window_counts).enumerate() {
+            let per_s = u64::from(count.wrapping_sub(previous)) * 1000 / u64::from(elapsed_ms);
+            if per_s > u64::from(threshold_per_s) {
+                storm |= IrqMask::from_bits_retain(1 << bit);
+            }
+        }
+        storm
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Returns how often each IRQ was read and cleared.
+    ///
+    /// Every read-and-clear of the Device is counted: [`wait_irq`](Device::wait_irq),
+    /// [`poll_rx`](Device::poll_rx), the transmit and receive helpers and the sniffer.
+    pub fn irq_counts(&self) -> &IrqCounts {
+        &self.state.irq_counts
+    }
+
+    /// Resets the IRQ counters and the storm detection window.
+    pub fn reset_irq_counts(&mut self) {
+        self.state.irq_counts = IrqCounts::default();
+    }
+
+    /// Returns the IRQs raised more often than a rate since the previous call.
+    ///
+    /// Call this periodically, e.g. every second. The first call, and the first call after
+    /// [`reset_irq_counts`](Device::reset_irq_counts), only start the measurement.
+    ///
+    /// # Arguments
+    /// * `threshold_per_s` - Highest acceptable rate of each IRQ, per second
+    /// * `now_ms` - Current time of a monotonic millisecond clock, which may wrap around
+    ///
+    /// # Example
+    /// Removing a storming IRQ from DIO1, checked every second:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn check(device: &mut Device<impl RadioBus>, now_ms: u32) {
+    ///     let storm = device.detect_irq_storm(500, now_ms);
+    ///     if !storm.is_empty() {
+    ///         device
+    ///             .modify_irq_routing(|routing| routing.dio1_mask.remove(storm))
+    ///             .unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn detect_irq_storm(&mut self, threshold_per_s: u32, now_ms: u32) -> IrqMask {
+        self.state.irq_counts.detect_storm(threshold_per_s, now_ms)
+    }
+}
@@ -0,0 +1,50 @@
+//! Records emitted through the log crate
+//!
+//! With the `log` feature, the [`Device`](crate::Device) emits records from the same points as
+//! the trace hook, whether or not the `trace` feature is enabled:
+//!
+//! | Level | Record |
+//! |-------|--------|
+//! | debug | Every command sent, with its opcode and parameters |
+//! | trace | Every register write, with its address and value |
+//! | debug | The IRQs reported by each read-and-clear of the IRQ status |
+//! | debug | Every failed command, register or buffer access, with its error |
+//!
+//! The records are formatted lazily by the logger, without heap allocation in the driver.
+//! Without the feature, none of this is compiled.
+
+use crate::{Error, IrqMask};
+
+/// Logs a command sent, or its failure.
+pub(super) fn command(opcode: u8, params: &[u8], result: &Result<(), Error>) {
+    match result {
+        Ok(()) => log::debug!("sx126x command 0x{opcode:02X} {params:02X?}"),
+        Err(err) => failure(err),
+    }
+}
+
+/// Logs a register write, or its failure.
+pub(super) fn register_write(address: u16, bytes: &[u8], result: &Result<(), Error>) {
+    match result {
+        Ok(()) => log::trace!("sx126x register 0x{address:04X} <- {bytes:02X?}"),
+        Err(err) => failure(err),
+    }
+}
+
+/// Logs the failure of an operation, if any.
+pub(super) fn result(result: &Result<(), Error>) {
+    if let Err(err) = result {
+        failure(err);
+    }
+}
+
+/// Logs the IRQs reported by a read-and-clear.
+pub(super) fn irqs(irq: IrqMask) {
+    if !irq.is_empty() {
+        log::debug!("sx126x irq {irq:?}");
+    }
+}
+
+fn failure(err: &Error) {
+    log::debug!("sx126x {err}");
+}
@@ -0,0 +1,1959 @@
+//! SX126x Radio Device Interface
+//!
+//! This module provides a high-level interface for interacting with SX126x series radio devices
+//! through SPI communication. It supports both synchronous and asynchronous operations.
+//!
+//! The interface is built around the `Device<BUS>` struct which wraps a [`RadioBus`] (any SPI
+//! device, or a custom transport, see [`crate::bus`]) and provides methods for:
+//! - Reading and writing device registers
+//! - Reading and writing to the device's buffer
+//! - Executing radio commands
+//! - Driving an external RF front-end around TX and RX transitions, see [`crate::frontend`]
+//! - Bringing the radio up in a single call, see [`crate::config::LoRaInit`]
+//! - Transmitting and receiving complete packets, optionally bounded by a `Duration`
+//! - Re-arming a continuous receive that has gone quiet, see [`RxWatchdog`]
+//! - Bounding BUSY waits and recovering a latched-up radio, see [`crate::pins`]
+//!
+//! # Example
+//! ```ignore
+//! use sx1262::Device;
+//!
+//! // Create device with SPI interface
+//! let spi = // ... SPI implementation
+//! let mut device = Device::new(spi);
+//!
+//! // Read a register
+//! let value: SomeRegister = device.read_register()?;
+//!
+//! // Write to buffer
+//! device.write_buffer(0, &[0x01, 0x02, 0x03])?;
+//! ```
+
+mod builder;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod cad;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod config;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod deadline;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod discover;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod dump;
+#[cfg(feature = "embassy")]
+mod embassy;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod frequency;
+#[cfg(feature = "sync")]
+mod guard;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod init;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod irq;
+#[cfg(feature = "metrics")]
+mod irq_counts;
+#[cfg(all(feature = "log", any(feature = "sync", feature = "async")))]
+mod log_records;
+#[cfg(feature = "metrics")]
+mod mode_dwell;
+#[cfg(feature = "oplog")]
+mod operation_log;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod payload;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod ping;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod recover;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod resync;
+mod rx_lifecycle;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod self_test;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod sniffer;
+mod stats;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod temporary;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod transceive;
+mod turnaround;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod validate;
+mod watchdog;
+
+pub use builder::DeviceBuilder;
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use discover::PartialRadioConfig;
+#[cfg(feature = "embassy")]
+pub use embassy::EmbassyRadioExt;
+#[cfg(feature = "sync")]
+pub use guard::{IrqSuspendGuard, ModeGuard};
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use irq::{IrqEvent, IrqHandle};
+#[cfg(feature = "metrics")]
+pub use irq_counts::IrqCounts;
+#[cfg(feature = "metrics")]
+use mode_dwell::ModeDwell;
+#[cfg(feature = "metrics")]
+pub use mode_dwell::{DwellMode, ModeDwellTimes};
+#[cfg(feature = "oplog")]
+pub use operation_log::{Operation, OperationLog, OPERATION_LOG_DEPTH, OPERATION_PARAMS_LEN};
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use payload::PayloadInfo;
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use ping::PingResult;
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use recover::Responsive;
+use rx_lifecycle::RxLifecycle;
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use self_test::{SelfTestOutcome, SelfTestPlan, SelfTestReport};
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use sniffer::SniffedFrame;
+pub use stats::{LinkStats, StatsReport};
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use transceive::{HeaderErrorPolicy, IqMode, RxOptions, RxPacket, TxDone, TxOptions};
+pub use turnaround::TurnaroundProfile;
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use validate::{ConfigMismatch, ConfigReport, FixedLengthWarning, ReadBackSource};
+pub use watchdog::RxWatchdog;
+
+#[cfg(any(feature = "sync", feature = "async"))]
+use core::convert::Infallible;
+use core::ops::RangeInclusive;
+
+/// Default maximum wait for BUSY to go low, see [`Device::set_busy_timeout_us`]
+const DEFAULT_BUSY_TIMEOUT_US: u32 = 100_000;
+
+/// Bytes before the data of a WriteBuffer transaction: opcode and offset
+const WRITE_BUFFER_HEADER_LEN: usize = 2;
+/// Bytes before the data of a ReadBuffer transaction: opcode, offset and NOP
+const READ_BUFFER_HEADER_LEN: usize = 3;
+/// Bytes before the data of a ReadRegister transaction: opcode, address and NOP
+const READ_REGISTER_HEADER_LEN: usize = 4;
+
+/// Returns the offsets of consecutive chunks of `chunk_len` bytes from `offset`, wrapping
+/// around the 256 byte data buffer.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn buffer_offsets(offset: u8, chunk_len: usize) -> impl Iterator<Item = u8> {
+    // Only the offset modulo 256 matters
+    let step = chunk_len.to_le_bytes()[0];
+    core::iter::successors(Some(offset), move |start| Some(start.wrapping_add(step)))
+}
+
+/// Longest buffer transfer in bytes, 256 bytes would wrap around onto the first one
+const MAX_BUFFER_TRANSFER_LEN: usize = 255;
+
+/// Rejects buffer transfers wrapping around the data buffer onto their own first byte.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn check_buffer_len(offset: u8, len: usize) -> Result<(), Error> {
+    if len > MAX_BUFFER_TRANSFER_LEN {
+        return Err(Error::buffer(
+            offset,
+            ErrorKind::BufferOverrun { length: len },
+        ));
+    }
+    Ok(())
+}
+
+/// Time source used to timestamp IRQs, see [`Device::set_clock`]
+///
+/// Returns the current time of a monotonic clock of the application, in any unit.
+pub type Clock = fn() -> u32;
+
+#[cfg(any(feature = "sync", feature = "async"))]
+use regiface::{
+    ByteArray, Command, FromByteArray, ReadableRegister, ToByteArray, WritableRegister,
+};
+
+#[cfg(feature = "trace")]
+use crate::trace::{RawResponse, TraceDirection, TraceEvent, TraceHook, TraceTarget};
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+#[cfg(any(feature = "sync", feature = "async"))]
+use crate::{
+    commands::{
+        busy_after_us, observed_irq_clear, observed_irq_routing, observed_packet_type,
+        requested_packet_type, starts_operation, track_config_items, track_image_calibration,
+        track_settled_mode,
+    },
+    error::ErrorKind,
+    frontend::Transition,
+    CommandStatus, GetPacketType, GetStatus, PreparedCommand, RfFrequencyConfig, SetDio3AsTcxoCtrl,
+    SetPacketType, SetSleep, SetTx, Status,
+};
+use crate::{
+    config::{ConfigItems, RadioConfig},
+    error::Error,
+    frontend::NoFrontend,
+    pins::{NoDelay, NoPin, Pins},
+    DioIrqConfig, ImageCalibConfig, IrqMask, OperatingMode, PacketType, Stats,
+};
+
+/// Returns whether a command is followed by a status check in paranoid mode.
+///
+/// Only parameterized commands without a response are checked. SetSleep is skipped as the
+/// check would wake the radio up.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn needs_status_check(opcode: u8, request: &[u8], response: &[u8]) -> bool {
+    !request.is_empty() && response.is_empty() && opcode != SetSleep::id()
+}
+
+/// Returns an error attributed to `opcode` when the status byte reports a failed command, or
+/// a probable reset of a radio expected in `expected_mode`.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn check_command_status(
+    opcode: u8,
+    status: u8,
+    expected_mode: Option<OperatingMode>,
+) -> Result<(), Error> {
+    if let (Ok(parsed), Some(expected)) = (Status::from_bytes([status]), expected_mode) {
+        if parsed.looks_like_unexpected_reset(expected) {
+            return Err(Error::command(
+                opcode,
+                ErrorKind::PossibleChipReset { expected },
+            ));
+        }
+    }
+    match CommandStatus::try_from((status >> 1) & 0x7) {
+        Ok(status @ (CommandStatus::ProcessingError | CommandStatus::ExecutionFailure)) => {
+            Err(Error::command(opcode, ErrorKind::CommandFailed { status }))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the packet type a command sets, which paranoid mode reads back.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn packet_type_to_verify(opcode: u8, request: &[u8]) -> Option<PacketType> {
+    if opcode != SetPacketType::id() {
+        return None;
+    }
+    observed_packet_type(opcode, request, &[])
+}
+
+/// Checks the GetPacketType response read back after setting a packet type.
+///
+/// Errors are attributed to `opcode`, the command that set the packet type.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn check_packet_type_readback(
+    opcode: u8,
+    expected: PacketType,
+    response: [u8; 2],
+) -> Result<(), Error> {
+    match observed_packet_type(GetPacketType::id(), &[], &response) {
+        Some(actual) if actual == expected => Ok(()),
+        Some(actual) => Err(Error::command(
+            opcode,
+            ErrorKind::ConfigVerificationFailed { expected, actual },
+        )),
+        None => Err(Error::command(opcode, ErrorKind::Deserialization)),
+    }
+}
+
+/// Main device interface for the SX126x radio.
+///
+/// This struct wraps a radio bus, usually an SPI interface, and provides methods to interact
+/// with the radio. It supports both synchronous operations through [`RadioBus`] and
+/// asynchronous operations through [`AsyncRadioBus`], which are implemented for the
+/// embedded-hal and embedded-hal-async SPI device traits respectively.
+///
+/// An optional RF front-end `FE` can be installed with
+/// [`with_frontend`](Device::with_frontend) to drive external PA/LNA control lines, and the
+/// BUSY, NRESET and DIO1 lines and a delay can be supplied with
+/// [`builder`](Device::builder) or [`new_with_parts`](Device::new_with_parts), see
+/// [`crate::pins`].
+///
+/// The Device holds no references or shared state of its own, so it is `Send` whenever its
+/// bus, front-end, pins and delay are, and can be moved into an RTIC or embassy task. See
+/// [`crate::bus`] for sharing the bus with other devices.
+pub struct Device<BUS, FE = NoFrontend, BUSY = NoPin, RESET = NoPin, DIO1 = NoPin, DELAY = NoDelay>
+{
+    bus: BUS,
+    frontend: FE,
+    busy: BUSY,
+    reset: RESET,
+    dio1: DIO1,
+    delay: DELAY,
+    settings: Settings,
+    state: TrackedState,
+}
+
+/// Behaviour of the driver chosen by the application, kept across resets of the radio
+struct Settings {
+    busy_timeout_us: u32,
+    packet_type_check: bool,
+    allow_empty_payload: bool,
+    paranoid_checks: bool,
+    auto_image_calibration: bool,
+    frequency_correction_ppb: i32,
+    rssi_offset_db: i8,
+    clock: Option<Clock>,
+    turnaround: TurnaroundProfile,
+    max_transfer_size: Option<usize>,
+    #[cfg(feature = "trace")]
+    trace_hook: Option<TraceHook>,
+    #[cfg(feature = "trace")]
+    response_capture: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            busy_timeout_us: DEFAULT_BUSY_TIMEOUT_US,
+            packet_type_check: true,
+            allow_empty_payload: false,
+            paranoid_checks: false,
+            auto_image_calibration: true,
+            frequency_correction_ppb: 0,
+            rssi_offset_db: 0,
+            clock: None,
+            turnaround: TurnaroundProfile::default(),
+            max_transfer_size: None,
+            #[cfg(feature = "trace")]
+            trace_hook: None,
+            #[cfg(feature = "trace")]
+            response_capture: false,
+        }
+    }
+}
+
+/// What the driver knows of the radio from the commands sent to it, nothing by default
+#[derive(Default)]
+struct TrackedState {
+    config: Option<RadioConfig>,
+    packet_type: Option<PacketType>,
+    settled_mode: Option<OperatingMode>,
+    image_calibration: Option<ImageCalibConfig>,
+    stats_packet_type: Option<PacketType>,
+    stats_baseline: Stats,
+    link_stats: LinkStats,
+    deferred_error: Option<Error>,
+    in_flight: bool,
+    cleared_irqs: IrqMask,
+    configured: ConfigItems,
+    irq_routing: Option<DioIrqConfig>,
+    dio3_tcxo: bool,
+    rx_lifecycle: RxLifecycle,
+    ping_sequence: u8,
+    cad_exit_mode: u8,
+    cad_running: bool,
+    last_fixed_tx_length: Option<u8>,
+    #[cfg(feature = "metrics")]
+    irq_counts: IrqCounts,
+    #[cfg(feature = "metrics")]
+    mode_dwell: ModeDwell,
+    #[cfg(feature = "oplog")]
+    operation_log: OperationLog,
+    #[cfg(feature = "trace")]
+    raw_response: Option<RawResponse>,
+}
+
+// The Device must stay movable between tasks when its parts are
+const _: fn() = || {
+    fn assert_send<T: Send>() {}
+    assert_send::<Device<NoPin, NoFrontend, NoPin, NoPin, NoPin, NoDelay>>();
+    #[cfg(any(feature = "sync", feature = "async"))]
+    assert_send::<IrqHandle<NoPin>>();
+};
+
+impl<BUS> Device<BUS> {
+    /// Creates a new Device instance wrapping the provided SPI interface or radio bus.
+    ///
+    /// # Arguments
+    /// * `bus` - An SPI interface implementing the required embedded-hal traits, or any other
+    ///   [`RadioBus`]/[`AsyncRadioBus`] implementation
+    pub fn new(bus: BUS) -> Self {
+        Self {
+            bus,
+            frontend: NoFrontend,
+            busy: NoPin,
+            reset: NoPin,
+            dio1: NoPin,
+            delay: NoDelay,
+            settings: Settings::default(),
+            state: TrackedState::default(),
+        }
+    }
+}
+
+impl<BUS, BUSY, RESET, DIO1, DELAY> Device<BUS, NoFrontend, BUSY, RESET, DIO1, DELAY> {
+    /// Creates a new Device instance with control lines and an owned delay.
+    ///
+    /// Helpers that need to wait, such as [`recover`](Device::recover), use the delay
+    /// internally. Each of them also has a `_with_delay` variant taking the delay as a
+    /// parameter, for use without giving up ownership of it.
+    ///
+    /// # Arguments
+    /// * `bus` - An SPI interface implementing the required embedded-hal traits, or any other
+    ///   [`RadioBus`]/[`AsyncRadioBus`] implementation
+    /// * `pins` - The BUSY, NRESET and DIO1 lines, [`NoPin`] for those not connected
+    /// * `delay` - A delay implementing `embedded_hal::delay::DelayNs` for the blocking
+    ///   methods, and `embedded_hal_async::delay::DelayNs` for the async ones
+    ///
+    /// # Example
+    /// ```no_run
+    /// use embedded_hal::{delay::DelayNs, digital::{InputPin, OutputPin}, spi::SpiDevice};
+    /// use sx1262::{pins::{NoPin, Pins}, Device};
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn create<SPI: SpiDevice, R: OutputPin, D: DelayNs>(spi: SPI, reset: R, delay: D) {
+    ///     let pins = Pins { busy: NoPin, reset, dio1: NoPin };
+    ///     let mut device = Device::new_with_parts(spi, pins, delay);
+    ///     device.recover().unwrap();
+    /// }
+    /// ```
+    pub fn new_with_parts(bus: BUS, pins: Pins<BUSY, RESET, DIO1>, delay: DELAY) -> Self {
+        Device::new(bus).with_parts(NoFrontend, pins, delay)
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Replaces the front-end, control lines and delay, keeping the rest of the state.
+    fn with_parts<F, B, R, I, D>(
+        self,
+        frontend: F,
+        pins: Pins<B, R, I>,
+        delay: D,
+    ) -> Device<BUS, F, B, R, I, D> {
+        Device {
+            bus: self.bus,
+            frontend,
+            busy: pins.busy,
+            reset: pins.reset,
+            dio1: pins.dio1,
+            delay,
+            settings: self.settings,
+            state: self.state,
+        }
+    }
+
+    /// Splits off the front-end, control lines and delay, keeping the rest of the state.
+    fn into_parts(self) -> (Device<BUS>, FE, Pins<BUSY, RESET, DIO1>, DELAY) {
+        let device = Device {
+            bus: self.bus,
+            frontend: NoFrontend,
+            busy: NoPin,
+            reset: NoPin,
+            dio1: NoPin,
+            delay: NoDelay,
+            settings: self.settings,
+            state: self.state,
+        };
+        let pins = Pins {
+            busy: self.busy,
+            reset: self.reset,
+            dio1: self.dio1,
+        };
+        (device, self.frontend, pins, self.delay)
+    }
+
+    /// Installs an RF front-end, replacing the current one.
+    ///
+    /// The front-end is notified around every TX, RX and idle transition issued through
+    /// [`execute_command`](Device::execute_command).
+    ///
+    /// # Arguments
+    /// * `frontend` - The front-end to install
+    pub fn with_frontend<F>(self, frontend: F) -> Device<BUS, F, BUSY, RESET, DIO1, DELAY> {
+        let (device, _, pins, delay) = self.into_parts();
+        device.with_parts(frontend, pins, delay)
+    }
+
+    /// Connects the BUSY line, replacing the current one.
+    ///
+    /// Every bus transaction then waits for BUSY to be low first, for at most the
+    /// [busy timeout](Device::set_busy_timeout_us).
+    ///
+    /// # Arguments
+    /// * `busy` - The BUSY line, usually a [`BusyPin`](crate::pins::BusyPin)
+    pub fn with_busy<B>(self, busy: B) -> Device<BUS, FE, B, RESET, DIO1, DELAY> {
+        let (device, frontend, pins, delay) = self.into_parts();
+        let pins = Pins {
+            busy,
+            reset: pins.reset,
+            dio1: pins.dio1,
+        };
+        device.with_parts(frontend, pins, delay)
+    }
+
+    /// Connects the NRESET line, replacing the current one.
+    ///
+    /// The line is only driven by [`recover`](Device::recover).
+    ///
+    /// # Arguments
+    /// * `reset` - The NRESET line, any output pin
+    pub fn with_reset<R>(self, reset: R) -> Device<BUS, FE, BUSY, R, DIO1, DELAY> {
+        let (device, frontend, pins, delay) = self.into_parts();
+        let pins = Pins {
+            busy: pins.busy,
+            reset,
+            dio1: pins.dio1,
+        };
+        device.with_parts(frontend, pins, delay)
+    }
+
+    /// Installs the delay used internally by helpers that need to wait, replacing the
+    /// current one.
+    ///
+    /// # Arguments
+    /// * `delay` - A delay implementing `embedded_hal::delay::DelayNs` for the blocking
+    ///   methods, and `embedded_hal_async::delay::DelayNs` for the async ones
+    pub fn with_delay<D>(self, delay: D) -> Device<BUS, FE, BUSY, RESET, DIO1, D> {
+        let (device, frontend, pins, _) = self.into_parts();
+        device.with_parts(frontend, pins, delay)
+    }
+
+    /// Sets the maximum time to wait for BUSY to go low before a bus transaction.
+    ///
+    /// Defaults to 100 ms, comfortably above the longest documented BUSY period (a full
+    /// calibration). Has no effect without a BUSY line.
+    ///
+    /// # Arguments
+    /// * `timeout_us` - Maximum wait in microseconds
+    pub fn set_busy_timeout_us(&mut self, timeout_us: u32) {
+        self.settings.busy_timeout_us = timeout_us;
+    }
+
+    /// Sets the maximum number of bytes clocked in a single SPI transaction, unlimited by
+    /// default.
+    ///
+    /// Some SPI DMA paths cannot move more than 255 bytes at once, less than a full buffer
+    /// write with its header. With a limit, buffer reads and writes and register span reads
+    /// are split into several transactions with advancing offsets or addresses,
+    /// each of at most `max_bytes` including the opcode, address and NOP bytes. A transaction
+    /// always carries at least one data byte. Commands, of at most 10 bytes, are never split.
+    ///
+    /// # Arguments
+    /// * `max_bytes` - Maximum transaction length in bytes, or `None` for no limit
+    ///
+    /// # Example
+    /// For an SPI DMA path limited to 255 bytes per transfer:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn send(device: &mut Device<impl RadioBus>, payload: &[u8]) {
+    ///     device.set_max_transfer_size(Some(255));
+    ///     device.write_buffer(0, payload).unwrap();
+    /// }
+    /// ```
+    pub fn set_max_transfer_size(&mut self, max_bytes: Option<usize>) {
+        self.settings.max_transfer_size = max_bytes;
+    }
+
+    /// Returns the number of data bytes of a transaction with a framing header of
+    /// `header_len` bytes, see [`set_max_transfer_size`](Device::set_max_transfer_size).
+    fn transfer_chunk_len(&self, header_len: usize) -> usize {
+        self.settings
+            .max_transfer_size
+            .map_or(usize::MAX, |max| max.saturating_sub(header_len).max(1))
+    }
+
+    /// Enables or disables the packet type check, enabled by default.
+    ///
+    /// The packet type set on the radio is tracked from SetPacketType and GetPacketType. While
+    /// it is known, SetModulationParams and SetPacketParams with parameters for the other
+    /// packet type are rejected with `ErrorKind::PacketTypeMismatch` instead of being sent,
+    /// as the radio accepts them and then misbehaves. The packet type of the parameters is
+    /// told from their serialized bytes, so prepared commands are checked too, and parameters
+    /// whose bytes could be for either packet type are sent unchecked. Disable the check for
+    /// unusual command sequences, e.g. when the packet type is changed behind the driver's
+    /// back.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether mismatched parameters are rejected
+    pub fn set_packet_type_check(&mut self, enabled: bool) {
+        self.settings.packet_type_check = enabled;
+    }
+
+    /// Allows or rejects transmitting an empty payload, rejected by default.
+    ///
+    /// An empty payload sends a header-only frame with an explicit LoRa header, and an
+    /// undefined frame otherwise. It is usually an application forgetting to stage the
+    /// payload, so [`transmit`](Device::transmit) rejects it with `TxError::EmptyPayload`.
+    /// Allow it for the rare legitimate uses, such as wake-up frames.
+    ///
+    /// # Arguments
+    /// * `allowed` - Whether empty payloads are sent
+    ///
+    /// # Example
+    /// A wake-up frame:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, Timeout};
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn wake_up(device: &mut Device<impl RadioBus>) {
+    ///     device.set_allow_empty_payload(true);
+    ///     device.transmit(&[], Timeout::default()).unwrap();
+    /// }
+    /// ```
+    pub fn set_allow_empty_payload(&mut self, allowed: bool) {
+        self.settings.allow_empty_payload = allowed;
+    }
+
+    /// Enables or disables paranoid checks, disabled by default.
+    ///
+    /// Commands without a response give no feedback, so a command dropped or rejected by the
+    /// radio goes unnoticed. With paranoid checks, every parameterized command without a
+    /// response except SetSleep is followed by a GetStatus, and a command status of
+    /// ProcessingError or ExecutionFailure is returned as `ErrorKind::CommandFailed` on the
+    /// command. SetPacketType is also followed by a GetPacketType, and a packet type other
+    /// than the one set is returned as `ErrorKind::ConfigVerificationFailed`. This roughly
+    /// doubles the bus traffic.
+    ///
+    /// The status is also checked for the fingerprint of an unexpected reset, e.g. a
+    /// brown-out, while the radio should be in STDBY_XOSC, FS or a continuous TX set with
+    /// SetStandby, SetFs, SetTxContinuousWave or SetTxInfinitePreamble. A radio back in
+    /// STDBY_RC with nothing to report is returned as `ErrorKind::PossibleChipReset`, and
+    /// should be [recovered](Device::recover) or reconfigured.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether commands are followed by a status check
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, ErrorKind, RfFrequencyConfig, SetRfFrequency};
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn hop(device: &mut Device<impl RadioBus>) {
+    ///     device.set_paranoid_checks(true);
+    ///     let config = RfFrequencyConfig { frequency: 868_000_000 };
+    ///     match device.execute_command(SetRfFrequency { config }) {
+    ///         Err(err) if matches!(err.kind(), ErrorKind::PossibleChipReset { .. }) => {
+    ///             device.recover().unwrap();
+    ///         }
+    ///         result => result.unwrap(),
+    ///     }
+    /// }
+    /// ```
+    pub fn set_paranoid_checks(&mut self, enabled: bool) {
+        self.settings.paranoid_checks = enabled;
+    }
+
+    /// Returns the span of RF frequencies covered by the image calibration on the radio, in
+    /// MHz, `None` until it is known.
+    ///
+    /// The span is tracked from every CalibrateImage sent, including those of
+    /// [`calibrate_image`](Device::calibrate_image) and
+    /// [`apply_config`](Device::apply_config), and becomes unknown after a full Calibrate, a
+    /// cold-start sleep or a reset. See [`ImageCalibConfig::span_mhz`].
+    pub fn image_calibration_span(&self) -> Option<RangeInclusive<u32>> {
+        self.state
+            .image_calibration
+            .as_ref()
+            .map(ImageCalibConfig::span_mhz)
+    }
+
+    /// Enables or disables the image calibration of
+    /// [`set_rf_frequency`](Device::set_rf_frequency), enabled by default.
+    ///
+    /// Disable it to keep a calibration made with
+    /// [`calibrate_image`](Device::calibrate_image) whatever the frequency.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether frequencies outside the calibrated span trigger a calibration
+    pub fn set_auto_image_calibration(&mut self, enabled: bool) {
+        self.settings.auto_image_calibration = enabled;
+    }
+
+    /// Sets the crystal frequency error corrected for, 0 by default.
+    ///
+    /// The frequencies sent by [`set_rf_frequency`](Device::set_rf_frequency) and
+    /// [`apply_config`](Device::apply_config) are scaled so that the radio, whose PLL runs
+    /// from the crystal, transmits on the requested frequency. The raw SetRfFrequency command
+    /// is not corrected.
+    ///
+    /// # Arguments
+    /// * `ppb` - Crystal error in parts per billion (1 ppm is 1000 ppb), positive when the
+    ///   crystal runs fast
+    pub fn set_frequency_correction_ppb(&mut self, ppb: i32) {
+        self.settings.frequency_correction_ppb = ppb;
+    }
+
+    /// Sets the correction added to every RSSI the driver converts to dBm, 0 by default.
+    ///
+    /// The offset is applied by [`rssi_inst`](Device::rssi_inst), the packet statuses of
+    /// [`next_sniffed_frame`](Device::next_sniffed_frame) and the RSSI recorded in [`link_stats`](Device::link_stats).
+    /// Raw values, such as the [`PacketStatus`](crate::PacketStatus) of an
+    /// [`RxPacket`](crate::RxPacket) or the GetRssiInst response, are left untouched. The
+    /// offset is recorded in the cached configuration and replaced by the one of the next
+    /// [`apply_config`](Device::apply_config).
+    ///
+    /// # Arguments
+    /// * `offset_db` - Correction in dB, as specified by the module vendor
+    pub fn set_rssi_offset_db(&mut self, offset_db: i8) {
+        self.settings.rssi_offset_db = offset_db;
+        if let Some(config) = &mut self.state.config {
+            config.rssi_offset_db = offset_db;
+        }
+    }
+
+    /// Returns the correction added to every RSSI the driver converts to dBm.
+    pub fn rssi_offset_db(&self) -> i8 {
+        self.settings.rssi_offset_db
+    }
+
+    /// Returns the frequency to send for an RF frequency, corrected for the crystal error.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn corrected_frequency(&self, frequency: RfFrequencyConfig) -> RfFrequencyConfig {
+        let offset = i64::from(frequency.frequency)
+            * i64::from(self.settings.frequency_correction_ppb)
+            / 1_000_000_000;
+        RfFrequencyConfig {
+            frequency: (i64::from(frequency.frequency) - offset) as u32,
+        }
+    }
+
+    /// Returns the IRQ enable and DIO mapping last set on the radio, `None` until it is known.
+    ///
+    /// Every SetDioIrqParams sent through the Device is tracked, including the one of
+    /// [`apply_config`](Device::apply_config). See
+    /// [`modify_irq_routing`](Device::modify_irq_routing) to change it.
+    pub fn irq_routing(&self) -> Option<DioIrqConfig> {
+        self.state.irq_routing
+    }
+
+    /// Returns whether DIO3 powers a TCXO, after a SetDio3AsTcxoCtrl sent through the Device
+    /// and until the next reset.
+    pub fn tcxo_control(&self) -> bool {
+        self.state.dio3_tcxo
+    }
+
+    /// Returns the packet type last set on the radio, `None` until it is known.
+    pub fn packet_type(&self) -> Option<PacketType> {
+        self.state.packet_type
+    }
+
+    /// Returns the configuration last applied with [`apply_config`](Device::apply_config).
+    ///
+    /// Returns `None` until a configuration has been applied. Changes made through
+    /// [`execute_command`](Device::execute_command) or register writes are not reflected.
+    pub fn snapshot_config(&self) -> Option<RadioConfig> {
+        self.state.config
+    }
+
+    /// Returns the link statistics accumulated by the driver.
+    ///
+    /// See [`LinkStats`] for what is accumulated and when.
+    pub fn link_stats(&self) -> &LinkStats {
+        &self.state.link_stats
+    }
+
+    /// Clears the link statistics accumulated by the driver.
+    ///
+    /// The radio's own counters are left untouched.
+    pub fn reset_link_stats(&mut self) {
+        self.state.link_stats = LinkStats::default();
+    }
+
+    /// Treats the radio as configured, for a radio configured outside this Device.
+    ///
+    /// [`transmit`](Device::transmit) and [`receive`](Device::receive) refuse to run until
+    /// the RF frequency, modulation parameters and packet parameters have been sent since
+    /// the last reset or cold-start sleep, so that a skipped or failed
+    /// [`apply_config`](Device::apply_config) cannot transmit on the reset frequency.
+    /// Use this when another driver instance or a bootloader configured the radio.
+    pub fn assume_configured(&mut self) {
+        self.state.configured = ConfigItems::all();
+    }
+
+    /// Returns a mutable reference to the installed RF front-end.
+    pub fn frontend_mut(&mut self) -> &mut FE {
+        &mut self.frontend
+    }
+
+    /// Releases the underlying SPI device or radio bus.
+    ///
+    /// This method consumes the Device instance and returns the wrapped bus.
+    pub fn release(self) -> BUS {
+        self.bus
+    }
+
+    /// Releases the underlying SPI device or radio bus and the installed RF front-end.
+    pub fn release_with_frontend(self) -> (BUS, FE) {
+        (self.bus, self.frontend)
+    }
+
+    /// Releases the underlying bus, the RF front-end, the control lines and the delay.
+    pub fn release_all(self) -> (BUS, FE, Pins<BUSY, RESET, DIO1>, DELAY) {
+        let (device, frontend, pins, delay) = self.into_parts();
+        (device.bus, frontend, pins, delay)
+    }
+
+    /// Installs a clock timestamping IRQs, or removes it with `None`.
+    ///
+    /// The clock is called once per event, as soon as the driver observes it: when DIO1 rises
+    /// in [`wait_irq`](Device::wait_irq), and when GetIrqStatus first reports the IRQs in the
+    /// polling helpers such as [`transmit`](Device::transmit),
+    /// [`receive`](Device::receive) and [`poll_rx`](Device::poll_rx). The timestamps are
+    /// returned with their events, and are `None` without a clock.
+    ///
+    /// With the `metrics` feature, the clock is also called on every change of operating mode
+    /// to count the time spent in each, see `mode_dwell_times`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, Timeout};
+    ///
+    /// /// Microseconds since boot
+    /// fn micros() -> u32 {
+    ///     // ... read a hardware timer ...
+    ///     0
+    /// }
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn send(device: &mut Device<impl RadioBus>) {
+    ///     device.set_clock(Some(micros));
+    ///     let sent_at = device.transmit(b"hello", Timeout::default()).unwrap().at;
+    /// }
+    /// ```
+    pub fn set_clock(&mut self, clock: Option<Clock>) {
+        self.settings.clock = clock;
+    }
+
+    /// Takes the error of a cleanup that could not report it, e.g. when a
+    /// [`ModeGuard`] or [`IrqSuspendGuard`] is dropped.
+    ///
+    /// Only the most recent such error is kept.
+    pub fn take_deferred_error(&mut self) -> Option<Error> {
+        self.state.deferred_error.take()
+    }
+
+    /// Counts the IRQs of a read-and-clear, and follows the operating mode they end, with the
+    /// `metrics` feature.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn record_irqs(&mut self, irq: IrqMask) {
+        #[cfg(feature = "metrics")]
+        self.state.irq_counts.record(irq);
+        #[cfg(feature = "metrics")]
+        self.state.mode_dwell.observe_irqs(irq, self.timestamp());
+        #[cfg(feature = "log")]
+        log_records::irqs(irq);
+        #[cfg(not(any(feature = "metrics", feature = "log")))]
+        let _ = irq;
+    }
+
+    /// Checks, in debug builds, that the IRQs a helper is about to wait on were cleared since
+    /// the radio last started an operation.
+    ///
+    /// A stale flag would end the wait as soon as it starts with the outcome of an earlier
+    /// operation. Only ClearIrqStatus sent through the Device is seen.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn expect_irqs_cleared(&self, irq: IrqMask) {
+        debug_assert!(
+            self.state.cleared_irqs.contains(irq),
+            "IRQs {:?} not cleared before starting an operation",
+            irq.difference(self.state.cleared_irqs)
+        );
+    }
+
+    /// Updates the state tracked from the commands sent, once the bus transaction is done.
+    ///
+    /// Shared by the sync and async paths so that both track exactly the same state.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn observe_command(&mut self, opcode: u8, request: &[u8], response: &[u8]) {
+        if let Some(packet_type) = observed_packet_type(opcode, request, response) {
+            self.state.packet_type = Some(packet_type);
+        }
+        track_image_calibration(&mut self.state.image_calibration, opcode, request);
+        if let Some(routing) = observed_irq_routing(opcode, request) {
+            self.state.irq_routing = Some(routing);
+        }
+        if opcode == SetTx::id() {
+            if let Some(len) = self
+                .state
+                .config
+                .and_then(|config| config.fixed_payload_length())
+            {
+                self.state.last_fixed_tx_length = Some(len);
+            }
+        }
+        if opcode == SetDio3AsTcxoCtrl::id() {
+            // Only a reset gives DIO3 back to the IRQ mapping
+            self.state.dio3_tcxo = true;
+        }
+        if let Some(irq) = observed_irq_clear(opcode, request) {
+            self.state.cleared_irqs |= irq;
+        } else if starts_operation(opcode) {
+            self.state.cleared_irqs = IrqMask::empty();
+        }
+        track_config_items(&mut self.state.configured, opcode, request);
+        if opcode == SetPacketType::id() {
+            // The radio drops the modulation and packet parameters, the sync word of the new
+            // modem may differ, so nothing of the cached configuration can be relied on
+            self.state.config = None;
+        }
+        track_settled_mode(&mut self.state.settled_mode, opcode, request);
+        self.track_cad_end(opcode, request, response);
+        self.state
+            .rx_lifecycle
+            .observe_command(opcode, request, response);
+        #[cfg(feature = "metrics")]
+        self.state
+            .mode_dwell
+            .observe_command(opcode, request, self.timestamp());
+    }
+
+    /// Rejects an operation when configuration items are missing, see
+    /// [`assume_configured`](Device::assume_configured).
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn check_configured(&self, opcode: u8) -> Result<(), Error> {
+        let missing = ConfigItems::all().difference(self.state.configured);
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::command(opcode, ErrorKind::NotConfigured { missing }))
+        }
+    }
+
+    /// Reads the installed clock, if any.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn timestamp(&self) -> Option<u32> {
+        self.settings.clock.map(|clock| clock())
+    }
+
+    /// Installs a hook called for every bus operation, or removes it with `None`.
+    ///
+    /// See [`crate::trace`] for the events reported.
+    #[cfg(feature = "trace")]
+    pub fn set_trace_hook(&mut self, hook: Option<TraceHook>) {
+        self.settings.trace_hook = hook;
+    }
+
+    /// Rejects parameters for another packet type than the one set on the radio.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn check_packet_type(&self, opcode: u8, provided: Option<PacketType>) -> Result<(), Error> {
+        match (self.state.packet_type, provided) {
+            (Some(configured), Some(provided))
+                if self.settings.packet_type_check && configured != provided =>
+            {
+                Err(Error::command(
+                    opcode,
+                    ErrorKind::PacketTypeMismatch {
+                        configured,
+                        provided,
+                    },
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Keeps the complete response of every command with a response, or stops with `false`.
+    ///
+    /// The response is read in full whatever the command, and the last one is returned by
+    /// [`last_raw_response`](Device::last_raw_response), status byte included. Capture is off
+    /// by default and turning it off drops the captured response.
+    #[cfg(feature = "trace")]
+    pub fn set_response_capture(&mut self, enabled: bool) {
+        self.settings.response_capture = enabled;
+        if !enabled {
+            self.state.raw_response = None;
+        }
+    }
+
+    /// Returns the complete response of the last command with a response, when
+    /// [captured](Device::set_response_capture).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, GetStats};
+    ///
+    /// # #[cfg(feature = "sync")]
+    /// fn inspect(device: &mut Device<impl RadioBus>) {
+    ///     device.set_response_capture(true);
+    ///     device.execute_command(GetStats).unwrap();
+    ///     let raw = device.last_raw_response().unwrap();
+    ///     println!("{:02X} {:02X?}", raw.opcode, raw.bytes());
+    /// }
+    /// ```
+    #[cfg(feature = "trace")]
+    pub fn last_raw_response(&self) -> Option<&RawResponse> {
+        self.state.raw_response.as_ref()
+    }
+
+    /// Reports the response of a command to the trace hook, and captures it when enabled.
+    #[cfg(all(feature = "trace", any(feature = "sync", feature = "async")))]
+    fn trace_response(&mut self, opcode: u8, response: &[u8]) {
+        if self.settings.response_capture && !response.is_empty() {
+            self.state.raw_response = Some(RawResponse::new(opcode, response));
+        }
+        self.trace(TraceTarget::Command(opcode), TraceDirection::Read, response);
+    }
+
+    #[cfg(feature = "trace")]
+    fn trace(&self, target: TraceTarget, direction: TraceDirection, bytes: &[u8]) {
+        if let Some(hook) = self.settings.trace_hook {
+            hook(TraceEvent {
+                target,
+                direction,
+                bytes,
+            });
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Reads a register value from the device.
+    ///
+    /// # Type Parameters
+    /// * `R` - Register type implementing ReadableRegister with u16 ID
+    ///
+    /// # Errors
+    /// * `Error::Register` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Register` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    /// * `Error::Register` with `ErrorKind::Deserialization` - Failed to parse register value
+    pub fn read_register<R>(&mut self) -> Result<R, Error>
+    where
+        R: ReadableRegister<IdType = u16>,
+    {
+        let mut raw_value = R::Array::new();
+        self.read_register_raw(R::id(), raw_value.as_mut())?;
+
+        R::from_bytes(raw_value).map_err(|_| Error::register(R::id(), ErrorKind::Deserialization))
+    }
+
+    /// Reads raw bytes from consecutive registers starting at an address.
+    ///
+    /// The whole span is read in a single ReadRegister (0x1D) transaction of any length: the
+    /// radio increments the address after every byte, so a span can cover several registers,
+    /// such as the sync word and the registers around it. The span is split into several
+    /// transactions beyond the [maximum transfer size](Device::set_max_transfer_size).
+    ///
+    /// # Arguments
+    /// * `address` - Address of the first register
+    /// * `bytes` - Buffer to store the register contents, one byte per register address
+    ///
+    /// # Errors
+    /// * `Error::Register` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Register` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    ///
+    /// # Example
+    /// Reading the CRC and sync word registers in one transaction:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn crc_and_sync_word(device: &mut Device<impl RadioBus>) -> [u8; 12] {
+    ///     // CrcInitialValue (0x06BC) to the end of SyncWord (0x06C7)
+    ///     let mut span = [0; 12];
+    ///     device.read_register_raw(0x06BC, &mut span).unwrap();
+    ///     span
+    /// }
+    /// ```
+    pub fn read_register_raw(&mut self, address: u16, bytes: &mut [u8]) -> Result<(), Error> {
+        let chunk_len = self.transfer_chunk_len(READ_REGISTER_HEADER_LEN);
+        if bytes.len() <= chunk_len {
+            return self.read_register_transaction(address, bytes);
+        }
+        for (chunk, start) in bytes.chunks_mut(chunk_len).zip((0..).step_by(chunk_len)) {
+            self.read_register_transaction(address.wrapping_add(start), chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Reads consecutive registers in a single transaction.
+    fn read_register_transaction(&mut self, address: u16, bytes: &mut [u8]) -> Result<(), Error> {
+        let result = self
+            .busy
+            .wait_ready(self.settings.busy_timeout_us)
+            .map_err(|err| Error::register(address, err.into()))
+            .and_then(|()| {
+                self.bus
+                    .read_register(address, bytes)
+                    .map_err(|_| Error::register(address, ErrorKind::Bus))
+            });
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Register(address),
+            TraceDirection::Read,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::result(&result);
+        result?;
+
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Register(address), TraceDirection::Read, bytes);
+
+        Ok(())
+    }
+
+    /// Writes a value to a device register.
+    ///
+    /// # Type Parameters
+    /// * `R` - Register type implementing WritableRegister with u16 ID
+    ///
+    /// # Arguments
+    /// * `register` - The register value to write
+    ///
+    /// # Errors
+    /// * `Error::Register` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Register` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    pub fn write_register<R>(&mut self, register: R) -> Result<(), Error>
+    where
+        R: WritableRegister<IdType = u16, Error = Infallible>,
+    {
+        let Ok(raw_value) = register.to_bytes();
+        self.write_register_raw(R::id(), raw_value.as_ref())
+    }
+
+    /// Writes raw bytes to consecutive registers starting at an address.
+    fn write_register_raw(&mut self, address: u16, bytes: &[u8]) -> Result<(), Error> {
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Register(address), TraceDirection::Write, bytes);
+
+        let result = self
+            .busy
+            .wait_ready(self.settings.busy_timeout_us)
+            .map_err(|err| Error::register(address, err.into()))
+            .and_then(|()| {
+                self.bus
+                    .write_register(address, bytes)
+                    .map_err(|_| Error::register(address, ErrorKind::Bus))
+            });
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Register(address),
+            TraceDirection::Write,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::register_write(address, bytes, &result);
+        result
+    }
+
+    /// Writes bytes to the device's buffer at a specified offset.
+    ///
+    /// The bytes are written in several transactions beyond the
+    /// [maximum transfer size](Device::set_max_transfer_size).
+    /// Nothing is sent for an empty `bytes`. At most 255 bytes are written at once, more would
+    /// wrap around the 256 byte data buffer onto the first ones.
+    ///
+    /// # Arguments
+    /// * `offset` - Starting position in the buffer
+    /// * `bytes` - Data to write
+    ///
+    /// # Errors
+    /// * `Error::Buffer` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Buffer` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    /// * `Error::Buffer` with `ErrorKind::BufferOverrun` - `bytes` is longer than 255 bytes
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn stage(device: &mut Device<impl RadioBus>) {
+    ///     device.write_buffer(0x80, &[0x01, 0x02, 0x03]).unwrap();
+    /// }
+    /// ```
+    pub fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        check_buffer_len(offset, bytes.len())?;
+        let chunk_len = self.transfer_chunk_len(WRITE_BUFFER_HEADER_LEN);
+        if bytes.len() <= chunk_len {
+            return self.write_buffer_transaction(offset, bytes);
+        }
+        for (chunk, start) in bytes
+            .chunks(chunk_len)
+            .zip(buffer_offsets(offset, chunk_len))
+        {
+            self.write_buffer_transaction(start, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Writes bytes to the buffer in a single transaction.
+    fn write_buffer_transaction(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Error> {
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Buffer(offset), TraceDirection::Write, bytes);
+
+        let result = self
+            .busy
+            .wait_ready(self.settings.busy_timeout_us)
+            .map_err(|err| Error::buffer(offset, err.into()))
+            .and_then(|()| {
+                self.bus
+                    .write_buffer(offset, bytes)
+                    .map_err(|_| Error::buffer(offset, ErrorKind::Bus))
+            });
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Buffer(offset),
+            TraceDirection::Write,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::result(&result);
+        result
+    }
+
+    /// Reads bytes from the device's buffer starting at a specified offset.
+    ///
+    /// The bytes are read in several transactions beyond the
+    /// [maximum transfer size](Device::set_max_transfer_size).
+    /// Nothing is sent for an empty `bytes`. At most 255 bytes are read at once, more would
+    /// wrap around the 256 byte data buffer onto the first ones.
+    ///
+    /// # Arguments
+    /// * `offset` - Starting position in the buffer to read from
+    /// * `bytes` - Buffer to store read data
+    ///
+    /// # Errors
+    /// * `Error::Buffer` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Buffer` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    /// * `Error::Buffer` with `ErrorKind::BufferOverrun` - `bytes` is longer than 255 bytes
+    pub fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        check_buffer_len(offset, bytes.len())?;
+        let chunk_len = self.transfer_chunk_len(READ_BUFFER_HEADER_LEN);
+        if bytes.len() <= chunk_len {
+            return self.read_buffer_transaction(offset, bytes);
+        }
+        for (chunk, start) in bytes
+            .chunks_mut(chunk_len)
+            .zip(buffer_offsets(offset, chunk_len))
+        {
+            self.read_buffer_transaction(start, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Reads bytes from the buffer in a single transaction.
+    fn read_buffer_transaction(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Error> {
+        let result = self
+            .busy
+            .wait_ready(self.settings.busy_timeout_us)
+            .map_err(|err| Error::buffer(offset, err.into()))
+            .and_then(|()| {
+                self.bus
+                    .read_buffer(offset, bytes)
+                    .map_err(|_| Error::buffer(offset, ErrorKind::Bus))
+            });
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Buffer(offset),
+            TraceDirection::Read,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::result(&result);
+        result?;
+
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Buffer(offset), TraceDirection::Read, bytes);
+
+        Ok(())
+    }
+
+    /// Waits for BUSY to go low, for at most `timeout_us`.
+    ///
+    /// The radio ignores any transaction started while BUSY is high, so NSS must only be
+    /// asserted once BUSY is low. Every command, register and buffer operation already waits,
+    /// for at most the [busy timeout](Device::set_busy_timeout_us). Waiting explicitly moves
+    /// that wait out of the next operation, e.g. to send a command at a precise time in a TDMA
+    /// slot or right after a frequency hop. BUSY can rise again until the radio has processed
+    /// the next command, so the wait only covers the commands already sent. Returns
+    /// immediately without a BUSY line.
+    ///
+    /// # Arguments
+    /// * `timeout_us` - Maximum wait in microseconds
+    ///
+    /// # Errors
+    /// * `Error::Control` with `ErrorKind::BusyStuck` - BUSY stayed high for the whole timeout
+    /// * `Error::Control` with `ErrorKind::Pin` - The BUSY line could not be read
+    ///
+    /// # Example
+    /// Starting TX at a slot boundary after a frequency hop:
+    /// ```no_run
+    /// use embedded_hal::{delay::DelayNs, digital::InputPin, spi::SpiDevice};
+    /// use sx1262::{
+    ///     pins::BusyPin, Device, PreparedCommand, RfFrequencyConfig, SetRfFrequency, SetTx, Timeout,
+    /// };
+    ///
+    /// fn hop_and_send(spi: impl SpiDevice, busy: impl InputPin, delay: impl DelayNs) {
+    ///     let mut device = Device::new(spi).with_busy(BusyPin::new(busy, delay));
+    ///     let tx = PreparedCommand::new(SetTx { timeout: Timeout::default() });
+    ///
+    ///     let config = RfFrequencyConfig { frequency: 915_200_000 };
+    ///     device.execute_command(SetRfFrequency { config }).unwrap();
+    ///     device.wait_busy(500).unwrap();
+    ///     // ... wait for the slot boundary ...
+    ///     device.execute_prepared(&tx).unwrap();
+    /// }
+    /// ```
+    pub fn wait_busy(&mut self, timeout_us: u32) -> Result<(), Error> {
+        self.busy
+            .wait_ready(timeout_us)
+            .map_err(|err| Error::control(err.into()))
+    }
+
+    /// Executes a command on the device.
+    ///
+    /// # Type Parameters
+    /// * `C` - Command type implementing the Command trait with u8 ID
+    ///
+    /// # Arguments
+    /// * `command` - The command to execute
+    ///
+    /// # Returns
+    /// Command response parameters on success
+    ///
+    /// # Important Notes
+    /// - The RF front-end is switched to TX or RX before SetTx, SetTxContinuousWave,
+    ///   SetTxInfinitePreamble, SetRx, SetRxDutyCycle and SetCad are sent
+    /// - The RF front-end is returned to idle after SetStandby, SetFs and SetSleep are sent,
+    ///   and when a command switching it to TX or RX fails
+    /// - After Calibrate, BUSY is waited for. Without a BUSY line the owned delay waits for
+    ///   [`CalibrationConfig::worst_case_duration_us`](crate::CalibrationConfig::worst_case_duration_us),
+    ///   as any command sent during the calibration is silently dropped
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Command` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    /// * `Error::Command` with `ErrorKind::Frontend` - The RF front-end failed to switch
+    /// * `Error::Command` with `ErrorKind::PacketTypeMismatch` - The modulation or packet
+    ///   parameters are for another packet type, see
+    ///   [`set_packet_type_check`](Device::set_packet_type_check)
+    /// * `Error::Command` with `ErrorKind::Deserialization` - Failed to parse command response
+    pub fn execute_command<C>(&mut self, command: C) -> Result<C::ResponseParameters, Error>
+    where
+        C: Command<IdType = u8>,
+        C::CommandParameters: ToByteArray<Error = Infallible>,
+    {
+        let Ok(request) = command.invoking_parameters().to_bytes();
+        self.check_packet_type(C::id(), requested_packet_type(C::id(), request.as_ref()))?;
+        let mut raw_response = <C::ResponseParameters as FromByteArray>::Array::new();
+
+        self.execute_raw(C::id(), request.as_ref(), raw_response.as_mut())?;
+
+        C::ResponseParameters::from_bytes(raw_response)
+            .map_err(|_| Error::command(C::id(), ErrorKind::Deserialization))
+    }
+
+    /// Executes a command serialized ahead of time.
+    ///
+    /// Behaves exactly like [`execute_command`](Device::execute_command), including the RF
+    /// front-end transitions, but sends the parameters serialized by
+    /// [`PreparedCommand::new`] instead of serializing them again.
+    ///
+    /// # Arguments
+    /// * `command` - The prepared command to execute
+    ///
+    /// # Returns
+    /// Command response parameters on success
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Command` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    /// * `Error::Command` with `ErrorKind::Frontend` - The RF front-end failed to switch
+    /// * `Error::Command` with `ErrorKind::PacketTypeMismatch` - The modulation or packet
+    ///   parameters are for another packet type, see
+    ///   [`set_packet_type_check`](Device::set_packet_type_check)
+    /// * `Error::Command` with `ErrorKind::Deserialization` - Failed to parse command response
+    pub fn execute_prepared<C>(
+        &mut self,
+        command: &PreparedCommand<C>,
+    ) -> Result<C::ResponseParameters, Error>
+    where
+        C: Command<IdType = u8>,
+        C::CommandParameters: ToByteArray<Error = Infallible>,
+    {
+        self.check_packet_type(C::id(), requested_packet_type(C::id(), command.params()))?;
+        let mut raw_response = <C::ResponseParameters as FromByteArray>::Array::new();
+
+        self.execute_raw(C::id(), command.params(), raw_response.as_mut())?;
+
+        C::ResponseParameters::from_bytes(raw_response)
+            .map_err(|_| Error::command(C::id(), ErrorKind::Deserialization))
+    }
+
+    /// Sends serialized command parameters, switching the RF front-end around the command.
+    fn execute_raw(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), Error> {
+        self.execute_raw_with_delay(opcode, request, response, None::<&mut NoDelay>)
+    }
+
+    /// Sends serialized command parameters like [`execute_raw`](Device::execute_raw),
+    /// waiting out a long command without BUSY with `lent` instead of the owned delay, if
+    /// given.
+    fn execute_raw_with_delay<L: OwnedDelay>(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+        lent: Option<&mut L>,
+    ) -> Result<(), Error> {
+        let result = self.execute_raw_unlogged(opcode, request, response, lent);
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Command(opcode),
+            TraceDirection::Write,
+            request,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::command(opcode, request, &result);
+        result
+    }
+
+    /// Sends serialized command parameters without recording them in the operation log.
+    fn execute_raw_unlogged<L: OwnedDelay>(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+        lent: Option<&mut L>,
+    ) -> Result<(), Error> {
+        let transition = Transition::for_opcode(opcode);
+
+        match transition {
+            Some(Transition::Tx) => self.frontend.enter_tx(),
+            Some(Transition::Rx) => self.frontend.enter_rx(),
+            _ => Ok(()),
+        }
+        .map_err(|_| Error::command(opcode, ErrorKind::Frontend))?;
+        let result = self.send_raw(opcode, request, response, transition, lent);
+        if result.is_err() && matches!(transition, Some(Transition::Tx | Transition::Rx)) {
+            // The PA or LNA must not stay enabled when the radio did not start the operation,
+            // the error of the command is the one reported
+            let _ = self.frontend.enter_idle();
+        }
+        result
+    }
+
+    /// Sends serialized command parameters once the RF front-end has been switched to TX or
+    /// RX, returning it to idle after the commands ending an operation.
+    fn send_raw<L: OwnedDelay>(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+        transition: Option<Transition>,
+        lent: Option<&mut L>,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Command(opcode), TraceDirection::Write, request);
+
+        self.busy
+            .wait_ready(self.settings.busy_timeout_us)
+            .map_err(|err| Error::command(opcode, err.into()))?;
+
+        self.bus
+            .command(opcode, request, response)
+            .map_err(|_| Error::command(opcode, ErrorKind::Bus))?;
+        self.observe_command(opcode, request, response);
+        if let Some(busy_us) = busy_after_us(opcode, request) {
+            if self.busy.is_connected() {
+                self.busy
+                    .wait_ready(self.settings.busy_timeout_us.max(busy_us * 2))
+                    .map_err(|err| Error::command(opcode, err.into()))?;
+            } else if let Some(delay) = lent {
+                delay.wait_us(busy_us);
+            } else {
+                // Commands sent while the radio is still busy are silently dropped
+                self.delay.wait_us(busy_us);
+            }
+        }
+
+        if transition == Some(Transition::Idle) {
+            self.frontend
+                .enter_idle()
+                .map_err(|_| Error::command(opcode, ErrorKind::Frontend))?;
+        }
+
+        #[cfg(feature = "trace")]
+        self.trace_response(opcode, response);
+
+        if self.settings.paranoid_checks && needs_status_check(opcode, request, response) {
+            let mut status = [0];
+            self.busy
+                .wait_ready(self.settings.busy_timeout_us)
+                .map_err(|err| Error::command(opcode, err.into()))?;
+            self.bus
+                .command(GetStatus::id(), &[], &mut status)
+                .map_err(|_| Error::command(opcode, ErrorKind::Bus))?;
+            let result = check_command_status(opcode, status[0], self.state.settled_mode);
+            if let Err(err) = result {
+                if let ErrorKind::PossibleChipReset { .. } = err.kind() {
+                    // A reset radio restarts in STDBY_RC
+                    self.state.settled_mode = Some(OperatingMode::StandbyRc);
+                }
+            }
+            result?;
+        }
+        if let (true, Some(expected)) = (
+            self.settings.paranoid_checks,
+            packet_type_to_verify(opcode, request),
+        ) {
+            let mut readback = [0; 2];
+            self.busy
+                .wait_ready(self.settings.busy_timeout_us)
+                .map_err(|err| Error::command(opcode, err.into()))?;
+            self.bus
+                .command(GetPacketType::id(), &[], &mut readback)
+                .map_err(|_| Error::command(opcode, ErrorKind::Bus))?;
+            self.state.packet_type = observed_packet_type(GetPacketType::id(), &[], &readback);
+            check_packet_type_readback(opcode, expected, readback)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously reads a register value from the device.
+    ///
+    /// This is the async version of [`read_register`](Device::read_register).
+    pub async fn read_register_async<R>(&mut self) -> Result<R, Error>
+    where
+        R: ReadableRegister<IdType = u16>,
+    {
+        let mut raw_value = R::Array::new();
+        self.read_register_raw_async(R::id(), raw_value.as_mut())
+            .await?;
+
+        R::from_bytes(raw_value).map_err(|_| Error::register(R::id(), ErrorKind::Deserialization))
+    }
+
+    /// Asynchronously reads raw bytes from consecutive registers starting at an address.
+    ///
+    /// This is the async version of [`read_register_raw`](Device::read_register_raw).
+    pub async fn read_register_raw_async(
+        &mut self,
+        address: u16,
+        bytes: &mut [u8],
+    ) -> Result<(), Error> {
+        let chunk_len = self.transfer_chunk_len(READ_REGISTER_HEADER_LEN);
+        if bytes.len() <= chunk_len {
+            return self.read_register_transaction_async(address, bytes).await;
+        }
+        for (chunk, start) in bytes.chunks_mut(chunk_len).zip((0..).step_by(chunk_len)) {
+            self.read_register_transaction_async(address.wrapping_add(start), chunk)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously reads consecutive registers in a single transaction.
+    async fn read_register_transaction_async(
+        &mut self,
+        address: u16,
+        bytes: &mut [u8],
+    ) -> Result<(), Error> {
+        let result = match self.busy.wait_ready(self.settings.busy_timeout_us).await {
+            Ok(()) => self
+                .bus
+                .read_register(address, bytes)
+                .await
+                .map_err(|_| Error::register(address, ErrorKind::Bus)),
+            Err(err) => Err(Error::register(address, err.into())),
+        };
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Register(address),
+            TraceDirection::Read,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::result(&result);
+        result?;
+
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Register(address), TraceDirection::Read, bytes);
+
+        Ok(())
+    }
+
+    /// Asynchronously writes a value to a device register.
+    ///
+    /// This is the async version of [`write_register`](Device::write_register).
+    pub async fn write_register_async<R>(&mut self, register: R) -> Result<(), Error>
+    where
+        R: WritableRegister<IdType = u16, Error = Infallible>,
+    {
+        let Ok(raw_value) = register.to_bytes();
+        self.write_register_raw_async(R::id(), raw_value.as_ref())
+            .await
+    }
+
+    /// Asynchronously writes raw bytes to consecutive registers starting at an address.
+    async fn write_register_raw_async(&mut self, address: u16, bytes: &[u8]) -> Result<(), Error> {
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Register(address), TraceDirection::Write, bytes);
+
+        let result = match self.busy.wait_ready(self.settings.busy_timeout_us).await {
+            Ok(()) => self
+                .bus
+                .write_register(address, bytes)
+                .await
+                .map_err(|_| Error::register(address, ErrorKind::Bus)),
+            Err(err) => Err(Error::register(address, err.into())),
+        };
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Register(address),
+            TraceDirection::Write,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::register_write(address, bytes, &result);
+        result
+    }
+
+    /// Asynchronously writes bytes to the device's buffer at a specified offset.
+    ///
+    /// This is the async version of [`write_buffer`](Device::write_buffer).
+    pub async fn write_buffer_async(&mut self, offset: u8, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        check_buffer_len(offset, bytes.len())?;
+        let chunk_len = self.transfer_chunk_len(WRITE_BUFFER_HEADER_LEN);
+        if bytes.len() <= chunk_len {
+            return self.write_buffer_transaction_async(offset, bytes).await;
+        }
+        for (chunk, start) in bytes
+            .chunks(chunk_len)
+            .zip(buffer_offsets(offset, chunk_len))
+        {
+            self.write_buffer_transaction_async(start, chunk).await?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously writes bytes to the buffer in a single transaction.
+    async fn write_buffer_transaction_async(
+        &mut self,
+        offset: u8,
+        bytes: &[u8],
+    ) -> Result<(), Error> {
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Buffer(offset), TraceDirection::Write, bytes);
+
+        let result = match self.busy.wait_ready(self.settings.busy_timeout_us).await {
+            Ok(()) => self
+                .bus
+                .write_buffer(offset, bytes)
+                .await
+                .map_err(|_| Error::buffer(offset, ErrorKind::Bus)),
+            Err(err) => Err(Error::buffer(offset, err.into())),
+        };
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Buffer(offset),
+            TraceDirection::Write,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::result(&result);
+        result
+    }
+
+    /// Asynchronously reads bytes from the device's buffer starting at a specified offset.
+    ///
+    /// This is the async version of [`read_buffer`](Device::read_buffer).
+    pub async fn read_buffer_async(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        check_buffer_len(offset, bytes.len())?;
+        let chunk_len = self.transfer_chunk_len(READ_BUFFER_HEADER_LEN);
+        if bytes.len() <= chunk_len {
+            return self.read_buffer_transaction_async(offset, bytes).await;
+        }
+        for (chunk, start) in bytes
+            .chunks_mut(chunk_len)
+            .zip(buffer_offsets(offset, chunk_len))
+        {
+            self.read_buffer_transaction_async(start, chunk).await?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously reads bytes from the buffer in a single transaction.
+    async fn read_buffer_transaction_async(
+        &mut self,
+        offset: u8,
+        bytes: &mut [u8],
+    ) -> Result<(), Error> {
+        let result = match self.busy.wait_ready(self.settings.busy_timeout_us).await {
+            Ok(()) => self
+                .bus
+                .read_buffer(offset, bytes)
+                .await
+                .map_err(|_| Error::buffer(offset, ErrorKind::Bus)),
+            Err(err) => Err(Error::buffer(offset, err.into())),
+        };
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Buffer(offset),
+            TraceDirection::Read,
+            bytes,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::result(&result);
+        result?;
+
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Buffer(offset), TraceDirection::Read, bytes);
+
+        Ok(())
+    }
+
+    /// Asynchronously waits for BUSY to go low, for at most `timeout_us`.
+    ///
+    /// This is the async version of [`wait_busy`](Device::wait_busy).
+    pub async fn wait_busy_async(&mut self, timeout_us: u32) -> Result<(), Error> {
+        self.busy
+            .wait_ready(timeout_us)
+            .await
+            .map_err(|err| Error::control(err.into()))
+    }
+
+    /// Asynchronously executes a command on the device.
+    ///
+    /// This is the async version of [`execute_command`](Device::execute_command).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::AsyncRadioBus, Calibrate, CalibrationConfig, Device};
+    ///
+    /// async fn calibrate(device: &mut Device<impl AsyncRadioBus>) {
+    ///     let config = CalibrationConfig::all();
+    ///     device.execute_command_async(Calibrate { config }).await.unwrap();
+    /// }
+    /// ```
+    pub async fn execute_command_async<C>(
+        &mut self,
+        command: C,
+    ) -> Result<C::ResponseParameters, Error>
+    where
+        C: Command<IdType = u8>,
+        C::CommandParameters: ToByteArray<Error = Infallible>,
+    {
+        let Ok(request) = command.invoking_parameters().to_bytes();
+        self.check_packet_type(C::id(), requested_packet_type(C::id(), request.as_ref()))?;
+        let mut raw_response = <C::ResponseParameters as FromByteArray>::Array::new();
+
+        self.execute_raw_async(C::id(), request.as_ref(), raw_response.as_mut())
+            .await?;
+
+        C::ResponseParameters::from_bytes(raw_response)
+            .map_err(|_| Error::command(C::id(), ErrorKind::Deserialization))
+    }
+
+    /// Asynchronously executes a command serialized ahead of time.
+    ///
+    /// This is the async version of [`execute_prepared`](Device::execute_prepared).
+    pub async fn execute_prepared_async<C>(
+        &mut self,
+        command: &PreparedCommand<C>,
+    ) -> Result<C::ResponseParameters, Error>
+    where
+        C: Command<IdType = u8>,
+        C::CommandParameters: ToByteArray<Error = Infallible>,
+    {
+        self.check_packet_type(C::id(), requested_packet_type(C::id(), command.params()))?;
+        let mut raw_response = <C::ResponseParameters as FromByteArray>::Array::new();
+
+        self.execute_raw_async(C::id(), command.params(), raw_response.as_mut())
+            .await?;
+
+        C::ResponseParameters::from_bytes(raw_response)
+            .map_err(|_| Error::command(C::id(), ErrorKind::Deserialization))
+    }
+
+    /// Asynchronously sends serialized command parameters, switching the RF front-end around
+    /// the command.
+    async fn execute_raw_async(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<(), Error> {
+        self.execute_raw_with_delay_async(opcode, request, response, None::<&mut NoDelay>)
+            .await
+    }
+
+    /// Asynchronously sends serialized command parameters, waiting out a long command without
+    /// BUSY with `lent` instead of the owned delay, if given.
+    ///
+    /// This is the async version of [`execute_raw_with_delay`](Device::execute_raw_with_delay).
+    async fn execute_raw_with_delay_async<L: AsyncOwnedDelay>(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+        lent: Option<&mut L>,
+    ) -> Result<(), Error> {
+        let result = self
+            .execute_raw_unlogged_async(opcode, request, response, lent)
+            .await;
+        #[cfg(feature = "oplog")]
+        self.log_operation(
+            TraceTarget::Command(opcode),
+            TraceDirection::Write,
+            request,
+            &result,
+        );
+        #[cfg(feature = "log")]
+        log_records::command(opcode, request, &result);
+        result
+    }
+
+    /// Asynchronously sends serialized command parameters without recording them in the
+    /// operation log.
+    async fn execute_raw_unlogged_async<L: AsyncOwnedDelay>(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+        lent: Option<&mut L>,
+    ) -> Result<(), Error> {
+        let transition = Transition::for_opcode(opcode);
+
+        match transition {
+            Some(Transition::Tx) => self.frontend.enter_tx().await,
+            Some(Transition::Rx) => self.frontend.enter_rx().await,
+            _ => Ok(()),
+        }
+        .map_err(|_| Error::command(opcode, ErrorKind::Frontend))?;
+        let result = self
+            .send_raw_async(opcode, request, response, transition, lent)
+            .await;
+        if result.is_err() && matches!(transition, Some(Transition::Tx | Transition::Rx)) {
+            // The PA or LNA must not stay enabled when the radio did not start the operation,
+            // the error of the command is the one reported
+            let _ = self.frontend.enter_idle().await;
+        }
+        result
+    }
+
+    /// Asynchronously sends serialized command parameters once the RF front-end has been
+    /// switched to TX or RX.
+    ///
+    /// This is the async version of [`send_raw`](Device::send_raw).
+    async fn send_raw_async<L: AsyncOwnedDelay>(
+        &mut self,
+        opcode: u8,
+        request: &[u8],
+        response: &mut [u8],
+        transition: Option<Transition>,
+        lent: Option<&mut L>,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "trace")]
+        self.trace(TraceTarget::Command(opcode), TraceDirection::Write, request);
+
+        self.busy
+            .wait_ready(self.settings.busy_timeout_us)
+            .await
+            .map_err(|err| Error::command(opcode, err.into()))?;
+
+        self.bus
+            .command(opcode, request, response)
+            .await
+            .map_err(|_| Error::command(opcode, ErrorKind::Bus))?;
+        self.observe_command(opcode, request, response);
+        if let Some(busy_us) = busy_after_us(opcode, request) {
+            if self.busy.is_connected() {
+                self.busy
+                    .wait_ready(self.settings.busy_timeout_us.max(busy_us * 2))
+                    .await
+                    .map_err(|err| Error::command(opcode, err.into()))?;
+            } else if let Some(delay) = lent {
+                delay.wait_us(busy_us).await;
+            } else {
+                // Commands sent while the radio is still busy are silently dropped
+                self.delay.wait_us(busy_us).await;
+            }
+        }
+
+        if transition == Some(Transition::Idle) {
+            self.frontend
+                .enter_idle()
+                .await
+                .map_err(|_| Error::command(opcode, ErrorKind::Frontend))?;
+        }
+
+        #[cfg(feature = "trace")]
+        self.trace_response(opcode, response);
+
+        if self.settings.paranoid_checks && needs_status_check(opcode, request, response) {
+            let mut status = [0];
+            self.busy
+                .wait_ready(self.settings.busy_timeout_us)
+                .await
+                .map_err(|err| Error::command(opcode, err.into()))?;
+            self.bus
+                .command(GetStatus::id(), &[], &mut status)
+                .await
+                .map_err(|_| Error::command(opcode, ErrorKind::Bus))?;
+            let result = check_command_status(opcode, status[0], self.state.settled_mode);
+            if let Err(err) = result {
+                if let ErrorKind::PossibleChipReset { .. } = err.kind() {
+                    // A reset radio restarts in STDBY_RC
+                    self.state.settled_mode = Some(OperatingMode::StandbyRc);
+                }
+            }
+            result?;
+        }
+        if let (true, Some(expected)) = (
+            self.settings.paranoid_checks,
+            packet_type_to_verify(opcode, request),
+        ) {
+            let mut readback = [0; 2];
+            self.busy
+                .wait_ready(self.settings.busy_timeout_us)
+                .await
+                .map_err(|err| Error::command(opcode, err.into()))?;
+            self.bus
+                .command(GetPacketType::id(), &[], &mut readback)
+                .await
+                .map_err(|_| Error::command(opcode, ErrorKind::Bus))?;
+            self.state.packet_type = observed_packet_type(GetPacketType::id(), &[], &readback);
+            check_packet_type_readback(opcode, expected, readback)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+//! Time spent in each operating mode for power budgeting
+//!
+//! The Device follows the operating mode from the commands it sends and the IRQs it reads, and
+//! accumulates the time spent in each mode with the installed [`Clock`](super::Clock).
+
+use regiface::Command;
+
+use super::Device;
+use crate::{
+    FallbackMode, IrqMask, SetCad, SetFs, SetRx, SetRxDutyCycle, SetRxTxFallbackMode, SetSleep,
+    SetStandby, SetTx, SetTxContinuousWave, SetTxInfinitePreamble,
+};
+
+/// SetRx timeout of a continuous RX, which no IRQ ends
+const RX_CONTINUOUS: [u8; 3] = [0xFF, 0xFF, 0xFF];
+
+/// Operating mode of the radio, including sleep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DwellMode {
+    /// Sleep, warm or cold start
+    Sleep,
+    /// STDBY_RC
+    StandbyRc,
+    /// STDBY_XOSC
+    StandbyXosc,
+    /// FS
+    FrequencySynthesizer,
+    /// TX, including continuous wave and infinite preamble
+    Transmit,
+    /// RX, including CAD and RX duty cycle
+    Receive,
+}
+
+impl From<FallbackMode> for DwellMode {
+    fn from(mode: FallbackMode) -> Self {
+        match mode {
+            FallbackMode::Fs => Self::FrequencySynthesizer,
+            FallbackMode::StdbyXosc => Self::StandbyXosc,
+            FallbackMode::StdbyRc => Self::StandbyRc,
+        }
+    }
+}
+
+/// Time spent in each operating mode, in units of the [`Clock`](super::Clock)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ModeDwellTimes {
+    /// Time asleep
+    pub sleep: u64,
+    /// Time in STDBY_RC
+    pub standby_rc: u64,
+    /// Time in STDBY_XOSC
+    pub standby_xosc: u64,
+    /// Time in FS
+    pub frequency_synthesizer: u64,
+    /// Time in TX
+    pub transmit: u64,
+    /// Time in RX
+    pub receive: u64,
+}
+
+impl ModeDwellTimes {
+    /// Returns the time spent in a mode.
+    pub fn get(&self, mode: DwellMode) -> u64 {
+        match mode {
+            DwellMode::Sleep => self.sleep,
+            DwellMode::StandbyRc => self.standby_rc,
+            DwellMode::StandbyXosc => self.standby_xosc,
+            DwellMode::FrequencySynthesizer => self.frequency_synthesizer,
+            DwellMode::Transmit => self.transmit,
+            DwellMode::Receive => self.receive,
+        }
+    }
+
+    /// Adds time to a mode.
+    fn add(&mut self, mode: DwellMode, elapsed: u32) {
+        let time = match mode {
+            DwellMode::Sleep => &mut self.sleep,
+            DwellMode::StandbyRc => &mut self.standby_rc,
+            DwellMode::StandbyXosc => &mut self.standby_xosc,
+            DwellMode::FrequencySynthesizer => &mut self.frequency_synthesizer,
+            DwellMode::Transmit => &mut self.transmit,
+            DwellMode::Receive => &mut self.receive,
+        };
+        *time = time.saturating_add(u64::from(elapsed));
+    }
+}
+
+/// Operating mode tracking and dwell time accumulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct ModeDwell {
+    times: ModeDwellTimes,
+    mode: Option<DwellMode>,
+    since: Option<u32>,
+    /// IRQs ending the current mode, and the mode entered then
+    ends_on: Option<(IrqMask, DwellMode)>,
+    fallback: DwellMode,
+}
+
+impl Default for ModeDwell {
+    fn default() -> Self {
+        Self {
+            times: ModeDwellTimes::default(),
+            mode: None,
+            since: None,
+            ends_on: None,
+            fallback: DwellMode::StandbyRc,
+        }
+    }
+}
+
+impl ModeDwell {
+    /// Returns the accumulated times, including the current mode up to `now`.
+    fn times(&self, now: Option<u32>) -> ModeDwellTimes {
+        let mut times = self.times;
+        if let (Some(mode), Some(since), Some(now)) = (self.mode, self.since, now) {
+            times.add(mode, now.wrapping_sub(since));
+        }
+        times
+    }
+
+    /// Clears the accumulated times, the current mode is counted again from `now`.
+    fn reset(&mut self, now: Option<u32>) {
+        self.times = ModeDwellTimes::default();
+        self.since = now;
+    }
+
+    /// Closes the time in the current mode and enters another one.
+    pub(super) fn enter(
+        &mut self,
+        mode: DwellMode,
+        ends_on: Option<(IrqMask, DwellMode)>,
+        now: Option<u32>,
+    ) {
+        self.times = self.times(now);
+        self.mode = Some(mode);
+        self.since = now;
+        self.ends_on = ends_on;
+    }
+
+    /// Follows a command sent to the radio.
+    pub(super) fn observe_command(&mut self, opcode: u8, request: &[u8], now: Option<u32>) {
+        if opcode == SetRxTxFallbackMode::id() {
+            if let Some(mode) = request.first().and_then(|&byte| fallback_mode(byte)) {
+                self.fallback = mode;
+            }
+        }
+
+        let tx_end = Some((IrqMask::TX_DONE | IrqMask::TIMEOUT, self.fallback));
+        let rx_end = Some((IrqMask::RX_DONE | IrqMask::TIMEOUT, self.fallback));
+        let (mode, ends_on) = if opcode == SetSleep::id() {
+            (DwellMode::Sleep, None)
+        } else if opcode == SetStandby::id() {
+            match request.first() {
+                Some(0) => (DwellMode::StandbyRc, None),
+                _ => (DwellMode::StandbyXosc, None),
+            }
+        } else if opcode == SetFs::id() {
+            (DwellMode::FrequencySynthesizer, None)
+        } else if opcode == SetTx::id() {
+            (DwellMode::Transmit, tx_end)
+        } else if [SetTxContinuousWave::id(), SetTxInfinitePreamble::id()].contains(&opcode) {
+            (DwellMode::Transmit, None)
+        } else if opcode == SetRx::id() {
+            let continuous = request == RX_CONTINUOUS;
+            (DwellMode::Receive, if continuous { None } else { rx_end })
+        } else if opcode == SetRxDutyCycle::id() {
+            (DwellMode::Receive, None)
+        } else if opcode == SetCad::id() {
+            (
+                DwellMode::Receive,
+                Some((IrqMask::CAD_DONE, DwellMode::StandbyRc)),
+            )
+        } else if self.mode == Some(DwellMode::Sleep) {
+            // Any other command wakes the radio up
+            (DwellMode::StandbyRc, None)
+        } else {
+            return;
+        };
+        self.enter(mode, ends_on, now);
+    }
+
+    /// Follows IRQs read from the radio, which may end a TX, RX or CAD.
+    pub(super) fn observe_irqs(&mut self, irq: IrqMask, now: Option<u32>) {
+        if let Some((ends_on, next)) = self.ends_on {
+            if irq.intersects(ends_on) {
+                self.enter(next, None, now);
+            }
+        }
+    }
+}
+
+/// Returns the mode of a serialized SetRxTxFallbackMode parameter.
+fn fallback_mode(byte: u8) -> Option<DwellMode> {
+    [
+        FallbackMode::Fs,
+        FallbackMode::StdbyXosc,
+        FallbackMode::StdbyRc,
+    ]
+    .into_iter()
+    .find(|&mode| mode as u8 == byte)
+    .map(DwellMode::from)
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Returns the time spent in each operating mode.
+    ///
+    /// Times are counted with the [clock](Device::set_clock) and are in its unit, e.g.
+    /// microseconds for a microsecond clock. Nothing is counted without a clock. The current
+    /// mode is included up to now.
+    ///
+    /// # Accuracy
+    /// The mode is followed from the commands sent and the IRQs read by the Device, so the
+    /// times are only as accurate as the transitions it observes:
+    /// - A TX or RX ends when its IRQ is read, not when it is raised, so the time in TX or RX
+    ///   includes the latency of reading the IRQs. The radio is then counted in the mode set
+    ///   with SetRxTxFallbackMode. IRQs read by an [`IrqHandle`](crate::IrqHandle) are not seen
+    /// - CAD is counted as RX and ends in STDBY_RC on CAD_DONE, a CAD followed by RX is not
+    ///   followed
+    /// - The RX and sleep periods of SetRxDutyCycle are all counted as RX
+    /// - A radio woken from sleep is counted in STDBY_RC from the next command
+    /// - Nothing is counted before the first command setting the mode
+    ///
+    /// # Example
+    /// With a microsecond clock:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn report(device: &mut Device<impl RadioBus>) {
+    ///     let times = device.mode_dwell_times();
+    ///     println!("{} µs in TX, {} µs in RX", times.transmit, times.receive);
+    ///     device.reset_mode_dwell_times();
+    /// }
+    /// ```
+    pub fn mode_dwell_times(&self) -> ModeDwellTimes {
+        self.state
+            .mode_dwell
+            .times(self.settings.clock.map(|clock| clock()))
+    }
+
+    /// Resets the time spent in each operating mode, the current mode is counted from now.
+    pub fn reset_mode_dwell_times(&mut self) {
+        self.state
+            .mode_dwell
+            .reset(self.settings.clock.map(|clock| clock()));
+    }
+}
@@ -0,0 +1,190 @@
+//! Ring buffer of the last radio operations for post-mortem debugging
+//!
+//! With the `oplog` feature, the [`Device`] records every command, register and buffer
+//! operation with its outcome in a fixed-size [`OperationLog`], overwriting the oldest entry
+//! once full. Recording copies a few bytes and never allocates, so the log can stay enabled in
+//! production and be dumped from RAM when a field unit misbehaves.
+
+use super::Device;
+#[cfg(any(feature = "sync", feature = "async"))]
+use crate::error::Error;
+use crate::{
+    error::ErrorKind,
+    trace::{TraceDirection, TraceTarget},
+};
+
+/// Number of operations kept by the [`Device`]
+pub const OPERATION_LOG_DEPTH: usize = 16;
+
+/// Number of parameter bytes kept per operation
+pub const OPERATION_PARAMS_LEN: usize = 4;
+
+/// A recorded radio operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Operation {
+    /// What the operation addressed
+    pub target: TraceTarget,
+    /// Whether the parameters were written or read
+    pub direction: TraceDirection,
+    /// The outcome, with the cause of a failure
+    pub result: Result<(), ErrorKind>,
+    params: [u8; OPERATION_PARAMS_LEN],
+    params_len: u8,
+}
+
+impl Operation {
+    /// Returns the first parameter bytes, at most [`OPERATION_PARAMS_LEN`].
+    ///
+    /// Commands keep the bytes sent, reads keep the bytes received.
+    pub fn params(&self) -> &[u8] {
+        &self.params[..usize::from(self.params_len)]
+    }
+}
+
+/// Fixed-size ring buffer of the last `N` operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OperationLog<const N: usize = OPERATION_LOG_DEPTH> {
+    entries: [Option<Operation>; N],
+    next: usize,
+}
+
+impl<const N: usize> Default for OperationLog<N> {
+    fn default() -> Self {
+        Self {
+            entries: [None; N],
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> OperationLog<N> {
+    /// Records an operation, overwriting the oldest one once full.
+    ///
+    /// # Arguments
+    /// * `target` - What the operation addressed
+    /// * `direction` - Whether the bytes were written or read
+    /// * `bytes` - The parameters, only the first [`OPERATION_PARAMS_LEN`] are kept
+    /// * `result` - The outcome
+    pub fn record(
+        &mut self,
+        target: TraceTarget,
+        direction: TraceDirection,
+        bytes: &[u8],
+        result: Result<(), ErrorKind>,
+    ) {
+        if N == 0 {
+            return;
+        }
+        let params_len = bytes.len().min(OPERATION_PARAMS_LEN);
+        let mut params = [0; OPERATION_PARAMS_LEN];
+        params[..params_len].copy_from_slice(&bytes[..params_len]);
+
+        self.entries[self.next] = Some(Operation {
+            target,
+            direction,
+            result,
+            params,
+            params_len: params_len as u8,
+        });
+        self.next = (self.next + 1) % N;
+    }
+
+    /// Returns the recorded operations, oldest first.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{
+    ///     trace::{TraceDirection, TraceTarget},
+    ///     ErrorKind, OperationLog,
+    /// };
+    ///
+    /// let mut log = OperationLog::<3>::default();
+    /// for opcode in 0..5 {
+    ///     log.record(TraceTarget::Command(opcode), TraceDirection::Write, &[opcode; 6], Ok(()));
+    /// }
+    ///
+    /// // The two oldest were overwritten
+    /// let opcodes: Vec<_> = log.iter().map(|op| op.target).collect();
+    /// assert_eq!(opcodes, [2, 3, 4].map(TraceTarget::Command));
+    /// assert_eq!(log.last().unwrap().params(), [4; 4]);
+    ///
+    /// log.record(TraceTarget::Buffer(0), TraceDirection::Read, &[], Err(ErrorKind::Bus));
+    /// assert_eq!(log.last().unwrap().result, Err(ErrorKind::Bus));
+    ///
+    /// log.clear();
+    /// assert_eq!(log.iter().count(), 0);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Operation> + '_ {
+        let (newer, older) = self.entries.split_at(self.next);
+        older.iter().chain(newer).flatten()
+    }
+
+    /// Returns the most recent operation, if any.
+    pub fn last(&self) -> Option<&Operation> {
+        let index = match self.next {
+            0 => N.checked_sub(1)?,
+            next => next - 1,
+        };
+        self.entries[index].as_ref()
+    }
+
+    /// Removes every recorded operation.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Returns the last [`OPERATION_LOG_DEPTH`] operations, oldest first.
+    ///
+    /// Every command, register and buffer operation is recorded once it completes, with the
+    /// first parameter bytes and the cause of a failure. Commands sent internally for paranoid
+    /// checks are not recorded.
+    ///
+    /// # Example
+    /// Reporting what led to a failure:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn report(device: &Device<impl RadioBus>) {
+    ///     for operation in device.operation_log() {
+    ///         let (target, result) = (operation.target, operation.result);
+    ///         println!("{target:?} {result:?} {:02X?}", operation.params());
+    ///     }
+    /// }
+    /// ```
+    pub fn operation_log(&self) -> impl DoubleEndedIterator<Item = &Operation> + '_ {
+        self.state.operation_log.iter()
+    }
+
+    /// Returns the most recent command recorded in the
+    /// [operation log](Device::operation_log), if any.
+    pub fn last_command(&self) -> Option<&Operation> {
+        self.state
+            .operation_log
+            .iter()
+            .rev()
+            .find(|op| matches!(op.target, TraceTarget::Command(_)))
+    }
+
+    /// Removes every operation from the [operation log](Device::operation_log).
+    pub fn clear_operation_log(&mut self) {
+        self.state.operation_log.clear();
+    }
+
+    /// Records a completed operation in the operation log.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    pub(super) fn log_operation(
+        &mut self,
+        target: TraceTarget,
+        direction: TraceDirection,
+        bytes: &[u8],
+        result: &Result<(), Error>,
+    ) {
+        let result = result.as_ref().map(|_| ()).map_err(Error::kind);
+        self.state
+            .operation_log
+            .record(target, direction, bytes, result);
+    }
+}
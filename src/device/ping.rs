@@ -0,0 +1,359 @@
+//! Round-trip latency measurement
+//!
+//! [`Device::ping`] sends a small frame that a peer running [`Device::respond_to_pings`] sends
+//! back, and measures the round trip with a microsecond clock. The time both frames spend on
+//! air is estimated from the applied configuration and subtracted, leaving the turnaround of
+//! both nodes plus the propagation delay, for coarse distance and latency characterization.
+//!
+//! # Frame format
+//! Both frames are sent as the payload of the configured packet format:
+//!
+//! | Frame | Byte 0 | Byte 1          | Then                                            |
+//! |-------|--------|-----------------|-------------------------------------------------|
+//! | Ping  | `0x50` | Sequence number | Payload, up to 252 bytes                        |
+//! | Reply | `0x70` | Sequence number | RSSI of the ping in dBm, then the ping payload  |
+//!
+//! The sequence number increments with every ping and wraps around. The RSSI is signed, with
+//! `0x7F` when the peer does not report it. Packets that are not pings are ignored by the
+//! responder.
+//!
+//! # Time on air
+//! The estimate follows the formulas of the datasheet: preamble, header, payload and CRC for
+//! LoRa, and every bit sent at the bit rate for GFSK. Without an applied configuration the
+//! airtime is unknown and the round trip is reported as measured.
+
+use super::{Clock, Device};
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::RadioConfig,
+    error::{PingError, TxError},
+    AddressFiltering, CrcType, GFSKPacketHeaderType, LoraPacketHeaderType, ModulationParams,
+    PacketParams, PacketStatus, PacketType, RxMode, Timeout,
+};
+
+/// First byte of a ping
+const PING: u8 = 0x50;
+/// First byte of a reply
+const REPLY: u8 = 0x70;
+/// RSSI of a reply whose sender does not report it
+const RSSI_UNKNOWN: i8 = i8::MAX;
+/// Longest ping payload, so that the reply fits in a packet
+const MAX_PING_PAYLOAD: usize = 252;
+
+/// A round trip measured by [`Device::ping`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PingResult {
+    /// Round-trip time in microseconds, without the time on air of both frames when known
+    pub rtt_us: u32,
+    /// Estimated time on air of the ping and its reply in microseconds, `None` without an
+    /// applied configuration
+    pub airtime_us: Option<u32>,
+    /// RSSI of the reply in dBm
+    pub rssi_here: i16,
+    /// RSSI of the ping at the peer in dBm, when reported
+    pub rssi_reported_by_peer: Option<i16>,
+}
+
+/// Writes the reply to `ping` into `frame` and returns its length, or `None` if `ping` is not
+/// a ping.
+fn reply_frame(frame: &mut [u8; 255], ping: &[u8], rssi: i16) -> Option<usize> {
+    let [PING, sequence, ref payload @ ..] = *ping else {
+        return None;
+    };
+    if payload.len() > MAX_PING_PAYLOAD {
+        return None;
+    }
+    let rssi = rssi.clamp(i16::from(i8::MIN), i16::from(RSSI_UNKNOWN) - 1) as i8;
+    let len = 3 + payload.len();
+    frame[..3].copy_from_slice(&[REPLY, sequence, rssi as u8]);
+    frame[3..len].copy_from_slice(payload);
+    Some(len)
+}
+
+/// Returns the RSSI reported by `reply`, or `None` if it does not answer `ping`.
+fn parse_reply(reply: &[u8], ping: &[u8]) -> Option<Option<i16>> {
+    let [REPLY, sequence, rssi, ref echo @ ..] = *reply else {
+        return None;
+    };
+    let [PING, sent, ref payload @ ..] = *ping else {
+        return None;
+    };
+    (sequence == sent && echo == payload).then(|| {
+        let rssi = rssi as i8;
+        (rssi != RSSI_UNKNOWN).then_some(i16::from(rssi))
+    })
+}
+
+/// Estimates the time on air of a packet with a payload of `len` bytes, in microseconds.
+fn airtime_us(config: &RadioConfig, len: usize) -> Option<u32> {
+    let len = len as u64;
+    let us = match (config.modulation, config.packet) {
+        (ModulationParams::LoRa(modulation), PacketParams::LoRa(packet)) => {
+            let sf = modulation.spreading_factor as u64;
+            let crc = if packet.crc_enable { 16 } else { 0 };
+            let header = match packet.header_type {
+                LoraPacketHeaderType::Variable => 20,
+                LoraPacketHeaderType::Fixed => 0,
+            };
+            // Symbols after the preamble, in quarters: 6.25 for SF5 and SF6, 4.25 otherwise
+            let (sync, bits) = if sf < 7 {
+                (25, 8 * len + crc + header)
+            } else {
+                (17, 8 * len + crc + 8 + header)
+            };
+            let bits_per_block = 4 * if modulation.low_data_rate_opt {
+                sf - 2
+            } else {
+                sf
+            };
+            let blocks = bits.saturating_sub(4 * sf).div_ceil(bits_per_block);
+            let symbols = 8 + blocks * (modulation.coding_rate as u64 + 4);
+            let quarters = 4 * u64::from(packet.preamble_length) + sync + 4 * symbols;
+            (quarters * (1_000_000 << sf)) / (4 * u64::from(modulation.bandwidth.hz()))
+        }
+        (ModulationParams::Gfsk(modulation), PacketParams::GFSK(packet)) => {
+            let crc = match packet.crc_type {
+                CrcType::CrcOff => 0,
+                CrcType::Crc1Byte | CrcType::Crc1ByteInv => 8,
+                CrcType::Crc2Byte | CrcType::Crc2ByteInv => 16,
+            };
+            let header = match packet.packet_type {
+                GFSKPacketHeaderType::Variable => 8,
+                GFSKPacketHeaderType::Fixed => 0,
+            };
+            let address = match packet.address_filtering {
+                AddressFiltering::Disable => 0,
+                AddressFiltering::Node | AddressFiltering::NodeAndBroadcast => 8,
+            };
+            let bits = u64::from(packet.preamble.as_bits())
+                + u64::from(packet.sync_word_length)
+                + header
+                + address
+                + 8 * len
+                + crc;
+            bits * 1_000_000 / u64::from(modulation.bit_rate.max(1))
+        }
+        _ => return None,
+    };
+    u32::try_from(us).ok()
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Writes the next ping into `frame` and returns its length.
+    fn ping_frame(&mut self, frame: &mut [u8; 255], payload: &[u8]) -> Result<usize, TxError> {
+        if payload.len() > MAX_PING_PAYLOAD {
+            return Err(TxError::PayloadTooLong(payload.len()));
+        }
+        let len = 2 + payload.len();
+        frame[..2].copy_from_slice(&[PING, self.state.ping_sequence]);
+        frame[2..len].copy_from_slice(payload);
+        self.state.ping_sequence = self.state.ping_sequence.wrapping_add(1);
+        Ok(len)
+    }
+
+    /// Returns the RSSI of a received packet in dBm.
+    fn packet_rssi_dbm(&self, status: &PacketStatus) -> i16 {
+        let raw = match self.state.packet_type {
+            Some(PacketType::Gfsk) => status.status[2],
+            _ => status.status[0],
+        };
+        (2 * i16::from(self.settings.rssi_offset_db) - i16::from(raw)) / 2
+    }
+
+    /// Checks the reply to a ping and subtracts the time on air from the round trip.
+    fn ping_result(
+        &self,
+        ping: &[u8],
+        reply: &[u8],
+        status: &PacketStatus,
+        elapsed_us: u32,
+    ) -> Result<PingResult, PingError> {
+        let rssi_reported_by_peer = parse_reply(reply, ping).ok_or(PingError::UnexpectedReply)?;
+        let airtime_us = self.state.config.as_ref().and_then(|config| {
+            airtime_us(config, ping.len())?.checked_add(airtime_us(config, reply.len())?)
+        });
+        Ok(PingResult {
+            rtt_us: elapsed_us.saturating_sub(airtime_us.unwrap_or(0)),
+            airtime_us,
+            rssi_here: self.packet_rssi_dbm(status),
+            rssi_reported_by_peer,
+        })
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Sends a ping and waits for its reply, measuring the round trip.
+    ///
+    /// The clock is read right before the ping is written and right after the reply is read,
+    /// so the round trip also includes the bus transfers on this side. See the
+    /// [module documentation](self) for the frames exchanged.
+    ///
+    /// # Arguments
+    /// * `payload` - Bytes sent back by the peer, at most 252
+    /// * `timeout` - TX timeout of the ping, then RX timeout of the reply, 0 to wait for the
+    ///   reply without timeout
+    /// * `clock` - Monotonic clock counting microseconds, which may wrap around
+    ///
+    /// # Errors
+    /// * `PingError::Tx` - Sending the ping failed as for [`transmit`](Device::transmit), or
+    ///   the payload is longer than 252 bytes
+    /// * `PingError::Rx` - Receiving the reply failed as for [`receive`](Device::receive)
+    /// * `PingError::UnexpectedReply` - The packet received does not answer this ping
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, Timeout};
+    ///
+    /// fn measure(device: &mut Device<impl RadioBus>, clock: fn() -> u32) {
+    ///     let result = device.ping(b"hello", Timeout::default(), clock).unwrap();
+    ///     let rtt_us = result.rtt_us;
+    /// }
+    /// ```
+    pub fn ping(
+        &mut self,
+        payload: &[u8],
+        timeout: Timeout,
+        clock: Clock,
+    ) -> Result<PingResult, PingError> {
+        let mut ping = [0; 255];
+        let len = self.ping_frame(&mut ping, payload)?;
+
+        let start = clock();
+        self.transmit(&ping[..len], timeout)?;
+        let mut reply = [0; 255];
+        let packet = self.receive(&mut reply, RxMode::Timed(timeout.0))?;
+        let elapsed_us = clock().wrapping_sub(start);
+
+        self.ping_result(
+            &ping[..len],
+            &reply[..packet.len],
+            &packet.status,
+            elapsed_us,
+        )
+    }
+
+    /// Answers `count` pings sent with [`ping`](Device::ping), skipping any other packet.
+    ///
+    /// Each reply carries the RSSI the ping was received with, and is sent as soon as the ping
+    /// is read.
+    ///
+    /// # Arguments
+    /// * `count` - Number of pings to answer
+    /// * `mode` - RX mode of each wait for a ping, with the timeout if any
+    /// * `timeout` - TX timeout of each reply
+    ///
+    /// # Errors
+    /// * `PingError::Rx` - Receiving a ping failed as for [`receive`](Device::receive)
+    /// * `PingError::Tx` - Sending a reply failed as for [`transmit`](Device::transmit)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, RxMode, Timeout};
+    ///
+    /// fn respond(device: &mut Device<impl RadioBus>) {
+    ///     device
+    ///         .respond_to_pings(10, RxMode::Single, Timeout::default())
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn respond_to_pings(
+        &mut self,
+        count: u16,
+        mode: RxMode,
+        timeout: Timeout,
+    ) -> Result<(), PingError> {
+        let mut answered = 0;
+        while answered < count {
+            let mut ping = [0; 255];
+            let packet = self.receive(&mut ping, mode)?;
+            let rssi = self.packet_rssi_dbm(&packet.status);
+
+            let mut reply = [0; 255];
+            if let Some(len) = reply_frame(&mut reply, &ping[..packet.len], rssi) {
+                self.transmit(&reply[..len], timeout)?;
+                answered += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously sends a ping and waits for its reply, measuring the round trip.
+    ///
+    /// This is the async version of [`ping`](Device::ping).
+    pub async fn ping_async(
+        &mut self,
+        payload: &[u8],
+        timeout: Timeout,
+        clock: Clock,
+    ) -> Result<PingResult, PingError> {
+        let mut ping = [0; 255];
+        let len = self.ping_frame(&mut ping, payload)?;
+
+        let start = clock();
+        self.transmit_async(&ping[..len], timeout).await?;
+        let mut reply = [0; 255];
+        let packet = self
+            .receive_async(&mut reply, RxMode::Timed(timeout.0))
+            .await?;
+        let elapsed_us = clock().wrapping_sub(start);
+
+        self.ping_result(
+            &ping[..len],
+            &reply[..packet.len],
+            &packet.status,
+            elapsed_us,
+        )
+    }
+
+    /// Asynchronously answers `count` pings, skipping any other packet.
+    ///
+    /// This is the async version of [`respond_to_pings`](Device::respond_to_pings).
+    pub async fn respond_to_pings_async(
+        &mut self,
+        count: u16,
+        mode: RxMode,
+        timeout: Timeout,
+    ) -> Result<(), PingError> {
+        let mut answered = 0;
+        while answered < count {
+            let mut ping = [0; 255];
+            let packet = self.receive_async(&mut ping, mode).await?;
+            let rssi = self.packet_rssi_dbm(&packet.status);
+
+            let mut reply = [0; 255];
+            if let Some(len) = reply_frame(&mut reply, &ping[..packet.len], rssi) {
+                self.transmit_async(&reply[..len], timeout).await?;
+                answered += 1;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,418 @@
+//! Recovery from a latched-up radio and liveness polling
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "metrics")]
+use crate::DwellMode;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::ConfigItems,
+    error::{Error, ErrorKind, StepExt},
+    pins::ResetPin,
+    DioIrqConfig, GetStatus, OperatingMode, PacketType, Status,
+};
+
+/// Time NRESET is held low, the datasheet minimum is 100 µs
+const RESET_PULSE_US: u32 = 200;
+
+/// Maximum time for the radio to start up after NRESET is released, the datasheet typical is
+/// 3.5 ms including calibration
+const STARTUP_TIMEOUT_US: u32 = 10_000;
+
+/// First interval between two GetStatus polls in microseconds
+const POLL_INITIAL_US: u32 = 32;
+
+/// Longest interval between two GetStatus polls in microseconds
+const POLL_MAX_US: u32 = 2_048;
+
+/// A valid status read by [`Device::wait_until_responsive`]
+#[derive(Debug, Clone, Copy)]
+pub struct Responsive {
+    /// The status returned by the radio
+    pub status: Status,
+    /// Time spent waiting before the status was read, in microseconds
+    pub waited_us: u32,
+}
+
+/// Exponential backoff between GetStatus polls
+struct Backoff {
+    max_wait_us: u32,
+    waited_us: u32,
+    interval_us: u32,
+}
+
+impl Backoff {
+    fn new(max_wait_us: u32) -> Self {
+        Self {
+            max_wait_us,
+            waited_us: 0,
+            interval_us: POLL_INITIAL_US,
+        }
+    }
+
+    /// Returns the next interval to wait, or the timeout error once the time is up.
+    fn next(&mut self) -> Result<u32, Error> {
+        let remaining = self.max_wait_us.saturating_sub(self.waited_us);
+        if remaining == 0 {
+            return Err(Error::command(
+                GetStatus::id(),
+                ErrorKind::Unresponsive {
+                    waited_us: self.waited_us,
+                },
+            ));
+        }
+        let interval = self.interval_us.min(remaining);
+        self.waited_us += interval;
+        self.interval_us = (self.interval_us * 2).min(POLL_MAX_US);
+        Ok(interval)
+    }
+}
+
+/// Returns whether a GetStatus error means the radio is not answering yet
+fn not_ready(err: &Error) -> bool {
+    err.kind() == ErrorKind::Deserialization
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Polls GetStatus until the radio returns a valid status.
+    ///
+    /// After a reset, a wake-up from sleep or a TCXO start there is a period where the radio
+    /// ignores the bus and the status byte reads as garbage. The status is polled with an
+    /// exponential backoff, starting at 32 µs and doubling up to 2 ms between polls, timed with
+    /// the owned delay.
+    ///
+    /// # Arguments
+    /// * `max_wait_us` - Maximum time to wait in microseconds
+    ///
+    /// # Returns
+    /// The status and the time waited before it was read
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Unresponsive` - No valid status within `max_wait_us`
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Command` with `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    pub fn wait_until_responsive(&mut self, max_wait_us: u32) -> Result<Responsive, Error>
+    where
+        DELAY: embedded_hal::delay::DelayNs,
+    {
+        self.poll_responsive(max_wait_us, |device, us| device.delay.delay_us(us))
+    }
+
+    /// Polls GetStatus until the radio returns a valid status, timed with the given delay.
+    ///
+    /// See [`wait_until_responsive`](Device::wait_until_responsive).
+    ///
+    /// # Arguments
+    /// * `max_wait_us` - Maximum time to wait in microseconds
+    /// * `delay` - Delay used between polls
+    pub fn wait_until_responsive_with_delay(
+        &mut self,
+        max_wait_us: u32,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<Responsive, Error> {
+        self.poll_responsive(max_wait_us, |_, us| delay.delay_us(us))
+    }
+
+    /// Polls GetStatus with backoff, waiting between polls with `delay_us`.
+    fn poll_responsive(
+        &mut self,
+        max_wait_us: u32,
+        mut delay_us: impl FnMut(&mut Self, u32),
+    ) -> Result<Responsive, Error> {
+        let mut backoff = Backoff::new(max_wait_us);
+        loop {
+            match self.execute_command(GetStatus) {
+                Ok(status) => {
+                    return Ok(Responsive {
+                        status,
+                        waited_us: backoff.waited_us,
+                    })
+                }
+                Err(err) if not_ready(&err) => {
+                    let interval = backoff.next()?;
+                    delay_us(self, interval);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+    RESET: ResetPin,
+{
+    /// Resets the radio through NRESET and re-applies the cached configuration.
+    ///
+    /// This is the documented recovery when BUSY stays high, reported as
+    /// `ErrorKind::BusyStuck`. The radio restarts in STDBY_RC with its packet counters
+    /// cleared, the RF front-end is returned to idle, and the configuration last applied with
+    /// [`apply_config`](Device::apply_config), if any, is applied again, TCXO and DIO2 RF
+    /// switch control included. The reset pulse and the start-up are timed with the owned
+    /// delay.
+    ///
+    /// # Errors
+    /// Errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | Pulse NRESET                      |
+    /// | 1    | Wait until responsive (GetStatus) |
+    /// | 2    | Return the RF front-end to idle   |
+    ///
+    /// Errors re-applying the configuration carry the step of
+    /// [`apply_config`](Device::apply_config).
+    ///
+    /// * `Error::Control` with `ErrorKind::NoResetPin` - No NRESET line is connected, the
+    ///   radio cannot be recovered by the driver
+    /// * `Error::Control` with `ErrorKind::Pin` - NRESET or BUSY could not be driven or read
+    /// * `Error::Command` with `ErrorKind::Unresponsive` - The radio did not start up
+    /// * `Error::Command` with `ErrorKind::BusyStuck` - BUSY stayed high after the reset
+    /// * `Error::Control` with `ErrorKind::Frontend` - The RF front-end failed to switch
+    ///
+    /// # Example
+    /// ```no_run
+    /// use embedded_hal::{delay::DelayNs, digital::OutputPin, spi::SpiDevice};
+    /// use sx1262::Device;
+    ///
+    /// fn restart(spi: impl SpiDevice, reset: impl OutputPin, delay: impl DelayNs) {
+    ///     let mut device = Device::new(spi).with_reset(reset).with_delay(delay);
+    ///     device.recover().unwrap();
+    /// }
+    /// ```
+    pub fn recover(&mut self) -> Result<(), Error>
+    where
+        DELAY: embedded_hal::delay::DelayNs,
+    {
+        self.reset_and_restore(|device, us| device.delay.delay_us(us))
+    }
+
+    /// Resets the radio through NRESET and re-applies the cached configuration, timed with
+    /// the given delay.
+    ///
+    /// See [`recover`](Device::recover).
+    ///
+    /// # Arguments
+    /// * `delay` - Delay used to time the reset pulse and the radio start-up
+    pub fn recover_with_delay(
+        &mut self,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<(), Error> {
+        self.reset_and_restore(|_, us| delay.delay_us(us))
+    }
+
+    /// Pulses NRESET and restores the driver state, waiting with `delay_us`.
+    fn reset_and_restore(&mut self, mut delay_us: impl FnMut(&mut Self, u32)) -> Result<(), Error> {
+        self.assert_reset().at_step(0)?;
+        delay_us(self, RESET_PULSE_US);
+        self.release_reset().at_step(0)?;
+
+        self.poll_responsive(STARTUP_TIMEOUT_US, delay_us)
+            .at_step(1)?;
+        self.stats_reset(None);
+        self.state.packet_type = Some(PacketType::default());
+        self.state.irq_routing = Some(DioIrqConfig::default());
+        self.state.dio3_tcxo = false;
+        self.state.cad_running = false;
+        self.state.image_calibration = None;
+        self.state.configured = ConfigItems::empty();
+        self.state.settled_mode = Some(OperatingMode::StandbyRc);
+        self.state.rx_lifecycle.reset();
+        #[cfg(feature = "metrics")]
+        self.state
+            .mode_dwell
+            .enter(DwellMode::StandbyRc, None, self.timestamp());
+
+        self.frontend
+            .enter_idle()
+            .map_err(|_| Error::control(ErrorKind::Frontend))
+            .at_step(2)?;
+
+        if let Some(config) = self.state.config {
+            self.apply_config(&config)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously polls GetStatus until the radio returns a valid status.
+    ///
+    /// This is the async version of [`wait_until_responsive`](Device::wait_until_responsive).
+    pub async fn wait_until_responsive_async(
+        &mut self,
+        max_wait_us: u32,
+    ) -> Result<Responsive, Error>
+    where
+        DELAY: embedded_hal_async::delay::DelayNs,
+    {
+        self.poll_responsive_async(max_wait_us, async |device: &mut Self, us| {
+            device.delay.delay_us(us).await
+        })
+        .await
+    }
+
+    /// Asynchronously polls GetStatus until the radio returns a valid status, timed with the
+    /// given delay.
+    ///
+    /// This is the async version of
+    /// [`wait_until_responsive_with_delay`](Device::wait_until_responsive_with_delay).
+    pub async fn wait_until_responsive_with_delay_async(
+        &mut self,
+        max_wait_us: u32,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<Responsive, Error> {
+        self.poll_responsive_async(max_wait_us, async |_: &mut Self, us| {
+            delay.delay_us(us).await
+        })
+        .await
+    }
+
+    /// Asynchronously polls GetStatus with backoff, waiting between polls with `delay_us`.
+    async fn poll_responsive_async(
+        &mut self,
+        max_wait_us: u32,
+        mut delay_us: impl AsyncFnMut(&mut Self, u32),
+    ) -> Result<Responsive, Error> {
+        let mut backoff = Backoff::new(max_wait_us);
+        loop {
+            match self.execute_command_async(GetStatus).await {
+                Ok(status) => {
+                    return Ok(Responsive {
+                        status,
+                        waited_us: backoff.waited_us,
+                    })
+                }
+                Err(err) if not_ready(&err) => {
+                    let interval = backoff.next()?;
+                    delay_us(self, interval).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+    RESET: ResetPin,
+{
+    /// Asynchronously resets the radio through NRESET and re-applies the cached configuration.
+    ///
+    /// This is the async version of [`recover`](Device::recover).
+    pub async fn recover_async(&mut self) -> Result<(), Error>
+    where
+        DELAY: embedded_hal_async::delay::DelayNs,
+    {
+        self.reset_and_restore_async(async |device: &mut Self, us| device.delay.delay_us(us).await)
+            .await
+    }
+
+    /// Asynchronously resets the radio through NRESET and re-applies the cached
+    /// configuration, timed with the given delay.
+    ///
+    /// This is the async version of [`recover_with_delay`](Device::recover_with_delay).
+    pub async fn recover_with_delay_async(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error> {
+        self.reset_and_restore_async(async |_: &mut Self, us| delay.delay_us(us).await)
+            .await
+    }
+
+    /// Asynchronously pulses NRESET and restores the driver state, waiting with `delay_us`.
+    async fn reset_and_restore_async(
+        &mut self,
+        mut delay_us: impl AsyncFnMut(&mut Self, u32),
+    ) -> Result<(), Error> {
+        self.assert_reset().at_step(0)?;
+        delay_us(self, RESET_PULSE_US).await;
+        self.release_reset().at_step(0)?;
+
+        self.poll_responsive_async(STARTUP_TIMEOUT_US, delay_us)
+            .await
+            .at_step(1)?;
+        self.stats_reset(None);
+        self.state.packet_type = Some(PacketType::default());
+        self.state.irq_routing = Some(DioIrqConfig::default());
+        self.state.dio3_tcxo = false;
+        self.state.cad_running = false;
+        self.state.image_calibration = None;
+        self.state.configured = ConfigItems::empty();
+        self.state.settled_mode = Some(OperatingMode::StandbyRc);
+        self.state.rx_lifecycle.reset();
+        #[cfg(feature = "metrics")]
+        self.state
+            .mode_dwell
+            .enter(DwellMode::StandbyRc, None, self.timestamp());
+
+        self.frontend
+            .enter_idle()
+            .await
+            .map_err(|_| Error::control(ErrorKind::Frontend))
+            .at_step(2)?;
+
+        if let Some(config) = self.state.config {
+            self.apply_config_async(&config).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    RESET: ResetPin,
+{
+    /// Drives NRESET low, failing when no reset line is connected.
+    fn assert_reset(&mut self) -> Result<(), Error> {
+        match self.reset.set_reset(true) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Error::control(ErrorKind::NoResetPin)),
+            Err(err) => Err(Error::control(err.into())),
+        }
+    }
+
+    /// Releases NRESET.
+    fn release_reset(&mut self) -> Result<(), Error> {
+        self.reset
+            .set_reset(false)
+            .map(|_| ())
+            .map_err(|err| Error::control(err.into()))
+    }
+}
@@ -0,0 +1,151 @@
+//! Resynchronization after a cancelled operation
+//!
+//! Async futures can be dropped at any await point, e.g. by `select!` or a timeout, and there
+//! is no async drop to clean up after them. A cancelled transmit or receive leaves the radio
+//! in TX or RX with IRQs pending, and the next operation would see them. The async transmit
+//! and receive helpers mark the operation in flight until it completes, and start with
+//! resynchronizing the radio when a previous one did not complete.
+//! [`Device::resync`] does the same on demand, e.g. right after a `select!` dropped a receive.
+//!
+//! Dropping a future in the middle of a bus transaction may leave the bus itself in an
+//! undefined state, which is up to the bus implementation to recover from.
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    error::{Error, StepExt},
+    ClearIrqStatus, GetStatus, IrqMask, OperatingMode, SetStandby, StandbyConfig,
+};
+
+/// Returns the operating mode of a raw status byte, if valid.
+fn status_mode(status: u8) -> Option<OperatingMode> {
+    OperatingMode::try_from((status >> 4) & 0x7).ok()
+}
+
+/// Returns whether the radio must be put back in standby.
+fn needs_standby(mode: Option<OperatingMode>) -> bool {
+    !matches!(
+        mode,
+        Some(OperatingMode::StandbyRc | OperatingMode::StandbyXosc)
+    )
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Brings the radio back to a known state after an interrupted operation.
+    ///
+    /// The operating mode is read with GetStatus. Any operation in progress, TX, RX, CAD or
+    /// FS, is aborted with SetStandby (STDBY_RC), which also puts the RF front-end back to
+    /// idle, and every IRQ is cleared. The radio then accepts a new operation.
+    ///
+    /// # Returns
+    /// The operating mode the radio was found in, `None` when the status was not valid
+    ///
+    /// # Errors
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | GetStatus                         |
+    /// | 1    | SetStandby (STDBY_RC)             |
+    /// | 2    | ClearIrqStatus                    |
+    pub fn resync(&mut self) -> Result<Option<OperatingMode>, Error> {
+        // The raw status byte, as Status rejects some command statuses left by earlier commands
+        let mut status = [0];
+        self.execute_raw(GetStatus::id(), &[], &mut status)
+            .at_step(0)?;
+        let mode = status_mode(status[0]);
+
+        if needs_standby(mode) {
+            self.execute_command(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .at_step(1)?;
+        }
+        self.execute_command(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .at_step(2)?;
+
+        self.state.in_flight = false;
+        Ok(mode)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously brings the radio back to a known state after an interrupted operation.
+    ///
+    /// This is the async version of [`resync`](Device::resync).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::AsyncRadioBus, Device};
+    ///
+    /// async fn restart(device: &mut Device<impl AsyncRadioBus>) {
+    ///     // A receive was dropped on a timeout of the application
+    ///     let mode = device.resync_async().await.unwrap();
+    /// }
+    /// ```
+    pub async fn resync_async(&mut self) -> Result<Option<OperatingMode>, Error> {
+        let mut status = [0];
+        self.execute_raw_async(GetStatus::id(), &[], &mut status)
+            .await
+            .at_step(0)?;
+        let mode = status_mode(status[0]);
+
+        if needs_standby(mode) {
+            self.execute_command_async(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .await
+            .at_step(1)?;
+        }
+        self.execute_command_async(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .await
+        .at_step(2)?;
+
+        self.state.in_flight = false;
+        Ok(mode)
+    }
+
+    /// Resynchronizes the radio if the previous operation did not complete, then marks a new
+    /// one in flight.
+    pub(super) async fn begin_operation_async(&mut self) -> Result<(), Error> {
+        if self.state.in_flight {
+            self.resync_async().await?;
+        }
+        self.state.in_flight = true;
+        Ok(())
+    }
+
+    /// Marks the operation in flight as complete.
+    pub(super) fn end_operation(&mut self) {
+        self.state.in_flight = false;
+    }
+}
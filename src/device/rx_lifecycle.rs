@@ -0,0 +1,82 @@
+//! Received packet lifecycle
+//!
+//! GetRxBufferStatus keeps returning the length and position of the last packet received
+//! until the next one arrives, so reading it again after re-entering RX yields a stale length.
+//! The [`Device`](super::Device) follows each packet through the commands it sends:
+//!
+//! | State | Entered on |
+//! |-------|------------|
+//! | Armed | SetTx, SetRx, SetRxDutyCycle, SetCad and the other operation starts |
+//! | Pending | GetIrqStatus reporting RX_DONE, the first time since it was last cleared |
+//! | Drained | A successful payload read, or a reset of the radio |
+//!
+//! The payload helpers only read a pending packet. Until the Device has seen RX started, e.g.
+//! right after it is created, the state is unknown and reads are allowed.
+
+use crate::{
+    commands::{observed_irq_clear, observed_irq_status, starts_operation},
+    IrqMask,
+};
+
+/// Where the last received packet stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum RxState {
+    /// RX was not seen started
+    #[default]
+    Unknown,
+    /// An operation was started, no packet was reported since
+    Armed,
+    /// RX_DONE was reported, the packet was not read yet
+    Pending,
+    /// The packet was read, or the radio was reset
+    Drained,
+}
+
+/// Tracks the received packet from the commands sent to the radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(super) struct RxLifecycle {
+    state: RxState,
+    /// RX_DONE was reported and not cleared since, so reporting it again is the same packet
+    rx_done_reported: bool,
+}
+
+impl RxLifecycle {
+    /// Updates the state from a command sent, once the bus transaction is done.
+    pub(super) fn observe_command(&mut self, opcode: u8, request: &[u8], response: &[u8]) {
+        if starts_operation(opcode) {
+            self.state = RxState::Armed;
+        } else if let Some(irq) = observed_irq_status(opcode, response) {
+            if irq.contains(IrqMask::RX_DONE) && !self.rx_done_reported {
+                self.rx_done_reported = true;
+                self.state = RxState::Pending;
+            }
+        } else if let Some(irq) = observed_irq_clear(opcode, request) {
+            if irq.contains(IrqMask::RX_DONE) {
+                self.rx_done_reported = false;
+            }
+        }
+    }
+
+    /// Returns whether a packet may be read, pending or with an unknown state.
+    pub(super) fn can_read(&self) -> bool {
+        matches!(self.state, RxState::Unknown | RxState::Pending)
+    }
+
+    /// Records that the packet was read.
+    pub(super) fn drain(&mut self) {
+        if self.state == RxState::Pending {
+            self.state = RxState::Drained;
+        }
+    }
+
+    /// Records a packet reported outside of the Device.
+    pub(super) fn mark_pending(&mut self) {
+        self.state = RxState::Pending;
+    }
+
+    /// Forgets the packet after a reset of the radio, which clears the IRQs.
+    pub(super) fn reset(&mut self) {
+        self.state = RxState::Drained;
+        self.rx_done_reported = false;
+    }
+}
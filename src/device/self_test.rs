@@ -0,0 +1,432 @@
+//! Production self-test
+//!
+//! A quick pass/fail of a radio before the RF chamber, composed of the probe, calibration,
+//! PLL, continuous wave and RSSI building blocks of the driver.
+
+use core::ops::RangeInclusive;
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    error::{Error, ErrorKind},
+    CalibrationConfig, ClearDeviceErrors, DeviceErrors, DeviceSelect, GetDeviceErrors, GetStatus,
+    OperatingMode, RampTime, RfFrequencyConfig, RxMode, SetFs, SetPaConfig, SetRfFrequency, SetRx,
+    SetStandby, SetTxContinuousWave, SetTxParams, StandbyConfig, TxPowerPlan,
+};
+
+/// Outcome of one step of a [`Device::self_test`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SelfTestOutcome {
+    /// The step did not run, as the radio did not answer
+    #[default]
+    NotRun,
+    /// The radio behaved as expected
+    Passed,
+    /// The radio did not behave as expected
+    Failed,
+}
+
+impl SelfTestOutcome {
+    fn from_passed(passed: bool) -> Self {
+        if passed {
+            Self::Passed
+        } else {
+            Self::Failed
+        }
+    }
+}
+
+/// Parameters of a [`Device::self_test`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SelfTestPlan {
+    /// Frequencies in Hz the PLL must lock at, usually the edges and the middle of the band.
+    /// The continuous wave and the noise floor use the middle one
+    pub frequencies: [u32; 3],
+    /// PA configuration and power of the continuous wave burst, keep it low
+    pub cw_power: TxPowerPlan,
+    /// Length of the continuous wave burst in milliseconds
+    pub cw_duration_ms: u32,
+    /// Time spent in RX before the RSSI is read, in milliseconds
+    pub rssi_settle_ms: u32,
+    /// Lowest acceptable noise floor in dBm
+    pub noise_floor_min_dbm: i16,
+    /// Highest acceptable noise floor in dBm, a screened room stays well below
+    pub noise_floor_max_dbm: i16,
+    /// Maximum time for the radio to answer GetStatus, in microseconds
+    pub probe_wait_us: u32,
+}
+
+impl SelfTestPlan {
+    /// Creates a plan over a band with the lowest output power of the device.
+    ///
+    /// The PLL is checked at both edges and the middle of the band. The continuous wave lasts
+    /// 10 ms, the RSSI is read after 5 ms in RX and must be within -135 to -90 dBm, and the
+    /// radio must answer within 10 ms.
+    ///
+    /// # Arguments
+    /// * `device` - The device tested
+    /// * `band` - The frequency band of the product in Hz
+    pub fn new(device: DeviceSelect, band: RangeInclusive<u32>) -> Self {
+        let (start, end) = (*band.start(), *band.end());
+        let lowest = *device.output_power_range().start();
+        Self {
+            frequencies: [start, start + (end - start) / 2, end],
+            cw_power: TxPowerPlan::resolve(device, lowest, RampTime::default())
+                .unwrap_or_else(|| unreachable!("the range start is in range")),
+            cw_duration_ms: 10,
+            rssi_settle_ms: 5,
+            noise_floor_min_dbm: -135,
+            noise_floor_max_dbm: -90,
+            probe_wait_us: 10_000,
+        }
+    }
+}
+
+/// Outcome of each step of a [`Device::self_test`]
+#[derive(Debug, Clone, Copy)]
+pub struct SelfTestReport {
+    /// The radio answered GetStatus within the probe wait. Every other step is only run when
+    /// this one passed
+    pub comms: SelfTestOutcome,
+    /// Calibrating every block raised no calibration error
+    pub calibration: SelfTestOutcome,
+    /// The device errors read after the calibration
+    pub device_errors: Option<DeviceErrors>,
+    /// FS was reached without a PLL lock error, for each of the plan frequencies
+    pub pll_lock: [SelfTestOutcome; 3],
+    /// TX was reached for the continuous wave burst without a PA ramping error
+    pub cw: SelfTestOutcome,
+    /// The noise floor read in RX is within the plan range
+    pub rssi: SelfTestOutcome,
+    /// The noise floor read in RX, in dBm
+    pub noise_floor_dbm: Option<i16>,
+}
+
+impl SelfTestReport {
+    fn new() -> Self {
+        Self {
+            comms: SelfTestOutcome::NotRun,
+            calibration: SelfTestOutcome::NotRun,
+            device_errors: None,
+            pll_lock: [SelfTestOutcome::NotRun; 3],
+            cw: SelfTestOutcome::NotRun,
+            rssi: SelfTestOutcome::NotRun,
+            noise_floor_dbm: None,
+        }
+    }
+
+    /// Returns whether every step passed.
+    pub fn passed(&self) -> bool {
+        [self.comms, self.calibration, self.cw, self.rssi]
+            .iter()
+            .chain(&self.pll_lock)
+            .all(|&outcome| outcome == SelfTestOutcome::Passed)
+    }
+}
+
+/// Returns whether the device errors report no calibration failure.
+///
+/// An XOSC start error is expected with a TCXO and not counted.
+fn calibrated(errors: &DeviceErrors) -> bool {
+    !(errors.rc64k_calib_err
+        || errors.rc13m_calib_err
+        || errors.pll_calib_err
+        || errors.adc_calib_err
+        || errors.img_calib_err)
+}
+
+/// Returns the operating mode of a raw status byte.
+fn mode_of(status: u8) -> Option<OperatingMode> {
+    OperatingMode::try_from((status >> 4) & 0x7).ok()
+}
+
+/// Grades a noise floor read, `None` when the radio was not in RX.
+fn rssi_outcome(plan: &SelfTestPlan, noise_floor_dbm: Option<i16>) -> SelfTestOutcome {
+    SelfTestOutcome::from_passed(
+        noise_floor_dbm.is_some_and(|dbm| {
+            (plan.noise_floor_min_dbm..=plan.noise_floor_max_dbm).contains(&dbm)
+        }),
+    )
+}
+
+/// Returns the RSSI read, `None` when the radio was not in RX, or the bus error.
+fn rssi_read(result: Result<i16, Error>) -> Result<Option<i16>, Error> {
+    match result {
+        Ok(dbm) => Ok(Some(dbm)),
+        Err(err) if matches!(err.kind(), ErrorKind::WrongMode { .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Runs a pass/fail test of the radio for production lines.
+    ///
+    /// | Step        | Operations                                                      |
+    /// |-------------|-----------------------------------------------------------------|
+    /// | comms       | GetStatus until a valid status, at most `probe_wait_us`         |
+    /// | calibration | ClearDeviceErrors, Calibrate of every block, GetDeviceErrors    |
+    /// | pll_lock    | Per frequency: ClearDeviceErrors, SetRfFrequency, SetFs,        |
+    /// |             | GetStatus for FS, GetDeviceErrors for a PLL lock error          |
+    /// | cw          | SetPaConfig, SetTxParams, SetTxContinuousWave, `cw_duration_ms`,|
+    /// |             | GetStatus for TX, SetStandby, GetDeviceErrors for a PA ramp     |
+    /// | rssi        | SetRx continuous, `rssi_settle_ms`, GetRssiInst in RX           |
+    ///
+    /// Every wait is bounded: by the plan, the busy timeout and the calibration worst case.
+    /// The radio is put back into STDBY_RC at the end, also when a step returns an error. The
+    /// frequency and PA configuration are left as tested, the cached configuration is dropped
+    /// and must be applied again.
+    ///
+    /// # Arguments
+    /// * `plan` - The test parameters
+    /// * `delay` - Delay for the probe, calibration, burst and RSSI waits
+    ///
+    /// # Returns
+    /// The outcome of each step, a failed step does not stop the following ones
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::BusyStuck` - BUSY stayed high past the busy timeout
+    ///
+    /// # Example
+    /// ```no_run
+    /// use embedded_hal::delay::DelayNs;
+    /// use sx1262::{bus::RadioBus, Device, DeviceSelect, SelfTestPlan};
+    ///
+    /// fn bring_up(device: &mut Device<impl RadioBus>, delay: &mut impl DelayNs) -> bool {
+    ///     let plan = SelfTestPlan::new(DeviceSelect::Sx1262, 863_000_000..=928_000_000);
+    ///     device.self_test(plan, delay).unwrap().passed()
+    /// }
+    /// ```
+    pub fn self_test(
+        &mut self,
+        plan: SelfTestPlan,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<SelfTestReport, Error> {
+        self.state.config = None;
+        let mut report = SelfTestReport::new();
+        let result = self.run_self_test(&plan, delay, &mut report);
+        let standby = self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        });
+        result?;
+        if report.comms == SelfTestOutcome::Passed {
+            standby?;
+        }
+        Ok(report)
+    }
+
+    /// Runs the steps of a self-test, filling in the report.
+    fn run_self_test(
+        &mut self,
+        plan: &SelfTestPlan,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+        report: &mut SelfTestReport,
+    ) -> Result<(), Error> {
+        match self.wait_until_responsive_with_delay(plan.probe_wait_us, delay) {
+            Ok(_) => report.comms = SelfTestOutcome::Passed,
+            Err(err) if matches!(err.kind(), ErrorKind::Unresponsive { .. }) => {
+                report.comms = SelfTestOutcome::Failed;
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        }
+
+        self.execute_command(ClearDeviceErrors)?;
+        self.calibrate_with_delay(CalibrationConfig::all(), delay)?;
+        let errors = self.execute_command(GetDeviceErrors)?.errors;
+        report.calibration = SelfTestOutcome::from_passed(calibrated(&errors));
+        report.device_errors = Some(errors);
+
+        for (outcome, &frequency) in report.pll_lock.iter_mut().zip(&plan.frequencies) {
+            self.execute_command(ClearDeviceErrors)?;
+            self.execute_command(SetRfFrequency {
+                config: RfFrequencyConfig { frequency },
+            })?;
+            self.execute_command(SetFs)?;
+            let mode = self.raw_mode()?;
+            let errors = self.execute_command(GetDeviceErrors)?.errors;
+            *outcome = SelfTestOutcome::from_passed(
+                mode == Some(OperatingMode::FrequencySynthesizer) && !errors.pll_lock_err,
+            );
+        }
+
+        self.execute_command(SetRfFrequency {
+            config: RfFrequencyConfig {
+                frequency: plan.frequencies[1],
+            },
+        })?;
+        self.execute_command(SetPaConfig {
+            config: plan.cw_power.pa_config,
+        })?;
+        self.execute_command(SetTxParams {
+            params: plan.cw_power.tx_params,
+        })?;
+        self.execute_command(ClearDeviceErrors)?;
+        self.execute_command(SetTxContinuousWave)?;
+        delay.delay_ms(plan.cw_duration_ms);
+        let mode = self.raw_mode()?;
+        self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })?;
+        let errors = self.execute_command(GetDeviceErrors)?.errors;
+        report.cw = SelfTestOutcome::from_passed(
+            mode == Some(OperatingMode::Transmit) && !errors.pa_ramp_err,
+        );
+
+        self.execute_command(SetRx {
+            mode: RxMode::Continuous,
+        })?;
+        delay.delay_ms(plan.rssi_settle_ms);
+        let noise_floor_dbm = rssi_read(self.rssi_inst())?;
+        report.rssi = rssi_outcome(plan, noise_floor_dbm);
+        report.noise_floor_dbm = noise_floor_dbm;
+        Ok(())
+    }
+
+    /// Reads the operating mode from the raw status byte.
+    fn raw_mode(&mut self) -> Result<Option<OperatingMode>, Error> {
+        // The raw status byte, as Status rejects some command statuses left by earlier commands
+        let mut status = [0];
+        self.execute_raw(GetStatus::id(), &[], &mut status)?;
+        Ok(mode_of(status[0]))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Runs a pass/fail test of the radio for production lines.
+    ///
+    /// This is the async version of [`self_test`](Device::self_test).
+    pub async fn self_test_async(
+        &mut self,
+        plan: SelfTestPlan,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<SelfTestReport, Error> {
+        self.state.config = None;
+        let mut report = SelfTestReport::new();
+        let result = self.run_self_test_async(&plan, delay, &mut report).await;
+        let standby = self
+            .execute_command_async(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .await;
+        result?;
+        if report.comms == SelfTestOutcome::Passed {
+            standby?;
+        }
+        Ok(report)
+    }
+
+    /// Runs the steps of a self-test, filling in the report.
+    async fn run_self_test_async(
+        &mut self,
+        plan: &SelfTestPlan,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        report: &mut SelfTestReport,
+    ) -> Result<(), Error> {
+        match self
+            .wait_until_responsive_with_delay_async(plan.probe_wait_us, delay)
+            .await
+        {
+            Ok(_) => report.comms = SelfTestOutcome::Passed,
+            Err(err) if matches!(err.kind(), ErrorKind::Unresponsive { .. }) => {
+                report.comms = SelfTestOutcome::Failed;
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        }
+
+        self.execute_command_async(ClearDeviceErrors).await?;
+        self.calibrate_with_delay_async(CalibrationConfig::all(), delay)
+            .await?;
+        let errors = self.execute_command_async(GetDeviceErrors).await?.errors;
+        report.calibration = SelfTestOutcome::from_passed(calibrated(&errors));
+        report.device_errors = Some(errors);
+
+        for (outcome, &frequency) in report.pll_lock.iter_mut().zip(&plan.frequencies) {
+            self.execute_command_async(ClearDeviceErrors).await?;
+            self.execute_command_async(SetRfFrequency {
+                config: RfFrequencyConfig { frequency },
+            })
+            .await?;
+            self.execute_command_async(SetFs).await?;
+            let mode = self.raw_mode_async().await?;
+            let errors = self.execute_command_async(GetDeviceErrors).await?.errors;
+            *outcome = SelfTestOutcome::from_passed(
+                mode == Some(OperatingMode::FrequencySynthesizer) && !errors.pll_lock_err,
+            );
+        }
+
+        self.execute_command_async(SetRfFrequency {
+            config: RfFrequencyConfig {
+                frequency: plan.frequencies[1],
+            },
+        })
+        .await?;
+        self.execute_command_async(SetPaConfig {
+            config: plan.cw_power.pa_config,
+        })
+        .await?;
+        self.execute_command_async(SetTxParams {
+            params: plan.cw_power.tx_params,
+        })
+        .await?;
+        self.execute_command_async(ClearDeviceErrors).await?;
+        self.execute_command_async(SetTxContinuousWave).await?;
+        delay.delay_ms(plan.cw_duration_ms).await;
+        let mode = self.raw_mode_async().await?;
+        self.execute_command_async(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .await?;
+        let errors = self.execute_command_async(GetDeviceErrors).await?.errors;
+        report.cw = SelfTestOutcome::from_passed(
+            mode == Some(OperatingMode::Transmit) && !errors.pa_ramp_err,
+        );
+
+        self.execute_command_async(SetRx {
+            mode: RxMode::Continuous,
+        })
+        .await?;
+        delay.delay_ms(plan.rssi_settle_ms).await;
+        let noise_floor_dbm = rssi_read(self.rssi_inst_async().await)?;
+        report.rssi = rssi_outcome(plan, noise_floor_dbm);
+        report.noise_floor_dbm = noise_floor_dbm;
+        Ok(())
+    }
+
+    /// Reads the operating mode from the raw status byte.
+    async fn raw_mode_async(&mut self) -> Result<Option<OperatingMode>, Error> {
+        // The raw status byte, as Status rejects some command statuses left by earlier commands
+        let mut status = [0];
+        self.execute_raw_async(GetStatus::id(), &[], &mut status)
+            .await?;
+        Ok(mode_of(status[0]))
+    }
+}
@@ -0,0 +1,324 @@
+//! Promiscuous GFSK reception
+//!
+//! Puts the radio in a mode where every GFSK frame it detects is handed to the application,
+//! with the payload CRC checked but not enforced, for debugging third-party FSK devices.
+
+use regiface::Register;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::RadioConfig,
+    error::{Error, ErrorKind, StepExt},
+    AddressFiltering, ClearIrqStatus, CrcType, DioIrqConfig, GFSKPacketHeaderType,
+    GFSKPacketParams, GetIrqStatus, GetPacketStatus, GfskModParams, GfskPacketStatus, IrqMask,
+    ModulationParams, PacketParams, PacketType, ResetStats, RxMode, SetDioIrqParams,
+    SetModulationParams, SetPacketParams, SetPacketType, SetRx, SetStandby, StandbyConfig,
+    SyncWord,
+};
+
+/// A frame received by [`Device::next_sniffed_frame`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SniffedFrame {
+    /// Length of the payload in bytes
+    pub len: usize,
+    /// Reception flags and RSSI of the frame, with
+    /// [`GfskRxStatus::CRC_ERROR`](crate::GfskRxStatus::CRC_ERROR) set when the CRC failed
+    pub status: GfskPacketStatus,
+}
+
+/// IRQs needed to sniff frames
+const SNIFF_IRQS: IrqMask = IrqMask::RX_DONE
+    .union(IrqMask::CRC_ERROR)
+    .union(IrqMask::HEADER_ERROR);
+
+/// Settings applied by [`Device::enter_promiscuous_gfsk`]
+struct Promiscuous {
+    packet: GFSKPacketParams,
+    irq: DioIrqConfig,
+    sync_word: Option<SyncWord>,
+}
+
+impl Promiscuous {
+    /// Derives the promiscuous settings from the cached configuration.
+    fn new(config: Option<&RadioConfig>, sync_word: Option<&[u8]>) -> Result<Self, Error> {
+        let mut packet = match config.map(|config| config.packet) {
+            Some(PacketParams::GFSK(params)) => params,
+            _ => GFSKPacketParams::default(),
+        };
+        packet.address_filtering = AddressFiltering::Disable;
+        packet.packet_type = GFSKPacketHeaderType::Variable;
+        packet.payload_length = u8::MAX;
+        if packet.crc_type == CrcType::CrcOff {
+            packet.crc_type = GFSKPacketParams::default().crc_type;
+        }
+
+        let sync_word = match sync_word {
+            Some(bytes) if bytes.len() > 8 => {
+                return Err(Error::register(SyncWord::id(), ErrorKind::Serialization))
+            }
+            Some(bytes) => {
+                let mut value = [0; 8];
+                value[..bytes.len()].copy_from_slice(bytes);
+                packet.sync_word_length = bytes.len() as u8 * 8;
+                Some(SyncWord { value })
+            }
+            None => None,
+        };
+
+        let mut irq = config.map(|config| config.irq).unwrap_or_default();
+        irq.irq_mask |= SNIFF_IRQS;
+
+        Ok(Self {
+            packet,
+            irq,
+            sync_word,
+        })
+    }
+
+    /// Records the settings in the cached configuration.
+    fn cache(&self, config: &mut Option<RadioConfig>, mod_params: GfskModParams) {
+        if let Some(config) = config {
+            config.modulation = ModulationParams::Gfsk(mod_params);
+            config.packet = PacketParams::GFSK(self.packet);
+            config.irq = self.irq;
+            if self.sync_word.is_some() {
+                config.gfsk_sync_word = self.sync_word;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Configures the radio to receive every GFSK frame it detects and starts continuous RX.
+    ///
+    /// Packets are made variable-length with the maximum payload length, address filtering is
+    /// disabled and the RX_DONE, CRC_ERROR and HEADER_ERROR IRQs are enabled. The CRC type
+    /// of the applied configuration is kept (a 2 byte CRC is used if it was off) so that CRC
+    /// failures are reported by [`next_sniffed_frame`](Device::next_sniffed_frame) rather
+    /// than hidden. The RF frequency must already be configured.
+    ///
+    /// # Arguments
+    /// * `mod_params` - Modulation of the frames to receive
+    /// * `sync_word` - Sync word of the frames to receive, up to 8 bytes, or `None` to keep
+    ///   the current sync word
+    ///
+    /// # Errors
+    /// Errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | SetStandby (STDBY_RC)             |
+    /// | 1    | SetPacketType, ResetStats         |
+    /// | 2    | SetModulationParams               |
+    /// | 3    | SetPacketParams                   |
+    /// | 4    | Sync word register                |
+    /// | 5    | SetDioIrqParams                   |
+    /// | 6    | ClearIrqStatus, SetRx             |
+    ///
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::Serialization` - The sync word is longer than 8 bytes
+    pub fn enter_promiscuous_gfsk(
+        &mut self,
+        mod_params: GfskModParams,
+        sync_word: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        let settings = Promiscuous::new(self.state.config.as_ref(), sync_word)?;
+        // Dropped by SetPacketType, and cached again with the settings sent
+        let mut config = self.state.config;
+
+        self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .at_step(0)?;
+        self.execute_command(SetPacketType {
+            packet_type: PacketType::Gfsk,
+        })
+        .at_step(1)?;
+        if self.state.stats_packet_type != Some(PacketType::Gfsk) {
+            self.execute_command(ResetStats).at_step(1)?;
+            self.stats_reset(Some(PacketType::Gfsk));
+        }
+        self.execute_command(SetModulationParams {
+            params: ModulationParams::Gfsk(mod_params),
+        })
+        .at_step(2)?;
+        self.execute_command(SetPacketParams {
+            params: PacketParams::GFSK(settings.packet),
+        })
+        .at_step(3)?;
+        if let Some(sync_word) = settings.sync_word {
+            self.write_register(sync_word).at_step(4)?;
+        }
+        self.execute_command(SetDioIrqParams {
+            config: settings.irq,
+        })
+        .at_step(5)?;
+
+        settings.cache(&mut config, mod_params);
+        self.state.config = config;
+
+        self.execute_command(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .at_step(6)?;
+        self.expect_irqs_cleared(SNIFF_IRQS);
+        self.execute_command(SetRx {
+            mode: RxMode::Continuous,
+        })
+        .at_step(6)?;
+        Ok(())
+    }
+
+    /// Waits for the next frame in promiscuous GFSK mode and reads it.
+    ///
+    /// GetIrqStatus is polled until a frame is received. Frames with an invalid length are
+    /// skipped, as they carry no payload, while frames failing the CRC check are returned
+    /// with [`GfskRxStatus::CRC_ERROR`](crate::GfskRxStatus::CRC_ERROR) set.
+    ///
+    /// # Arguments
+    /// * `bytes` - Buffer to store the payload, at least as long as the payload
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::BufferTooSmall` - The buffer is shorter than the payload
+    pub fn next_sniffed_frame(&mut self, bytes: &mut [u8]) -> Result<SniffedFrame, Error> {
+        loop {
+            let irq = self.execute_command(GetIrqStatus)?.irq_mask;
+            if !irq.intersects(SNIFF_IRQS) {
+                continue;
+            }
+            self.record_irqs(irq);
+            self.execute_command(ClearIrqStatus { irq_mask: irq })?;
+            if irq.contains(IrqMask::RX_DONE) {
+                break;
+            }
+        }
+
+        let len = self.read_received_payload(bytes)?;
+        let status = self
+            .execute_command(GetPacketStatus)?
+            .packet_status
+            .gfsk_with_offset(self.settings.rssi_offset_db);
+        Ok(SniffedFrame { len, status })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously configures the radio to receive every GFSK frame it detects and starts
+    /// continuous RX.
+    ///
+    /// This is the async version of [`enter_promiscuous_gfsk`](Device::enter_promiscuous_gfsk).
+    pub async fn enter_promiscuous_gfsk_async(
+        &mut self,
+        mod_params: GfskModParams,
+        sync_word: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        let settings = Promiscuous::new(self.state.config.as_ref(), sync_word)?;
+        // Dropped by SetPacketType, and cached again with the settings sent
+        let mut config = self.state.config;
+
+        self.execute_command_async(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .await
+        .at_step(0)?;
+        self.execute_command_async(SetPacketType {
+            packet_type: PacketType::Gfsk,
+        })
+        .await
+        .at_step(1)?;
+        if self.state.stats_packet_type != Some(PacketType::Gfsk) {
+            self.execute_command_async(ResetStats).await.at_step(1)?;
+            self.stats_reset(Some(PacketType::Gfsk));
+        }
+        self.execute_command_async(SetModulationParams {
+            params: ModulationParams::Gfsk(mod_params),
+        })
+        .await
+        .at_step(2)?;
+        self.execute_command_async(SetPacketParams {
+            params: PacketParams::GFSK(settings.packet),
+        })
+        .await
+        .at_step(3)?;
+        if let Some(sync_word) = settings.sync_word {
+            self.write_register_async(sync_word).await.at_step(4)?;
+        }
+        self.execute_command_async(SetDioIrqParams {
+            config: settings.irq,
+        })
+        .await
+        .at_step(5)?;
+
+        settings.cache(&mut config, mod_params);
+        self.state.config = config;
+
+        self.execute_command_async(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .await
+        .at_step(6)?;
+        self.expect_irqs_cleared(SNIFF_IRQS);
+        self.execute_command_async(SetRx {
+            mode: RxMode::Continuous,
+        })
+        .await
+        .at_step(6)?;
+        Ok(())
+    }
+
+    /// Asynchronously waits for the next frame in promiscuous GFSK mode and reads it.
+    ///
+    /// This is the async version of [`next_sniffed_frame`](Device::next_sniffed_frame).
+    pub async fn next_sniffed_frame_async(
+        &mut self,
+        bytes: &mut [u8],
+    ) -> Result<SniffedFrame, Error> {
+        loop {
+            let irq = self.execute_command_async(GetIrqStatus).await?.irq_mask;
+            if !irq.intersects(SNIFF_IRQS) {
+                continue;
+            }
+            self.record_irqs(irq);
+            self.execute_command_async(ClearIrqStatus { irq_mask: irq })
+                .await?;
+            if irq.contains(IrqMask::RX_DONE) {
+                break;
+            }
+        }
+
+        let len = self.read_received_payload_async(bytes).await?;
+        let status = self
+            .execute_command_async(GetPacketStatus)
+            .await?
+            .packet_status
+            .gfsk_with_offset(self.settings.rssi_offset_db);
+        Ok(SniffedFrame { len, status })
+    }
+}
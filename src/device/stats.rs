@@ -0,0 +1,398 @@
+//! Packet statistics helpers
+
+#[cfg(any(feature = "sync", feature = "async"))]
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+#[cfg(any(feature = "sync", feature = "async"))]
+use crate::{
+    error::{Error, ErrorKind},
+    GetRssiInst, GetStats, OperatingMode, ResetStats,
+};
+use crate::{PacketStatus, PacketType, Stats};
+
+/// Converts a raw GetRssiInst value to dBm, rounded towards the higher power, and corrects it.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn rssi_inst_dbm(rssi: u8, offset_db: i8) -> i16 {
+    i16::from(offset_db) - i16::from(rssi) / 2
+}
+
+/// Checks that a raw status byte reports RX, where GetRssiInst is meaningful.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn check_receiving(status: u8) -> Result<(), Error> {
+    let mode = OperatingMode::try_from((status >> 4) & 0x7).ok();
+    if mode == Some(OperatingMode::Receive) {
+        return Ok(());
+    }
+    Err(Error::command(
+        GetRssiInst::id(),
+        ErrorKind::WrongMode { found: mode },
+    ))
+}
+
+/// Packet statistics tagged with the packet type they were counted under
+///
+/// The header error counter counts LoRa header CRC errors under [`PacketType::LoRa`] and
+/// invalid length fields under [`PacketType::Gfsk`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StatsReport {
+    /// The counters read from the radio
+    pub stats: Stats,
+    /// Packet type the counters were accumulated under, `None` when the counters have not been
+    /// reset by the driver and may mix packet types
+    pub packet_type: Option<PacketType>,
+}
+
+/// Minimum, maximum and sum of a signal quality measurement, in the radio's raw units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct SignalStats {
+    count: u32,
+    min: i16,
+    max: i16,
+    sum: i64,
+}
+
+impl SignalStats {
+    fn record(&mut self, value: i16) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count = self.count.saturating_add(1);
+        self.sum += i64::from(value);
+    }
+
+    fn min(&self, scale: f32) -> Option<f32> {
+        (self.count > 0).then(|| f32::from(self.min) / scale)
+    }
+
+    fn max(&self, scale: f32) -> Option<f32> {
+        (self.count > 0).then(|| f32::from(self.max) / scale)
+    }
+
+    fn mean(&self, scale: f32) -> Option<f32> {
+        (self.count > 0).then(|| self.sum as f32 / self.count as f32 / scale)
+    }
+}
+
+/// Link statistics accumulated by the driver
+///
+/// The radio's packet counters are 16 bits wide, wrap silently and are cleared by ResetStats.
+/// [`Device::harvest_stats`] adds the counters to these 32 bit totals and resets them, and
+/// [`Device::receive`] records the signal quality of every packet it accepts. The totals are
+/// only cleared by [`Device::reset_link_stats`].
+///
+/// # Important Notes
+/// - Counts accumulated by the radio since the last harvest are lost when the radio's counters
+///   are reset by [`Device::reset_stats`] or by [`Device::apply_config`]
+/// - SNR is only reported for LoRa packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LinkStats {
+    /// Number of packets received
+    pub packets_received: u32,
+    /// Number of packets with a CRC error
+    pub packets_crc_error: u32,
+    /// Number of packets with a header error
+    pub packets_header_error: u32,
+    /// Number of header errors a receive kept waiting past instead of returning them, see
+    /// [`HeaderErrorPolicy`](crate::HeaderErrorPolicy)
+    pub header_errors_skipped: u32,
+    rssi: SignalStats,
+    snr: SignalStats,
+}
+
+impl LinkStats {
+    /// Adds the counts between two readings of the radio's counters.
+    ///
+    /// A counter lower in `current` than in `previous` is assumed to have wrapped once.
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{LinkStats, Stats};
+    ///
+    /// let mut link = LinkStats::default();
+    /// let previous = Stats { packets_received: 65_530, ..Default::default() };
+    /// let current = Stats { packets_received: 4, ..Default::default() };
+    ///
+    /// link.accumulate(&previous, &current);
+    /// assert_eq!(link.packets_received, 10);
+    /// ```
+    pub fn accumulate(&mut self, previous: &Stats, current: &Stats) {
+        let delta = |previous: u16, current: u16| u32::from(current.wrapping_sub(previous));
+
+        self.packets_received = self
+            .packets_received
+            .saturating_add(delta(previous.packets_received, current.packets_received));
+        self.packets_crc_error = self
+            .packets_crc_error
+            .saturating_add(delta(previous.packets_crc_error, current.packets_crc_error));
+        self.packets_header_error = self.packets_header_error.saturating_add(delta(
+            previous.packets_header_error,
+            current.packets_header_error,
+        ));
+    }
+
+    /// Records the signal quality of an accepted packet.
+    ///
+    /// # Arguments
+    /// * `packet_type` - Packet type the status was read under
+    /// * `status` - The packet status read with GetPacketStatus
+    pub fn record_packet(&mut self, packet_type: PacketType, status: &PacketStatus) {
+        self.record_packet_with_offset(packet_type, status, 0);
+    }
+
+    /// Records the signal quality of an accepted packet, adding an offset to its RSSI.
+    ///
+    /// # Arguments
+    /// * `packet_type` - Packet type the status was read under
+    /// * `status` - The packet status read with GetPacketStatus
+    /// * `offset_db` - Correction added to the RSSI, 0 for none
+    ///
+    /// # Example
+    /// ```
+    /// use sx1262::{LinkStats, PacketStatus, PacketType};
+    ///
+    /// let mut link = LinkStats::default();
+    /// let status = PacketStatus { status: [0xA0, 0x28, 0xA0] };
+    ///
+    /// link.record_packet_with_offset(PacketType::LoRa, &status, -2);
+    /// assert_eq!(link.rssi_mean(), Some(-82.0));
+    /// assert_eq!(link.snr_mean(), Some(10.0));
+    /// ```
+    pub fn record_packet_with_offset(
+        &mut self,
+        packet_type: PacketType,
+        status: &PacketStatus,
+        offset_db: i8,
+    ) {
+        let offset = 2 * i16::from(offset_db);
+        match packet_type {
+            PacketType::LoRa => {
+                self.rssi.record(offset - i16::from(status.status[0]));
+                self.snr.record(i16::from(status.status[1] as i8));
+            }
+            PacketType::Gfsk => self.rssi.record(offset - i16::from(status.status[2])),
+        }
+    }
+
+    /// Returns the number of packets whose signal quality was recorded.
+    pub fn packets_recorded(&self) -> u32 {
+        self.rssi.count
+    }
+
+    /// Returns the lowest packet RSSI recorded, in dBm.
+    pub fn rssi_min(&self) -> Option<f32> {
+        self.rssi.min(2.0)
+    }
+
+    /// Returns the highest packet RSSI recorded, in dBm.
+    pub fn rssi_max(&self) -> Option<f32> {
+        self.rssi.max(2.0)
+    }
+
+    /// Returns the mean packet RSSI recorded, in dBm.
+    pub fn rssi_mean(&self) -> Option<f32> {
+        self.rssi.mean(2.0)
+    }
+
+    /// Returns the lowest LoRa packet SNR recorded, in dB.
+    pub fn snr_min(&self) -> Option<f32> {
+        self.snr.min(4.0)
+    }
+
+    /// Returns the highest LoRa packet SNR recorded, in dB.
+    pub fn snr_max(&self) -> Option<f32> {
+        self.snr.max(4.0)
+    }
+
+    /// Returns the mean LoRa packet SNR recorded, in dB.
+    pub fn snr_mean(&self) -> Option<f32> {
+        self.snr.mean(4.0)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Reads the packet statistics.
+    ///
+    /// The counters are reset by [`apply_config`](Device::apply_config) whenever the packet
+    /// type changes, so the report is tagged with the packet type of the applied
+    /// configuration.
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn stats(&mut self) -> Result<StatsReport, Error> {
+        let stats = self.execute_command(GetStats)?.stats;
+        Ok(StatsReport {
+            stats,
+            packet_type: self.state.stats_packet_type,
+        })
+    }
+
+    /// Resets the packet statistics.
+    ///
+    /// Subsequent reports are tagged with the packet type of the applied configuration, if
+    /// any.
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn reset_stats(&mut self) -> Result<(), Error> {
+        self.execute_command(ResetStats)?;
+        self.stats_reset(self.state.config.map(|config| config.packet_type()));
+        Ok(())
+    }
+
+    /// Adds the radio's packet counters to the [`LinkStats`] and resets them.
+    ///
+    /// Call this more often than the busiest counter can wrap, i.e. before 65536 packets have
+    /// been received.
+    ///
+    /// # Returns
+    /// The updated link statistics
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn harvest_stats(&mut self) -> Result<LinkStats, Error> {
+        let stats = self.execute_command(GetStats)?.stats;
+        self.state
+            .link_stats
+            .accumulate(&self.state.stats_baseline, &stats);
+        self.state.stats_baseline = stats;
+
+        self.reset_stats()?;
+        Ok(self.state.link_stats)
+    }
+
+    /// Reads the instantaneous RSSI in dBm, corrected by the offset set with
+    /// [`set_rssi_offset_db`](Device::set_rssi_offset_db).
+    ///
+    /// The value is only meaningful while the radio is receiving, so the operating mode in the
+    /// status byte of the response is checked. The RSSI is reported in steps of 0.5 dB and
+    /// rounded towards the higher power.
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    /// * `Error::Command` with `ErrorKind::WrongMode` - The radio is not in RX
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn channel_busy(device: &mut Device<impl RadioBus>) -> bool {
+    ///     device.rssi_inst().unwrap() > -90
+    /// }
+    /// ```
+    pub fn rssi_inst(&mut self) -> Result<i16, Error> {
+        let mut response = [0; 2];
+        self.execute_raw(GetRssiInst::id(), &[], &mut response)?;
+        check_receiving(response[0])?;
+        Ok(rssi_inst_dbm(response[1], self.settings.rssi_offset_db))
+    }
+
+    /// Reads the instantaneous RSSI in dBm without checking the operating mode.
+    ///
+    /// This is [`rssi_inst`](Device::rssi_inst) for deliberate sampling outside of RX, e.g.
+    /// during FS or CAD transitions, where the value may not reflect the channel.
+    ///
+    /// # Errors
+    /// * `Error::Command` with `ErrorKind::Bus` - Bus communication failed
+    pub fn rssi_inst_unchecked(&mut self) -> Result<i16, Error> {
+        let mut response = [0; 2];
+        self.execute_raw(GetRssiInst::id(), &[], &mut response)?;
+        Ok(rssi_inst_dbm(response[1], self.settings.rssi_offset_db))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously reads the packet statistics.
+    ///
+    /// This is the async version of [`stats`](Device::stats).
+    pub async fn stats_async(&mut self) -> Result<StatsReport, Error> {
+        let stats = self.execute_command_async(GetStats).await?.stats;
+        Ok(StatsReport {
+            stats,
+            packet_type: self.state.stats_packet_type,
+        })
+    }
+
+    /// Asynchronously resets the packet statistics.
+    ///
+    /// This is the async version of [`reset_stats`](Device::reset_stats).
+    pub async fn reset_stats_async(&mut self) -> Result<(), Error> {
+        self.execute_command_async(ResetStats).await?;
+        self.stats_reset(self.state.config.map(|config| config.packet_type()));
+        Ok(())
+    }
+
+    /// Asynchronously adds the radio's packet counters to the [`LinkStats`] and resets them.
+    ///
+    /// This is the async version of [`harvest_stats`](Device::harvest_stats).
+    pub async fn harvest_stats_async(&mut self) -> Result<LinkStats, Error> {
+        let stats = self.execute_command_async(GetStats).await?.stats;
+        self.state
+            .link_stats
+            .accumulate(&self.state.stats_baseline, &stats);
+        self.state.stats_baseline = stats;
+
+        self.reset_stats_async().await?;
+        Ok(self.state.link_stats)
+    }
+
+    /// Asynchronously reads the instantaneous RSSI in dBm.
+    ///
+    /// This is the async version of [`rssi_inst`](Device::rssi_inst).
+    pub async fn rssi_inst_async(&mut self) -> Result<i16, Error> {
+        let mut response = [0; 2];
+        self.execute_raw_async(GetRssiInst::id(), &[], &mut response)
+            .await?;
+        check_receiving(response[0])?;
+        Ok(rssi_inst_dbm(response[1], self.settings.rssi_offset_db))
+    }
+
+    /// Asynchronously reads the instantaneous RSSI in dBm without checking the operating
+    /// mode.
+    ///
+    /// This is the async version of [`rssi_inst_unchecked`](Device::rssi_inst_unchecked).
+    pub async fn rssi_inst_unchecked_async(&mut self) -> Result<i16, Error> {
+        let mut response = [0; 2];
+        self.execute_raw_async(GetRssiInst::id(), &[], &mut response)
+            .await?;
+        Ok(rssi_inst_dbm(response[1], self.settings.rssi_offset_db))
+    }
+}
+
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Records that the radio's packet counters were reset under a packet type.
+    pub(super) fn stats_reset(&mut self, packet_type: Option<PacketType>) {
+        self.state.stats_packet_type = packet_type;
+        self.state.stats_baseline = Stats::default();
+    }
+}
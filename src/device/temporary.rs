@@ -0,0 +1,170 @@
+//! Scoped temporary configuration changes
+//!
+//! Helpers that change a parameter, run a closure and put the original value back, on the
+//! error path too. The original values come from the configuration cached by
+//! [`apply_config`](Device::apply_config), so restoring them needs no bus read.
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::{ConfigItems, RadioConfig},
+    error::{Error, ErrorKind},
+    RfFrequencyConfig, SetRfFrequency, SetTxParams, TxParams,
+};
+
+/// Returns the cached configuration, or an error naming the command that needed it.
+fn cached<C: Command<IdType = u8>>(config: Option<RadioConfig>) -> Result<RadioConfig, Error> {
+    config.ok_or(Error::command(
+        C::id(),
+        ErrorKind::NotConfigured {
+            missing: ConfigItems::all(),
+        },
+    ))
+}
+
+/// Combines the result of the body with the result of restoring the original value.
+///
+/// An error from the body takes precedence over an error restoring.
+fn finish<R>(result: Result<R, Error>, restored: Result<(), Error>) -> Result<R, Error> {
+    let value = result?;
+    restored?;
+    Ok(value)
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Runs a closure with the RF frequency temporarily changed.
+    ///
+    /// The frequency of the applied configuration is restored after the closure returns,
+    /// whether it succeeded or not.
+    ///
+    /// # Arguments
+    /// * `frequency` - RF frequency to use while the closure runs
+    /// * `body` - The closure to run
+    ///
+    /// # Errors
+    /// * `ErrorKind::NotConfigured` - No configuration has been applied
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * Any error returned by the closure, which takes precedence over a failure to restore
+    ///
+    /// # Important Notes
+    /// - Image calibration is not repeated for the temporary frequency
+    pub fn with_frequency<R>(
+        &mut self,
+        frequency: RfFrequencyConfig,
+        body: impl FnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let original = cached::<SetRfFrequency>(self.state.config)?.frequency;
+
+        self.execute_command(SetRfFrequency { config: frequency })?;
+        let result = body(self);
+        let restored = self
+            .execute_command(SetRfFrequency { config: original })
+            .map(drop);
+
+        finish(result, restored)
+    }
+
+    /// Runs a closure with the TX output power temporarily changed.
+    ///
+    /// The ramp time is kept, and the TX parameters of the applied configuration are restored
+    /// after the closure returns, whether it succeeded or not.
+    ///
+    /// # Arguments
+    /// * `power` - Output power in dBm to use while the closure runs
+    /// * `body` - The closure to run
+    ///
+    /// # Errors
+    /// * `ErrorKind::NotConfigured` - No configuration has been applied
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * Any error returned by the closure, which takes precedence over a failure to restore
+    pub fn with_tx_power<R>(
+        &mut self,
+        power: i8,
+        body: impl FnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let original = cached::<SetTxParams>(self.state.config)?.tx_params;
+
+        self.execute_command(SetTxParams {
+            params: TxParams { power, ..original },
+        })?;
+        let result = body(self);
+        let restored = self
+            .execute_command(SetTxParams { params: original })
+            .map(drop);
+
+        finish(result, restored)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously runs a closure with the RF frequency temporarily changed.
+    ///
+    /// This is the async version of [`with_frequency`](Device::with_frequency).
+    pub async fn with_frequency_async<R>(
+        &mut self,
+        frequency: RfFrequencyConfig,
+        body: impl AsyncFnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let original = cached::<SetRfFrequency>(self.state.config)?.frequency;
+
+        self.execute_command_async(SetRfFrequency { config: frequency })
+            .await?;
+        let result = body(self).await;
+        let restored = self
+            .execute_command_async(SetRfFrequency { config: original })
+            .await
+            .map(drop);
+
+        finish(result, restored)
+    }
+
+    /// Asynchronously runs a closure with the TX output power temporarily changed.
+    ///
+    /// This is the async version of [`with_tx_power`](Device::with_tx_power).
+    pub async fn with_tx_power_async<R>(
+        &mut self,
+        power: i8,
+        body: impl AsyncFnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let original = cached::<SetTxParams>(self.state.config)?.tx_params;
+
+        self.execute_command_async(SetTxParams {
+            params: TxParams { power, ..original },
+        })
+        .await?;
+        let result = body(self).await;
+        let restored = self
+            .execute_command_async(SetTxParams { params: original })
+            .await
+            .map(drop);
+
+        finish(result, restored)
+    }
+}
@@ -0,0 +1,1258 @@
+//! Transmit and receive helpers
+//!
+//! These helpers run a complete TX or RX cycle by polling GetIrqStatus, for applications that
+//! do not need to interleave other work with the radio.
+
+use regiface::Command;
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::RadioConfig,
+    error::{Error, ErrorKind, RepeatedTxError, RxError, TxError},
+    ClearIrqStatus, GFSKPacketHeaderType, GetIrqStatus, GetPacketStatus, GfskRxStatus, IrqMask,
+    LoRaHeaderMode, LoraPacketHeaderType, PacketParams, PacketStatus, PacketType, RxMode,
+    SetPacketParams, SetRx, SetStandby, SetTx, StandbyConfig, Timeout,
+};
+
+/// A packet sent by [`Device::transmit`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxDone {
+    /// Time TX_DONE was observed, when a clock is installed with
+    /// [`set_clock`](Device::set_clock)
+    pub at: Option<u32>,
+}
+
+/// A packet received by [`Device::receive`]
+#[derive(Debug, Clone, Copy)]
+pub struct RxPacket {
+    /// Length of the payload in bytes
+    pub len: usize,
+    /// Signal quality of the packet
+    pub status: PacketStatus,
+    /// Whether the payload passed the CRC check, only `false` when
+    /// [`RxOptions::deliver_crc_failures`] is set
+    pub crc_ok: bool,
+    /// Time RX_DONE was observed, when a clock is installed with
+    /// [`set_clock`](Device::set_clock)
+    pub timestamp: Option<u32>,
+}
+
+/// Handling of a header error by the receive helpers
+///
+/// In LoRa explicit-header mode a HEADER_ERROR means the packet is lost. In single RX the
+/// radio then falls back to standby, so a receive that should stay available must re-arm RX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HeaderErrorPolicy {
+    /// Return `RxError::Header`, or report HEADER_ERROR to the caller of
+    /// [`Device::poll_rx_with_options`]
+    #[default]
+    Return,
+    /// Clear the IRQ, send SetRx again and keep waiting. This restarts the RX timeout
+    RestartRx,
+    /// Clear the IRQ and keep waiting without re-arming RX, for continuous RX where the radio
+    /// stays in RX
+    Count,
+}
+
+/// Options of [`Device::receive_with_options`]
+///
+/// The [`Default`] rejects packets failing the payload CRC check with `RxError::Crc` and
+/// returns header errors with `RxError::Header`.
+///
+/// # Example
+/// Keeping packets whose CRC failed, e.g. for a forward error correction layer above:
+/// ```no_run
+/// use sx1262::{bus::RadioBus, Device, RxMode, RxOptions};
+///
+/// # #[cfg(feature = "sync")]
+/// fn receive_all(device: &mut Device<impl RadioBus>, bytes: &mut [u8]) -> bool {
+///     let options = RxOptions {
+///         deliver_crc_failures: true,
+///         ..RxOptions::default()
+///     };
+///     let packet = device
+///         .receive_with_options(bytes, RxMode::Single, options)
+///         .unwrap();
+///     packet.crc_ok
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxOptions {
+    /// Return packets failing the payload CRC check with [`RxPacket::crc_ok`] cleared instead
+    /// of an `RxError::Crc`
+    pub deliver_crc_failures: bool,
+    /// Handling of a header error, see [`HeaderErrorPolicy`]
+    pub on_header_error: HeaderErrorPolicy,
+    /// LoRa IQ polarity of this receive, the configured one is restored afterwards. Ignored by
+    /// [`poll_rx_with_options`](Device::poll_rx_with_options)
+    pub iq: Option<IqMode>,
+}
+
+/// Options of [`Device::transmit_with_options`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxOptions {
+    /// LoRa IQ polarity of this transmit, the configured one is restored afterwards
+    pub iq: Option<IqMode>,
+}
+
+/// LoRa IQ polarity
+///
+/// Uplinks use standard IQ and downlinks inverted IQ, so a repeater flips the polarity
+/// between receiving and retransmitting, see [`Device::set_iq_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IqMode {
+    /// Standard IQ
+    #[default]
+    Standard,
+    /// Inverted IQ
+    Inverted,
+}
+
+impl IqMode {
+    /// Returns the mode of the IQ inversion bit of the LoRa packet parameters.
+    pub const fn from_inverted(inverted: bool) -> Self {
+        if inverted {
+            Self::Inverted
+        } else {
+            Self::Standard
+        }
+    }
+
+    /// Returns whether the IQ are inverted.
+    pub const fn is_inverted(self) -> bool {
+        matches!(self, Self::Inverted)
+    }
+}
+
+/// Compile-time check that a payload of `N` bytes fits in a packet
+struct PayloadLength<const N: usize>;
+
+impl<const N: usize> PayloadLength<N> {
+    const CHECK: () = assert!(N <= 255, "payload length must not exceed 255 bytes");
+}
+
+/// IRQs ending a TX cycle
+const TX_END: IrqMask = IrqMask::TX_DONE.union(IrqMask::TIMEOUT);
+
+/// Time a read of the IRQ status is counted for in microseconds, a lower bound since its 4
+/// bytes alone take 2 µs at the 16 MHz maximum SPI clock
+pub(super) const IRQ_POLL_US: u32 = 1;
+
+/// IRQs ending an RX cycle
+pub(super) const RX_END: IrqMask = IrqMask::RX_DONE
+    .union(IrqMask::TIMEOUT)
+    .union(IrqMask::HEADER_ERROR);
+
+/// Returns the packet parameters to send for a payload length, if they need to change.
+///
+/// Only variable-length packet formats are changed, a fixed-length format must already match.
+fn packet_params_for_length(config: Option<&RadioConfig>, len: u8) -> Option<PacketParams> {
+    let mut packet = config?.packet;
+    let payload_length = match &mut packet {
+        PacketParams::LoRa(params) if params.header_type == LoraPacketHeaderType::Variable => {
+            &mut params.payload_length
+        }
+        PacketParams::GFSK(params) if params.packet_type == GFSKPacketHeaderType::Variable => {
+            &mut params.payload_length
+        }
+        _ => return None,
+    };
+
+    if *payload_length == len {
+        return None;
+    }
+    *payload_length = len;
+    Some(packet)
+}
+
+/// Returns an error unless the payload length matches a fixed-length packet format.
+///
+/// Without a fixed-length format, any length is accepted unless `require_fixed` is set.
+fn check_fixed_length(
+    config: Option<&RadioConfig>,
+    len: usize,
+    require_fixed: bool,
+) -> Result<(), Option<u8>> {
+    match config.and_then(RadioConfig::fixed_payload_length) {
+        Some(configured) if configured as usize == len => Ok(()),
+        None if !require_fixed => Ok(()),
+        configured => Err(configured),
+    }
+}
+
+/// Returns an error unless a receive buffer can hold a fixed-length GFSK payload.
+///
+/// The GFSK demodulator captures exactly the configured length, which the buffer must hold,
+/// and a configured length of 0 captures nothing.
+fn check_rx_buffer(config: Option<&RadioConfig>, len: usize) -> Result<(), u8> {
+    match config.map(|config| config.packet) {
+        Some(PacketParams::GFSK(params))
+            if params.packet_type == GFSKPacketHeaderType::Fixed
+                && (params.payload_length == 0 || len < params.payload_length as usize) =>
+        {
+            Err(params.payload_length)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the outcome of a TX cycle from the IRQs that ended it.
+fn tx_outcome(irq: IrqMask, at: Option<u32>) -> Result<TxDone, TxError> {
+    if irq.contains(IrqMask::TX_DONE) {
+        Ok(TxDone { at })
+    } else {
+        Err(TxError::Timeout)
+    }
+}
+
+/// Returns whether a header error is skipped rather than ending a receive, and if so whether
+/// RX must be re-armed.
+///
+/// Header errors accompanied by RX_DONE are left to [`rx_outcome`].
+pub(super) fn skipped_header_error(irq: IrqMask, options: RxOptions) -> Option<bool> {
+    if !irq.contains(IrqMask::HEADER_ERROR) || irq.contains(IrqMask::RX_DONE) {
+        return None;
+    }
+    match options.on_header_error {
+        HeaderErrorPolicy::Return => None,
+        HeaderErrorPolicy::RestartRx => Some(true),
+        HeaderErrorPolicy::Count => Some(false),
+    }
+}
+
+/// Returns the outcome of an RX cycle from the IRQs that ended it.
+///
+/// # Returns
+/// Whether the payload passed the CRC check
+fn rx_outcome(irq: IrqMask, options: RxOptions) -> Result<bool, RxError> {
+    if irq.contains(IrqMask::HEADER_ERROR) {
+        Err(RxError::Header)
+    } else if irq.contains(IrqMask::CRC_ERROR) && !options.deliver_crc_failures {
+        Err(RxError::Crc)
+    } else if irq.contains(IrqMask::RX_DONE) {
+        Ok(!irq.contains(IrqMask::CRC_ERROR))
+    } else {
+        Err(RxError::Timeout)
+    }
+}
+
+/// Returns whether a packet passed the CRC check according to its packet status.
+///
+/// GFSK reports a failed CRC in the RxStatus even with the CRC_ERROR IRQ masked.
+fn crc_ok(
+    packet_type: Option<PacketType>,
+    status: &PacketStatus,
+    options: RxOptions,
+) -> Result<bool, RxError> {
+    let failed = packet_type == Some(PacketType::Gfsk)
+        && status.gfsk().rx_status.contains(GfskRxStatus::CRC_ERROR);
+    if failed && !options.deliver_crc_failures {
+        return Err(RxError::Crc);
+    }
+    Ok(!failed)
+}
+
+#[cfg(any(feature = "sync", feature = "async"))]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Returns how long the IRQ status is polled for in microseconds: the radio's timeout with
+    /// the busy timeout as a margin, or the busy timeout alone when the radio has no timeout.
+    pub(super) fn irq_poll_limit_us(&self, timeout: Timeout) -> u32 {
+        let margin = self.settings.busy_timeout_us;
+        match timeout.0 & 0xFF_FFFF {
+            0 | 0xFF_FFFF => margin,
+            steps => ((u64::from(steps) * 15_625).div_ceil(1_000) as u32).saturating_add(margin),
+        }
+    }
+
+    /// Returns the payload length to send, unless the transmit helpers must reject it.
+    fn tx_payload_length(&self, len: usize) -> Result<u8, TxError> {
+        if len == 0 && !self.settings.allow_empty_payload {
+            return Err(TxError::EmptyPayload);
+        }
+        let short = u8::try_from(len).map_err(|_| TxError::PayloadTooLong(len))?;
+        check_fixed_length(self.state.config.as_ref(), len, false).map_err(|configured| {
+            TxError::PayloadLengthMismatch {
+                configured,
+                requested: len,
+            }
+        })?;
+        Ok(short)
+    }
+
+    /// Returns an error unless the receive helpers can read a payload into `len` bytes.
+    fn check_rx_buffer(&self, len: usize) -> Result<(), RxError> {
+        check_rx_buffer(self.state.config.as_ref(), len).map_err(|configured| {
+            RxError::PayloadLengthMismatch {
+                configured: Some(configured),
+                requested: len,
+            }
+        })
+    }
+
+    /// Returns whether the cached configuration is LoRa with an implicit header.
+    fn implicit_lora_header(&self) -> bool {
+        matches!(
+            self.state.config.map(|config| config.packet),
+            Some(PacketParams::LoRa(params))
+                if matches!(params.header_mode(), LoRaHeaderMode::Implicit { .. })
+        )
+    }
+
+    /// Adds a received packet passing the CRC check to the link statistics.
+    fn record_received(&mut self, status: &PacketStatus, crc_ok: bool) {
+        if let (Some(config), true) = (self.state.config, crc_ok) {
+            self.state.link_stats.record_packet_with_offset(
+                config.packet_type(),
+                status,
+                self.settings.rssi_offset_db,
+            );
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Transmits a packet and waits for it to be sent.
+    ///
+    /// The payload is written at the TX base address and, with a variable-length packet
+    /// format, the payload length is updated in the packet parameters before SetTx is sent.
+    /// GetIrqStatus is then polled until the packet is sent or the timeout elapses.
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to send, at most 255
+    /// * `timeout` - TX timeout, the [`Default`] of 0 disables it
+    ///
+    /// # Returns
+    /// The time the packet was sent, see [`set_clock`](Device::set_clock)
+    ///
+    /// # Errors
+    /// * `TxError::Device` - Bus communication failed, or `ErrorKind::NotConfigured` when the
+    ///   frequency, modulation or packet parameters were not sent since the last reset, see
+    ///   [`assume_configured`](Device::assume_configured)
+    /// * `TxError::Timeout` - The timeout elapsed before the packet was sent
+    /// * `TxError::Device` with `ErrorKind::IrqTimeout` - Neither TX_DONE nor TIMEOUT was read
+    ///   within the timeout plus the [busy timeout](Device::set_busy_timeout_us), or the busy
+    ///   timeout alone when the timeout is disabled. The radio is put in STDBY_RC
+    /// * `TxError::EmptyPayload` - The payload is empty, see
+    ///   [`set_allow_empty_payload`](Device::set_allow_empty_payload)
+    /// * `TxError::PayloadTooLong` - The payload is longer than 255 bytes
+    /// * `TxError::PayloadLengthMismatch` - The payload does not match the configured
+    ///   fixed-length packet format
+    ///
+    /// # Important Notes
+    /// - The configuration must have been applied with [`apply_config`](Device::apply_config)
+    ///   for the TX base address and packet parameters to be known, otherwise the payload is
+    ///   written at offset 0 and the packet parameters are left unchanged
+    /// - TX_DONE and TIMEOUT are enabled and routed to DIO1 with
+    ///   [`modify_irq_routing`](Device::modify_irq_routing) when the IRQ routing is known,
+    ///   otherwise they must be enabled in the IRQ mask
+    /// - IRQs left pending by earlier operations are cleared before SetTx, so they cannot end
+    ///   the wait early. The same holds for [`receive`](Device::receive)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, LoRaInit, RfFrequencyConfig, Timeout};
+    ///
+    /// fn send(device: &mut Device<impl RadioBus>) {
+    ///     let frequency = RfFrequencyConfig { frequency: 868_100_000 };
+    ///     device.apply_config(&LoRaInit::new(frequency).config().unwrap()).unwrap();
+    ///     device.transmit(b"hello", Timeout::default()).unwrap();
+    /// }
+    /// ```
+    pub fn transmit(&mut self, payload: &[u8], timeout: Timeout) -> Result<TxDone, TxError> {
+        self.transmit_with_options(payload, timeout, TxOptions::default())
+    }
+
+    /// Transmits a packet with options and waits for it to be sent.
+    ///
+    /// This is [`transmit`](Device::transmit) with the LoRa IQ polarity selected by
+    /// `options`, which [`RxOptions::iq`] selects for a receive. The polarity is sent before
+    /// SetTx and the configured one is restored once the transmit ends.
+    ///
+    /// # Arguments
+    /// * `payload` - The payload to send, up to 255 bytes
+    /// * `timeout` - TX timeout
+    /// * `options` - Transmit options
+    ///
+    /// # Errors
+    /// As [`transmit`](Device::transmit). When [`TxOptions::iq`] is set, the errors of
+    /// [`set_iq_mode`](Device::set_iq_mode) are returned as `TxError::Device`, and an error of
+    /// the transmit is returned before one restoring the configured polarity
+    ///
+    /// # Example
+    /// A repeater receiving with standard IQ and retransmitting with inverted IQ:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, IqMode, RxMode, RxOptions, Timeout, TxOptions};
+    ///
+    /// fn repeat(device: &mut Device<impl RadioBus>) {
+    ///     let options = RxOptions { iq: Some(IqMode::Standard), ..RxOptions::default() };
+    ///     let mut bytes = [0; 255];
+    ///     let packet = device
+    ///         .receive_with_options(&mut bytes, RxMode::Single, options)
+    ///         .unwrap();
+    ///
+    ///     let options = TxOptions { iq: Some(IqMode::Inverted) };
+    ///     let payload = &bytes[..packet.len];
+    ///     device.transmit_with_options(payload, Timeout::default(), options).unwrap();
+    /// }
+    /// ```
+    pub fn transmit_with_options(
+        &mut self,
+        payload: &[u8],
+        timeout: Timeout,
+        options: TxOptions,
+    ) -> Result<TxDone, TxError> {
+        self.check_configured(SetTx::id())?;
+        let len = self.tx_payload_length(payload.len())?;
+        self.set_payload_length(len)?;
+        self.ensure_irq_routes(TX_END)?;
+
+        let baseline = self.override_iq(options.iq)?;
+        let sent = self.run_transmit(payload, timeout);
+        let restored = self.restore_iq(baseline);
+        let done = sent?;
+        restored?;
+        Ok(done)
+    }
+
+    /// Sends a payload and waits for TX_DONE or TIMEOUT, once the radio is prepared.
+    fn run_transmit(&mut self, payload: &[u8], timeout: Timeout) -> Result<TxDone, TxError> {
+        let base = self
+            .state
+            .config
+            .map_or(0, |config| config.buffer_base.tx_base_addr);
+        self.execute_command(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })?;
+        self.write_buffer(base, payload)?;
+        self.send_staged(timeout)
+    }
+
+    /// Sends the payload already in the buffer and waits for TX_DONE or TIMEOUT, once the
+    /// IRQs are cleared.
+    fn send_staged(&mut self, timeout: Timeout) -> Result<TxDone, TxError> {
+        self.expect_irqs_cleared(TX_END);
+        self.execute_command(SetTx { timeout })?;
+
+        let limit_us = self.irq_poll_limit_us(timeout);
+        let mut waited_us = 0;
+        let (irq, at) = loop {
+            let irq = self.execute_command(GetIrqStatus)?.irq_mask;
+            if irq.intersects(TX_END) {
+                break (irq, self.timestamp());
+            }
+            waited_us += IRQ_POLL_US;
+            if waited_us >= limit_us {
+                return Err(self.abandon_poll(waited_us).into());
+            }
+        };
+        self.record_irqs(irq);
+        self.execute_command(ClearIrqStatus { irq_mask: irq })?;
+
+        tx_outcome(irq, at)
+    }
+
+    /// Returns the radio to STDBY_RC after the IRQ poll gave up, so that the PA is not left
+    /// keyed, and returns the error to report.
+    pub(super) fn abandon_poll(&mut self, waited_us: u32) -> Error {
+        let _ = self.execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        });
+        Error::command(GetIrqStatus::id(), ErrorKind::IrqTimeout { waited_us })
+    }
+
+    /// Transmits a fixed-length packet and waits for it to be sent.
+    ///
+    /// This is [`transmit`](Device::transmit) for payloads whose length is known at compile
+    /// time. Payloads longer than 255 bytes fail to compile.
+    ///
+    /// # Errors
+    /// As [`transmit`](Device::transmit), and additionally
+    /// * `TxError::PayloadLengthMismatch` - The applied configuration does not use a
+    ///   fixed-length packet format of `N` bytes
+    pub fn transmit_fixed<const N: usize>(
+        &mut self,
+        payload: &[u8; N],
+        timeout: Timeout,
+    ) -> Result<TxDone, TxError> {
+        let () = PayloadLength::<N>::CHECK;
+        check_fixed_length(self.state.config.as_ref(), N, true).map_err(|configured| {
+            TxError::PayloadLengthMismatch {
+                configured,
+                requested: N,
+            }
+        })?;
+
+        self.transmit(payload, timeout)
+    }
+
+    /// Transmits the same packet `count` times, `interval_ms` apart, writing the payload once.
+    ///
+    /// The radio keeps the data buffer between transmissions, so the payload and its length
+    /// are only written before the first one. Each transmission then only clears the IRQs,
+    /// sends SetTx and waits for TX_DONE, saving the buffer write for beacons and ranging
+    /// frames. The interval is waited with the owned delay between the end of a transmission
+    /// and the start of the next one. Sleeping the radio in between would lose the buffer.
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to send, at most 255
+    /// * `count` - Number of transmissions, nothing is sent for 0
+    /// * `interval_ms` - Wait between two transmissions in milliseconds
+    /// * `timeout` - TX timeout of each transmission
+    ///
+    /// # Errors
+    /// The first error of [`transmit`](Device::transmit) stops the repetition, and is returned
+    /// with the number of transmissions completed before it
+    ///
+    /// # Example
+    /// Three beacons a second apart:
+    /// ```no_run
+    /// use embedded_hal::{delay::DelayNs, spi::SpiDevice};
+    /// use sx1262::{Device, Timeout};
+    ///
+    /// fn beacons(spi: impl SpiDevice, delay: impl DelayNs) {
+    ///     let mut device = Device::new(spi).with_delay(delay);
+    ///     device.transmit_repeated(b"beacon", 3, 1_000, Timeout::default()).unwrap();
+    /// }
+    /// ```
+    pub fn transmit_repeated(
+        &mut self,
+        payload: &[u8],
+        count: u16,
+        interval_ms: u32,
+        timeout: Timeout,
+    ) -> Result<(), RepeatedTxError>
+    where
+        DELAY: embedded_hal::delay::DelayNs,
+    {
+        self.repeat_transmit(payload, count, timeout, |device| {
+            device.delay.delay_ms(interval_ms)
+        })
+    }
+
+    /// Transmits the same packet `count` times, `interval_ms` apart timed with the given delay,
+    /// writing the payload once.
+    ///
+    /// See [`transmit_repeated`](Device::transmit_repeated).
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to send, at most 255
+    /// * `count` - Number of transmissions, nothing is sent for 0
+    /// * `interval_ms` - Wait between two transmissions in milliseconds
+    /// * `timeout` - TX timeout of each transmission
+    /// * `delay` - Delay used between transmissions
+    pub fn transmit_repeated_with_delay(
+        &mut self,
+        payload: &[u8],
+        count: u16,
+        interval_ms: u32,
+        timeout: Timeout,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<(), RepeatedTxError> {
+        self.repeat_transmit(payload, count, timeout, |_| delay.delay_ms(interval_ms))
+    }
+
+    /// Writes the payload once and sends it `count` times, calling `wait` between two
+    /// transmissions.
+    fn repeat_transmit(
+        &mut self,
+        payload: &[u8],
+        count: u16,
+        timeout: Timeout,
+        mut wait: impl FnMut(&mut Self),
+    ) -> Result<(), RepeatedTxError> {
+        if count == 0 {
+            return Ok(());
+        }
+        self.stage_repeated(payload)
+            .map_err(|source| RepeatedTxError { sent: 0, source })?;
+
+        for sent in 0..count {
+            let result = if sent == 0 {
+                self.send_staged(timeout)
+            } else {
+                wait(self);
+                self.execute_command(ClearIrqStatus {
+                    irq_mask: IrqMask::all(),
+                })
+                .map_err(TxError::from)
+                .and_then(|_| self.send_staged(timeout))
+            };
+            result.map_err(|source| RepeatedTxError { sent, source })?;
+        }
+        Ok(())
+    }
+
+    /// Checks and writes the payload of a repeated transmission, clearing the IRQs.
+    fn stage_repeated(&mut self, payload: &[u8]) -> Result<(), TxError> {
+        self.check_configured(SetTx::id())?;
+        let len = self.tx_payload_length(payload.len())?;
+        self.set_payload_length(len)?;
+        self.ensure_irq_routes(TX_END)?;
+
+        let base = self
+            .state
+            .config
+            .map_or(0, |config| config.buffer_base.tx_base_addr);
+        self.execute_command(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })?;
+        self.write_buffer(base, payload)?;
+        Ok(())
+    }
+
+    /// Receives a packet.
+    ///
+    /// GetIrqStatus is polled until a packet is received, a header error is detected or the
+    /// timeout elapses. The payload is then read into the provided buffer.
+    ///
+    /// # Arguments
+    /// * `bytes` - Buffer to store the payload, at least as long as the payload
+    /// * `mode` - RX mode, with the timeout if any
+    ///
+    /// # Returns
+    /// The length and signal quality of the packet, which is also recorded in the
+    /// [`LinkStats`](crate::LinkStats)
+    ///
+    /// # Errors
+    /// * `RxError::Device` - Bus communication failed, the buffer is shorter than the payload,
+    ///   or the radio is not configured as for [`transmit`](Device::transmit)
+    /// * `RxError::Timeout` - The timeout elapsed before a packet was received
+    /// * `RxError::Device` with `ErrorKind::IrqTimeout` - Nothing ended RX within the timeout
+    ///   plus the [busy timeout](Device::set_busy_timeout_us), or the busy timeout alone in
+    ///   [`RxMode::Single`] and [`RxMode::Continuous`]. The radio is put in STDBY_RC
+    /// * `RxError::Crc` - A packet was received with a bad payload CRC
+    /// * `RxError::Header` - A packet was received with a bad header
+    /// * `RxError::PayloadLengthMismatch` - GFSK packets are fixed-length and the buffer is
+    ///   shorter than the configured length, or that length is 0, before RX is armed
+    ///
+    /// # Important Notes
+    /// - With a variable-length packet format, the payload length in the packet parameters is
+    ///   raised back to 255 after a transmit so that any packet is accepted
+    /// - The payload length comes from GetRxBufferStatus with an explicit LoRa header, and
+    ///   from the configuration with an [implicit one](crate::LoRaHeaderMode::Implicit), where
+    ///   an RX timeout is followed by
+    ///   [`apply_implicit_header_timeout_workaround`](Device::apply_implicit_header_timeout_workaround)
+    /// - RX_DONE, TIMEOUT, CRC_ERROR and HEADER_ERROR are enabled and routed to DIO1 with
+    ///   [`modify_irq_routing`](Device::modify_irq_routing) when the IRQ routing is known,
+    ///   otherwise they must be enabled in the IRQ mask
+    pub fn receive(&mut self, bytes: &mut [u8], mode: RxMode) -> Result<RxPacket, RxError> {
+        self.receive_with_options(bytes, mode, RxOptions::default())
+    }
+
+    /// Receives a packet with options.
+    ///
+    /// This is [`receive`](Device::receive) with the handling of CRC failures selected by
+    /// `options`. A packet failing the CRC check is still read from the buffer, at the
+    /// position and length given by GetRxBufferStatus, and is not recorded in the
+    /// [`LinkStats`](crate::LinkStats).
+    ///
+    /// # Arguments
+    /// * `bytes` - Buffer to store the payload, at least as long as the payload
+    /// * `mode` - RX mode, with the timeout if any
+    /// * `options` - Receive options
+    ///
+    /// A header error without RX_DONE is handled according to
+    /// [`RxOptions::on_header_error`]. When it is skipped, HEADER_ERROR is cleared before SetRx
+    /// is sent again, and the skip is counted in
+    /// [`LinkStats::header_errors_skipped`](crate::LinkStats::header_errors_skipped).
+    ///
+    /// # Errors
+    /// As [`receive`](Device::receive), without `RxError::Crc` when
+    /// [`RxOptions::deliver_crc_failures`] is set and without `RxError::Header` unless
+    /// [`RxOptions::on_header_error`] is [`HeaderErrorPolicy::Return`]. The errors of
+    /// [`RxOptions::iq`] are those of [`transmit_with_options`](Device::transmit_with_options)
+    ///
+    /// # Example
+    /// Skipping header errors without restarting RX:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, HeaderErrorPolicy, RxMode, RxOptions};
+    ///
+    /// fn receive(device: &mut Device<impl RadioBus>, bytes: &mut [u8]) -> usize {
+    ///     let options = RxOptions {
+    ///         on_header_error: HeaderErrorPolicy::Count,
+    ///         ..RxOptions::default()
+    ///     };
+    ///     device.receive_with_options(bytes, RxMode::Continuous, options).unwrap().len
+    /// }
+    /// ```
+    pub fn receive_with_options(
+        &mut self,
+        bytes: &mut [u8],
+        mode: RxMode,
+        options: RxOptions,
+    ) -> Result<RxPacket, RxError> {
+        self.check_configured(SetRx::id())?;
+        self.check_rx_buffer(bytes.len())?;
+        self.set_payload_length(u8::MAX)?;
+        self.ensure_irq_routes(RX_END | IrqMask::CRC_ERROR)?;
+
+        let baseline = self.override_iq(options.iq)?;
+        let received = self.run_receive(bytes, mode, options);
+        let restored = self.restore_iq(baseline);
+        let packet = received?;
+        restored?;
+        Ok(packet)
+    }
+
+    /// Arms RX and waits for a packet, once the radio is prepared.
+    fn run_receive(
+        &mut self,
+        bytes: &mut [u8],
+        mode: RxMode,
+        options: RxOptions,
+    ) -> Result<RxPacket, RxError> {
+        self.execute_command(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })?;
+        self.expect_irqs_cleared(RX_END | IrqMask::CRC_ERROR);
+        self.execute_command(SetRx { mode })?;
+
+        let limit_us = self.irq_poll_limit_us(mode.into());
+        let mut waited_us = 0;
+        let (irq, timestamp) = loop {
+            let irq = self.execute_command(GetIrqStatus)?.irq_mask;
+            if !irq.intersects(RX_END) {
+                waited_us += IRQ_POLL_US;
+                if waited_us >= limit_us {
+                    return Err(self.abandon_poll(waited_us).into());
+                }
+                continue;
+            }
+            let Some(restart) = skipped_header_error(irq, options) else {
+                break (irq, self.timestamp());
+            };
+            self.record_irqs(irq);
+            self.execute_command(ClearIrqStatus { irq_mask: irq })?;
+            self.state.link_stats.header_errors_skipped = self
+                .state
+                .link_stats
+                .header_errors_skipped
+                .saturating_add(1);
+            if restart {
+                self.expect_irqs_cleared(irq);
+                self.execute_command(SetRx { mode })?;
+            }
+        };
+        self.record_irqs(irq);
+        self.execute_command(ClearIrqStatus { irq_mask: irq })?;
+        self.finish_receive(bytes, irq, timestamp, options)
+    }
+
+    /// Receives a fixed-length packet.
+    ///
+    /// This is [`receive`](Device::receive) for payloads whose length is known at compile
+    /// time. Payloads longer than 255 bytes fail to compile.
+    ///
+    /// # Errors
+    /// As [`receive`](Device::receive), and additionally
+    /// * `RxError::PayloadLengthMismatch` - The applied configuration does not use a
+    ///   fixed-length packet format of `N` bytes
+    pub fn receive_fixed<const N: usize>(&mut self, mode: RxMode) -> Result<[u8; N], RxError> {
+        let () = PayloadLength::<N>::CHECK;
+        check_fixed_length(self.state.config.as_ref(), N, true).map_err(|configured| {
+            RxError::PayloadLengthMismatch {
+                configured,
+                requested: N,
+            }
+        })?;
+
+        let mut bytes = [0; N];
+        self.receive(&mut bytes, mode)?;
+        Ok(bytes)
+    }
+
+    /// Turns the cleared IRQs that ended an RX cycle into the received packet.
+    pub(super) fn finish_receive(
+        &mut self,
+        bytes: &mut [u8],
+        irq: IrqMask,
+        timestamp: Option<u32>,
+        options: RxOptions,
+    ) -> Result<RxPacket, RxError> {
+        let implicit = self.implicit_lora_header();
+        if implicit && irq.contains(IrqMask::TIMEOUT) {
+            self.apply_implicit_header_timeout_workaround()?;
+        }
+        let irq_crc_ok = rx_outcome(irq, options)?;
+
+        let status = self.execute_command(GetPacketStatus)?.packet_status;
+        let crc_ok = irq_crc_ok && crc_ok(self.state.packet_type, &status, options)?;
+        // Without a header, GetRxBufferStatus only repeats the configured length
+        let len = if implicit {
+            self.read_received_payload_fast(bytes)?
+        } else {
+            self.read_received_payload(bytes)?
+        };
+        self.record_received(&status, crc_ok);
+        Ok(RxPacket {
+            len,
+            status,
+            crc_ok,
+            timestamp,
+        })
+    }
+
+    /// Applies the IQ polarity of an operation before SetTx or SetRx.
+    ///
+    /// Returns the configured polarity when it must be restored afterwards.
+    fn override_iq(&mut self, iq: Option<IqMode>) -> Result<Option<IqMode>, crate::Error> {
+        let Some(iq) = iq else {
+            return Ok(None);
+        };
+        let baseline = IqMode::from_inverted(self.cached_lora_packet()?.iq_inversion_enable);
+        self.write_iq_mode(iq)?;
+        Ok((iq != baseline).then_some(baseline))
+    }
+
+    /// Restores the configured IQ polarity after an operation.
+    fn restore_iq(&mut self, baseline: Option<IqMode>) -> Result<(), crate::Error> {
+        match baseline {
+            Some(iq) => self.write_iq_mode(iq).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Updates the payload length of a variable-length packet format if it differs.
+    pub(super) fn set_payload_length(&mut self, len: u8) -> Result<(), crate::Error> {
+        if let Some(params) = packet_params_for_length(self.state.config.as_ref(), len) {
+            self.execute_command(SetPacketParams { params })?;
+            if let Some(config) = &mut self.state.config {
+                config.packet = params;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously transmits a packet and waits for it to be sent.
+    ///
+    /// This is the async version of [`transmit`](Device::transmit).
+    ///
+    /// # Cancellation
+    /// When the future is dropped before completion the radio may be left in TX. The next
+    /// async transmit or receive first calls [`resync_async`](Device::resync_async).
+    pub async fn transmit_async(
+        &mut self,
+        payload: &[u8],
+        timeout: Timeout,
+    ) -> Result<TxDone, TxError> {
+        self.transmit_with_options_async(payload, timeout, TxOptions::default())
+            .await
+    }
+
+    /// Asynchronously transmits a packet with options and waits for it to be sent.
+    ///
+    /// This is the async version of [`transmit_with_options`](Device::transmit_with_options).
+    ///
+    /// # Cancellation
+    /// As [`transmit_async`](Device::transmit_async). The configured IQ polarity is not
+    /// restored either.
+    pub async fn transmit_with_options_async(
+        &mut self,
+        payload: &[u8],
+        timeout: Timeout,
+        options: TxOptions,
+    ) -> Result<TxDone, TxError> {
+        self.check_configured(SetTx::id())?;
+        let len = self.tx_payload_length(payload.len())?;
+        self.begin_operation_async().await?;
+        self.set_payload_length_async(len).await?;
+        self.ensure_irq_routes_async(TX_END).await?;
+
+        let baseline = self.override_iq_async(options.iq).await?;
+        let sent = self.run_transmit_async(payload, timeout).await;
+        let restored = self.restore_iq_async(baseline).await;
+        let done = sent?;
+        restored?;
+        Ok(done)
+    }
+
+    /// Asynchronously sends a payload and waits for TX_DONE or TIMEOUT, once the radio is
+    /// prepared.
+    async fn run_transmit_async(
+        &mut self,
+        payload: &[u8],
+        timeout: Timeout,
+    ) -> Result<TxDone, TxError> {
+        let base = self
+            .state
+            .config
+            .map_or(0, |config| config.buffer_base.tx_base_addr);
+        self.execute_command_async(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .await?;
+        self.write_buffer_async(base, payload).await?;
+        self.send_staged_async(timeout).await
+    }
+
+    /// Asynchronously sends the payload already in the buffer and waits for TX_DONE or
+    /// TIMEOUT, once the IRQs are cleared.
+    async fn send_staged_async(&mut self, timeout: Timeout) -> Result<TxDone, TxError> {
+        self.expect_irqs_cleared(TX_END);
+        self.execute_command_async(SetTx { timeout }).await?;
+
+        let limit_us = self.irq_poll_limit_us(timeout);
+        let mut waited_us = 0;
+        let (irq, at) = loop {
+            let irq = self.execute_command_async(GetIrqStatus).await?.irq_mask;
+            if irq.intersects(TX_END) {
+                break (irq, self.timestamp());
+            }
+            waited_us += IRQ_POLL_US;
+            if waited_us >= limit_us {
+                return Err(self.abandon_poll_async(waited_us).await.into());
+            }
+        };
+        self.record_irqs(irq);
+        self.execute_command_async(ClearIrqStatus { irq_mask: irq })
+            .await?;
+        self.end_operation();
+
+        tx_outcome(irq, at)
+    }
+
+    /// Asynchronously returns the radio to STDBY_RC after the IRQ poll gave up, so that the PA
+    /// is not left keyed, and returns the error to report.
+    pub(super) async fn abandon_poll_async(&mut self, waited_us: u32) -> Error {
+        let standby = self
+            .execute_command_async(SetStandby {
+                config: StandbyConfig::Rc,
+            })
+            .await;
+        if standby.is_ok() {
+            self.end_operation();
+        }
+        Error::command(GetIrqStatus::id(), ErrorKind::IrqTimeout { waited_us })
+    }
+
+    /// Asynchronously transmits a fixed-length packet and waits for it to be sent.
+    ///
+    /// This is the async version of [`transmit_fixed`](Device::transmit_fixed).
+    pub async fn transmit_fixed_async<const N: usize>(
+        &mut self,
+        payload: &[u8; N],
+        timeout: Timeout,
+    ) -> Result<TxDone, TxError> {
+        let () = PayloadLength::<N>::CHECK;
+        check_fixed_length(self.state.config.as_ref(), N, true).map_err(|configured| {
+            TxError::PayloadLengthMismatch {
+                configured,
+                requested: N,
+            }
+        })?;
+
+        self.transmit_async(payload, timeout).await
+    }
+
+    /// Asynchronously transmits the same packet `count` times, `interval_ms` apart, writing
+    /// the payload once.
+    ///
+    /// This is the async version of [`transmit_repeated`](Device::transmit_repeated).
+    pub async fn transmit_repeated_async(
+        &mut self,
+        payload: &[u8],
+        count: u16,
+        interval_ms: u32,
+        timeout: Timeout,
+    ) -> Result<(), RepeatedTxError>
+    where
+        DELAY: embedded_hal_async::delay::DelayNs,
+    {
+        self.repeat_transmit_async(payload, count, timeout, async |device: &mut Self| {
+            device.delay.delay_ms(interval_ms).await
+        })
+        .await
+    }
+
+    /// Asynchronously transmits the same packet `count` times, `interval_ms` apart timed with
+    /// the given delay, writing the payload once.
+    ///
+    /// This is the async version of
+    /// [`transmit_repeated_with_delay`](Device::transmit_repeated_with_delay).
+    pub async fn transmit_repeated_with_delay_async(
+        &mut self,
+        payload: &[u8],
+        count: u16,
+        interval_ms: u32,
+        timeout: Timeout,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), RepeatedTxError> {
+        self.repeat_transmit_async(payload, count, timeout, async |_: &mut Self| {
+            delay.delay_ms(interval_ms).await
+        })
+        .await
+    }
+
+    /// Asynchronously writes the payload once and sends it `count` times, calling `wait`
+    /// between two transmissions.
+    async fn repeat_transmit_async(
+        &mut self,
+        payload: &[u8],
+        count: u16,
+        timeout: Timeout,
+        mut wait: impl AsyncFnMut(&mut Self),
+    ) -> Result<(), RepeatedTxError> {
+        if count == 0 {
+            return Ok(());
+        }
+        self.stage_repeated_async(payload)
+            .await
+            .map_err(|source| RepeatedTxError { sent: 0, source })?;
+
+        for sent in 0..count {
+            let result = if sent == 0 {
+                self.send_staged_async(timeout).await
+            } else {
+                wait(self).await;
+                self.restage_repeated_async(timeout).await
+            };
+            result.map_err(|source| RepeatedTxError { sent, source })?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously checks and writes the payload of a repeated transmission, clearing the
+    /// IRQs.
+    async fn stage_repeated_async(&mut self, payload: &[u8]) -> Result<(), TxError> {
+        self.check_configured(SetTx::id())?;
+        let len = self.tx_payload_length(payload.len())?;
+        self.begin_operation_async().await?;
+        self.set_payload_length_async(len).await?;
+        self.ensure_irq_routes_async(TX_END).await?;
+
+        let base = self
+            .state
+            .config
+            .map_or(0, |config| config.buffer_base.tx_base_addr);
+        self.execute_command_async(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .await?;
+        self.write_buffer_async(base, payload).await?;
+        Ok(())
+    }
+
+    /// Asynchronously clears the IRQs and sends the staged payload again.
+    async fn restage_repeated_async(&mut self, timeout: Timeout) -> Result<TxDone, TxError> {
+        self.begin_operation_async().await?;
+        self.execute_command_async(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .await?;
+        self.send_staged_async(timeout).await
+    }
+
+    /// Asynchronously receives a packet.
+    ///
+    /// This is the async version of [`receive`](Device::receive).
+    pub async fn receive_async(
+        &mut self,
+        bytes: &mut [u8],
+        mode: RxMode,
+    ) -> Result<RxPacket, RxError> {
+        self.receive_with_options_async(bytes, mode, RxOptions::default())
+            .await
+    }
+
+    /// Asynchronously receives a packet with options.
+    ///
+    /// This is the async version of [`receive_with_options`](Device::receive_with_options).
+    ///
+    /// # Cancellation
+    /// When the future is dropped before completion the radio may be left in RX. The next
+    /// async transmit or receive first calls [`resync_async`](Device::resync_async). An IQ
+    /// polarity set by [`RxOptions::iq`] is not restored.
+    pub async fn receive_with_options_async(
+        &mut self,
+        bytes: &mut [u8],
+        mode: RxMode,
+        options: RxOptions,
+    ) -> Result<RxPacket, RxError> {
+        self.check_configured(SetRx::id())?;
+        self.check_rx_buffer(bytes.len())?;
+        self.begin_operation_async().await?;
+        self.set_payload_length_async(u8::MAX).await?;
+        self.ensure_irq_routes_async(RX_END | IrqMask::CRC_ERROR)
+            .await?;
+
+        let baseline = self.override_iq_async(options.iq).await?;
+        let received = self.run_receive_async(bytes, mode, options).await;
+        let restored = self.restore_iq_async(baseline).await;
+        let packet = received?;
+        restored?;
+        Ok(packet)
+    }
+
+    /// Asynchronously arms RX and waits for a packet, once the radio is prepared.
+    async fn run_receive_async(
+        &mut self,
+        bytes: &mut [u8],
+        mode: RxMode,
+        options: RxOptions,
+    ) -> Result<RxPacket, RxError> {
+        self.execute_command_async(ClearIrqStatus {
+            irq_mask: IrqMask::all(),
+        })
+        .await?;
+        self.expect_irqs_cleared(RX_END | IrqMask::CRC_ERROR);
+        self.execute_command_async(SetRx { mode }).await?;
+
+        let limit_us = self.irq_poll_limit_us(mode.into());
+        let mut waited_us = 0;
+        let (irq, timestamp) = loop {
+            let irq = self.execute_command_async(GetIrqStatus).await?.irq_mask;
+            if !irq.intersects(RX_END) {
+                waited_us += IRQ_POLL_US;
+                if waited_us >= limit_us {
+                    return Err(self.abandon_poll_async(waited_us).await.into());
+                }
+                continue;
+            }
+            let Some(restart) = skipped_header_error(irq, options) else {
+                break (irq, self.timestamp());
+            };
+            self.record_irqs(irq);
+            self.execute_command_async(ClearIrqStatus { irq_mask: irq })
+                .await?;
+            self.state.link_stats.header_errors_skipped = self
+                .state
+                .link_stats
+                .header_errors_skipped
+                .saturating_add(1);
+            if restart {
+                self.expect_irqs_cleared(irq);
+                self.execute_command_async(SetRx { mode }).await?;
+            }
+        };
+        self.record_irqs(irq);
+        self.execute_command_async(ClearIrqStatus { irq_mask: irq })
+            .await?;
+        self.end_operation();
+        self.finish_receive_async(bytes, irq, timestamp, options)
+            .await
+    }
+
+    /// Asynchronously receives a fixed-length packet.
+    ///
+    /// This is the async version of [`receive_fixed`](Device::receive_fixed).
+    pub async fn receive_fixed_async<const N: usize>(
+        &mut self,
+        mode: RxMode,
+    ) -> Result<[u8; N], RxError> {
+        let () = PayloadLength::<N>::CHECK;
+        check_fixed_length(self.state.config.as_ref(), N, true).map_err(|configured| {
+            RxError::PayloadLengthMismatch {
+                configured,
+                requested: N,
+            }
+        })?;
+
+        let mut bytes = [0; N];
+        self.receive_async(&mut bytes, mode).await?;
+        Ok(bytes)
+    }
+
+    /// Asynchronously turns the cleared IRQs that ended an RX cycle into the received packet.
+    pub(super) async fn finish_receive_async(
+        &mut self,
+        bytes: &mut [u8],
+        irq: IrqMask,
+        timestamp: Option<u32>,
+        options: RxOptions,
+    ) -> Result<RxPacket, RxError> {
+        let implicit = self.implicit_lora_header();
+        if implicit && irq.contains(IrqMask::TIMEOUT) {
+            self.apply_implicit_header_timeout_workaround_async()
+                .await?;
+        }
+        let irq_crc_ok = rx_outcome(irq, options)?;
+
+        let status = self
+            .execute_command_async(GetPacketStatus)
+            .await?
+            .packet_status;
+        let crc_ok = irq_crc_ok && crc_ok(self.state.packet_type, &status, options)?;
+        // Without a header, GetRxBufferStatus only repeats the configured length
+        let len = if implicit {
+            self.read_received_payload_fast_async(bytes).await?
+        } else {
+            self.read_received_payload_async(bytes).await?
+        };
+        self.record_received(&status, crc_ok);
+        Ok(RxPacket {
+            len,
+            status,
+            crc_ok,
+            timestamp,
+        })
+    }
+
+    /// Asynchronously applies the IQ polarity of an operation before SetTx or SetRx.
+    ///
+    /// This is the async version of `override_iq`.
+    async fn override_iq_async(
+        &mut self,
+        iq: Option<IqMode>,
+    ) -> Result<Option<IqMode>, crate::Error> {
+        let Some(iq) = iq else {
+            return Ok(None);
+        };
+        let baseline = IqMode::from_inverted(self.cached_lora_packet()?.iq_inversion_enable);
+        self.write_iq_mode_async(iq).await?;
+        Ok((iq != baseline).then_some(baseline))
+    }
+
+    /// Asynchronously restores the configured IQ polarity after an operation.
+    async fn restore_iq_async(&mut self, baseline: Option<IqMode>) -> Result<(), crate::Error> {
+        match baseline {
+            Some(iq) => self.write_iq_mode_async(iq).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Asynchronously updates the payload length of a variable-length packet format if it
+    /// differs.
+    pub(super) async fn set_payload_length_async(&mut self, len: u8) -> Result<(), crate::Error> {
+        if let Some(params) = packet_params_for_length(self.state.config.as_ref(), len) {
+            self.execute_command_async(SetPacketParams { params })
+                .await?;
+            if let Some(config) = &mut self.state.config {
+                config.packet = params;
+            }
+        }
+        Ok(())
+    }
+}
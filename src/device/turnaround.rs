@@ -0,0 +1,167 @@
+//! Turnaround profiles
+//!
+//! The time from standby to TX or RX is mostly spent starting the crystal oscillator and
+//! locking the PLL. A [`TurnaroundProfile`] chooses which of them stay running between
+//! operations, trading idle current for latency.
+
+#[cfg(any(feature = "sync", feature = "async"))]
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+#[cfg(any(feature = "sync", feature = "async"))]
+use crate::{
+    error::{Error, StepExt},
+    SetRxTxFallbackMode, SetStandby,
+};
+use crate::{FallbackMode, StandbyConfig};
+
+/// Standby and fallback modes kept between radio operations
+///
+/// | Profile      | Idle standby | After TX/RX | Idle current | Turnaround        |
+/// |--------------|--------------|-------------|--------------|-------------------|
+/// | `LowPower`   | STDBY_RC     | STDBY_RC    | ~0.6 mA      | XOSC start + PLL  |
+/// | `Balanced`   | STDBY_RC     | STDBY_XOSC  | ~0.8 mA      | PLL after TX/RX   |
+/// | `LowLatency` | STDBY_XOSC   | FS          | ~2.1 mA      | None after TX/RX  |
+///
+/// The idle current is the one between back to back operations, from the datasheet with the
+/// DC-DC regulator. SetRegulatorMode is only accepted in STDBY_RC, see
+/// [`set_regulator_mode`](Device::set_regulator_mode) for how it is handled in STDBY_XOSC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TurnaroundProfile {
+    /// Oscillator and PLL stopped between operations, the reset configuration of the radio
+    #[default]
+    LowPower,
+    /// Oscillator kept running after TX and RX, for replies following a reception
+    Balanced,
+    /// Oscillator always running and PLL kept locked after TX and RX, for TDMA slots of a
+    /// few milliseconds
+    LowLatency,
+}
+
+impl TurnaroundProfile {
+    /// Returns the standby mode the radio idles in.
+    pub const fn standby(self) -> StandbyConfig {
+        match self {
+            Self::LowPower | Self::Balanced => StandbyConfig::Rc,
+            Self::LowLatency => StandbyConfig::Xosc,
+        }
+    }
+
+    /// Returns the mode the radio falls back to after TX and RX.
+    pub const fn fallback_mode(self) -> FallbackMode {
+        match self {
+            Self::LowPower => FallbackMode::StdbyRc,
+            Self::Balanced => FallbackMode::StdbyXosc,
+            Self::LowLatency => FallbackMode::Fs,
+        }
+    }
+}
+
+#[cfg(any(feature = "sync", feature = "async"))]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY> {
+    /// Returns the turnaround profile set with
+    /// [`set_turnaround_profile`](Device::set_turnaround_profile).
+    pub fn turnaround_profile(&self) -> TurnaroundProfile {
+        self.settings.turnaround
+    }
+
+    /// Records a turnaround profile applied to the radio.
+    fn cache_turnaround_profile(&mut self, profile: TurnaroundProfile) {
+        self.settings.turnaround = profile;
+        if let Some(config) = &mut self.state.config {
+            config.fallback_mode = profile.fallback_mode();
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Applies a turnaround profile.
+    ///
+    /// The radio is placed in the standby mode of the profile and its fallback mode is set.
+    /// The fallback mode is recorded in the cached configuration, if any, and replaced by the
+    /// one of the next [`apply_config`](Device::apply_config). The transmit and receive
+    /// helpers end in the fallback mode, and the RX restart of
+    /// [`poll_rx`](Device::poll_rx) goes through the standby mode of the profile.
+    ///
+    /// # Arguments
+    /// * `profile` - The turnaround profile
+    ///
+    /// # Errors
+    /// Errors carry the index of the failing step:
+    ///
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | SetStandby                        |
+    /// | 1    | SetRxTxFallbackMode               |
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, TurnaroundProfile};
+    ///
+    /// fn configure(device: &mut Device<impl RadioBus>) {
+    ///     device
+    ///         .set_turnaround_profile(TurnaroundProfile::LowLatency)
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn set_turnaround_profile(&mut self, profile: TurnaroundProfile) -> Result<(), Error> {
+        self.execute_command(SetStandby {
+            config: profile.standby(),
+        })
+        .at_step(0)?;
+        self.execute_command(SetRxTxFallbackMode {
+            mode: profile.fallback_mode(),
+        })
+        .at_step(1)?;
+        self.cache_turnaround_profile(profile);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously applies a turnaround profile.
+    ///
+    /// This is the async version of [`set_turnaround_profile`](Device::set_turnaround_profile).
+    pub async fn set_turnaround_profile_async(
+        &mut self,
+        profile: TurnaroundProfile,
+    ) -> Result<(), Error> {
+        self.execute_command_async(SetStandby {
+            config: profile.standby(),
+        })
+        .await
+        .at_step(0)?;
+        self.execute_command_async(SetRxTxFallbackMode {
+            mode: profile.fallback_mode(),
+        })
+        .await
+        .at_step(1)?;
+        self.cache_turnaround_profile(profile);
+        Ok(())
+    }
+}
@@ -0,0 +1,389 @@
+//! Configuration read-back
+//!
+//! Compares the configuration cached by the driver with what the radio reports, for the
+//! parts of the configuration that can be read back.
+
+use regiface::{Command, FromByteArray, Register};
+
+use super::Device;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+use crate::{
+    config::{ConfigChanges, RadioConfig},
+    errata::{self, Workaround},
+    error::Error,
+    registers::MAX_REGISTER_LEN,
+    DeviceSelect, GetPacketType, IqPolaritySetup, LoraSyncWord, OcpConfiguration, RetentionList,
+    RxGain, SyncWord, TxClampConfig, TxModulation,
+};
+
+/// Largest number of items checked by [`Device::validate_current_config`]
+const MAX_CHECKS: usize = 9;
+
+/// Parts of the configuration that cannot be read back from the radio
+///
+/// SetPaConfig is only checked through the OCP threshold it sets.
+const UNVERIFIABLE: ConfigChanges = ConfigChanges::REGULATOR_MODE
+    .union(ConfigChanges::IMAGE_CALIBRATION)
+    .union(ConfigChanges::FREQUENCY)
+    .union(ConfigChanges::PA_CONFIG)
+    .union(ConfigChanges::TX_PARAMS)
+    .union(ConfigChanges::MODULATION)
+    .union(ConfigChanges::PACKET)
+    .union(ConfigChanges::BUFFER_BASE)
+    .union(ConfigChanges::FALLBACK_MODE)
+    .union(ConfigChanges::IRQ);
+
+/// Where a value compared by [`Device::validate_current_config`] was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReadBackSource {
+    /// The response of a command, by opcode
+    Command(u8),
+    /// A register, by address
+    Register(u16),
+}
+
+/// A part of the configuration the radio reports differently than cached
+///
+/// Values are the compared bits of the response or register, big-endian. For the retention
+/// list, `expected` is the address of the register that must be retained and `actual` is that
+/// address when it is listed, 0 otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigMismatch {
+    /// Part of the configuration that differs
+    pub item: ConfigChanges,
+    /// Where the value was read from
+    pub source: ReadBackSource,
+    /// Value the cached configuration implies
+    pub expected: u64,
+    /// Value read back
+    pub actual: u64,
+}
+
+/// A fixed payload length differing from the one this Device last transmitted with
+///
+/// Fixed-length packets carry no length, a receiver expecting another length than the
+/// transmitter captures shifted bytes and reports CRC errors that look like random
+/// corruption. In loopback tests, where one Device plays both sides, this catches the mismatch
+/// before any packet is lost.
+///
+/// # Example
+/// ```no_run
+/// use sx1262::{bus::RadioBus, Device};
+///
+/// # #[cfg(feature = "sync")]
+/// fn check(device: &mut Device<impl RadioBus>) {
+///     let report = device.validate_current_config().unwrap();
+///     if let Some(warning) = report.fixed_length_warning {
+///         // Received packets are cut to `warning.configured` bytes
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FixedLengthWarning {
+    /// Fixed payload length of the cached configuration
+    pub configured: u8,
+    /// Fixed payload length of the last SetTx sent through the Device
+    pub transmitted: u8,
+}
+
+/// Outcome of [`Device::validate_current_config`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigReport {
+    mismatches: [Option<ConfigMismatch>; MAX_CHECKS],
+    /// Parts of the configuration that were read back
+    pub checked: ConfigChanges,
+    /// Parts of the configuration that cannot be read back, all of them without a cached
+    /// configuration
+    pub unverifiable: ConfigChanges,
+    /// The configured fixed payload length differs from the last transmitted one, not a
+    /// mismatch with the radio
+    pub fixed_length_warning: Option<FixedLengthWarning>,
+}
+
+impl ConfigReport {
+    /// Returns the parts of the configuration read back differently than cached.
+    pub fn mismatches(&self) -> impl Iterator<Item = &ConfigMismatch> {
+        self.mismatches.iter().flatten()
+    }
+
+    /// Returns true when every part read back matches the cached configuration.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches().next().is_none()
+    }
+
+    fn new(config: Option<&RadioConfig>, last_fixed_tx_length: Option<u8>) -> Self {
+        let configured = config.and_then(RadioConfig::fixed_payload_length);
+        Self {
+            mismatches: [None; MAX_CHECKS],
+            checked: ConfigChanges::empty(),
+            unverifiable: match config {
+                Some(_) => UNVERIFIABLE,
+                None => ConfigChanges::all(),
+            },
+            fixed_length_warning: match (configured, last_fixed_tx_length) {
+                (Some(configured), Some(transmitted)) if configured != transmitted => {
+                    Some(FixedLengthWarning {
+                        configured,
+                        transmitted,
+                    })
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn record(&mut self, check: &Check, actual: u64) {
+        self.checked |= check.item;
+        if actual == check.expected() {
+            return;
+        }
+        if let Some(slot) = self.mismatches.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(ConfigMismatch {
+                item: check.item,
+                source: check.source,
+                expected: check.expected(),
+                actual,
+            });
+        }
+    }
+}
+
+/// How a read-back value is compared
+#[derive(Debug, Clone, Copy)]
+enum Expected {
+    /// The bits of `mask` equal `value`
+    Bits { mask: u64, value: u64 },
+    /// The retention list holds the register address
+    Retained(u16),
+}
+
+/// A part of the configuration to read back
+#[derive(Debug, Clone, Copy)]
+struct Check {
+    item: ConfigChanges,
+    source: ReadBackSource,
+    len: usize,
+    expected: Expected,
+}
+
+impl Check {
+    /// Compares a whole register.
+    fn register<R: Register<IdType = u16>>(item: ConfigChanges, len: usize, value: u64) -> Self {
+        Self::register_bits::<R>(item, len, u64::MAX >> (64 - 8 * len), value)
+    }
+
+    /// Compares the bits of `mask` of a register.
+    fn register_bits<R: Register<IdType = u16>>(
+        item: ConfigChanges,
+        len: usize,
+        mask: u64,
+        value: u64,
+    ) -> Self {
+        Self {
+            item,
+            source: ReadBackSource::Register(R::id()),
+            len,
+            expected: Expected::Bits { mask, value },
+        }
+    }
+
+    /// Checks the register written by a workaround, applied when `required` or else undone.
+    fn workaround(workaround: Workaround, required: bool) -> Self {
+        let item = ConfigChanges::WORKAROUNDS;
+        // The reversible workarounds clear a bit set at reset
+        let bit_2 = if required { 0x00 } else { 0x04 };
+        match workaround {
+            Workaround::LoRa500kHzModulation => {
+                Self::register_bits::<TxModulation>(item, 1, 0x04, bit_2)
+            }
+            Workaround::TxClamp => Self::register_bits::<TxClampConfig>(item, 1, 0x1E, 0x1E),
+            Workaround::InvertedIq => Self::register_bits::<IqPolaritySetup>(item, 1, 0x04, bit_2),
+            Workaround::RxGainRetention => Self {
+                item,
+                source: ReadBackSource::Register(RetentionList::id()),
+                len: MAX_REGISTER_LEN,
+                expected: Expected::Retained(RxGain::id()),
+            },
+        }
+    }
+
+    fn expected(&self) -> u64 {
+        match self.expected {
+            Expected::Bits { value, .. } => value,
+            Expected::Retained(address) => address.into(),
+        }
+    }
+
+    /// Returns the compared value of the bytes read back.
+    fn actual(&self, bytes: &[u8]) -> u64 {
+        match self.expected {
+            Expected::Bits { mask, .. } => {
+                bytes
+                    .iter()
+                    .fold(0, |value, &byte| (value << 8) | u64::from(byte))
+                    & mask
+            }
+            Expected::Retained(address) => {
+                let mut raw = [0; MAX_REGISTER_LEN];
+                raw.copy_from_slice(bytes);
+                let Ok(list) = RetentionList::from_bytes(raw);
+                if list.get_entries().contains(&address) {
+                    address.into()
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+/// Returns the parts of a configuration that can be read back.
+fn checks(config: &RadioConfig) -> impl Iterator<Item = Check> + '_ {
+    let packet_type = Check {
+        item: ConfigChanges::PACKET_TYPE,
+        source: ReadBackSource::Command(GetPacketType::id()),
+        len: 1,
+        expected: Expected::Bits {
+            mask: 0xFF,
+            value: config.packet_type() as u64,
+        },
+    };
+    let lora_sync_word = config.lora_sync_word.map(|sync_word| {
+        Check::register::<LoraSyncWord>(ConfigChanges::LORA_SYNC_WORD, 2, sync_word.value.into())
+    });
+    let gfsk_sync_word = config.gfsk_sync_word.map(|sync_word| {
+        Check::register::<SyncWord>(
+            ConfigChanges::GFSK_SYNC_WORD,
+            8,
+            u64::from_be_bytes(sync_word.value),
+        )
+    });
+    let rx_gain =
+        Check::register::<RxGain>(ConfigChanges::RX_GAIN, 1, config.rx_gain.to_byte().into());
+    // SetPaConfig resets the OCP threshold to 60 mA on the SX1261, 140 mA otherwise
+    let ocp = Check::register::<OcpConfiguration>(
+        ConfigChanges::PA_CONFIG,
+        1,
+        match config.pa_config.device_sel {
+            DeviceSelect::Sx1261 => 0x18,
+            _ => 0x38,
+        },
+    );
+
+    [Some(packet_type), lora_sync_word, gfsk_sync_word]
+        .into_iter()
+        .flatten()
+        .chain([rx_gain, ocp])
+        .chain(
+            errata::workaround_states(config)
+                .map(|(workaround, required)| Check::workaround(workaround, required)),
+        )
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Reads back every readable part of the configuration and compares it with the one
+    /// cached by [`apply_config`](Device::apply_config).
+    ///
+    /// The packet type (GetPacketType), the sync words, the RX gain, the OCP threshold set by
+    /// SetPaConfig and the registers written by the errata workarounds are read
+    /// back. The remaining parts have no read-back path and are listed as
+    /// [`unverifiable`](ConfigReport::unverifiable). Nothing is written to the radio.
+    ///
+    /// A fixed payload length differing from the one of the last transmit is reported as a
+    /// [`fixed_length_warning`](ConfigReport::fixed_length_warning).
+    ///
+    /// # Errors
+    /// * `ErrorKind::Bus` - Bus communication failed
+    /// * `ErrorKind::Deserialization` - GetPacketType returned an unknown packet type
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device};
+    ///
+    /// fn check(device: &mut Device<impl RadioBus>) {
+    ///     let report = device.validate_current_config().unwrap();
+    ///     for mismatch in report.mismatches() {
+    ///         // Re-apply the configuration, or report the other writer
+    ///     }
+    /// }
+    /// ```
+    pub fn validate_current_config(&mut self) -> Result<ConfigReport, Error> {
+        let mut report =
+            ConfigReport::new(self.state.config.as_ref(), self.state.last_fixed_tx_length);
+        let Some(config) = self.state.config else {
+            return Ok(report);
+        };
+
+        let mut raw = [0; MAX_REGISTER_LEN];
+        for check in checks(&config) {
+            let bytes = &mut raw[..check.len];
+            match check.source {
+                ReadBackSource::Command(_) => {
+                    bytes[0] = self.execute_command(GetPacketType)?.packet_type as u8;
+                }
+                ReadBackSource::Register(address) => self.read_register_raw(address, bytes)?,
+            }
+            report.record(&check, check.actual(bytes));
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously reads back every readable part of the configuration and compares it
+    /// with the cached one.
+    ///
+    /// This is the async version of
+    /// [`validate_current_config`](Device::validate_current_config).
+    pub async fn validate_current_config_async(&mut self) -> Result<ConfigReport, Error> {
+        let mut report =
+            ConfigReport::new(self.state.config.as_ref(), self.state.last_fixed_tx_length);
+        let Some(config) = self.state.config else {
+            return Ok(report);
+        };
+
+        let mut raw = [0; MAX_REGISTER_LEN];
+        for check in checks(&config) {
+            let bytes = &mut raw[..check.len];
+            match check.source {
+                ReadBackSource::Command(_) => {
+                    bytes[0] = self.execute_command_async(GetPacketType).await?.packet_type as u8;
+                }
+                ReadBackSource::Register(address) => {
+                    self.read_register_raw_async(address, bytes).await?
+                }
+            }
+            report.record(&check, check.actual(bytes));
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,317 @@
+//! Continuous receive watchdog
+//!
+//! Continuous RX on the SX126x has been reported to wedge after long periods, with no RX IRQ
+//! ever raised again until RX is re-entered. [`RxWatchdog`] tracks the last RX activity and
+//! [`Device::poll_rx`] re-arms RX once the radio has been quiet for too long.
+
+#[cfg(any(feature = "sync", feature = "async"))]
+use super::Device;
+use crate::IrqMask;
+#[cfg(feature = "async")]
+use crate::{
+    bus::AsyncRadioBus,
+    frontend::AsyncRfFrontend,
+    pins::{AsyncBusyWait, AsyncOwnedDelay},
+};
+#[cfg(feature = "sync")]
+use crate::{
+    bus::RadioBus,
+    frontend::RfFrontend,
+    pins::{BusyWait, OwnedDelay},
+};
+#[cfg(any(feature = "sync", feature = "async"))]
+use crate::{
+    device::{transceive::skipped_header_error, HeaderErrorPolicy, IrqEvent, RxOptions},
+    error::{Error, StepExt},
+    ClearIrqStatus, GetIrqStatus, RxMode, SetRx, SetStandby,
+};
+
+/// Options of [`Device::poll_rx`], re-arming RX after a header error
+#[cfg(any(feature = "sync", feature = "async"))]
+const POLL_RX_OPTIONS: RxOptions = RxOptions {
+    deliver_crc_failures: false,
+    on_header_error: HeaderErrorPolicy::RestartRx,
+    iq: None,
+};
+
+/// IRQs showing that the receiver is alive
+const RX_ACTIVITY: IrqMask = IrqMask::PREAMBLE_DETECTED
+    .union(IrqMask::SYNC_WORD_VALID)
+    .union(IrqMask::HEADER_ERROR)
+    .union(IrqMask::RX_DONE)
+    .union(IrqMask::CRC_ERROR);
+
+/// Watchdog re-arming a continuous receive that has gone quiet
+///
+/// Times are read from a monotonic time source of the application, in any unit as long as the
+/// quiet limit uses the same one. They may wrap around.
+///
+/// # Example
+/// ```
+/// use sx1262::{IrqMask, RxWatchdog};
+///
+/// // Re-arm after 60 s without RX activity, times in milliseconds
+/// let mut watchdog = RxWatchdog::new(60_000);
+///
+/// assert!(!watchdog.poll(0, IrqMask::empty()));
+/// assert!(!watchdog.poll(50_000, IrqMask::PREAMBLE_DETECTED));
+/// // The radio wedges, 60 s after the last preamble RX must be re-entered
+/// assert!(!watchdog.poll(110_000, IrqMask::empty()));
+/// assert!(watchdog.poll(110_001, IrqMask::empty()));
+/// assert_eq!(watchdog.recoveries(), 1);
+/// // The quiet period restarts from the recovery
+/// assert!(!watchdog.poll(170_001, IrqMask::empty()));
+/// assert!(!watchdog.poll(170_002, IrqMask::RX_DONE));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxWatchdog {
+    quiet_limit: u32,
+    last_activity: Option<u32>,
+    recoveries: u32,
+}
+
+impl RxWatchdog {
+    /// Creates a watchdog.
+    ///
+    /// # Arguments
+    /// * `quiet_limit` - Longest time without RX activity before RX is re-armed
+    pub const fn new(quiet_limit: u32) -> Self {
+        Self {
+            quiet_limit,
+            last_activity: None,
+            recoveries: 0,
+        }
+    }
+
+    /// Records the IRQs read at a point in time.
+    ///
+    /// The first call, and any call with an RX IRQ (preamble, sync word or header, RX done, CRC
+    /// or header error), restarts the quiet period.
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    /// * `irq` - IRQs read since the previous call
+    ///
+    /// # Returns
+    /// `true` when the quiet limit was exceeded and RX must be re-armed, which is counted as a
+    /// recovery and restarts the quiet period
+    pub fn poll(&mut self, now: u32, irq: IrqMask) -> bool {
+        match self.last_activity {
+            Some(last) if !irq.intersects(RX_ACTIVITY) => {
+                if now.wrapping_sub(last) <= self.quiet_limit {
+                    return false;
+                }
+                self.recoveries += 1;
+                self.last_activity = Some(now);
+                true
+            }
+            _ => {
+                self.last_activity = Some(now);
+                false
+            }
+        }
+    }
+
+    /// Restarts the quiet period, e.g. after RX was entered by the application.
+    pub fn restart(&mut self, now: u32) {
+        self.last_activity = Some(now);
+    }
+
+    /// Returns the number of times RX was re-armed.
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+
+    /// Returns the longest time without RX activity before RX is re-armed.
+    pub fn quiet_limit(&self) -> u32 {
+        self.quiet_limit
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: RadioBus,
+    FE: RfFrontend,
+    BUSY: BusyWait,
+    DELAY: OwnedDelay,
+{
+    /// Reads and clears the pending IRQs of a continuous receive, re-arming RX when the
+    /// watchdog expires.
+    ///
+    /// Call this periodically, or from the DIO1 interrupt and periodically, while the radio is
+    /// in continuous RX. When the watchdog reports that the radio has been quiet past its limit,
+    /// SetStandby, in the standby mode of the
+    /// [`turnaround_profile`](Device::turnaround_profile), and SetRx with
+    /// [`RxMode::Continuous`] are sent and the recovery is counted in
+    /// [`RxWatchdog::recoveries`]. A header error is cleared and RX re-armed, see
+    /// [`poll_rx_with_options`](Device::poll_rx_with_options) to report it instead.
+    ///
+    /// # Arguments
+    /// * `watchdog` - The watchdog of this receive
+    /// * `now` - Current time of the watchdog's time source
+    ///
+    /// # Returns
+    /// The IRQs that were pending, to be handled by the application, e.g. RX_DONE, and the
+    /// time they were observed
+    ///
+    /// # Errors
+    /// | Step | Operation                         |
+    /// |------|-----------------------------------|
+    /// | 0    | GetIrqStatus, ClearIrqStatus      |
+    /// | 1    | SetStandby                        |
+    /// | 2    | SetRx                             |
+    pub fn poll_rx(&mut self, watchdog: &mut RxWatchdog, now: u32) -> Result<IrqEvent, Error> {
+        self.poll_rx_with_options(watchdog, now, POLL_RX_OPTIONS)
+    }
+
+    /// Reads and clears the pending IRQs of a continuous receive with options.
+    ///
+    /// This is [`poll_rx`](Device::poll_rx) with the handling of header errors selected by
+    /// [`RxOptions::on_header_error`], which [`poll_rx`](Device::poll_rx) sets to
+    /// [`HeaderErrorPolicy::RestartRx`]. A skipped header error is removed from the returned
+    /// IRQs and counted in
+    /// [`LinkStats::header_errors_skipped`](crate::LinkStats::header_errors_skipped). The
+    /// other options are not used.
+    ///
+    /// # Arguments
+    /// * `watchdog` - The watchdog of this receive
+    /// * `now` - Current time of the watchdog's time source
+    /// * `options` - Receive options
+    ///
+    /// # Errors
+    /// As [`poll_rx`](Device::poll_rx), with the SetRx re-arming RX after a header error at
+    /// step 2
+    ///
+    /// # Example
+    /// Handling header errors in the application:
+    /// ```no_run
+    /// use sx1262::{bus::RadioBus, Device, IrqMask, RxOptions, RxWatchdog};
+    ///
+    /// fn poll(device: &mut Device<impl RadioBus>, watchdog: &mut RxWatchdog, now: u32) {
+    ///     let event = device
+    ///         .poll_rx_with_options(watchdog, now, RxOptions::default())
+    ///         .unwrap();
+    ///     if event.irq_mask.contains(IrqMask::HEADER_ERROR) {
+    ///         // Count it, RX is still running
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_rx_with_options(
+        &mut self,
+        watchdog: &mut RxWatchdog,
+        now: u32,
+        options: RxOptions,
+    ) -> Result<IrqEvent, Error> {
+        let mut irq_mask = self.execute_command(GetIrqStatus).at_step(0)?.irq_mask;
+        let mut at = None;
+        if !irq_mask.is_empty() {
+            at = self.timestamp();
+            self.record_irqs(irq_mask);
+            self.execute_command(ClearIrqStatus { irq_mask })
+                .at_step(0)?;
+        }
+
+        let skipped = skipped_header_error(irq_mask, options);
+        if watchdog.poll(now, irq_mask) {
+            self.execute_command(SetStandby {
+                config: self.settings.turnaround.standby(),
+            })
+            .at_step(1)?;
+            self.execute_command(SetRx {
+                mode: RxMode::Continuous,
+            })
+            .at_step(2)?;
+        } else if skipped == Some(true) {
+            self.execute_command(SetRx {
+                mode: RxMode::Continuous,
+            })
+            .at_step(2)?;
+        }
+        if skipped.is_some() {
+            irq_mask.remove(IrqMask::HEADER_ERROR);
+            self.state.link_stats.header_errors_skipped = self
+                .state
+                .link_stats
+                .header_errors_skipped
+                .saturating_add(1);
+        }
+        Ok(IrqEvent { irq_mask, at })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<BUS, FE, BUSY, RESET, DIO1, DELAY> Device<BUS, FE, BUSY, RESET, DIO1, DELAY>
+where
+    BUS: AsyncRadioBus,
+    FE: AsyncRfFrontend,
+    BUSY: AsyncBusyWait,
+    DELAY: AsyncOwnedDelay,
+{
+    /// Asynchronously reads and clears the pending IRQs of a continuous receive, re-arming RX
+    /// when the watchdog expires.
+    ///
+    /// This is the async version of [`poll_rx`](Device::poll_rx).
+    pub async fn poll_rx_async(
+        &mut self,
+        watchdog: &mut RxWatchdog,
+        now: u32,
+    ) -> Result<IrqEvent, Error> {
+        self.poll_rx_with_options_async(watchdog, now, POLL_RX_OPTIONS)
+            .await
+    }
+
+    /// Asynchronously reads and clears the pending IRQs of a continuous receive with options.
+    ///
+    /// This is the async version of [`poll_rx_with_options`](Device::poll_rx_with_options).
+    pub async fn poll_rx_with_options_async(
+        &mut self,
+        watchdog: &mut RxWatchdog,
+        now: u32,
+        options: RxOptions,
+    ) -> Result<IrqEvent, Error> {
+        let mut irq_mask = self
+            .execute_command_async(GetIrqStatus)
+            .await
+            .at_step(0)?
+            .irq_mask;
+        let mut at = None;
+        if !irq_mask.is_empty() {
+            at = self.timestamp();
+            self.record_irqs(irq_mask);
+            self.execute_command_async(ClearIrqStatus { irq_mask })
+                .await
+                .at_step(0)?;
+        }
+
+        let skipped = skipped_header_error(irq_mask, options);
+        if watchdog.poll(now, irq_mask) {
+            self.execute_command_async(SetStandby {
+                config: self.settings.turnaround.standby(),
+            })
+            .await
+            .at_step(1)?;
+            self.execute_command_async(SetRx {
+                mode: RxMode::Continuous,
+            })
+            .await
+            .at_step(2)?;
+        } else if skipped == Some(true) {
+            self.execute_command_async(SetRx {
+                mode: RxMode::Continuous,
+            })
+            .await
+            .at_step(2)?;
+        }
+        if skipped.is_some() {
+            irq_mask.remove(IrqMask::HEADER_ERROR);
+            self.state.link_stats.header_errors_skipped = self
+                .state
+                .link_stats
+                .header_errors_skipped
+                .saturating_add(1);
+        }
+        Ok(IrqEvent { irq_mask, at })
+    }
+}
@@ -0,0 +1,126 @@
+//! Silicon errata workarounds
+//!
+//! Chapter 15 of the datasheet documents a number of known limitations of the SX1261/2 and the
+//! register tweaks that work around them. Which ones are needed depends on the configuration in
+//! use, so this module keeps a table mapping configuration conditions to workarounds.
+//! [`Device::apply_errata_workarounds`](crate::Device::apply_errata_workarounds) walks that table
+//! and applies exactly the entries that match.
+//!
+//! A workaround is only listed as required when the configuration moves the affected register
+//! away from its reset value. The LoRa 500 kHz and inverted IQ workarounds are
+//! [reversible](Workaround::is_reversible): a configuration no longer requiring them writes
+//! their register back to the reset value, as the radio keeps it across configurations. Each
+//! workaround can also be applied on its own through the corresponding
+//! `Device::apply_*_workaround` method.
+//!
+//! The implicit header timeout workaround (datasheet 15.3) is not part of the table: it must be
+//! applied after every RX timeout rather than at configuration time. The receive helpers do so
+//! with an [implicit header](crate::LoRaHeaderMode::Implicit), see
+//! [`Device::apply_implicit_header_timeout_workaround`](crate::Device::apply_implicit_header_timeout_workaround).
+
+use crate::{
+    config::{ConfigChanges, RadioConfig},
+    DeviceSelect, LoRaBandwidth, ModulationParams, PacketParams, RxGain,
+};
+
+/// A configuration-dependent silicon workaround
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Workaround {
+    /// Modulation quality with LoRa 500 kHz bandwidth (datasheet 15.1)
+    ///
+    /// Clears bit 2 of the [`TxModulation`](crate::TxModulation) register.
+    LoRa500kHzModulation,
+    /// Better resistance of the high-power PA to antenna mismatch (datasheet 15.2)
+    ///
+    /// Sets bits 4:1 of the [`TxClampConfig`](crate::TxClampConfig) register.
+    TxClamp,
+    /// Optimizing the inverted IQ operation (datasheet 15.4)
+    ///
+    /// Clears bit 2 of the [`IqPolaritySetup`](crate::IqPolaritySetup) register.
+    InvertedIq,
+    /// Retention of the boosted RX gain across warm-start sleep (datasheet 9.6)
+    ///
+    /// Adds the [`RxGain`] register to the [`RetentionList`](crate::RetentionList).
+    RxGainRetention,
+}
+
+impl Workaround {
+    /// Returns the parts of a configuration deciding whether the workaround applies.
+    pub(crate) fn inputs(self) -> ConfigChanges {
+        match self {
+            Self::LoRa500kHzModulation => ConfigChanges::MODULATION,
+            Self::TxClamp => ConfigChanges::PA_CONFIG,
+            Self::InvertedIq => ConfigChanges::PACKET,
+            Self::RxGainRetention => ConfigChanges::RX_GAIN,
+        }
+    }
+
+    /// Returns whether the workaround is undone, restoring the reset value of its register,
+    /// by a configuration that no longer requires it.
+    pub fn is_reversible(self) -> bool {
+        matches!(self, Self::LoRa500kHzModulation | Self::InvertedIq)
+    }
+}
+
+/// An entry in the errata table
+#[derive(Debug, Clone, Copy)]
+pub struct Erratum {
+    /// The workaround to apply
+    pub workaround: Workaround,
+    /// Returns true when the configuration requires the workaround
+    pub applies: fn(&RadioConfig) -> bool,
+}
+
+/// Table mapping configuration conditions to the workarounds they require
+pub const ERRATA: &[Erratum] = &[
+    Erratum {
+        workaround: Workaround::LoRa500kHzModulation,
+        applies: |config| {
+            matches!(
+                config.modulation,
+                ModulationParams::LoRa(params) if params.bandwidth == LoRaBandwidth::Bw500
+            )
+        },
+    },
+    Erratum {
+        workaround: Workaround::TxClamp,
+        applies: |config| {
+            matches!(
+                config.pa_config.device_sel,
+                DeviceSelect::Sx1262 | DeviceSelect::Sx1268 | DeviceSelect::Llcc68
+            )
+        },
+    },
+    Erratum {
+        workaround: Workaround::InvertedIq,
+        applies: |config| {
+            matches!(
+                config.packet,
+                PacketParams::LoRa(params) if params.iq_inversion_enable
+            )
+        },
+    },
+    Erratum {
+        workaround: Workaround::RxGainRetention,
+        applies: |config| config.rx_gain == RxGain::Boosted,
+    },
+];
+
+/// Returns the workarounds required by the given configuration, in table order.
+pub fn required_workarounds(config: &RadioConfig) -> impl Iterator<Item = Workaround> + '_ {
+    ERRATA
+        .iter()
+        .filter(move |erratum| (erratum.applies)(config))
+        .map(|erratum| erratum.workaround)
+}
+
+/// Returns the workarounds to write for the given configuration, in table order, with whether
+/// each is required.
+///
+/// Reversible workarounds are always listed, the others only when required.
+pub fn workaround_states(config: &RadioConfig) -> impl Iterator<Item = (Workaround, bool)> + '_ {
+    ERRATA
+        .iter()
+        .map(move |erratum| (erratum.workaround, (erratum.applies)(config)))
+        .filter(|&(workaround, required)| required || workaround.is_reversible())
+}
@@ -0,0 +1,642 @@
+//! Driver error type
+//!
+//! Every [`Device`](crate::Device) method returns an [`Error`] carrying the command opcode,
+//! register address or buffer offset the failure happened on, and the step index when the
+//! failure happened inside a composite helper such as
+//! [`apply_config`](crate::Device::apply_config). The underlying cause is an [`ErrorKind`].
+//!
+//! A radio that keeps BUSY high past the busy timeout is reported with
+//! [`ErrorKind::BusyStuck`], distinct from the TX and RX timeouts, see
+//! [`Device::recover`](crate::Device::recover). Likewise, a transmit or receive whose IRQ
+//! never arrives is given up with [`ErrorKind::IrqTimeout`].
+//!
+//! The transmit and receive helpers return [`TxError`] and [`RxError`], which add the radio
+//! outcomes that are not driver failures, such as a timeout or a CRC error.
+
+use core::fmt;
+
+use crate::{
+    config::{ConfigError, ConfigItems},
+    pins::{BusyError, PinError},
+    CommandStatus, OperatingMode, PacketType,
+};
+
+/// The underlying cause of an [`Error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorKind {
+    /// Communication over the radio bus failed
+    Bus,
+    /// A value could not be serialized
+    Serialization,
+    /// A response or register value could not be parsed
+    Deserialization,
+    /// The RF front-end failed to switch
+    Frontend,
+    /// The caller's buffer cannot hold the data
+    BufferTooSmall {
+        /// Number of bytes required
+        required: usize,
+    },
+    /// No received packet is waiting to be read: RX was started again or the packet was
+    /// already read, see [`Device::mark_packet_pending`](crate::Device::mark_packet_pending)
+    NoPacketPending,
+    /// A buffer transfer is longer than 255 bytes, it would wrap around the data buffer onto
+    /// its own first bytes
+    BufferOverrun {
+        /// Number of bytes requested
+        length: usize,
+    },
+    /// The operation needs a configuration applied with
+    /// [`apply_config`](crate::Device::apply_config)
+    NotConfigured {
+        /// Items not applied, all of them when no configuration is cached
+        missing: ConfigItems,
+    },
+    /// BUSY stayed high for the whole busy timeout, the radio may be latched up
+    BusyStuck {
+        /// Time waited in microseconds
+        waited_us: u32,
+    },
+    /// A control line (BUSY, NRESET) could not be read or driven
+    Pin,
+    /// Recovery needs an NRESET line, which is not connected
+    NoResetPin,
+    /// The parameters are for a different packet type than the one set on the radio, see
+    /// [`Device::set_packet_type_check`](crate::Device::set_packet_type_check)
+    PacketTypeMismatch {
+        /// Packet type set on the radio
+        configured: PacketType,
+        /// Packet type of the parameters
+        provided: PacketType,
+    },
+    /// The radio reported a failed command in the status read after it, see
+    /// [`Device::set_paranoid_checks`](crate::Device::set_paranoid_checks)
+    CommandFailed {
+        /// Command status reported by the radio
+        status: CommandStatus,
+    },
+    /// The RF frequency is outside the range of the device
+    FrequencyOutOfRange {
+        /// The requested frequency in Hz
+        frequency: u32,
+    },
+    /// The radio did not return a valid status within the allowed time
+    Unresponsive {
+        /// Time waited in microseconds
+        waited_us: u32,
+    },
+    /// Neither the end of a transmit or receive nor the radio's timeout was reported within
+    /// the timeout plus the busy timeout, or the busy timeout alone without a radio timeout
+    IrqTimeout {
+        /// Time waited in microseconds, counting each read of the IRQ status as 1 µs
+        waited_us: u32,
+    },
+    /// A setting read back from the radio differs from the one just written
+    ConfigVerificationFailed {
+        /// Packet type written
+        expected: PacketType,
+        /// Packet type read back
+        actual: PacketType,
+    },
+    /// A register read back differs from the value just written
+    VerificationFailed,
+    /// The command is only accepted in another operating mode
+    WrongMode {
+        /// Operating mode the radio was in, `None` when the status was not valid
+        found: Option<OperatingMode>,
+    },
+    /// The radio reported the status of a freshly reset chip while in another mode, it
+    /// probably reset behind the driver's back, see
+    /// [`Status::looks_like_unexpected_reset`](crate::Status::looks_like_unexpected_reset)
+    PossibleChipReset {
+        /// Operating mode the radio was expected in
+        expected: OperatingMode,
+    },
+    /// The IRQ routing is needed but was not set through the driver, see
+    /// [`Device::irq_routing`](crate::Device::irq_routing)
+    IrqRoutingUnknown,
+    /// The PLL reported a lock error after entering FS, see
+    /// [`Device::lock_pll`](crate::Device::lock_pll)
+    PllLockFailed {
+        /// The requested frequency in Hz
+        frequency: u32,
+    },
+    /// The DIO pin is already used for another function, see
+    /// [`Device::set_dio3_as_tcxo_ctrl`](crate::Device::set_dio3_as_tcxo_ctrl)
+    PinInUse {
+        /// The pin requested
+        pin: DioPin,
+        /// The function the pin is used for
+        owner: PinOwner,
+    },
+}
+
+/// A DIO pin of the radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DioPin {
+    /// DIO1
+    Dio1,
+    /// DIO2
+    Dio2,
+    /// DIO3
+    Dio3,
+}
+
+/// A function a DIO pin is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PinOwner {
+    /// IRQs are routed to the pin with SetDioIrqParams
+    IrqMapping,
+    /// The pin powers a TCXO after SetDio3AsTcxoCtrl
+    TcxoControl,
+}
+
+impl From<BusyError> for ErrorKind {
+    fn from(err: BusyError) -> Self {
+        match err {
+            BusyError::Stuck { waited_us } => Self::BusyStuck { waited_us },
+            BusyError::Pin => Self::Pin,
+        }
+    }
+}
+
+impl From<PinError> for ErrorKind {
+    fn from(_: PinError) -> Self {
+        Self::Pin
+    }
+}
+
+impl From<regiface::errors::Error> for ErrorKind {
+    fn from(err: regiface::errors::Error) -> Self {
+        match err {
+            regiface::errors::Error::BusError => Self::Bus,
+            regiface::errors::Error::SerializationError => Self::Serialization,
+            regiface::errors::Error::DeserializationError => Self::Deserialization,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bus => f.write_str("bus error"),
+            Self::Serialization => f.write_str("serialization error"),
+            Self::Deserialization => f.write_str("deserialization error"),
+            Self::Frontend => f.write_str("RF front-end error"),
+            Self::BufferTooSmall { required } => {
+                write!(f, "buffer too small, {required} bytes required")
+            }
+            Self::NoPacketPending => f.write_str("no received packet pending"),
+            Self::BufferOverrun { length } => {
+                write!(f, "{length} byte transfer overruns the data buffer")
+            }
+            Self::NotConfigured { missing } => write!(f, "not configured, missing {missing:?}"),
+            Self::BusyStuck { waited_us } => write!(f, "BUSY stuck high for {waited_us} us"),
+            Self::Pin => f.write_str("control line error"),
+            Self::NoResetPin => f.write_str("no reset line connected"),
+            Self::PacketTypeMismatch {
+                configured,
+                provided,
+            } => write!(
+                f,
+                "{provided:?} parameters while the packet type is {configured:?}"
+            ),
+            Self::CommandFailed { status } => write!(f, "command failed with {status:?}"),
+            Self::FrequencyOutOfRange { frequency } => {
+                write!(f, "frequency {frequency} Hz out of range")
+            }
+            Self::Unresponsive { waited_us } => {
+                write!(f, "radio unresponsive after {waited_us} us")
+            }
+            Self::IrqTimeout { waited_us } => write!(f, "no IRQ after {waited_us} us"),
+            Self::ConfigVerificationFailed { expected, actual } => {
+                write!(f, "read back {actual:?} after setting {expected:?}")
+            }
+            Self::VerificationFailed => f.write_str("read back differs from written value"),
+            Self::WrongMode { found: Some(mode) } => write!(f, "not accepted in {mode:?}"),
+            Self::WrongMode { found: None } => f.write_str("not accepted in the current mode"),
+            Self::PossibleChipReset { expected } => {
+                write!(
+                    f,
+                    "possible chip reset, STDBY_RC while expecting {expected:?}"
+                )
+            }
+            Self::IrqRoutingUnknown => f.write_str("IRQ routing unknown"),
+            Self::PllLockFailed { frequency } => {
+                write!(f, "PLL did not lock at {frequency} Hz")
+            }
+            Self::PinInUse { pin, owner } => write!(f, "{pin:?} already used for {owner:?}"),
+        }
+    }
+}
+
+/// Driver error with the context it happened in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Executing a command failed
+    Command {
+        /// Opcode of the command
+        opcode: u8,
+        /// Index of the step within a composite helper, if any
+        step: Option<u8>,
+        /// The underlying cause
+        source: ErrorKind,
+    },
+    /// Reading or writing a register failed
+    Register {
+        /// Address of the register
+        address: u16,
+        /// Index of the step within a composite helper, if any
+        step: Option<u8>,
+        /// The underlying cause
+        source: ErrorKind,
+    },
+    /// Reading or writing the data buffer failed
+    Buffer {
+        /// Offset into the data buffer
+        offset: u8,
+        /// Index of the step within a composite helper, if any
+        step: Option<u8>,
+        /// The underlying cause
+        source: ErrorKind,
+    },
+    /// Driving the control lines outside of a bus transaction failed
+    Control {
+        /// Index of the step within a composite helper, if any
+        step: Option<u8>,
+        /// The underlying cause
+        source: ErrorKind,
+    },
+}
+
+impl Error {
+    pub(crate) fn command(opcode: u8, source: ErrorKind) -> Self {
+        Self::Command {
+            opcode,
+            step: None,
+            source,
+        }
+    }
+
+    pub(crate) fn register(address: u16, source: ErrorKind) -> Self {
+        Self::Register {
+            address,
+            step: None,
+            source,
+        }
+    }
+
+    pub(crate) fn buffer(offset: u8, source: ErrorKind) -> Self {
+        Self::Buffer {
+            offset,
+            step: None,
+            source,
+        }
+    }
+
+    pub(crate) fn control(source: ErrorKind) -> Self {
+        Self::Control { step: None, source }
+    }
+
+    /// Returns the underlying cause of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Self::Command { source, .. }
+            | Self::Register { source, .. }
+            | Self::Buffer { source, .. }
+            | Self::Control { source, .. } => source,
+        }
+    }
+
+    /// Returns the index of the step within a composite helper the error happened on, if any.
+    pub fn step(&self) -> Option<u8> {
+        match *self {
+            Self::Command { step, .. }
+            | Self::Register { step, .. }
+            | Self::Buffer { step, .. }
+            | Self::Control { step, .. } => step,
+        }
+    }
+
+    /// Records the index of the step within a composite helper the error happened on.
+    pub(crate) fn at_step(mut self, index: u8) -> Self {
+        match &mut self {
+            Self::Command { step, .. }
+            | Self::Register { step, .. }
+            | Self::Buffer { step, .. }
+            | Self::Control { step, .. } => *step = Some(index),
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command { opcode, source, .. } => {
+                write!(f, "command 0x{opcode:02X} failed: {source}")?
+            }
+            Self::Register {
+                address, source, ..
+            } => write!(f, "register 0x{address:04X} access failed: {source}")?,
+            Self::Buffer { offset, source, .. } => {
+                write!(f, "buffer access at offset {offset} failed: {source}")?
+            }
+            Self::Control { source, .. } => write!(f, "control line failed: {source}")?,
+        }
+        if let Some(step) = self.step() {
+            write!(f, " (step {step})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by the transmit helpers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxError {
+    /// The driver failed to talk to the radio
+    Device(Error),
+    /// The TX timeout elapsed before the packet was sent
+    Timeout,
+    /// The payload is empty, see
+    /// [`Device::set_allow_empty_payload`](crate::Device::set_allow_empty_payload)
+    EmptyPayload,
+    /// The payload is longer than the 255 bytes the radio can send
+    PayloadTooLong(usize),
+    /// The payload length does not match the configured fixed-length packet format
+    PayloadLengthMismatch {
+        /// Configured payload length, `None` without a fixed-length packet format
+        configured: Option<u8>,
+        /// Length of the payload passed in
+        requested: usize,
+    },
+}
+
+impl From<Error> for TxError {
+    fn from(err: Error) -> Self {
+        Self::Device(err)
+    }
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Device(err) => err.fmt(f),
+            Self::Timeout => f.write_str("TX timeout"),
+            Self::EmptyPayload => f.write_str("empty payload"),
+            Self::PayloadTooLong(len) => write!(f, "payload of {len} bytes is too long"),
+            Self::PayloadLengthMismatch {
+                configured: Some(configured),
+                requested,
+            } => write!(
+                f,
+                "payload of {requested} bytes does not match configured length {configured}"
+            ),
+            Self::PayloadLengthMismatch {
+                configured: None,
+                requested,
+            } => write!(
+                f,
+                "payload of {requested} bytes requires a fixed-length packet format"
+            ),
+        }
+    }
+}
+
+/// Error returned by [`Device::transmit_repeated`](crate::Device::transmit_repeated)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RepeatedTxError {
+    /// Number of transmissions completed before the failure
+    pub sent: u16,
+    /// The failure that stopped the repetition
+    pub source: TxError,
+}
+
+impl fmt::Display for RepeatedTxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} after {} transmissions", self.source, self.sent)
+    }
+}
+
+/// Error returned by the receive helpers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RxError {
+    /// The driver failed to talk to the radio
+    Device(Error),
+    /// The RX timeout elapsed before a packet was received
+    Timeout,
+    /// A packet was received with a bad payload CRC
+    Crc,
+    /// A packet was received with a bad header (LoRa header CRC, GFSK length)
+    Header,
+    /// The receive buffer does not match the configured fixed-length packet format
+    PayloadLengthMismatch {
+        /// Configured payload length, `None` without a fixed-length packet format
+        configured: Option<u8>,
+        /// Length of the receive buffer
+        requested: usize,
+    },
+}
+
+impl From<Error> for RxError {
+    fn from(err: Error) -> Self {
+        Self::Device(err)
+    }
+}
+
+impl fmt::Display for RxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Device(err) => err.fmt(f),
+            Self::Timeout => f.write_str("RX timeout"),
+            Self::Crc => f.write_str("payload CRC error"),
+            Self::Header => f.write_str("header error"),
+            Self::PayloadLengthMismatch {
+                configured: Some(configured),
+                requested,
+            } => write!(
+                f,
+                "buffer of {requested} bytes does not match configured length {configured}"
+            ),
+            Self::PayloadLengthMismatch {
+                configured: None,
+                requested,
+            } => write!(
+                f,
+                "buffer of {requested} bytes requires a fixed-length packet format"
+            ),
+        }
+    }
+}
+
+/// Error returned by [`Device::ping`](crate::Device::ping) and
+/// [`Device::respond_to_pings`](crate::Device::respond_to_pings)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PingError {
+    /// Sending the ping or its reply failed
+    Tx(TxError),
+    /// Receiving the ping or its reply failed
+    Rx(RxError),
+    /// The packet received is not the reply to the ping sent
+    UnexpectedReply,
+}
+
+impl From<TxError> for PingError {
+    fn from(err: TxError) -> Self {
+        Self::Tx(err)
+    }
+}
+
+impl From<RxError> for PingError {
+    fn from(err: RxError) -> Self {
+        Self::Rx(err)
+    }
+}
+
+impl fmt::Display for PingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tx(err) => err.fmt(f),
+            Self::Rx(err) => err.fmt(f),
+            Self::UnexpectedReply => f.write_str("unexpected ping reply"),
+        }
+    }
+}
+
+/// Step of [`Device::init_lora`](crate::Device::init_lora) and
+/// [`Device::init_gfsk`](crate::Device::init_gfsk)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InitStep {
+    /// SetStandby (STDBY_RC)
+    Standby,
+    /// SetRegulatorMode
+    RegulatorMode,
+    /// SetDio3AsTcxoCtrl, ClearDeviceErrors
+    Tcxo,
+    /// Calibrate
+    Calibrate,
+    /// CalibrateImage
+    ImageCalibration,
+    /// SetPacketType, ResetStats
+    PacketType,
+    /// SetRfFrequency
+    RfFrequency,
+    /// SetPaConfig
+    PaConfig,
+    /// SetTxParams
+    TxParams,
+    /// SetModulationParams
+    ModulationParams,
+    /// SetPacketParams
+    PacketParams,
+    /// SetBufferBaseAddress
+    BufferBaseAddress,
+    /// SetRxTxFallbackMode
+    FallbackMode,
+    /// SetDioIrqParams
+    DioIrq,
+    /// RX gain register
+    RxGain,
+    /// Sync word register
+    SyncWord,
+    /// Errata workarounds
+    Errata,
+}
+
+impl InitStep {
+    /// Returns the bring-up step of a step of [`apply_config`](crate::Device::apply_config).
+    pub(crate) fn from_config_step(step: Option<u8>) -> Self {
+        match step {
+            Some(0) => Self::Standby,
+            Some(1) => Self::RegulatorMode,
+            Some(2) => Self::ImageCalibration,
+            Some(3) => Self::PacketType,
+            Some(4) => Self::RfFrequency,
+            Some(5) => Self::PaConfig,
+            Some(6) => Self::TxParams,
+            Some(7) => Self::ModulationParams,
+            Some(8) => Self::PacketParams,
+            Some(9) => Self::BufferBaseAddress,
+            Some(10) => Self::FallbackMode,
+            Some(11) => Self::DioIrq,
+            Some(12) => Self::RxGain,
+            Some(13 | 14) => Self::SyncWord,
+            _ => Self::Errata,
+        }
+    }
+}
+
+impl fmt::Display for InitStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Standby => "standby",
+            Self::RegulatorMode => "regulator mode",
+            Self::Tcxo => "TCXO",
+            Self::Calibrate => "calibration",
+            Self::ImageCalibration => "image calibration",
+            Self::PacketType => "packet type",
+            Self::RfFrequency => "RF frequency",
+            Self::PaConfig => "PA configuration",
+            Self::TxParams => "TX parameters",
+            Self::ModulationParams => "modulation parameters",
+            Self::PacketParams => "packet parameters",
+            Self::BufferBaseAddress => "buffer base addresses",
+            Self::FallbackMode => "fallback mode",
+            Self::DioIrq => "DIO and IRQ mapping",
+            Self::RxGain => "RX gain",
+            Self::SyncWord => "sync word",
+            Self::Errata => "errata workarounds",
+        })
+    }
+}
+
+/// Error returned by [`Device::init_lora`](crate::Device::init_lora) and
+/// [`Device::init_gfsk`](crate::Device::init_gfsk)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitError {
+    /// The bring-up parameters are inconsistent, nothing was sent to the radio
+    Config(ConfigError),
+    /// The driver failed to talk to the radio
+    Device {
+        /// The failing step
+        step: InitStep,
+        /// The underlying error
+        source: Error,
+    },
+}
+
+impl From<ConfigError> for InitError {
+    fn from(err: ConfigError) -> Self {
+        Self::Config(err)
+    }
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config(err) => write!(f, "invalid bring-up parameters: {err:?}"),
+            Self::Device { step, source } => write!(f, "{step} failed: {source}"),
+        }
+    }
+}
+
+/// Attaches a step index to the error of a composite helper step
+pub(crate) trait StepExt {
+    /// Records the index of the step the error happened on.
+    fn at_step(self, index: u8) -> Self;
+}
+
+impl<T> StepExt for Result<T, Error> {
+    fn at_step(self, index: u8) -> Self {
+        self.map_err(|err| err.at_step(index))
+    }
+}
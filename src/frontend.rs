@@ -0,0 +1,192 @@
+//! External RF front-end control
+//!
+//! Many SX1262 modules place an external PA and LNA between the radio and the antenna, switched
+//! by TXEN/RXEN GPIOs that must be driven before the radio keys up and released afterwards.
+//! DIO2 can only drive a single-pin RF switch, so this module provides hooks the [`Device`]
+//! calls around every mode transition instead.
+//!
+//! - [`RfFrontend`] is called from the blocking command path
+//! - [`AsyncRfFrontend`] is called from the async command path, and is implemented for every
+//!   [`RfFrontend`]
+//! - [`NoFrontend`] is the default and does nothing
+//! - [`TxRxEnablePins`] drives the common two-pin TXEN/RXEN arrangement
+//!
+//! # Ordering
+//! - `enter_tx` is called before SetTx, SetTxContinuousWave and SetTxInfinitePreamble are sent
+//! - `enter_rx` is called before SetRx, SetRxDutyCycle and SetCad are sent
+//! - `enter_idle` is called after SetStandby, SetFs and SetSleep have been sent
+//! - `enter_idle` is also called when a command fails after `enter_tx` or `enter_rx`, so that
+//!   the PA or LNA does not stay enabled while the radio is idle
+//!
+//! # Example
+//! ```no_run
+//! use embedded_hal::{digital::OutputPin, spi::SpiDevice};
+//! use sx1262::{frontend::TxRxEnablePins, Device};
+//!
+//! fn create<SPI: SpiDevice, P: OutputPin>(spi: SPI, txen: P, rxen: P) {
+//!     let device = Device::new(spi).with_frontend(TxRxEnablePins::new(txen, rxen));
+//! }
+//! ```
+//!
+//! [`Device`]: crate::Device
+
+use core::convert::Infallible;
+
+use embedded_hal::digital::OutputPin;
+
+/// Blocking control of an external RF front-end
+pub trait RfFrontend {
+    /// Error type returned by the front-end
+    type Error;
+
+    /// Prepares the front-end for transmission, e.g. enabling the external PA.
+    fn enter_tx(&mut self) -> Result<(), Self::Error>;
+
+    /// Prepares the front-end for reception, e.g. enabling the external LNA.
+    fn enter_rx(&mut self) -> Result<(), Self::Error>;
+
+    /// Returns the front-end to its idle, lowest power state.
+    fn enter_idle(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous control of an external RF front-end
+///
+/// This is the async version of [`RfFrontend`], and is implemented for every type implementing
+/// [`RfFrontend`].
+#[allow(async_fn_in_trait)]
+pub trait AsyncRfFrontend {
+    /// Error type returned by the front-end
+    type Error;
+
+    /// Prepares the front-end for transmission, e.g. enabling the external PA.
+    async fn enter_tx(&mut self) -> Result<(), Self::Error>;
+
+    /// Prepares the front-end for reception, e.g. enabling the external LNA.
+    async fn enter_rx(&mut self) -> Result<(), Self::Error>;
+
+    /// Returns the front-end to its idle, lowest power state.
+    async fn enter_idle(&mut self) -> Result<(), Self::Error>;
+}
+
+impl<T: RfFrontend> AsyncRfFrontend for T {
+    type Error = T::Error;
+
+    async fn enter_tx(&mut self) -> Result<(), Self::Error> {
+        RfFrontend::enter_tx(self)
+    }
+
+    async fn enter_rx(&mut self) -> Result<(), Self::Error> {
+        RfFrontend::enter_rx(self)
+    }
+
+    async fn enter_idle(&mut self) -> Result<(), Self::Error> {
+        RfFrontend::enter_idle(self)
+    }
+}
+
+/// Front-end placeholder for modules without external PA/LNA control
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFrontend;
+
+impl RfFrontend for NoFrontend {
+    type Error = Infallible;
+
+    fn enter_tx(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn enter_rx(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn enter_idle(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Front-end driven by separate TXEN and RXEN output pins
+///
+/// Both pins are active high. At most one pin is driven high at any time, and the inactive
+/// pin is always released before the active one is asserted.
+#[derive(Debug)]
+pub struct TxRxEnablePins<TXEN, RXEN> {
+    txen: TXEN,
+    rxen: RXEN,
+}
+
+impl<TXEN, RXEN> TxRxEnablePins<TXEN, RXEN> {
+    /// Creates a front-end from the TXEN and RXEN pins.
+    ///
+    /// The pins are not driven until the first mode transition.
+    pub fn new(txen: TXEN, rxen: RXEN) -> Self {
+        Self { txen, rxen }
+    }
+
+    /// Releases the TXEN and RXEN pins.
+    pub fn release(self) -> (TXEN, RXEN) {
+        (self.txen, self.rxen)
+    }
+}
+
+impl<TXEN, RXEN> RfFrontend for TxRxEnablePins<TXEN, RXEN>
+where
+    TXEN: OutputPin,
+    RXEN: OutputPin<Error = TXEN::Error>,
+{
+    type Error = TXEN::Error;
+
+    fn enter_tx(&mut self) -> Result<(), Self::Error> {
+        self.rxen.set_low()?;
+        self.txen.set_high()
+    }
+
+    fn enter_rx(&mut self) -> Result<(), Self::Error> {
+        self.txen.set_low()?;
+        self.rxen.set_high()
+    }
+
+    fn enter_idle(&mut self) -> Result<(), Self::Error> {
+        self.txen.set_low()?;
+        self.rxen.set_low()
+    }
+}
+
+/// Front-end transition triggered by a command
+#[cfg(any(feature = "sync", feature = "async"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Transition {
+    /// `enter_tx` is called before the command is sent
+    Tx,
+    /// `enter_rx` is called before the command is sent
+    Rx,
+    /// `enter_idle` is called after the command is sent
+    Idle,
+}
+
+#[cfg(any(feature = "sync", feature = "async"))]
+impl Transition {
+    /// Returns the transition triggered by the command with the given opcode, if any.
+    pub(crate) fn for_opcode(opcode: u8) -> Option<Self> {
+        use crate::{
+            SetCad, SetFs, SetRx, SetRxDutyCycle, SetSleep, SetStandby, SetTx, SetTxContinuousWave,
+            SetTxInfinitePreamble,
+        };
+        use regiface::Command;
+
+        if [
+            SetTx::id(),
+            SetTxContinuousWave::id(),
+            SetTxInfinitePreamble::id(),
+        ]
+        .contains(&opcode)
+        {
+            Some(Self::Tx)
+        } else if [SetRx::id(), SetRxDutyCycle::id(), SetCad::id()].contains(&opcode) {
+            Some(Self::Rx)
+        } else if [SetStandby::id(), SetFs::id(), SetSleep::id()].contains(&opcode) {
+            Some(Self::Idle)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! GFSK packet engine helpers
+//!
+//! - [`software`]: Software implementations of the whitening and CRC performed by the packet
+//!   engine, for decoding captured frames off-target
+
+pub mod software;
@@ -0,0 +1,132 @@
+//! Software whitening and CRC
+//!
+//! These are bit-exact software implementations of the data whitening and CRC computed by the
+//! GFSK packet engine, usable in `no_std`. They allow frames captured off-target (e.g. with an
+//! SDR) to be de-whitened and CRC-checked, and provide reference values for the
+//! [`WhiteningInitialValue`](crate::WhiteningInitialValue),
+//! [`CrcInitialValue`](crate::CrcInitialValue) and [`CrcPolynomial`](crate::CrcPolynomial)
+//! registers.
+//!
+//! # Important Notes
+//! - Whitening covers the payload and CRC, but not the preamble, sync word or address
+//! - The CRC is computed over the header (when variable length) and the payload, before
+//!   whitening
+
+/// Whitens or de-whitens data in place.
+///
+/// The whitening sequence is generated by a 9-bit LFSR with polynomial x⁹+x⁵+1, seeded from
+/// the 9-bit [`WhiteningInitialValue`](crate::WhiteningInitialValue). Each data byte is XORed
+/// with the 8 least significant bits of the LFSR, which is then advanced by 8 steps. The
+/// operation is its own inverse.
+///
+/// # Arguments
+/// * `seed` - The whitening initial value, only the 9 least significant bits are used
+/// * `data` - The data to whiten or de-whiten
+///
+/// # Example
+/// ```
+/// use sx1262::gfsk::software::whiten;
+///
+/// // Seeded with all ones, the sequence is the PN9 sequence
+/// let mut data = [0u8; 4];
+/// whiten(0x1FF, &mut data);
+/// assert_eq!(data, [0xFF, 0xE1, 0x1D, 0x9A]);
+///
+/// whiten(0x1FF, &mut data);
+/// assert_eq!(data, [0; 4]);
+/// ```
+pub fn whiten(seed: u16, data: &mut [u8]) {
+    let mut state = seed & 0x1FF;
+
+    for byte in data {
+        *byte ^= state as u8;
+        for _ in 0..8 {
+            let feedback = (state ^ (state >> 5)) & 1;
+            state = (state >> 1) | (feedback << 8);
+        }
+    }
+}
+
+/// Bit ordering and output options of a CRC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CrcOptions {
+    /// Process each byte least significant bit first and reflect the result
+    pub reflect: bool,
+    /// Invert the result
+    pub invert: bool,
+}
+
+/// CRC-16 preset matching the IBM settings recommended by the datasheet
+///
+/// Initial value 0xFFFF, polynomial 0x8005, not inverted.
+pub const IBM: (u16, u16, CrcOptions) = (
+    0xFFFF,
+    0x8005,
+    CrcOptions {
+        reflect: false,
+        invert: false,
+    },
+);
+
+/// CRC-16 preset matching the CCITT settings recommended by the datasheet
+///
+/// Initial value 0x1D0F, polynomial 0x1021, inverted.
+pub const CCITT: (u16, u16, CrcOptions) = (
+    0x1D0F,
+    0x1021,
+    CrcOptions {
+        reflect: false,
+        invert: true,
+    },
+);
+
+/// Computes a 16-bit CRC over the given data.
+///
+/// The packet engine processes data most significant bit first, so `options.reflect` is false
+/// for frames produced by the radio.
+///
+/// # Arguments
+/// * `init` - The CRC initial value
+/// * `poly` - The CRC polynomial
+/// * `options` - Bit ordering and output inversion
+/// * `data` - The data to compute the CRC over
+///
+/// # Example
+/// ```
+/// use sx1262::gfsk::software::{crc, CrcOptions};
+///
+/// // CRC-16/CCITT-FALSE check value
+/// assert_eq!(crc(0xFFFF, 0x1021, CrcOptions::default(), b"123456789"), 0x29B1);
+///
+/// // CRC-16/ARC check value
+/// let reflected = CrcOptions { reflect: true, invert: false };
+/// assert_eq!(crc(0x0000, 0x8005, reflected, b"123456789"), 0xBB3D);
+/// ```
+pub fn crc(init: u16, poly: u16, options: CrcOptions, data: &[u8]) -> u16 {
+    let mut crc = init;
+
+    for &byte in data {
+        let byte = if options.reflect {
+            byte.reverse_bits()
+        } else {
+            byte
+        };
+
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ poly
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    if options.reflect {
+        crc = crc.reverse_bits();
+    }
+    if options.invert {
+        crc = !crc;
+    }
+    crc
+}
@@ -1,18 +1,21 @@
 #![no_std]
+// Without either feature there is no Device implementation to use the crate-internal helpers
+#![cfg_attr(not(any(feature = "sync", feature = "async")), allow(dead_code))]
 //! SX126x Radio Driver
 //!
 //! This crate provides a type-safe interface for the Semtech SX1261/2 sub-GHz radio transceivers.
 //! The SX1261/2 are highly integrated, long range, low power radio transceivers designed for use
-//! in ISM band applications.
+//! in ISM band applications. The register-compatible SX1268 is also supported.
 //!
 //! # Features
-//! - Frequency range: 150-960 MHz
+//! - Frequency range: 150-960 MHz (SX1268: 410-810 MHz)
 //! - Modulation support:
 //!   - LoRa: SF5-12, BW 7.8-500kHz
 //!   - (G)FSK: BR 0.6-300kbps
 //! - Output power:
 //!   - SX1261: -17 to +15 dBm
 //!   - SX1262: -9 to +22 dBm
+//!   - SX1268: -9 to +22 dBm
 //! - Receive sensitivity down to -148 dBm
 //! - Integrated voltage regulation (DC-DC or LDO)
 //! - Programmable DIO pins for interrupts and control
@@ -24,6 +27,10 @@
 //!   - Provides high-level API for register access and command execution
 //!   - Manages SPI communication with the radio
 //!
+//! - [`bus`]: Transport abstraction over the radio's SPI framing
+//!   - Implemented for every embedded-hal SPI device
+//!   - Allows custom transports such as the STM32WL SUBGHZSPI
+//!
 //! - [`registers`]: Register definitions for direct hardware access
 //!   - [`registers::rf`]: RF-related registers (frequency, power, etc)
 //!   - [`registers::packet`]: Packet handling registers
@@ -36,6 +43,39 @@
 //!   - [`commands::operational`]: Operating mode control
 //!   - [`commands::status`]: Status monitoring and statistics
 //!
+//! - [`config`]: Complete radio configuration applied in a single call
+//!
+//! - `config_text`: `KEY=VALUE` text configuration for field provisioning
+//!
+//! - `decode`: Decoding of captured bus traffic for debugging
+//!
+//! - [`errata`]: Table of silicon workarounds and the configurations that require them
+//!
+//! - [`error`]: Driver error type carrying the command, register and step a failure happened on
+//!
+//! - [`frontend`]: Hooks for external PA/LNA control around TX and RX transitions
+//!
+//! - [`gfsk`]: Software whitening and CRC matching the GFSK packet engine
+//!
+//! - [`pins`]: Optional BUSY and NRESET control lines
+//!
+//! - [`presets`]: Ready-made configurations, such as SX127x FSK interoperability
+//!
+//! # Cargo Features
+//! - `sync` (default): Blocking [`Device`] methods over embedded-hal
+//! - `async`: Async [`Device`] methods over embedded-hal-async
+//! - `config_text`: `KEY=VALUE` parsing and rendering of [`RadioConfig`], see `config_text`
+//! - `decode`: Decoding of captured bus frames into operations, see `decode`
+//! - `defmt`: defmt formatting support
+//! - `heapless`: Receive helpers returning `heapless::Vec`
+//! - `log`: Records of commands, register writes, IRQs and errors through the `log` crate, and a
+//!   log adapter for the `trace` hook
+//! - `metrics`: Per-IRQ counters, interrupt storm detection and operating mode dwell times,
+//!   see `IrqCounts` and `ModeDwellTimes`
+//! - `oplog`: Ring buffer of the last radio operations, see `OperationLog`. Enables `trace`
+//! - `serde`: Serialize and Deserialize for [`RadioConfig`] and the types it contains
+//! - `trace`: Hook observing every bus transaction, see `trace`
+//!
 //! # Usage
 //! The driver uses the `regiface` crate to provide a type-safe interface
 //! for register access and command execution. The main entry point is the
@@ -55,7 +95,7 @@
 //! # Important Notes
 //! - Most configuration must be done in STDBY_RC mode
 //! - Packet type must be set before other RF configuration
-//! - PA configuration depends on device type (SX1261/2)
+//! - PA configuration depends on device type (SX1261/2/8)
 //! - TCXO configuration requires special handling
 //! - Some registers have interdependencies
 //!
@@ -64,6 +104,7 @@
 //! use embedded_hal::spi::SpiDevice;
 //! use sx1262::{Device, commands::{SetStandby, StandbyConfig}, Error};
 //!
+//! # #[cfg(feature = "sync")]
 //! fn configure_radio<SPI: SpiDevice>(spi: SPI) -> Result<Device<SPI>, Error> {
 //!     let mut device = Device::new(spi);
 //!     
@@ -74,13 +115,51 @@
 //! }
 //! ```
 
-pub use regiface::errors::Error;
+pub use error::{
+    DioPin, Error, ErrorKind, InitError, InitStep, PinOwner, PingError, RepeatedTxError, RxError,
+    TxError,
+};
 use regiface::*;
 
+pub mod bus;
 pub mod commands;
+pub mod config;
+#[cfg(feature = "config_text")]
+pub mod config_text;
+#[cfg(feature = "decode")]
+pub mod decode;
 pub mod device;
+pub mod errata;
+pub mod error;
+pub mod frontend;
+pub mod gfsk;
+#[cfg(feature = "linux")]
+pub mod linux;
+pub mod pins;
+pub mod presets;
 pub mod registers;
+#[cfg(feature = "trace")]
+pub mod trace;
 
 pub use commands::*;
-pub use device::Device;
+pub use config::{
+    ConfigChanges, ConfigError, ConfigItems, GfskInit, LoRaInit, RadioConfig, RadioConfigBuilder,
+};
+#[cfg(feature = "embassy")]
+pub use device::EmbassyRadioExt;
+pub use device::{
+    Clock, Device, DeviceBuilder, LinkStats, RxWatchdog, StatsReport, TurnaroundProfile,
+};
+#[cfg(any(feature = "sync", feature = "async"))]
+pub use device::{
+    ConfigMismatch, ConfigReport, FixedLengthWarning, HeaderErrorPolicy, IqMode, IrqEvent,
+    IrqHandle, PartialRadioConfig, PayloadInfo, PingResult, ReadBackSource, Responsive, RxOptions,
+    RxPacket, SelfTestOutcome, SelfTestPlan, SelfTestReport, SniffedFrame, TxDone, TxOptions,
+};
+#[cfg(feature = "metrics")]
+pub use device::{DwellMode, IrqCounts, ModeDwellTimes};
+#[cfg(feature = "sync")]
+pub use device::{IrqSuspendGuard, ModeGuard};
+#[cfg(feature = "oplog")]
+pub use device::{Operation, OperationLog, OPERATION_LOG_DEPTH, OPERATION_PARAMS_LEN};
 pub use registers::*;
@@ -0,0 +1,149 @@
+//! Wiring for Linux hosts
+//!
+//! With the `linux` feature, [`open`] builds a fully wired [`Device`] for a radio on spidev with
+//! its control lines on the GPIO character device, as on the SX1262 HATs of Raspberry Pi
+//! gateways. It uses `linux-embedded-hal` and applies the relaxed timings described in
+//! [hosted platforms](crate::pins#hosted-platforms):
+//!
+//! - BUSY is polled every [`BUSY_POLL_INTERVAL_US`] microseconds
+//! - The [busy timeout](Device::set_busy_timeout_us) is [`BUSY_TIMEOUT_US`]
+//!
+//! DIO1 should then be waited for with [`Device::wait_irq_polled`].
+//!
+//! # Example
+//! ```no_run
+//! use sx1262::linux::{open, Wiring};
+//!
+//! let wiring = Wiring {
+//!     spidev: "/dev/spidev0.0",
+//!     gpiochip: "/dev/gpiochip0",
+//!     busy: 20,
+//!     reset: 18,
+//!     dio1: 16,
+//!     spi_hz: 2_000_000,
+//! };
+//! let mut device = open(&wiring).unwrap();
+//! device.recover().unwrap();
+//! ```
+
+extern crate std;
+
+use core::fmt;
+
+use linux_embedded_hal::{
+    gpio_cdev::{self, Chip, LineRequestFlags},
+    spidev::{SpiModeFlags, SpidevOptions},
+    CdevPin, Delay, SpidevDevice,
+};
+
+use crate::{frontend::NoFrontend, pins::BusyPin, Device};
+
+/// Interval between two reads of BUSY in microseconds
+pub const BUSY_POLL_INTERVAL_US: u32 = 100;
+
+/// Maximum wait for BUSY to go low in microseconds, allowing for scheduling latency
+pub const BUSY_TIMEOUT_US: u32 = 1_000_000;
+
+/// Name the GPIO lines are requested under
+const CONSUMER: &str = "sx1262";
+
+/// A [`Device`] on spidev with its control lines on the GPIO character device
+pub type LinuxDevice =
+    Device<SpidevDevice, NoFrontend, BusyPin<CdevPin, Delay>, CdevPin, CdevPin, Delay>;
+
+/// Where the radio is connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wiring<'a> {
+    /// Path of the spidev device, e.g. `/dev/spidev0.0`
+    pub spidev: &'a str,
+    /// Path of the GPIO chip, e.g. `/dev/gpiochip0`
+    pub gpiochip: &'a str,
+    /// Line offset of BUSY
+    pub busy: u32,
+    /// Line offset of NRESET
+    pub reset: u32,
+    /// Line offset of DIO1
+    pub dio1: u32,
+    /// SPI clock in Hz, at most 16 MHz
+    pub spi_hz: u32,
+}
+
+/// Failure to open the radio
+#[derive(Debug)]
+pub enum OpenError {
+    /// The spidev device could not be opened or configured
+    Spi(std::io::Error),
+    /// A GPIO line could not be requested
+    Gpio(gpio_cdev::Error),
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spi(err) => write!(f, "spidev: {err}"),
+            Self::Gpio(err) => write!(f, "GPIO: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for OpenError {}
+
+impl From<std::io::Error> for OpenError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Spi(err)
+    }
+}
+
+impl From<gpio_cdev::Error> for OpenError {
+    fn from(err: gpio_cdev::Error) -> Self {
+        Self::Gpio(err)
+    }
+}
+
+/// Requests a GPIO line and wraps it as a pin.
+fn request_line(
+    chip: &mut Chip,
+    offset: u32,
+    flags: LineRequestFlags,
+    default: u8,
+) -> Result<CdevPin, gpio_cdev::Error> {
+    let handle = chip.get_line(offset)?.request(flags, default, CONSUMER)?;
+    CdevPin::new(handle)
+}
+
+/// Opens the radio and returns a fully wired [`Device`].
+///
+/// SPI is configured in mode 0 with 8 bit words. NRESET is requested as an output driven high,
+/// BUSY and DIO1 as inputs. The radio is not reset, see [`Device::recover`].
+///
+/// # Arguments
+/// * `wiring` - The spidev device, GPIO chip and line offsets of the radio
+///
+/// # Errors
+/// * `OpenError::Spi` - The spidev device could not be opened or configured
+/// * `OpenError::Gpio` - The GPIO chip could not be opened or a line could not be requested,
+///   e.g. because it is used by another process
+pub fn open(wiring: &Wiring<'_>) -> Result<LinuxDevice, OpenError> {
+    let mut spi = SpidevDevice::open(wiring.spidev)?;
+    spi.configure(
+        &SpidevOptions::new()
+            .bits_per_word(8)
+            .max_speed_hz(wiring.spi_hz)
+            .mode(SpiModeFlags::SPI_MODE_0)
+            .build(),
+    )?;
+
+    let mut chip = Chip::new(wiring.gpiochip)?;
+    let busy = request_line(&mut chip, wiring.busy, LineRequestFlags::INPUT, 0)?;
+    let reset = request_line(&mut chip, wiring.reset, LineRequestFlags::OUTPUT, 1)?;
+    let dio1 = request_line(&mut chip, wiring.dio1, LineRequestFlags::INPUT, 0)?;
+
+    let mut device = Device::builder(spi)
+        .busy(BusyPin::new(busy, Delay).with_poll_interval_us(BUSY_POLL_INTERVAL_US))
+        .reset(reset)
+        .dio1(dio1)
+        .delay(Delay)
+        .build();
+    device.set_busy_timeout_us(BUSY_TIMEOUT_US);
+    Ok(device)
+}
@@ -0,0 +1,346 @@
+//! Radio control lines and delay
+//!
+//! Besides the bus, the SX126x has a BUSY output, which is high while the radio cannot accept
+//! a command, an NRESET input and the DIO1 interrupt output. All are optional for the
+//! [`Device`], and are supplied with [`Device::builder`] or bundled in [`Pins`] for
+//! [`Device::new_with_parts`]:
+//!
+//! - [`NoPin`] is the default and stands in for an unconnected line
+//! - [`BusyPin`] bundles the BUSY input with the delay used to poll it, and is installed with
+//!   [`Device::with_busy`]. The [`Device`] then waits for BUSY to be low before every bus
+//!   transaction, for at most the [busy timeout](crate::Device::set_busy_timeout_us)
+//! - [`ResetPin`] is implemented for every output pin and NRESET is installed with
+//!   [`Device::with_reset`], enabling [`Device::recover`]
+//!
+//! Helpers that need to wait use the delay owned by the [`Device`], installed with
+//! [`Device::new_with_parts`] or [`Device::with_delay`]. [`NoDelay`] is the default, in which
+//! case only their `_with_delay` variants, taking the delay as a parameter, are available.
+//! Without a BUSY line, commands keeping the radio busy for long, such as Calibrate, are waited
+//! out with the owned delay too, see [`OwnedDelay`].
+//!
+//! # Example
+//! ```no_run
+//! use embedded_hal::{delay::DelayNs, digital::{InputPin, OutputPin}, spi::SpiDevice};
+//! use sx1262::{pins::BusyPin, Device};
+//!
+//! fn create<SPI: SpiDevice, B: InputPin, R: OutputPin, D: DelayNs>(
+//!     spi: SPI,
+//!     busy: B,
+//!     reset: R,
+//!     delay: D,
+//! ) {
+//!     let device = Device::new(spi)
+//!         .with_busy(BusyPin::new(busy, delay))
+//!         .with_reset(reset);
+//! }
+//! ```
+//!
+//! # Hosted platforms
+//! On a Linux gateway, with the radio on spidev and the control lines on the GPIO character
+//! device, every pin read is a system call and every sleep overshoots by tens of
+//! microseconds. The defaults suit a microcontroller and should be relaxed:
+//!
+//! - Poll BUSY every 100 µs or so with [`BusyPin::with_poll_interval_us`]
+//! - Allow for scheduling latency with a [busy timeout](crate::Device::set_busy_timeout_us) of
+//!   a second
+//! - Sleep between reads of DIO1 with [`Device::wait_irq_polled`], or use the async helpers
+//!
+//! ```no_run
+//! use embedded_hal::{delay::DelayNs, digital::{InputPin, OutputPin}, spi::SpiDevice};
+//! use sx1262::{pins::{BusyPin, Pins}, Device};
+//!
+//! # #[cfg(feature = "sync")]
+//! fn create<SPI, B, R, I, D>(spi: SPI, busy: B, reset: R, dio1: I, delay: D, busy_delay: D)
+//! where
+//!     SPI: SpiDevice,
+//!     B: InputPin,
+//!     R: OutputPin,
+//!     I: InputPin,
+//!     D: DelayNs,
+//! {
+//!     let busy = BusyPin::new(busy, busy_delay).with_poll_interval_us(100);
+//!     let mut device = Device::new_with_parts(spi, Pins { busy, reset, dio1 }, delay);
+//!     device.set_busy_timeout_us(1_000_000);
+//!     device.recover().unwrap();
+//!     let event = device.wait_irq_polled(1_000).unwrap();
+//! }
+//! ```
+//!
+//! With the `linux` feature, `linux::open` wires a [`Device`] from spidev and the GPIO character
+//! device with these settings.
+//!
+//! [`Device`]: crate::Device
+//! [`Device::wait_irq_polled`]: crate::Device::wait_irq_polled
+//! [`Device::with_busy`]: crate::Device::with_busy
+//! [`Device::with_reset`]: crate::Device::with_reset
+//! [`Device::recover`]: crate::Device::recover
+//! [`Device::new_with_parts`]: crate::Device::new_with_parts
+//! [`Device::builder`]: crate::Device::builder
+//! [`Device::with_delay`]: crate::Device::with_delay
+
+use embedded_hal::digital::{InputPin, OutputPin};
+
+/// Placeholder for an unconnected control line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoPin;
+
+/// Placeholder for a [`Device`](crate::Device) without an owned delay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoDelay;
+
+/// The control lines of a [`Device`](crate::Device)
+///
+/// Use [`NoPin`] for the lines that are not connected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Pins<BUSY = NoPin, RESET = NoPin, DIO1 = NoPin> {
+    /// The BUSY line, see [`BusyWait`]
+    pub busy: BUSY,
+    /// The NRESET line, see [`ResetPin`]
+    pub reset: RESET,
+    /// The DIO1 interrupt line
+    pub dio1: DIO1,
+}
+
+/// Failure to read or drive a control line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PinError;
+
+/// Failure waiting for BUSY to go low
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusyError {
+    /// BUSY stayed high for the whole timeout
+    Stuck {
+        /// Time waited in microseconds
+        waited_us: u32,
+    },
+    /// The BUSY pin could not be read
+    Pin,
+}
+
+/// Blocking wait for the radio to accept a command
+///
+/// Called before every bus transaction, and after commands keeping the radio busy for long
+/// such as Calibrate, never during one. Implementations must not use the radio bus, so that it
+/// stays available to other devices while the radio is busy.
+pub trait BusyWait {
+    /// Waits until BUSY is low.
+    ///
+    /// # Arguments
+    /// * `timeout_us` - Maximum time to wait in microseconds
+    fn wait_ready(&mut self, timeout_us: u32) -> Result<(), BusyError>;
+
+    /// Returns whether BUSY is actually read, `false` for [`NoPin`].
+    ///
+    /// Without BUSY, operations keeping the radio busy for long must be waited for with a delay
+    /// instead.
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+/// Asynchronous wait for the radio to accept a command
+///
+/// This is the async version of [`BusyWait`].
+#[allow(async_fn_in_trait)]
+pub trait AsyncBusyWait {
+    /// Waits until BUSY is low.
+    ///
+    /// This is the async version of [`BusyWait::wait_ready`].
+    async fn wait_ready(&mut self, timeout_us: u32) -> Result<(), BusyError>;
+
+    /// Returns whether BUSY is actually read, `false` for [`NoPin`].
+    ///
+    /// This is the async version of [`BusyWait::is_connected`].
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+impl BusyWait for NoPin {
+    fn wait_ready(&mut self, _timeout_us: u32) -> Result<(), BusyError> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        false
+    }
+}
+
+impl AsyncBusyWait for NoPin {
+    async fn wait_ready(&mut self, _timeout_us: u32) -> Result<(), BusyError> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        false
+    }
+}
+
+/// BUSY input polled with a delay
+///
+/// The pin is polled every [`POLL_INTERVAL_US`](BusyPin::POLL_INTERVAL_US) microseconds by
+/// default, see [`with_poll_interval_us`](BusyPin::with_poll_interval_us). The delay must
+/// implement `embedded_hal::delay::DelayNs` for the blocking [`Device`] methods, and
+/// `embedded_hal_async::delay::DelayNs` for the async ones.
+///
+/// The time waited is counted in poll intervals, so a delay that oversleeps, as on a
+/// non-realtime OS, stretches the [busy timeout](crate::Device::set_busy_timeout_us) by the
+/// same factor.
+///
+/// [`Device`]: crate::Device
+#[derive(Debug)]
+pub struct BusyPin<P, D> {
+    pin: P,
+    delay: D,
+    poll_interval_us: u32,
+}
+
+impl<P, D> BusyPin<P, D> {
+    /// Default interval between two reads of the pin in microseconds
+    pub const POLL_INTERVAL_US: u32 = 10;
+
+    /// Creates a BUSY input from the pin and the delay used to poll it.
+    pub fn new(pin: P, delay: D) -> Self {
+        Self {
+            pin,
+            delay,
+            poll_interval_us: Self::POLL_INTERVAL_US,
+        }
+    }
+
+    /// Sets the interval between two reads of the pin, at least 1 µs.
+    ///
+    /// On a hosted OS, where every read is a system call and the shortest sleep is tens of
+    /// microseconds, an interval around 100 µs avoids spinning on the GPIO character device.
+    ///
+    /// # Arguments
+    /// * `interval_us` - Interval in microseconds
+    ///
+    /// # Example
+    /// ```no_run
+    /// use embedded_hal::{delay::DelayNs, digital::InputPin};
+    /// use sx1262::pins::BusyPin;
+    ///
+    /// fn hosted_busy<P: InputPin, D: DelayNs>(pin: P, delay: D) -> BusyPin<P, D> {
+    ///     BusyPin::new(pin, delay).with_poll_interval_us(100)
+    /// }
+    /// ```
+    pub fn with_poll_interval_us(mut self, interval_us: u32) -> Self {
+        self.poll_interval_us = interval_us.max(1);
+        self
+    }
+
+    /// Releases the pin and the delay.
+    pub fn release(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P, D> BusyWait for BusyPin<P, D>
+where
+    P: InputPin,
+    D: embedded_hal::delay::DelayNs,
+{
+    fn wait_ready(&mut self, timeout_us: u32) -> Result<(), BusyError> {
+        let mut waited_us = 0;
+        while self.pin.is_high().map_err(|_| BusyError::Pin)? {
+            if waited_us >= timeout_us {
+                return Err(BusyError::Stuck { waited_us });
+            }
+            self.delay.delay_us(self.poll_interval_us);
+            waited_us = waited_us.saturating_add(self.poll_interval_us);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P, D> AsyncBusyWait for BusyPin<P, D>
+where
+    P: InputPin,
+    D: embedded_hal_async::delay::DelayNs,
+{
+    async fn wait_ready(&mut self, timeout_us: u32) -> Result<(), BusyError> {
+        let mut waited_us = 0;
+        while self.pin.is_high().map_err(|_| BusyError::Pin)? {
+            if waited_us >= timeout_us {
+                return Err(BusyError::Stuck { waited_us });
+            }
+            self.delay.delay_us(self.poll_interval_us).await;
+            waited_us = waited_us.saturating_add(self.poll_interval_us);
+        }
+        Ok(())
+    }
+}
+
+/// Blocking delay owned by a [`Device`](crate::Device)
+///
+/// Implemented for every `embedded_hal::delay::DelayNs`, and for [`NoDelay`]. Without a BUSY
+/// line, the [`Device`](crate::Device) waits out commands keeping the radio busy for long, such
+/// as Calibrate, with it.
+pub trait OwnedDelay {
+    /// Waits for a number of microseconds, returns immediately for [`NoDelay`].
+    fn wait_us(&mut self, us: u32);
+}
+
+/// Asynchronous delay owned by a [`Device`](crate::Device)
+///
+/// This is the async version of [`OwnedDelay`], implemented for every
+/// `embedded_hal_async::delay::DelayNs`, and for [`NoDelay`].
+#[allow(async_fn_in_trait)]
+pub trait AsyncOwnedDelay {
+    /// Waits for a number of microseconds, returns immediately for [`NoDelay`].
+    ///
+    /// This is the async version of [`OwnedDelay::wait_us`].
+    async fn wait_us(&mut self, us: u32);
+}
+
+impl OwnedDelay for NoDelay {
+    fn wait_us(&mut self, _us: u32) {}
+}
+
+impl AsyncOwnedDelay for NoDelay {
+    async fn wait_us(&mut self, _us: u32) {}
+}
+
+impl<D: embedded_hal::delay::DelayNs> OwnedDelay for D {
+    fn wait_us(&mut self, us: u32) {
+        self.delay_us(us);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D: embedded_hal_async::delay::DelayNs> AsyncOwnedDelay for D {
+    async fn wait_us(&mut self, us: u32) {
+        self.delay_us(us).await;
+    }
+}
+
+/// NRESET output
+///
+/// Implemented for every output pin, and for [`NoPin`] when NRESET is not connected.
+pub trait ResetPin {
+    /// Drives NRESET low when `asserted`, high otherwise.
+    ///
+    /// # Returns
+    /// `false` when no reset line is connected
+    fn set_reset(&mut self, asserted: bool) -> Result<bool, PinError>;
+}
+
+impl ResetPin for NoPin {
+    fn set_reset(&mut self, _asserted: bool) -> Result<bool, PinError> {
+        Ok(false)
+    }
+}
+
+impl<P: OutputPin> ResetPin for P {
+    fn set_reset(&mut self, asserted: bool) -> Result<bool, PinError> {
+        if asserted {
+            self.set_low()
+        } else {
+            self.set_high()
+        }
+        .map_err(|_| PinError)?;
+        Ok(true)
+    }
+}
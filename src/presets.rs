@@ -0,0 +1,206 @@
+//! Ready-made configurations for common interoperability scenarios
+//!
+//! - [`sx127x_fsk_compat`]: GFSK settings matching an SX1272/SX1276 FSK packet-mode link
+//!
+//! # SX127x FSK interoperability
+//! [`sx127x_fsk_compat`] covers the parts of the packet format that have to agree bit for bit:
+//! packet format, CRC and whitening. A few chip-level differences remain that the preset cannot
+//! express on the SX126x side:
+//!
+//! - **Preamble polarity**: the SX127x sends `0xAA` by default where the SX126x sends `0x55`.
+//!   Set `PreamblePolarity` in the SX127x `RegSyncConfig` so the bit adjoining the sync word
+//!   matches.
+//! - **Sync word**: the preset only sets the sync word length. Write the bytes themselves with the
+//!   [`SyncWord`](crate::SyncWord) register. Avoid `0x00` bytes, which the SX127x does not
+//!   support in its sync word.
+//! - **Receiver bandwidth**: the SX127x `RegRxBw` is single-sideband, so it should be set to half
+//!   of the double-sideband [`GfskBandwidth`] chosen here.
+//! - **Register scaling**: the SX127x bit rate register is 32 times coarser than the SX126x one
+//!   and its deviation step is 61.035 Hz, so both ends may round the same nominal values
+//!   differently. The resulting error stays well within the receiver tolerance.
+//! - **Payload length**: the SX127x FIFO is 64 bytes deep, so longer packets need the SX127x host
+//!   to service the FIFO while the packet is in flight.
+
+use crate::{
+    CrcInitialValue, CrcPolynomial, CrcType, GFSKPacketParams, GfskBandwidth, GfskModParams,
+    GfskPulseShape, PacketParamsError, PreambleDetectorLength, PreambleLength,
+    WhiteningInitialValue,
+};
+
+/// CRC settings for the GFSK packet engine
+///
+/// Bundles the [`CrcType`] of the packet parameters with the values of the
+/// [`CrcInitialValue`] and [`CrcPolynomial`] registers, which must agree with the remote end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GfskCrcPreset {
+    /// CRC type of the packet parameters
+    pub crc_type: CrcType,
+    /// Value of the CRC initial value register
+    pub initial_value: CrcInitialValue,
+    /// Value of the CRC polynomial register
+    pub polynomial: CrcPolynomial,
+}
+
+impl GfskCrcPreset {
+    /// 2-byte inverted CCITT CRC, as computed by the SX127x with `CrcWhiteningType` cleared
+    ///
+    /// Initial value 0x1D0F, polynomial 0x1021. See [`software::CCITT`](crate::gfsk::software::CCITT).
+    pub const CCITT: Self = Self {
+        crc_type: CrcType::Crc2ByteInv,
+        initial_value: CrcInitialValue { value: 0x1D0F },
+        polynomial: CrcPolynomial { value: 0x1021 },
+    };
+}
+
+/// Seed of the 9-bit whitening LFSR
+///
+/// Only the 9 least significant bits of [`WhiteningInitialValue`] hold the seed; the upper bits
+/// of the register are reserved and should be preserved, see [`WhiteningSeed::apply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WhiteningSeed {
+    /// The 9-bit seed
+    pub value: u16,
+}
+
+impl WhiteningSeed {
+    /// All-ones seed producing the PN9 sequence used by the SX127x
+    ///
+    /// This differs from the SX126x reset value of 0x0100.
+    pub const PN9: Self = Self { value: 0x01FF };
+
+    /// Returns `current` with its seed bits replaced by this seed.
+    ///
+    /// `current` should be read back from the radio so the reserved bits are written unchanged.
+    pub fn apply(self, current: WhiteningInitialValue) -> WhiteningInitialValue {
+        WhiteningInitialValue {
+            value: (current.value & !0x01FF) | (self.value & 0x01FF),
+        }
+    }
+}
+
+/// Receiver bandwidths in Hz, narrowest first
+const BANDWIDTHS: [(u32, GfskBandwidth); 21] = [
+    (4_800, GfskBandwidth::Bw48),
+    (5_800, GfskBandwidth::Bw58),
+    (7_300, GfskBandwidth::Bw73),
+    (9_700, GfskBandwidth::Bw97),
+    (11_700, GfskBandwidth::Bw117),
+    (14_600, GfskBandwidth::Bw146),
+    (19_500, GfskBandwidth::Bw195),
+    (23_400, GfskBandwidth::Bw234),
+    (29_300, GfskBandwidth::Bw293),
+    (39_000, GfskBandwidth::Bw39),
+    (46_900, GfskBandwidth::Bw469),
+    (58_600, GfskBandwidth::Bw586),
+    (78_200, GfskBandwidth::Bw782),
+    (93_800, GfskBandwidth::Bw938),
+    (117_300, GfskBandwidth::Bw1173),
+    (156_200, GfskBandwidth::Bw1562),
+    (187_200, GfskBandwidth::Bw1872),
+    (232_300, GfskBandwidth::Bw2323),
+    (312_000, GfskBandwidth::Bw3120),
+    (373_600, GfskBandwidth::Bw3736),
+    (467_000, GfskBandwidth::Bw4670),
+];
+
+/// Returns the narrowest bandwidth of at least `hz`, or the widest one.
+fn bandwidth_for(hz: u32) -> GfskBandwidth {
+    BANDWIDTHS
+        .iter()
+        .find(|&&(bw, _)| bw >= hz)
+        .map_or(GfskBandwidth::Bw4670, |&(_, bw)| bw)
+}
+
+/// Generates GFSK settings that interoperate with an SX127x in FSK packet mode.
+///
+/// The packet format matches the SX127x with `RegPacketConfig1` = `0xD0`: variable length,
+/// CCITT CRC and PN9 whitening, without address filtering. The preamble is 5 bytes with a
+/// 16-bit detector, no pulse shaping is applied and the receiver bandwidth is the narrowest
+/// covering Carson's rule (`2 * fdev + bit_rate`).
+///
+/// The deviation is not checked here; [`RadioConfig`](crate::RadioConfig) validates it against
+/// the bandwidth when the configuration is built. See the [module documentation](self) for the
+/// settings left to the caller.
+///
+/// # Arguments
+/// * `bit_rate` - Bit rate in bits per second
+/// * `fdev` - Frequency deviation in Hz
+/// * `sync_word` - The sync word, only its length is used
+///
+/// # Errors
+/// * `PacketParamsError::SyncWordTooLong` - The sync word is longer than 8 bytes
+///
+/// # Example
+/// Known answers from a working SX1276 configuration at 4.8 kbps with a 5 kHz deviation:
+/// ```
+/// use sx1262::presets::{sx127x_fsk_compat, GfskCrcPreset, WhiteningSeed};
+/// use sx1262::{CrcType, GFSKPacketHeaderType, GfskBandwidth, WhiteningInitialValue};
+///
+/// // SX1276 registers
+/// let reg_bitrate: u32 = 0x1A0B;
+/// let reg_fdev: u32 = 0x0052;
+/// let reg_preamble: u16 = 0x0005;
+/// let reg_sync_config: u8 = 0xB2;
+/// let reg_packet_config1: u8 = 0xD0;
+///
+/// let (modulation, packet, crc, whitening) =
+///     sx127x_fsk_compat(4_800, 5_000, &[0xC1, 0x94, 0xC1]).unwrap();
+///
+/// // Bit rate and deviation agree to within the register resolution
+/// let sx1276_bit_rate = 32_000_000 / reg_bitrate;
+/// assert!(modulation.bit_rate.abs_diff(sx1276_bit_rate) <= 1);
+/// let sx1276_fdev = reg_fdev * 61_035 / 1_000;
+/// assert!(modulation.freq_deviation.abs_diff(sx1276_fdev) < 61);
+/// assert_eq!(modulation.bandwidth, GfskBandwidth::Bw195);
+///
+/// // Preamble and sync word lengths
+/// assert_eq!(packet.preamble.as_bits(), reg_preamble * 8);
+/// assert_eq!(packet.sync_word_length, ((reg_sync_config & 0x07) + 1) * 8);
+///
+/// // Variable length, whitening, CRC on with the CCITT settings
+/// assert_eq!(reg_packet_config1 & 0x80 != 0, packet.packet_type == GFSKPacketHeaderType::Variable);
+/// assert_eq!((reg_packet_config1 >> 5) & 0x03 == 0b10, packet.whitening_enable);
+/// assert_eq!(reg_packet_config1 & 0x10 != 0, packet.crc_type != CrcType::CrcOff);
+/// assert_eq!(reg_packet_config1 & 0x01, 0);
+/// assert_eq!(crc, GfskCrcPreset::CCITT);
+/// assert_eq!(crc.initial_value.value, 0x1D0F);
+/// assert_eq!(crc.polynomial.value, 0x1021);
+///
+/// // PN9 seed, keeping the reserved register bits
+/// assert_eq!(whitening, WhiteningSeed::PN9);
+/// let register = whitening.apply(WhiteningInitialValue { value: 0x0100 });
+/// assert_eq!(register.value, 0x01FF);
+/// ```
+pub fn sx127x_fsk_compat(
+    bit_rate: u32,
+    fdev: u32,
+    sync_word: &[u8],
+) -> Result<
+    (
+        GfskModParams,
+        GFSKPacketParams,
+        GfskCrcPreset,
+        WhiteningSeed,
+    ),
+    PacketParamsError,
+> {
+    let crc = GfskCrcPreset::CCITT;
+
+    let packet = GFSKPacketParams::builder()
+        .preamble(PreambleLength::bytes(5))
+        .preamble_detector(PreambleDetectorLength::Bits16)
+        .sync_word_bytes(u8::try_from(sync_word.len()).unwrap_or(u8::MAX))
+        .variable_length()
+        .crc(crc.crc_type)
+        .whitening(true)
+        .build()?;
+
+    let modulation = GfskModParams {
+        bit_rate,
+        pulse_shape: GfskPulseShape::NoFilter,
+        bandwidth: bandwidth_for(fdev.saturating_mul(2).saturating_add(bit_rate)),
+        freq_deviation: fdev,
+    };
+
+    Ok((modulation, packet, crc, WhiteningSeed::PN9))
+}
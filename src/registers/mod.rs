@@ -1,6 +1,15 @@
 //! Register definitions for the SX126x radio
 //! Generated from DS_SX1261-2_V1.2.pdf datasheet
 
+// Serialization runs on every bus transaction, where a panic is a hard fault on no_std
+// targets: lengths must be proven by the types, failures propagated
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic
+)]
+
 mod dio;
 mod packet;
 mod rf;
@@ -10,3 +19,173 @@ pub use dio::*;
 pub use packet::*;
 pub use rf::*;
 pub use system::*;
+
+/// Access allowed to a register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegisterAccess {
+    /// The register can be read and written
+    ReadWrite,
+    /// The register can only be read
+    ReadOnly,
+}
+
+/// Description of a register defined in this module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegisterInfo {
+    /// Name of the register type
+    pub name: &'static str,
+    /// Address of the first byte of the register
+    pub address: u16,
+    /// Length of the register in bytes
+    pub len: usize,
+    /// Access allowed to the register
+    pub access: RegisterAccess,
+    /// Operating mode restrictions on the register's value, if any
+    pub mode_note: Option<&'static str>,
+}
+
+/// Length of the longest register in [`REGISTERS`]
+pub const MAX_REGISTER_LEN: usize = 9;
+
+const fn info(
+    name: &'static str,
+    address: u16,
+    len: usize,
+    access: RegisterAccess,
+    mode_note: Option<&'static str>,
+) -> RegisterInfo {
+    RegisterInfo {
+        name,
+        address,
+        len,
+        access,
+        mode_note,
+    }
+}
+
+/// Every register defined in this module, in address order
+///
+/// ```
+/// use regiface::{ByteArray, FromByteArray, Register};
+/// use sx1262::registers::*;
+///
+/// // Every typed register is described with its address and length
+/// macro_rules! check {
+///     ($($ty:ty),* $(,)?) => {$({
+///         let info = REGISTERS.iter().find(|info| info.name == stringify!($ty)).unwrap();
+///         let value = <$ty as FromByteArray>::Array::new();
+///         assert_eq!(info.address, <$ty>::id());
+///         assert_eq!(info.len, ByteArray::as_ref(&value).len());
+///     })*
+///     assert_eq!([$(stringify!($ty)),*].len(), REGISTERS.len());
+///     };
+/// }
+///
+/// check!(
+///     RetentionList, DioOutputEnable, DioInputEnable, DioPullUpControl, DioPullDownControl,
+///     WhiteningInitialValue, CrcInitialValue, CrcPolynomial, SyncWord, NodeAddress,
+///     BroadcastAddress, IqPolaritySetup, LoraSyncWord, RandomNumber, TxModulation, RxGain,
+///     TxClampConfig, OcpConfiguration, RtcControl, XtaTrim, XtbTrim, Dio3OutputVoltage,
+///     EventMask,
+/// );
+///
+/// assert!(REGISTERS.windows(2).all(|pair| pair[0].address < pair[1].address));
+/// assert!(REGISTERS.iter().all(|info| info.len <= MAX_REGISTER_LEN));
+/// ```
+pub const REGISTERS: &[RegisterInfo] = &[
+    info("RetentionList", 0x029F, 9, RegisterAccess::ReadWrite, None),
+    info(
+        "DioOutputEnable",
+        0x0580,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info("DioInputEnable", 0x0583, 1, RegisterAccess::ReadWrite, None),
+    info(
+        "DioPullUpControl",
+        0x0584,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info(
+        "DioPullDownControl",
+        0x0585,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info(
+        "WhiteningInitialValue",
+        0x06B8,
+        2,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info(
+        "CrcInitialValue",
+        0x06BC,
+        2,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info("CrcPolynomial", 0x06BE, 2, RegisterAccess::ReadWrite, None),
+    info("SyncWord", 0x06C0, 8, RegisterAccess::ReadWrite, None),
+    info("NodeAddress", 0x06CD, 1, RegisterAccess::ReadWrite, None),
+    info(
+        "BroadcastAddress",
+        0x06CE,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info(
+        "IqPolaritySetup",
+        0x0736,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info("LoraSyncWord", 0x0740, 2, RegisterAccess::ReadWrite, None),
+    info(
+        "RandomNumber",
+        0x0819,
+        4,
+        RegisterAccess::ReadOnly,
+        Some("random only while the receiver is running"),
+    ),
+    info("TxModulation", 0x0889, 1, RegisterAccess::ReadWrite, None),
+    info("RxGain", 0x08AC, 1, RegisterAccess::ReadWrite, None),
+    info("TxClampConfig", 0x08D8, 1, RegisterAccess::ReadWrite, None),
+    info(
+        "OcpConfiguration",
+        0x08E7,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info("RtcControl", 0x0902, 1, RegisterAccess::ReadWrite, None),
+    info(
+        "XtaTrim",
+        0x0911,
+        1,
+        RegisterAccess::ReadWrite,
+        Some("writable only in STDBY_XOSC"),
+    ),
+    info(
+        "XtbTrim",
+        0x0912,
+        1,
+        RegisterAccess::ReadWrite,
+        Some("writable only in STDBY_XOSC"),
+    ),
+    info(
+        "Dio3OutputVoltage",
+        0x0920,
+        1,
+        RegisterAccess::ReadWrite,
+        None,
+    ),
+    info("EventMask", 0x0944, 1, RegisterAccess::ReadWrite, None),
+];
@@ -19,7 +19,7 @@ use regiface::{register, FromByteArray, ReadableRegister, ToByteArray, WritableR
 ///
 /// Reading this register returns a new random 32-bit value each time.
 #[register(0x0819u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister)]
 pub struct RandomNumber {
     /// 32-bit random number value
     pub value: u32,
@@ -34,7 +34,7 @@ pub struct RandomNumber {
 /// # Important Notes
 /// - Must be configured before each packet transmission
 #[register(0x0889u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister)]
 pub struct TxModulation {
     pub data: u8,
 }
@@ -58,8 +58,8 @@ impl TxModulation {
 
 impl Default for TxModulation {
     fn default() -> Self {
-        // Preserve the datasheet default (bit 0) and default to the non-500kHz setting (bit 2 set).
-        Self { data: 0x01 | 0x04 }
+        // Reset value, bit 2 is only cleared for LoRa 500 kHz
+        Self { data: 0x05 }
     }
 }
 
@@ -83,22 +83,18 @@ pub struct InvalidGainMode(pub u8);
 /// Note: The RX Gain setting is not retained when waking from sleep mode. To include this
 /// register in retention memory, additional configuration is required.
 #[register(0x08ACu16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RxGain {
     /// Power saving gain mode (~4.2mA in DC-DC mode)
     /// Lower power consumption but reduced sensitivity
+    #[default]
     PowerSaving,
     /// Boosted gain mode (~4.8mA in DC-DC mode)
     /// Maximum sensitivity but higher power consumption
     Boosted,
 }
 
-impl Default for RxGain {
-    fn default() -> Self {
-        Self::PowerSaving
-    }
-}
-
 impl RxGain {
     /// Convert a raw byte value to RxGainMode
     pub fn from_byte(value: u8) -> Result<Self, InvalidGainMode> {
@@ -128,7 +124,7 @@ impl RxGain {
 /// - For SX1261: Use default value
 /// - Must be configured after power-on reset or wake from cold start
 #[register(0x08D8u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister)]
 pub struct TxClampConfig {
     config: u8,
 }
@@ -141,9 +137,8 @@ impl TxClampConfig {
 
 impl Default for TxClampConfig {
     fn default() -> Self {
-        Self {
-            config: 0xC8 | 0x1E, // Default value for SX1262 with workaround applied
-        }
+        // Reset value, bits 4:1 are only set by the workaround
+        Self { config: 0xC8 }
     }
 }
 
@@ -165,7 +160,7 @@ impl Default for TxClampConfig {
 ///   supply voltage as current draw is inversely proportional to VBAT
 /// - Value is automatically reconfigured when SetPaConfig() is called
 #[register(0x08E7u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister)]
 pub struct OcpConfiguration {
     /// OCP current limit in steps of 2.5mA
     /// - Range: 0x00-0xFF (0-637.5mA)
@@ -10,11 +10,21 @@
 
 use core::convert::Infallible;
 
-use regiface::{register, FromByteArray, ReadableRegister, ToByteArray, WritableRegister};
+use regiface::{
+    register, FromByteArray, ReadableRegister, Register, ToByteArray, WritableRegister,
+};
 
 const MAX_RETENTION_ENTRIES: usize = 4;
 
-/// Retention register (address: 0x02F9)
+/// Error returned when a [`RetentionList`] has no free entry left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetentionListFull {
+    /// Address of the register that could not be added
+    pub address: u16,
+}
+
+/// Retention register (address: 0x029F)
 ///
 /// Used to store addresses of registers whose values
 /// should be retained during sleep mode.
@@ -25,8 +35,8 @@ const MAX_RETENTION_ENTRIES: usize = 4;
 /// - Up to 4 register addresses can be stored
 /// - Each entry is a 16-bit register address
 /// - The first byte indicates the number of valid entries
-#[regiface::register(0x02F9u16)]
-#[derive(Debug, Copy, Clone, ReadableRegister, WritableRegister, Default)]
+#[regiface::register(0x029Fu16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister, Default)]
 pub struct RetentionList {
     /// Number of valid retention entries. Maximum: 4
     n_entries: u8,
@@ -35,16 +45,50 @@ pub struct RetentionList {
 }
 
 impl RetentionList {
+    /// Creates a retention list holding the given register addresses.
+    ///
+    /// Duplicate addresses take a single entry.
+    ///
+    /// # Arguments
+    /// * `addresses` - Register addresses to retain, at most 4 distinct ones
+    ///
+    /// # Errors
+    /// * `RetentionListFull` - More than 4 distinct addresses were given
+    ///
+    /// # Example
+    /// ```
+    /// use regiface::Register;
+    /// use sx1262::{IqPolaritySetup, RetentionList, RxGain};
+    ///
+    /// let list = RetentionList::from_registers(&[RxGain::id(), IqPolaritySetup::id()]).unwrap();
+    /// assert!(list.contains::<RxGain>());
+    /// assert_eq!(list.into_iter().collect::<Vec<_>>(), [0x08AC, 0x0736]);
+    ///
+    /// assert!(RetentionList::from_registers(&[1, 2, 3, 4, 5]).is_err());
+    /// ```
+    pub fn from_registers(addresses: &[u16]) -> Result<Self, RetentionListFull> {
+        let mut list = Self::default();
+        for &address in addresses {
+            list.add_entry(address)
+                .map_err(|()| RetentionListFull { address })?;
+        }
+        Ok(list)
+    }
+
+    /// Returns whether the register `R` is retained.
+    pub fn contains<R: Register<IdType = u16>>(&self) -> bool {
+        self.get_entries().contains(&R::id())
+    }
+
     /// Adds a register address to the retention list.
     /// If the address already exists, no action is taken and Ok(()) is returned.
+    #[allow(clippy::result_unit_err)]
     pub fn add_entry(&mut self, reg_addr: u16) -> Result<(), ()> {
-        if (self.n_entries as usize) >= MAX_RETENTION_ENTRIES {
-            return Err(());
-        }
         if self.get_entries().contains(&reg_addr) {
             return Ok(());
         }
-        self.entries[self.n_entries as usize] = reg_addr;
+        let slot = self.entries.get_mut(self.n_entries as usize).ok_or(())?;
+        *slot = reg_addr;
         self.n_entries += 1;
         Ok(())
     }
@@ -52,7 +96,9 @@ impl RetentionList {
     /// Returns a slice of the current retention entries.
     /// The slice length is equal to the number of valid entries.
     pub fn get_entries(&self) -> &[u16] {
-        &self.entries[..(self.n_entries as usize)]
+        self.entries
+            .get(..(self.n_entries as usize))
+            .unwrap_or(&self.entries)
     }
 
     /// Removes a register address from the retention list.
@@ -61,18 +107,37 @@ impl RetentionList {
     /// - Removal is O(1) but may not preserve the original order of entries
     /// - The storage at the old last position is not explicitly cleared
     /// - If duplicate addresses exist, only the first match is removed
+    #[allow(clippy::result_unit_err)]
     pub fn remove_entry(&mut self, reg_addr: u16) -> Result<(), ()> {
-        for i in 0..(self.n_entries as usize) {
-            if self.entries[i] == reg_addr {
-                self.n_entries -= 1;
-                let last = self.n_entries as usize;
-                if i != last {
-                    self.entries[i] = self.entries[last];
-                }
-                return Ok(());
-            }
+        let entries = self.get_entries();
+        let (Some(i), Some(&last)) = (entries.iter().position(|&e| e == reg_addr), entries.last())
+        else {
+            return Err(());
+        };
+        if let Some(entry) = self.entries.get_mut(i) {
+            *entry = last;
         }
-        Err(())
+        self.n_entries -= 1;
+        Ok(())
+    }
+}
+
+impl IntoIterator for RetentionList {
+    type Item = u16;
+    type IntoIter = core::iter::Take<core::array::IntoIter<u16, MAX_RETENTION_ENTRIES>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.get_entries().len();
+        self.entries.into_iter().take(len)
+    }
+}
+
+impl<'a> IntoIterator for &'a RetentionList {
+    type Item = u16;
+    type IntoIter = core::iter::Copied<core::slice::Iter<'a, u16>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.get_entries().iter().copied()
     }
 }
 
@@ -89,7 +154,7 @@ impl RetentionList {
 /// - RTC should be stopped after implicit header timeout
 /// - RTC uses the RC64k oscillator as time base
 #[register(0x0902u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister, Default)]
 pub struct RtcControl {
     /// Enable RTC operation
     /// - true = RTC enabled
@@ -110,7 +175,7 @@ pub struct RtcControl {
 /// - Must be in STDBY_XOSC to change value
 /// - Changes before STDBY_XOSC will be overwritten
 #[register(0x0911u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister)]
 pub struct XtaTrim {
     /// XTA pin capacitance trimming
     /// - Steps of 0.47pF
@@ -141,7 +206,7 @@ impl Default for XtaTrim {
 /// - Changes before STDBY_XOSC will be overwritten
 /// - When using TCXO, XTB should be left unconnected
 #[register(0x0912u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister)]
 pub struct XtbTrim {
     /// XTB pin capacitance trimming
     /// - Steps of 0.47pF
@@ -158,24 +223,36 @@ impl Default for XtbTrim {
     }
 }
 
+/// Bit of the event mask register holding the RTC timeout event (datasheet 15.3)
+const TIMEOUT_EVENT_BIT: u8 = 1 << 1;
+
 /// Event mask register (address: 0x0944)
 ///
-/// Controls which events generate interrupts.
-/// Used in conjunction with the IRQ system to control
-/// which events can trigger interrupts on DIO pins.
+/// Only the RTC timeout event bit is documented, set to clear the event as part of the
+/// implicit header timeout workaround (datasheet 15.3). The other bits are kept as read so
+/// that a read-modify-write leaves them untouched.
 ///
-/// # Important Notes
-/// - Used to clear timeout events in implicit header mode
-/// - Part of workaround for implicit header timeout behavior
-/// - Should be used carefully as it affects system responsiveness
+/// # Example
+/// ```
+/// use regiface::{FromByteArray, ToByteArray};
+/// use sx1262::EventMask;
+///
+/// let mut mask = EventMask::from_bytes([0xA5]).unwrap();
+/// assert!(!mask.timeout_event);
+///
+/// mask.timeout_event = true;
+/// assert_eq!(mask.to_bytes().unwrap(), [0xA7]);
+///
+/// mask.timeout_event = false;
+/// assert_eq!(mask.to_bytes().unwrap(), [0xA5]);
+/// ```
 #[register(0x0944u16)]
-#[derive(Debug, Clone, Copy, ReadableRegister, WritableRegister, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ReadableRegister, WritableRegister, Default)]
 pub struct EventMask {
-    /// Event mask bits
-    /// Each bit masks a specific event type:
-    /// - 0 = Event masked (no interrupt)
-    /// - 1 = Event enabled (generates interrupt)
-    pub mask: u8,
+    /// RTC timeout event, write true to clear it
+    pub timeout_event: bool,
+    /// Undocumented bits, the timeout event bit is ignored
+    pub other: u8,
 }
 
 impl FromByteArray for RetentionList {
@@ -183,11 +260,15 @@ impl FromByteArray for RetentionList {
     type Array = [u8; 2 * MAX_RETENTION_ENTRIES + 1];
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
-        let n_entries = bytes[0];
+        // The count comes from the radio, never trust it to index the entries
+        let [count, addresses @ ..] = bytes;
+        let n_entries = count.min(MAX_RETENTION_ENTRIES as u8);
 
         let mut entries = [0u16; MAX_RETENTION_ENTRIES];
-        for i in 0..MAX_RETENTION_ENTRIES {
-            entries[i] = u16::from_be_bytes([bytes[2 * i + 1], bytes[2 * i + 2]]);
+        for (entry, address) in entries.iter_mut().zip(addresses.chunks_exact(2)) {
+            if let [msb, lsb] = *address {
+                *entry = u16::from_be_bytes([msb, lsb]);
+            }
         }
         Ok(RetentionList { n_entries, entries })
     }
@@ -199,11 +280,12 @@ impl ToByteArray for RetentionList {
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
         let mut arr = Self::Array::default();
-        arr[0] = self.n_entries;
-        for (i, entry) in self.entries.iter().enumerate() {
-            let bytes = entry.to_be_bytes();
-            arr[2 * i + 1] = bytes[0];
-            arr[2 * i + 2] = bytes[1];
+        let [count, addresses @ ..] = &mut arr;
+        *count = self.n_entries;
+        for (address, entry) in addresses.chunks_exact_mut(2).zip(self.entries) {
+            if let [msb, lsb] = address {
+                [*msb, *lsb] = entry.to_be_bytes();
+            }
         }
         Ok(arr)
     }
@@ -274,7 +356,10 @@ impl FromByteArray for EventMask {
     type Array = [u8; 1];
 
     fn from_bytes(bytes: Self::Array) -> Result<Self, Self::Error> {
-        Ok(Self { mask: bytes[0] })
+        Ok(Self {
+            timeout_event: bytes[0] & TIMEOUT_EVENT_BIT != 0,
+            other: bytes[0] & !TIMEOUT_EVENT_BIT,
+        })
     }
 }
 
@@ -283,6 +368,11 @@ impl ToByteArray for EventMask {
     type Array = [u8; 1];
 
     fn to_bytes(self) -> Result<Self::Array, Self::Error> {
-        Ok([self.mask])
+        let timeout_event = if self.timeout_event {
+            TIMEOUT_EVENT_BIT
+        } else {
+            0
+        };
+        Ok([self.other & !TIMEOUT_EVENT_BIT | timeout_event])
     }
 }
@@ -0,0 +1,116 @@
+//! Bus transaction tracing
+//!
+//! With the `trace` feature, a hook can be installed on the [`Device`](crate::Device) with
+//! [`set_trace_hook`](crate::Device::set_trace_hook). The hook is called with a [`TraceEvent`]
+//! for every operation of every bus transaction: once with the bytes written, and once with the
+//! bytes read back when the transaction returns data. Without the feature, tracing compiles away
+//! entirely.
+//!
+//! Ready-made hooks are provided for logging through defmt ([`defmt_hook`], with the `defmt`
+//! feature) and log ([`log_hook`], with the `log` feature).
+//!
+//! The complete response of the last command, status byte included, can also be kept for
+//! inspection with [`set_response_capture`](crate::Device::set_response_capture), e.g. to see
+//! how the status evolves across the bytes of a transaction when diagnosing dropped commands.
+//!
+//! # Example
+//! ```ignore
+//! use sx1262::trace::TraceEvent;
+//!
+//! fn print_trace(event: TraceEvent<'_>) {
+//!     // Forward to your logging facility of choice
+//! }
+//!
+//! device.set_trace_hook(Some(print_trace));
+//! ```
+
+use crate::GetStatsResponse;
+
+/// What a traced operation addressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TraceTarget {
+    /// A command with the given opcode
+    Command(u8),
+    /// Registers starting at the given address
+    Register(u16),
+    /// The data buffer starting at the given offset
+    Buffer(u8),
+}
+
+/// Direction of a traced operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TraceDirection {
+    /// Bytes sent to the radio
+    Write,
+    /// Bytes received from the radio
+    Read,
+}
+
+/// A single traced bus operation
+///
+/// The bytes exclude the framing header (opcode, address or offset), which is described by the
+/// [`target`](TraceEvent::target).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TraceEvent<'a> {
+    /// What the operation addressed
+    pub target: TraceTarget,
+    /// Whether the bytes were written or read
+    pub direction: TraceDirection,
+    /// The bytes transferred
+    pub bytes: &'a [u8],
+}
+
+/// Hook called for every traced bus operation
+pub type TraceHook = fn(TraceEvent<'_>);
+
+/// Number of bytes kept of a captured response, the longest documented response (GetStats)
+pub const RAW_RESPONSE_CAPACITY: usize = GetStatsResponse::SIZE;
+
+/// The complete response of a command, as clocked out by the radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RawResponse {
+    /// Opcode of the command
+    pub opcode: u8,
+    bytes: [u8; RAW_RESPONSE_CAPACITY],
+    len: u8,
+}
+
+impl RawResponse {
+    /// Keeps the first [`RAW_RESPONSE_CAPACITY`] bytes of a response.
+    pub(crate) fn new(opcode: u8, response: &[u8]) -> Self {
+        let len = response.len().min(RAW_RESPONSE_CAPACITY);
+        let mut bytes = [0; RAW_RESPONSE_CAPACITY];
+        bytes[..len].copy_from_slice(&response[..len]);
+        Self {
+            opcode,
+            bytes,
+            len: len as u8,
+        }
+    }
+
+    /// Returns the response bytes, starting with the status byte.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+}
+
+/// Trace hook logging every operation at trace level through defmt
+#[cfg(feature = "defmt")]
+pub fn defmt_hook(event: TraceEvent<'_>) {
+    defmt::trace!("sx126x {:?}", event);
+}
+
+/// Trace hook logging every operation at trace level through log
+#[cfg(feature = "log")]
+pub fn log_hook(event: TraceEvent<'_>) {
+    log::trace!(
+        "sx126x {:?} {:?} {:02X?}",
+        event.target,
+        event.direction,
+        event.bytes
+    );
+}
@@ -0,0 +1,623 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use core::time::Duration;
+use std::{cell::RefCell, rc::Rc};
+
+use common::{block_on, Busy, Delay, Frontend, Op, Radio, Switch};
+use regiface::{Command, Register};
+use sx1262::{
+    error::{RxError, TxError},
+    Calibrate, CalibrationConfig, CommandStatus, ConfigChanges, Device, DioIrqConfig, DioPin,
+    Error, ErrorKind, GfskInit, ImageCalibConfig, IrqMask, LoRaBandwidth, LoRaInit, LoRaModParams,
+    OperatingMode, PacketType, PinOwner, PreparedCommand, RadioConfig, RegulatorMode,
+    RetentionList, RfFrequencyConfig, RxGain, RxMode, SetRfFrequency, SetRx, SetStandby, SetTx,
+    SpreadingFactor, StandbyConfig, TcxoConfig, TcxoVoltage, Timeout, TurnaroundProfile,
+};
+
+/// TX modulation register, bit 2 is cleared for LoRa 500 kHz
+const TX_MODULATION: u16 = 0x0889;
+/// IQ polarity register, bit 2 is cleared for inverted IQ
+const IQ_POLARITY: u16 = 0x0736;
+
+fn lora(frequency: u32) -> RadioConfig {
+    LoRaInit::new(RfFrequencyConfig { frequency })
+        .config()
+        .unwrap()
+}
+
+fn set_rf_frequency() -> SetRfFrequency {
+    SetRfFrequency {
+        config: RfFrequencyConfig {
+            frequency: 868_000_000,
+        },
+    }
+}
+
+fn lora_with(bandwidth: LoRaBandwidth, inverted_iq: bool) -> RadioConfig {
+    let mut init = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    });
+    init.modulation = LoRaModParams::new(SpreadingFactor::SF7, bandwidth);
+    init.packet.iq_inversion_enable = inverted_iq;
+    init.config().unwrap()
+}
+
+#[test]
+fn async_commands_wait_check_and_cache_like_sync_ones() {
+    let radio = Radio::new();
+    let busy = Busy::default();
+    let mut device = Device::new(radio.clone()).with_busy(busy.clone());
+    device.set_busy_timeout_us(1_000);
+
+    // The wait after Calibrate allows for twice the worst case
+    let calibrate = || Calibrate {
+        config: CalibrationConfig::all(),
+    };
+    block_on(device.execute_command_async(calibrate())).unwrap();
+    assert_eq!(busy.waits(), [1_000, 7_000]);
+
+    // A stuck BUSY fails the command before it is sent
+    busy.set_stuck(true);
+    radio.clear();
+    let err = block_on(device.execute_command_async(calibrate())).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BusyStuck { waited_us: 1_000 });
+    assert!(radio.opcodes().is_empty());
+    busy.set_stuck(false);
+
+    // SetRegulatorMode is rejected in RX
+    radio.set_status(0x54);
+    let err = block_on(device.set_regulator_mode_async(RegulatorMode::LdoOnly)).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::WrongMode {
+            found: Some(OperatingMode::Receive)
+        }
+    );
+    assert_eq!(radio.count(0x96), 0);
+    radio.set_status(0x24);
+
+    // The image calibration is skipped when the configuration is applied again
+    let frequency = RfFrequencyConfig {
+        frequency: 868_100_000,
+    };
+    let config = LoRaInit::new(frequency).config().unwrap();
+    block_on(device.apply_config_async(&config)).unwrap();
+    block_on(device.apply_config_async(&config)).unwrap();
+    assert_eq!(radio.count(0x98), 1);
+}
+
+#[test]
+fn async_config_diff_undoes_workarounds_no_longer_required() {
+    let radio = Radio::new();
+    radio
+        .set_registers(TX_MODULATION, &[0x05])
+        .set_registers(IQ_POLARITY, &[0x0D]);
+    let mut device = Device::new(radio.clone());
+    block_on(device.apply_config_diff_async(&lora_with(LoRaBandwidth::Bw500, true))).unwrap();
+    assert_eq!(radio.register(TX_MODULATION), 0x01);
+    assert_eq!(radio.register(IQ_POLARITY), 0x09);
+
+    let changes =
+        block_on(device.apply_config_diff_async(&lora_with(LoRaBandwidth::Bw125, false))).unwrap();
+    assert!(changes.contains(ConfigChanges::WORKAROUNDS));
+    assert_eq!(radio.register(TX_MODULATION), 0x05);
+    assert_eq!(radio.register(IQ_POLARITY), 0x0D);
+}
+
+#[test]
+fn async_transfers_are_split_beyond_the_max_transfer_size() {
+    let payload: Vec<u8> = (0..=254).collect();
+    let radio = Radio::new();
+    radio.set_registers(0x0700, &payload[..64]);
+    radio.state().max_transaction = Some(16);
+    let mut device = Device::new(radio.clone());
+
+    // Unlimited, the single transaction is refused
+    assert!(block_on(device.write_buffer_async(0x80, &payload)).is_err());
+
+    // Split into transactions of 14 bytes written, 13 bytes read, wrapping around the buffer
+    device.set_max_transfer_size(Some(16));
+    block_on(device.write_buffer_async(0x80, &payload)).unwrap();
+    let mut read = [0; 255];
+    block_on(device.read_buffer_async(0x80, &mut read)).unwrap();
+    assert_eq!(read[..], payload[..]);
+
+    // Register span reads are split too, in 12 bytes
+    let mut span = [0; 40];
+    block_on(device.read_register_raw_async(0x0704, &mut span)).unwrap();
+    assert_eq!(span[..], payload[4..44]);
+
+    // The refused transaction, then 19 writes, 20 buffer reads and 4 register reads
+    assert_eq!(radio.state().transactions, 1 + 19 + 20 + 4);
+}
+
+#[test]
+fn async_buffer_transfers_are_bounded() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let overrun = Error::Buffer {
+        offset: 0,
+        step: None,
+        source: ErrorKind::BufferOverrun { length: 256 },
+    };
+
+    // Empty transfers are skipped
+    block_on(device.write_buffer_async(0, &[])).unwrap();
+    block_on(device.read_buffer_async(0, &mut [])).unwrap();
+
+    // 255 bytes are sent in one transaction, 256 are refused without one
+    block_on(device.write_buffer_async(0, &[0xAA; 255])).unwrap();
+    block_on(device.read_buffer_async(0, &mut [0; 255])).unwrap();
+    assert_eq!(
+        block_on(device.write_buffer_async(0, &[0xAA; 256])),
+        Err(overrun)
+    );
+    assert_eq!(
+        block_on(device.read_buffer_async(0, &mut [0; 256])),
+        Err(overrun)
+    );
+    assert_eq!(
+        radio.ops(),
+        [Op::WriteBuffer(0, vec![0xAA; 255]), Op::ReadBuffer(0, 255)]
+    );
+}
+
+#[test]
+fn async_paranoid_checks_report_failed_commands() {
+    // A failure to execute in every status byte
+    let radio = Radio::new();
+    radio.set_status(0x2A);
+    let mut device = Device::new(radio);
+    assert!(block_on(device.execute_command_async(set_rf_frequency())).is_ok());
+
+    device.set_paranoid_checks(true);
+    let err = block_on(device.execute_command_async(set_rf_frequency())).unwrap_err();
+    assert_eq!(
+        err,
+        Error::Command {
+            opcode: SetRfFrequency::id(),
+            step: None,
+            source: ErrorKind::CommandFailed {
+                status: CommandStatus::ExecutionFailure
+            },
+        }
+    );
+}
+
+#[test]
+fn async_paranoid_checks_detect_a_chip_reset() {
+    // A radio reset by a brown-out while in STDBY_XOSC
+    let radio = Radio::new();
+    radio.set_status(0x32);
+    let mut device = Device::new(radio.clone());
+    device.set_paranoid_checks(true);
+    block_on(device.execute_command_async(SetStandby {
+        config: StandbyConfig::Xosc,
+    }))
+    .unwrap();
+    block_on(device.execute_command_async(set_rf_frequency())).unwrap();
+
+    // STDBY_RC with a reserved command status
+    radio.set_status(0x22);
+    let err = block_on(device.execute_command_async(set_rf_frequency())).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PossibleChipReset {
+            expected: OperatingMode::StandbyXosc
+        }
+    );
+
+    // The radio is now known to be in STDBY_RC
+    block_on(device.execute_command_async(set_rf_frequency())).unwrap();
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn async_raw_responses_are_captured_on_request() {
+    use sx1262::GetStats;
+
+    // Clocks out a status that changes on every byte
+    let radio = Radio::new();
+    radio.set_hook(|_, _, _, response| {
+        for (byte, i) in response.iter_mut().zip(0..) {
+            *byte = 0x24 + i;
+        }
+        true
+    });
+    let mut device = Device::new(radio);
+    device.set_response_capture(true);
+    block_on(device.execute_command_async(GetStats)).unwrap();
+    let raw = device.last_raw_response().unwrap();
+    assert_eq!(raw.opcode, 0x10);
+    assert_eq!(raw.bytes(), [0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2A]);
+}
+
+#[test]
+fn async_busy_is_waited_for_explicitly() {
+    let radio = Radio::new();
+    let busy = Busy::default();
+    let mut device = Device::new(radio.clone()).with_busy(busy.clone());
+    device.set_busy_timeout_us(1_000);
+    let tx = PreparedCommand::new(SetTx {
+        timeout: Timeout::default(),
+    });
+
+    // Hop, let the radio settle, then start TX at the slot boundary
+    block_on(device.execute_command_async(set_rf_frequency())).unwrap();
+    block_on(device.wait_busy_async(500)).unwrap();
+    block_on(device.execute_prepared_async(&tx)).unwrap();
+    assert_eq!(radio.opcodes(), [0x86, 0x83]);
+    assert_eq!(busy.waits(), [1_000, 500, 1_000]);
+
+    busy.set_stuck(true);
+    assert_eq!(
+        block_on(device.wait_busy_async(0)),
+        Err(Error::Control {
+            step: None,
+            source: ErrorKind::BusyStuck { waited_us: 0 }
+        })
+    );
+}
+
+#[test]
+fn async_config_is_applied_like_sync() {
+    let mut config = lora_with(LoRaBandwidth::Bw500, true);
+    config.tcxo = Some(TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    });
+    let sync = Radio::new();
+    Device::new(sync.clone()).apply_config(&config).unwrap();
+
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    block_on(device.apply_config_async(&config)).unwrap();
+    assert_eq!(radio.ops(), sync.ops());
+    assert_eq!(device.snapshot_config(), Some(config));
+    assert_eq!(device.packet_type(), Some(PacketType::LoRa));
+}
+
+#[test]
+fn async_dropped_packet_type_fails_before_the_frequency() {
+    for paranoid in [false, true] {
+        let radio = Radio::new();
+        // SetPacketType is dropped, the radio stays in GFSK
+        radio.set_hook(|_, opcode, _, _| opcode == 0x8A);
+        let mut device = Device::new(radio.clone());
+        device.set_paranoid_checks(paranoid);
+
+        let err = block_on(device.apply_config_async(&lora(868_100_000))).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::ConfigVerificationFailed {
+                expected: PacketType::LoRa,
+                actual: PacketType::Gfsk,
+            }
+        );
+        assert_eq!(err.step(), Some(3));
+        assert_eq!(device.packet_type(), Some(PacketType::Gfsk));
+
+        // Failed before the frequency was sent, and read back once
+        assert_eq!(radio.count(0x86), 0);
+        assert_eq!(radio.count(0x11), 1);
+    }
+}
+
+#[test]
+fn async_workarounds_are_undone_by_a_configuration_not_requiring_them() {
+    let radio = Radio::new();
+    radio
+        .set_registers(TX_MODULATION, &[0x05])
+        .set_registers(IQ_POLARITY, &[0x0D]);
+    let mut device = Device::new(radio.clone());
+
+    block_on(device.apply_config_async(&lora_with(LoRaBandwidth::Bw500, true))).unwrap();
+    assert_eq!(radio.register(TX_MODULATION), 0x01);
+    assert_eq!(radio.register(IQ_POLARITY), 0x09);
+
+    block_on(device.apply_config_async(&lora_with(LoRaBandwidth::Bw125, false))).unwrap();
+    assert_eq!(radio.register(TX_MODULATION), 0x05);
+    assert_eq!(radio.register(IQ_POLARITY), 0x0D);
+}
+
+#[test]
+fn async_config_diff_sends_what_changed() {
+    let radio = Radio::new();
+    let sent = || {
+        let opcodes = radio.opcodes();
+        radio.clear();
+        opcodes
+    };
+    let mut device = Device::new(radio.clone());
+
+    let mut config = lora(868_100_000);
+    block_on(device.apply_config_diff_async(&config)).unwrap();
+    assert!(sent().contains(&0x8A));
+
+    // Nothing changed, nothing sent
+    let changes = block_on(device.apply_config_diff_async(&config)).unwrap();
+    assert_eq!(changes, ConfigChanges::empty());
+    assert!(sent().is_empty());
+
+    // A hop within the calibrated band only sends SetRfFrequency
+    config.frequency.frequency = 868_300_000;
+    let changes = block_on(device.apply_config_diff_async(&config)).unwrap();
+    assert_eq!(changes, ConfigChanges::FREQUENCY);
+    assert_eq!(sent(), [0x86]);
+}
+
+#[test]
+fn async_tcxo_control_and_dio3_routes_exclude_each_other() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let tcxo = TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    };
+
+    // CAD_DONE routed to DIO3 keeps the TCXO off
+    block_on(device.modify_irq_routing_async(|routing| {
+        routing.irq_mask |= IrqMask::CAD_DONE;
+        routing.dio3_mask |= IrqMask::CAD_DONE;
+    }))
+    .unwrap();
+    let err = block_on(device.set_dio3_as_tcxo_ctrl_async(tcxo)).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PinInUse {
+            pin: DioPin::Dio3,
+            owner: PinOwner::IrqMapping
+        }
+    );
+    assert_eq!(radio.opcodes(), [0x08]);
+
+    // Forced: SetDio3AsTcxoCtrl, ClearDeviceErrors and Calibrate
+    block_on(device.force_dio3_as_tcxo_ctrl_async(tcxo)).unwrap();
+    assert_eq!(radio.opcodes(), [0x08, 0x97, 0x07, 0x89]);
+
+    // DIO3 now powers the TCXO, new routes to it are refused unless forced
+    let route_tx = |routing: &mut DioIrqConfig| {
+        routing.irq_mask |= IrqMask::TX_DONE;
+        routing.dio3_mask |= IrqMask::TX_DONE;
+    };
+    let err = block_on(device.modify_irq_routing_async(route_tx)).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PinInUse {
+            pin: DioPin::Dio3,
+            owner: PinOwner::TcxoControl
+        }
+    );
+    block_on(device.force_irq_routing_async(route_tx)).unwrap();
+    assert_eq!(radio.count(0x08), 2);
+}
+
+#[test]
+fn async_retention_list_is_read_back() {
+    let list = RetentionList::from_registers(&[RxGain::id()]).unwrap();
+
+    let mut device = Device::new(Radio::new());
+    block_on(device.apply_retention_async(&list)).unwrap();
+
+    // A stuck radio keeps the retention count at 0
+    let radio = Radio::new();
+    radio.state().read_only.insert(RetentionList::id());
+    let mut device = Device::new(radio);
+    assert!(block_on(device.apply_retention_async(&list)).is_err());
+}
+
+#[test]
+fn async_image_calibration_covers_a_span() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    // One calibration over the 863-870 and 902-928MHz bands
+    block_on(device.calibrate_image_async(ImageCalibConfig {
+        freq1: 0xD7,
+        freq2: 0xE9,
+    }))
+    .unwrap();
+    assert_eq!(device.image_calibration_span(), Some(860..=932));
+    for frequency in [868_100_000, 915_000_000] {
+        block_on(device.apply_config_async(&lora(frequency))).unwrap();
+    }
+    assert_eq!(radio.count(0x98), 1);
+
+    // Outside the span the image is calibrated again
+    block_on(device.apply_config_async(&lora(433_920_000))).unwrap();
+    assert_eq!(radio.count(0x98), 2);
+    assert_eq!(device.image_calibration_span(), Some(428..=444));
+}
+
+#[test]
+fn async_calibration_waits_for_the_worst_case_without_busy() {
+    let delay = Delay::default();
+    let mut device = Device::new(Radio::new()).with_delay(delay.clone());
+    block_on(device.calibrate_async(CalibrationConfig::PLL | CalibrationConfig::IMAGE)).unwrap();
+    assert_eq!(delay.sleeps_ns(), [1_700_000]);
+
+    // A delay given for one call is used instead
+    let mut lent = Delay::default();
+    block_on(device.calibrate_with_delay_async(CalibrationConfig::PLL, &mut lent)).unwrap();
+    assert_eq!(lent.count(), 1);
+    assert_eq!(delay.count(), 1);
+}
+
+#[test]
+fn async_bring_up_waits_out_the_calibration_without_busy() {
+    let radio = Radio::new();
+    let delay = Delay::default();
+    // The number of sleeps preceding every command
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let (record, counted) = (seen.clone(), delay.clone());
+    radio.set_hook(move |_, opcode, _, _| {
+        record.borrow_mut().push((opcode, counted.count()));
+        false
+    });
+    let mut device = Device::new(radio).with_delay(delay.clone());
+
+    let mut init = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    });
+    init.tcxo = Some(TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    });
+    block_on(device.init_lora_async(init)).unwrap();
+    assert_eq!(delay.sleeps_ns(), [3_500_000]);
+    let seen = seen.borrow();
+    let calibrate = seen.iter().position(|&(opcode, _)| opcode == 0x89).unwrap();
+    assert_eq!((seen[calibrate].1, seen[calibrate + 1].1), (0, 1));
+}
+
+#[test]
+fn async_bring_up_sends_the_documented_sequence() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let mut init = GfskInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    });
+    init.tcxo = Some(TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    });
+    block_on(device.init_gfsk_async(init)).unwrap();
+    assert_eq!(
+        radio.opcodes(),
+        [
+            0x80, 0x96, 0x80, 0x96, 0x9D, 0x97, 0x07, 0x89, 0x98, 0x8A, 0x11, 0x00, 0x86, 0x95,
+            0x8E, 0x8B, 0x8C, 0x8F, 0x93, 0x08
+        ]
+    );
+}
+
+#[test]
+fn async_regulator_mode_is_only_set_in_standby() {
+    let radio = Radio::new();
+    // Follows the standby mode
+    radio.set_hook(|state, opcode, request, _| {
+        if opcode == 0x80 {
+            state.status = if request[0] == 0 { 0x24 } else { 0x34 };
+        }
+        false
+    });
+    let sent = || -> Vec<(u8, Vec<u8>)> {
+        let commands = radio.commands();
+        radio.clear();
+        commands
+            .into_iter()
+            .filter(|(opcode, _)| *opcode != 0xC0)
+            .collect()
+    };
+    let mut device = Device::new(radio.clone());
+
+    // Sent in STDBY_RC
+    block_on(device.set_regulator_mode_async(RegulatorMode::DcDcLdo)).unwrap();
+    assert_eq!(sent(), [(0x96, vec![0x01])]);
+
+    // Routed through STDBY_RC when idling in STDBY_XOSC
+    block_on(device.set_turnaround_profile_async(TurnaroundProfile::LowLatency)).unwrap();
+    sent();
+    block_on(device.set_regulator_mode_async(RegulatorMode::DcDcLdo)).unwrap();
+    assert_eq!(
+        sent(),
+        [(0x80, vec![0x00]), (0x96, vec![0x01]), (0x80, vec![0x01])]
+    );
+}
+
+#[test]
+fn async_rx_gain_is_optionally_retained() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    block_on(device.set_rx_gain_async(RxGain::Boosted, false)).unwrap();
+    assert_eq!(radio.register_writes(), [(0x08AC, vec![0x96])]);
+    radio.clear();
+
+    block_on(device.set_rx_gain_async(RxGain::Boosted, true)).unwrap();
+    assert_eq!(
+        radio.register_writes(),
+        [
+            (0x08AC, vec![0x96]),
+            (0x029F, vec![0x01, 0x08, 0xAC, 0, 0, 0, 0, 0, 0])
+        ]
+    );
+}
+
+#[test]
+fn async_deadlines_are_enforced_by_the_radio() {
+    let radio = Radio::new();
+    radio
+        .raise_on(0x83, IrqMask::TIMEOUT.bits())
+        .raise_on(0x82, IrqMask::TIMEOUT.bits());
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+
+    // 1 s and 100 ms in steps of 15.625 µs
+    let result = block_on(device.transmit_timeout_async(&[1, 2], Duration::from_secs(1)));
+    assert!(matches!(result, Err(TxError::Timeout)));
+    assert_eq!(radio.last(0x83).unwrap(), [0x00, 0xFA, 0x00]);
+
+    let mut payload = [0; 8];
+    let result = block_on(device.receive_timeout_async(&mut payload, Duration::from_millis(100)));
+    assert!(matches!(result, Err(RxError::Timeout)));
+    assert_eq!(radio.last(0x82).unwrap(), [0x00, 0x19, 0x00]);
+}
+
+#[test]
+fn async_sleep_wakes_the_radio_after_the_duration() {
+    let radio = Radio::new();
+    let delay = Delay::default();
+    let mut device = Device::new(radio.clone()).with_delay(delay.clone());
+
+    // Warm start, then woken and polled
+    block_on(device.sleep_for_async(Duration::from_millis(10))).unwrap();
+    assert_eq!(radio.opcodes(), [0x84, 0xC0, 0xC0]);
+    assert_eq!(radio.last(0x84).unwrap(), [0x04]);
+    assert_eq!(delay.sleeps_ns(), [10_000_000]);
+
+    // The radio is given time to save its configuration
+    delay.clear();
+    block_on(device.sleep_for_async(Duration::ZERO)).unwrap();
+    assert_eq!(delay.sleeps_ns(), [500_000]);
+}
+
+#[test]
+fn async_frontend_returns_to_idle_when_the_command_fails() {
+    let radio = Radio::new();
+    radio.set_failing(0x82, true);
+    let frontend = Frontend::new(&radio);
+    let mut device = Device::new(radio.clone()).with_frontend(frontend.clone());
+
+    let err = block_on(device.execute_command_async(SetRx {
+        mode: RxMode::Single,
+    }))
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Bus);
+    assert_eq!(frontend.switches(), [(Switch::Rx, 0), (Switch::Idle, 1)]);
+}
+
+#[test]
+fn async_polls_give_up_when_no_irq_is_raised() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+    device.set_busy_timeout_us(100);
+
+    let sent = block_on(device.transmit_async(&[1, 2], Timeout(64)));
+    let Err(TxError::Device(err)) = sent else {
+        panic!("{sent:?}");
+    };
+    assert_eq!(err.kind(), ErrorKind::IrqTimeout { waited_us: 1_100 });
+    assert_eq!(radio.count(0x12), 1_100);
+    assert_eq!(radio.opcodes().last(), Some(&0x80));
+
+    let mut bytes = [0; 255];
+    let received = block_on(device.receive_async(&mut bytes, RxMode::Single));
+    let Err(RxError::Device(err)) = received else {
+        panic!("{received:?}");
+    };
+    assert_eq!(err.kind(), ErrorKind::IrqTimeout { waited_us: 100 });
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::{Chip, Transaction};
+use sx1262::Device;
+
+#[test]
+fn shared_bus_transactions_do_not_interleave() {
+    let bus = Rc::new(RefCell::new(Vec::new()));
+    let mut a = Device::new(Chip::on(bus.clone(), 1));
+    let mut b = Device::new(Chip::on(bus.clone(), 2));
+
+    let mut value = [0; 2];
+    a.write_buffer(0, &[0xAA, 0xBB]).unwrap();
+    b.read_register_raw(0x0740, &mut value).unwrap();
+    a.read_register_raw(0x0740, &mut value).unwrap();
+    assert_eq!(value, [1, 1]);
+
+    let transaction = |cs, written: &[u8], read| Transaction {
+        cs,
+        written: written.to_vec(),
+        read,
+    };
+    assert_eq!(
+        bus.take(),
+        [
+            transaction(1, &[0x0E, 0x00, 0xAA, 0xBB], 0),
+            transaction(2, &[0x1D, 0x07, 0x40, 0x00], 2),
+            transaction(1, &[0x1D, 0x07, 0x40, 0x00], 2),
+        ]
+    );
+}
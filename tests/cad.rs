@@ -0,0 +1,88 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Delay, Radio};
+use sx1262::{CadParams, Device, IrqMask, Timeout, TurnaroundProfile};
+
+const CAD_DONE: u16 = IrqMask::CAD_DONE.bits();
+const CAD_DETECTED: u16 = IrqMask::CAD_DETECTED.bits();
+
+#[test]
+fn cad_assisted_receive_skips_false_detections() {
+    let radio = Radio::new();
+    // SetCad ends in CAD_DONE alone unless scripted otherwise
+    radio.raise_on(0xC5, CAD_DONE).respond(0x13, &[2, 0x80]);
+    radio.state().buffer[0x80..0x82].copy_from_slice(&[0xAB, 0xCD]);
+    // Idle, detected then RX timeout, detected then RX_DONE
+    radio.script_irqs([
+        CAD_DONE,
+        CAD_DONE | CAD_DETECTED,
+        IrqMask::TIMEOUT.bits(),
+        CAD_DONE | CAD_DETECTED,
+        IrqMask::RX_DONE.bits(),
+    ]);
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+    let mut delay = Delay::default();
+    let mut bytes = [0; 255];
+
+    let packet = device
+        .receive_cad_assisted(&mut bytes, CadParams::default(), 50, &mut delay, || false)
+        .unwrap()
+        .unwrap();
+    assert_eq!(&bytes[..packet.len], &[0xAB, 0xCD]);
+    assert_eq!(radio.count(0xC5), 3);
+    assert_eq!(delay.sleeps_ms(), [50, 50]);
+    // SetCadParams uses the CAD + RX exit mode
+    assert_eq!(radio.last(0x88).unwrap()[3], 0x01);
+}
+
+#[test]
+fn cad_assisted_receive_stops_on_request() {
+    let radio = Radio::new();
+    radio.raise_on(0xC5, CAD_DONE);
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+    let mut delay = Delay::default();
+    let mut bytes = [0; 255];
+
+    let mut checks = 0;
+    let stopped =
+        device.receive_cad_assisted(&mut bytes, CadParams::default(), 50, &mut delay, || {
+            checks += 1;
+            checks > 3
+        });
+    assert!(stopped.unwrap().is_none());
+    assert_eq!(radio.count(0xC5), 3);
+    assert_eq!(delay.sleeps_ms(), [50, 50, 50]);
+}
+
+#[test]
+fn transmit_after_cad_only_sends_on_a_free_channel() {
+    let radio = Radio::new();
+    radio.raise_on(0x83, IrqMask::TX_DONE.bits());
+    radio.raise_on(0xC5, CAD_DONE | CAD_DETECTED);
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+    device
+        .set_turnaround_profile(TurnaroundProfile::LowLatency)
+        .unwrap();
+
+    // CAD_DONE and CAD_DETECTED: nothing is sent
+    radio.clear();
+    let sent = device.transmit_after_cad(&[1, 2], CadParams::default(), Timeout::default());
+    assert!(sent.unwrap().is_none());
+    assert_eq!(radio.count(0x83), 0);
+    // SetCadParams uses the CAD only exit mode
+    assert_eq!(radio.last(0x88).unwrap()[3], 0x00);
+
+    // CAD_DONE alone: back from STDBY_RC to FS before SetTx
+    radio.raise_on(0xC5, CAD_DONE);
+    radio.clear();
+    let sent = device.transmit_after_cad(&[1, 2], CadParams::default(), Timeout::default());
+    assert!(sent.unwrap().is_some());
+    let opcodes = radio.opcodes();
+    let position = |opcode| opcodes.iter().position(|&op| op == opcode).unwrap();
+    assert!(position(0xC5) < position(0xC1) && position(0xC1) < position(0x83));
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{
+    error::ErrorKind, ClearIrqStatus, CrcType, Device, DioIrqConfig, GFSKPacketHeaderType,
+    GFSKPacketParams, GfskInit, IrqMask, LoRaInit, LoRaPacketParams, LoraPacketHeaderType,
+    PacketParams, PacketType, PreambleDetectorLength, PreparedCommand, RfFrequencyConfig,
+    SetDioIrqParams, SetModulationParams, SetPacketParams, SetPacketType,
+};
+
+const FREQUENCY: RfFrequencyConfig = RfFrequencyConfig {
+    frequency: 868_100_000,
+};
+
+#[test]
+fn reserved_irq_bits_are_dropped_on_the_bus() {
+    let radio = Radio::new();
+    let all = IrqMask::from_bits_retain(0xFFFF);
+    let mut device = Device::new(radio.clone());
+    let config = DioIrqConfig {
+        irq_mask: all,
+        dio1_mask: all,
+        dio2_mask: all,
+        dio3_mask: all,
+    };
+    device.execute_command(SetDioIrqParams { config }).unwrap();
+    device
+        .execute_command(ClearIrqStatus { irq_mask: all })
+        .unwrap();
+
+    let commands = radio.commands();
+    assert_eq!(commands[0], (0x08, [0x01, 0xFF].repeat(4)));
+    assert_eq!(commands[1], (0x02, vec![0x01, 0xFF]));
+}
+
+#[test]
+fn parameters_for_another_packet_type_are_rejected() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let lora = LoRaInit::new(FREQUENCY).config().unwrap();
+    let gfsk = GfskInit::new(FREQUENCY).config().unwrap();
+    let mismatch = |configured, provided| ErrorKind::PacketTypeMismatch {
+        configured,
+        provided,
+    };
+
+    // GFSK parameters while the radio is in LoRa, sent or prepared
+    device
+        .execute_command(SetPacketType {
+            packet_type: PacketType::LoRa,
+        })
+        .unwrap();
+    let modulation = SetModulationParams {
+        params: gfsk.modulation,
+    };
+    let err = device.execute_command(modulation.clone()).unwrap_err();
+    assert_eq!(err.kind(), mismatch(PacketType::LoRa, PacketType::Gfsk));
+    let err = device
+        .execute_prepared(&PreparedCommand::new(modulation))
+        .unwrap_err();
+    assert_eq!(err.kind(), mismatch(PacketType::LoRa, PacketType::Gfsk));
+    let err = device
+        .execute_command(SetPacketParams {
+            params: gfsk.packet,
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), mismatch(PacketType::LoRa, PacketType::Gfsk));
+    assert_eq!(radio.count(0x8B) + radio.count(0x8C), 0);
+
+    // LoRa parameters are accepted
+    device
+        .execute_command(SetModulationParams {
+            params: lora.modulation,
+        })
+        .unwrap();
+    device
+        .execute_command(SetPacketParams {
+            params: lora.packet,
+        })
+        .unwrap();
+
+    // and rejected once the radio is in GFSK
+    device
+        .execute_command(SetPacketType {
+            packet_type: PacketType::Gfsk,
+        })
+        .unwrap();
+    let packet = SetPacketParams {
+        params: lora.packet,
+    };
+    let err = device.execute_command(packet.clone()).unwrap_err();
+    assert_eq!(err.kind(), mismatch(PacketType::Gfsk, PacketType::LoRa));
+
+    // unless the check is disabled
+    device.set_packet_type_check(false);
+    device.execute_command(packet).unwrap();
+    assert_eq!(radio.count(0x8C), 2);
+}
+
+#[test]
+fn ambiguous_packet_parameters_are_not_rejected() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    // Serialized as [0x00, 0x20, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00], as LoRa ones could
+    let gfsk = PacketParams::GFSK(GFSKPacketParams {
+        preamble_detector_length: PreambleDetectorLength::Off,
+        packet_type: GFSKPacketHeaderType::Fixed,
+        payload_length: 0,
+        crc_type: CrcType::Crc1Byte,
+        whitening_enable: false,
+        ..GFSKPacketParams::default()
+    });
+    let implicit = PacketParams::LoRa(LoRaPacketParams {
+        header_type: LoraPacketHeaderType::Fixed,
+        payload_length: 16,
+        ..LoRaPacketParams::default()
+    });
+
+    // Sent in both packet types
+    for packet_type in [PacketType::Gfsk, PacketType::LoRa] {
+        device
+            .execute_command(SetPacketType { packet_type })
+            .unwrap();
+        device
+            .execute_command(SetPacketParams { params: gfsk })
+            .unwrap();
+    }
+    assert_eq!(radio.count(0x8C), 2);
+
+    // An implicit header is LoRa whatever the payload length
+    device
+        .execute_command(SetPacketType {
+            packet_type: PacketType::Gfsk,
+        })
+        .unwrap();
+    let err = device
+        .execute_command(SetPacketParams { params: implicit })
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PacketTypeMismatch {
+            configured: PacketType::Gfsk,
+            provided: PacketType::LoRa,
+        }
+    );
+}
@@ -0,0 +1,788 @@
+//! Simulated radio, SPI bus, pins and delay shared by the integration tests
+
+#![allow(dead_code)]
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet, VecDeque},
+    convert::Infallible,
+    rc::Rc,
+};
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{ErrorType as PinErrorType, InputPin, OutputPin},
+    spi::{ErrorType as SpiErrorType, Operation, SpiDevice},
+};
+use sx1262::{
+    bus::{RadioBus, READ_BUFFER, READ_REGISTER, WRITE_BUFFER, WRITE_REGISTER},
+    frontend::RfFrontend,
+    pins::{BusyError, BusyWait},
+};
+
+/// Status byte of a radio in STDBY_RC with nothing to report
+pub const STATUS: u8 = 0x24;
+
+/// A transaction seen by the [`Radio`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    /// Opcode and parameters of a command
+    Command(u8, Vec<u8>),
+    /// Address and length of a register read
+    ReadRegister(u16, usize),
+    /// Address and bytes of a register write
+    WriteRegister(u16, Vec<u8>),
+    /// Offset and length of a buffer read
+    ReadBuffer(u8, usize),
+    /// Offset and bytes of a buffer write
+    WriteBuffer(u8, Vec<u8>),
+}
+
+/// Custom handling of a command, returning `false` to fall back to the default one
+///
+/// The hook runs before anything is written to the response, so it can e.g. change the
+/// status byte reported for the command.
+pub type Hook = Box<dyn FnMut(&mut State, u8, &[u8], &mut [u8]) -> bool>;
+
+/// The simulated radio behind a [`Radio`]
+pub struct State {
+    /// Every transaction, in order
+    pub ops: Vec<Op>,
+    /// Status byte of every command response
+    pub status: u8,
+    /// IRQ status, cleared by ClearIrqStatus
+    pub irq: u16,
+    /// IRQ statuses reported by the next GetIrqStatus, before `irq`
+    pub irq_script: VecDeque<u16>,
+    /// IRQs raised by an opcode, e.g. TX_DONE by SetTx
+    pub raises: HashMap<u8, u16>,
+    /// Response parameters of an opcode, after the status byte
+    pub responses: HashMap<u8, Vec<u8>>,
+    /// Opcodes failing with a bus error, including the framing opcodes of the register and
+    /// buffer accesses
+    pub failing: HashSet<u8>,
+    /// Register contents, 0 when never written
+    pub registers: HashMap<u16, u8>,
+    /// Registers ignoring writes
+    pub read_only: HashSet<u16>,
+    /// Data buffer
+    pub buffer: [u8; 256],
+    /// Packet type reported by GetPacketType, set by SetPacketType
+    pub packet_type: u8,
+    /// Longest transaction accepted, framing included
+    pub max_transaction: Option<usize>,
+    /// Number of transactions, refused ones included
+    pub transactions: usize,
+    hook: Option<Hook>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            ops: Vec::new(),
+            status: STATUS,
+            irq: 0,
+            irq_script: VecDeque::new(),
+            raises: HashMap::new(),
+            responses: HashMap::new(),
+            failing: HashSet::new(),
+            registers: HashMap::new(),
+            read_only: HashSet::new(),
+            buffer: [0; 256],
+            packet_type: 0,
+            max_transaction: None,
+            transactions: 0,
+            hook: None,
+        }
+    }
+}
+
+impl State {
+    fn transaction(&mut self, len: usize) -> Result<(), ()> {
+        self.transactions += 1;
+        match self.max_transaction {
+            Some(max) if len > max => Err(()),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_failing(&self, opcode: u8) -> Result<(), ()> {
+        if self.failing.contains(&opcode) {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn command(&mut self, opcode: u8, request: &[u8], response: &mut [u8]) -> Result<(), ()> {
+        self.ops.push(Op::Command(opcode, request.to_vec()));
+        self.transaction(1 + request.len() + response.len())?;
+        self.check_failing(opcode)?;
+        if let Some(mut hook) = self.hook.take() {
+            let handled = hook(self, opcode, request, response);
+            self.hook = Some(hook);
+            if handled {
+                return Ok(());
+            }
+        }
+        response.fill(self.status);
+        if let Some(parameters) = self.responses.get(&opcode) {
+            let len = parameters.len().min(response.len().saturating_sub(1));
+            response[1..=len].copy_from_slice(&parameters[..len]);
+        } else {
+            match opcode {
+                0x02 => self.irq &= !u16::from_be_bytes([request[0], request[1]]),
+                0x11 => response[1] = self.packet_type,
+                0x12 => {
+                    let irq = self.irq_script.pop_front().unwrap_or(self.irq);
+                    response[1..3].copy_from_slice(&irq.to_be_bytes());
+                }
+                _ => {}
+            }
+        }
+        if opcode == 0x8A {
+            self.packet_type = request[0];
+        }
+        self.irq |= self.raises.get(&opcode).copied().unwrap_or(0);
+        Ok(())
+    }
+
+    fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), ()> {
+        self.ops.push(Op::ReadRegister(address, value.len()));
+        self.transaction(4 + value.len())?;
+        self.check_failing(READ_REGISTER)?;
+        for (byte, offset) in value.iter_mut().zip(0..) {
+            *byte = self.register(address.wrapping_add(offset));
+        }
+        Ok(())
+    }
+
+    fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), ()> {
+        self.ops.push(Op::WriteRegister(address, value.to_vec()));
+        self.transaction(3 + value.len())?;
+        self.check_failing(WRITE_REGISTER)?;
+        for (&byte, offset) in value.iter().zip(0..) {
+            let address = address.wrapping_add(offset);
+            if !self.read_only.contains(&address) {
+                self.registers.insert(address, byte);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), ()> {
+        self.ops.push(Op::ReadBuffer(offset, bytes.len()));
+        self.transaction(3 + bytes.len())?;
+        self.check_failing(READ_BUFFER)?;
+        for (byte, i) in bytes.iter_mut().zip(0..) {
+            *byte = self.buffer[usize::from(offset.wrapping_add(i))];
+        }
+        Ok(())
+    }
+
+    fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.ops.push(Op::WriteBuffer(offset, bytes.to_vec()));
+        self.transaction(2 + bytes.len())?;
+        self.check_failing(WRITE_BUFFER)?;
+        for (&byte, i) in bytes.iter().zip(0..) {
+            self.buffer[usize::from(offset.wrapping_add(i))] = byte;
+        }
+        Ok(())
+    }
+
+    /// Returns the content of a register.
+    pub fn register(&self, address: u16) -> u8 {
+        self.registers.get(&address).copied().unwrap_or(0)
+    }
+}
+
+/// Handle on a simulated radio, clones share the same radio
+///
+/// Commands answer the status byte followed by the scripted response of their opcode, if
+/// any. Otherwise ClearIrqStatus clears the IRQ status, GetIrqStatus reports it and
+/// GetPacketType reports the packet type last set, other responses are filled with the
+/// status byte.
+#[derive(Clone, Default)]
+pub struct Radio(Rc<RefCell<State>>);
+
+impl Radio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Borrows the simulated radio.
+    pub fn state(&self) -> std::cell::RefMut<'_, State> {
+        self.0.borrow_mut()
+    }
+
+    /// Raises `irq` whenever `opcode` is received.
+    pub fn raise_on(&self, opcode: u8, irq: u16) -> &Self {
+        self.state().raises.insert(opcode, irq);
+        self
+    }
+
+    /// Answers `opcode` with `parameters` after the status byte.
+    pub fn respond(&self, opcode: u8, parameters: &[u8]) -> &Self {
+        self.state().responses.insert(opcode, parameters.to_vec());
+        self
+    }
+
+    /// Queues IRQ statuses for the next GetIrqStatus commands.
+    pub fn script_irqs(&self, irqs: impl IntoIterator<Item = u16>) -> &Self {
+        self.state().irq_script.extend(irqs);
+        self
+    }
+
+    /// Sets the status byte of every response.
+    pub fn set_status(&self, status: u8) -> &Self {
+        self.state().status = status;
+        self
+    }
+
+    /// Sets the packet type reported by GetPacketType, 0 for GFSK and 1 for LoRa.
+    pub fn set_packet_type(&self, packet_type: u8) -> &Self {
+        self.state().packet_type = packet_type;
+        self
+    }
+
+    /// Makes `opcode` fail with a bus error, or succeed again.
+    pub fn set_failing(&self, opcode: u8, failing: bool) -> &Self {
+        let mut state = self.state();
+        if failing {
+            state.failing.insert(opcode);
+        } else {
+            state.failing.remove(&opcode);
+        }
+        drop(state);
+        self
+    }
+
+    /// Installs custom handling of commands, see [`Hook`].
+    pub fn set_hook(
+        &self,
+        hook: impl FnMut(&mut State, u8, &[u8], &mut [u8]) -> bool + 'static,
+    ) -> &Self {
+        self.state().hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Writes registers as the radio would.
+    pub fn set_registers(&self, address: u16, value: &[u8]) -> &Self {
+        let mut state = self.state();
+        for (&byte, offset) in value.iter().zip(0..) {
+            state.registers.insert(address.wrapping_add(offset), byte);
+        }
+        drop(state);
+        self
+    }
+
+    /// Returns the content of a register.
+    pub fn register(&self, address: u16) -> u8 {
+        self.state().register(address)
+    }
+
+    /// Returns every transaction so far.
+    pub fn ops(&self) -> Vec<Op> {
+        self.state().ops.clone()
+    }
+
+    /// Forgets the transactions so far.
+    pub fn clear(&self) {
+        self.state().ops.clear();
+    }
+
+    /// Returns the opcode and parameters of every command so far.
+    pub fn commands(&self) -> Vec<(u8, Vec<u8>)> {
+        self.state()
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Command(opcode, request) => Some((*opcode, request.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the opcode of every command so far.
+    pub fn opcodes(&self) -> Vec<u8> {
+        self.commands()
+            .into_iter()
+            .map(|(opcode, _)| opcode)
+            .collect()
+    }
+
+    /// Returns the parameters of the last `opcode` command.
+    pub fn last(&self, opcode: u8) -> Option<Vec<u8>> {
+        self.commands()
+            .into_iter()
+            .rev()
+            .find(|(op, _)| *op == opcode)
+            .map(|(_, request)| request)
+    }
+
+    /// Returns the number of `opcode` commands so far.
+    pub fn count(&self, opcode: u8) -> usize {
+        self.opcodes().iter().filter(|&&op| op == opcode).count()
+    }
+
+    /// Returns the address and bytes of every register write so far.
+    pub fn register_writes(&self) -> Vec<(u16, Vec<u8>)> {
+        self.state()
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::WriteRegister(address, value) => Some((*address, value.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl RadioBus for Radio {
+    type Error = ();
+
+    fn command(&mut self, opcode: u8, request: &[u8], response: &mut [u8]) -> Result<(), ()> {
+        self.state().command(opcode, request, response)
+    }
+
+    fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), ()> {
+        self.state().read_register(address, value)
+    }
+
+    fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), ()> {
+        self.state().write_register(address, value)
+    }
+
+    fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), ()> {
+        self.state().read_buffer(offset, bytes)
+    }
+
+    fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.state().write_buffer(offset, bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+impl sx1262::bus::AsyncRadioBus for Radio {
+    type Error = ();
+
+    async fn command(&mut self, opcode: u8, request: &[u8], response: &mut [u8]) -> Result<(), ()> {
+        self.state().command(opcode, request, response)
+    }
+
+    async fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), ()> {
+        self.state().read_register(address, value)
+    }
+
+    async fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), ()> {
+        self.state().write_register(address, value)
+    }
+
+    async fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), ()> {
+        self.state().read_buffer(offset, bytes)
+    }
+
+    async fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.state().write_buffer(offset, bytes)
+    }
+}
+
+/// A [`Radio`] whose async commands are pending once before completing, like a bus waiting
+/// for a DMA transfer
+#[cfg(feature = "async")]
+#[derive(Clone, Default)]
+pub struct Yielding(pub Radio);
+
+#[cfg(feature = "async")]
+async fn yield_now() {
+    let mut yielded = false;
+    core::future::poll_fn(|cx| {
+        if yielded {
+            return core::task::Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        core::task::Poll::Pending
+    })
+    .await
+}
+
+#[cfg(feature = "async")]
+impl sx1262::bus::AsyncRadioBus for Yielding {
+    type Error = ();
+
+    async fn command(&mut self, opcode: u8, request: &[u8], response: &mut [u8]) -> Result<(), ()> {
+        yield_now().await;
+        self.0.state().command(opcode, request, response)
+    }
+
+    async fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), ()> {
+        self.0.state().read_register(address, value)
+    }
+
+    async fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), ()> {
+        self.0.state().write_register(address, value)
+    }
+
+    async fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), ()> {
+        self.0.state().read_buffer(offset, bytes)
+    }
+
+    async fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.0.state().write_buffer(offset, bytes)
+    }
+}
+
+/// A transaction seen by a [`Chip`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    /// Chip select of the device
+    pub cs: u8,
+    /// Bytes written
+    pub written: Vec<u8>,
+    /// Number of bytes read
+    pub read: usize,
+}
+
+/// SPI device recording its transactions on a bus shared with other chips
+///
+/// Chips with a chip select other than 0 read their chip select, chip 0 reads the bytes given
+/// by `read` from their position in the transaction.
+#[derive(Clone)]
+pub struct Chip {
+    pub cs: u8,
+    pub bus: Rc<RefCell<Vec<Transaction>>>,
+    pub read: fn(usize) -> u8,
+}
+
+impl Chip {
+    /// A chip alone on its bus, reading the status byte until `read` is changed.
+    pub fn new() -> Self {
+        Self::on(Rc::default(), 0)
+    }
+
+    /// A chip sharing `bus`.
+    pub fn on(bus: Rc<RefCell<Vec<Transaction>>>, cs: u8) -> Self {
+        Self {
+            cs,
+            bus,
+            read: |_| STATUS,
+        }
+    }
+
+    /// Returns every transaction on the bus so far.
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.bus.borrow().clone()
+    }
+}
+
+impl SpiErrorType for Chip {
+    type Error = Infallible;
+}
+
+impl SpiDevice for Chip {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+        // Holding the borrow for the whole transaction, like RefCellDevice
+        let mut bus = self.bus.borrow_mut();
+        let mut transaction = Transaction {
+            cs: self.cs,
+            written: Vec::new(),
+            read: 0,
+        };
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => transaction.written.extend_from_slice(bytes),
+                Operation::Read(bytes) => {
+                    for byte in bytes.iter_mut() {
+                        *byte = if self.cs == 0 {
+                            (self.read)(transaction.read)
+                        } else {
+                            self.cs
+                        };
+                        transaction.read += 1;
+                    }
+                }
+                _ => unimplemented!(),
+            }
+        }
+        bus.push(transaction);
+        Ok(())
+    }
+}
+
+/// Digital line failing to be read or driven
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinFault;
+
+impl embedded_hal::digital::Error for PinFault {
+    fn kind(&self) -> embedded_hal::digital::ErrorKind {
+        embedded_hal::digital::ErrorKind::Other
+    }
+}
+
+/// The simulated line behind a [`Pin`]
+#[derive(Debug, Default)]
+pub struct Line {
+    /// Levels read next, before `high`
+    pub script: VecDeque<bool>,
+    /// Level read once the script is exhausted, or last driven
+    pub high: bool,
+    /// Levels driven, in order
+    pub driven: Vec<bool>,
+    /// Number of reads
+    pub reads: usize,
+    /// Whether reading or driving fails
+    pub faulty: bool,
+}
+
+/// Handle on a simulated digital line, clones share the same line
+#[derive(Debug, Clone, Default)]
+pub struct Pin(pub Rc<RefCell<Line>>);
+
+impl Pin {
+    /// A line read high.
+    pub fn high() -> Self {
+        let pin = Self::default();
+        pin.0.borrow_mut().high = true;
+        pin
+    }
+
+    /// A line read low.
+    pub fn low() -> Self {
+        Self::default()
+    }
+
+    /// A line read as `levels`, then at the last one.
+    pub fn scripted(levels: impl IntoIterator<Item = bool>) -> Self {
+        let pin = Self::default();
+        let mut line = pin.0.borrow_mut();
+        line.script.extend(levels);
+        line.high = line.script.back().copied().unwrap_or(false);
+        drop(line);
+        pin
+    }
+
+    /// Borrows the simulated line.
+    pub fn line(&self) -> std::cell::RefMut<'_, Line> {
+        self.0.borrow_mut()
+    }
+}
+
+impl PinErrorType for Pin {
+    type Error = PinFault;
+}
+
+impl InputPin for Pin {
+    fn is_high(&mut self) -> Result<bool, PinFault> {
+        let mut line = self.line();
+        if line.faulty {
+            return Err(PinFault);
+        }
+        line.reads += 1;
+        let high = line.high;
+        Ok(line.script.pop_front().unwrap_or(high))
+    }
+
+    fn is_low(&mut self) -> Result<bool, PinFault> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+impl OutputPin for Pin {
+    fn set_low(&mut self) -> Result<(), PinFault> {
+        self.drive(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), PinFault> {
+        self.drive(true)
+    }
+}
+
+impl Pin {
+    fn drive(&mut self, high: bool) -> Result<(), PinFault> {
+        let mut line = self.line();
+        if line.faulty {
+            return Err(PinFault);
+        }
+        line.driven.push(high);
+        line.high = high;
+        Ok(())
+    }
+}
+
+/// Delay recording the requested sleeps in nanoseconds, clones share the record
+#[derive(Debug, Clone, Default)]
+pub struct Delay(pub Rc<RefCell<Vec<u64>>>);
+
+impl Delay {
+    /// Returns every sleep so far in nanoseconds.
+    pub fn sleeps_ns(&self) -> Vec<u64> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns every sleep so far in milliseconds, rounded down.
+    pub fn sleeps_ms(&self) -> Vec<u64> {
+        self.sleeps_ns().iter().map(|ns| ns / 1_000_000).collect()
+    }
+
+    /// Returns the time slept so far in microseconds.
+    pub fn total_us(&self) -> u64 {
+        self.sleeps_ns().iter().sum::<u64>() / 1_000
+    }
+
+    /// Returns the number of sleeps so far.
+    pub fn count(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Forgets the sleeps so far.
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+impl DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.borrow_mut().push(u64::from(ns));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.0.borrow_mut().push(u64::from(us) * 1_000);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.0.borrow_mut().push(u64::from(ms) * 1_000_000);
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        DelayNs::delay_ns(self, ns);
+    }
+
+    async fn delay_us(&mut self, us: u32) {
+        DelayNs::delay_us(self, us);
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        DelayNs::delay_ms(self, ms);
+    }
+}
+
+/// BUSY line recording the timeout of every wait, clones share the record
+#[derive(Debug, Clone, Default)]
+pub struct Busy {
+    waits: Rc<RefCell<Vec<u32>>>,
+    stuck: Rc<Cell<bool>>,
+}
+
+impl Busy {
+    /// Returns the timeout of every wait so far in microseconds.
+    pub fn waits(&self) -> Vec<u32> {
+        self.waits.borrow().clone()
+    }
+
+    /// Keeps BUSY high for the whole timeout of every wait, or lets it go low again.
+    pub fn set_stuck(&self, stuck: bool) {
+        self.stuck.set(stuck);
+    }
+}
+
+impl BusyWait for Busy {
+    fn wait_ready(&mut self, timeout_us: u32) -> Result<(), BusyError> {
+        self.waits.borrow_mut().push(timeout_us);
+        if self.stuck.get() {
+            Err(BusyError::Stuck {
+                waited_us: timeout_us,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl sx1262::pins::AsyncBusyWait for Busy {
+    async fn wait_ready(&mut self, timeout_us: u32) -> Result<(), BusyError> {
+        BusyWait::wait_ready(self, timeout_us)
+    }
+}
+
+/// Transition of an RF front-end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Switch {
+    Tx,
+    Rx,
+    Idle,
+}
+
+/// RF front-end recording every transition with the number of commands the [`Radio`] had
+/// seen, clones share the record
+#[derive(Clone)]
+pub struct Frontend {
+    radio: Radio,
+    switches: Rc<RefCell<Vec<(Switch, usize)>>>,
+    failing: Rc<Cell<bool>>,
+}
+
+impl Frontend {
+    /// A front-end ordered against the commands sent to `radio`.
+    pub fn new(radio: &Radio) -> Self {
+        Self {
+            radio: radio.clone(),
+            switches: Rc::default(),
+            failing: Rc::default(),
+        }
+    }
+
+    /// Returns every transition so far, with the number of commands sent before it.
+    pub fn switches(&self) -> Vec<(Switch, usize)> {
+        self.switches.borrow().clone()
+    }
+
+    /// Makes every transition fail, or succeed again.
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.set(failing);
+    }
+
+    fn switch(&mut self, switch: Switch) -> Result<(), ()> {
+        let sent = self.radio.commands().len();
+        self.switches.borrow_mut().push((switch, sent));
+        if self.failing.get() {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl RfFrontend for Frontend {
+    type Error = ();
+
+    fn enter_tx(&mut self) -> Result<(), ()> {
+        self.switch(Switch::Tx)
+    }
+
+    fn enter_rx(&mut self) -> Result<(), ()> {
+        self.switch(Switch::Rx)
+    }
+
+    fn enter_idle(&mut self) -> Result<(), ()> {
+        self.switch(Switch::Idle)
+    }
+}
+
+/// Runs a future that never waits on anything, as the async helpers over the [`Radio`] do.
+#[cfg(feature = "async")]
+pub fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    use core::task::{Context, Poll, Waker};
+
+    let mut future = core::pin::pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,463 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::{Busy, Delay, Radio};
+use regiface::Register;
+use sx1262::{
+    error::ErrorKind, Calibrate, CalibrationConfig, ConfigChanges, Device, DioIrqConfig, DioPin,
+    GfskInit, ImageCalibConfig, IrqMask, LoRaBandwidth, LoRaInit, LoRaModParams, ModulationParams,
+    OperatingMode, PacketType, PinOwner, RadioConfig, RegulatorMode, RetentionList,
+    RfFrequencyConfig, RxGain, SetModulationParams, SetPacketParams, SetPacketType,
+    SpreadingFactor, TcxoConfig, TcxoVoltage, TurnaroundProfile,
+};
+
+/// TX modulation register, bit 2 is cleared for LoRa 500 kHz
+const TX_MODULATION: u16 = 0x0889;
+/// IQ polarity register, bit 2 is cleared for inverted IQ
+const IQ_POLARITY: u16 = 0x0736;
+
+fn lora(frequency: u32) -> RadioConfig {
+    LoRaInit::new(RfFrequencyConfig { frequency })
+        .config()
+        .unwrap()
+}
+
+fn lora_with(bandwidth: LoRaBandwidth, inverted_iq: bool) -> RadioConfig {
+    let mut init = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    });
+    init.modulation = LoRaModParams::new(SpreadingFactor::SF7, bandwidth);
+    init.packet.iq_inversion_enable = inverted_iq;
+    init.config().unwrap()
+}
+
+/// A radio with the registers of the reversible workarounds at their reset values
+fn reset_radio() -> Radio {
+    let radio = Radio::new();
+    radio
+        .set_registers(TX_MODULATION, &[0x05])
+        .set_registers(IQ_POLARITY, &[0x0D]);
+    radio
+}
+
+#[test]
+fn dropped_packet_type_fails_before_the_frequency() {
+    for paranoid in [false, true] {
+        let radio = Radio::new();
+        // SetPacketType is dropped, the radio stays in GFSK
+        radio.set_hook(|_, opcode, _, _| opcode == 0x8A);
+        let mut device = Device::new(radio.clone());
+        device.set_paranoid_checks(paranoid);
+
+        let err = device.apply_config(&lora(868_100_000)).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::ConfigVerificationFailed {
+                expected: PacketType::LoRa,
+                actual: PacketType::Gfsk,
+            }
+        );
+        assert_eq!(err.step(), Some(3));
+        assert_eq!(device.packet_type(), Some(PacketType::Gfsk));
+
+        // Failed before the frequency was sent, and read back once
+        assert_eq!(radio.count(0x86), 0);
+        assert_eq!(radio.count(0x11), 1);
+    }
+}
+
+/// Asserts the registers of the workarounds read back as the cached configuration implies.
+fn assert_workarounds_read_back(device: &mut Device<Radio>) {
+    let report = device.validate_current_config().unwrap();
+    assert!(report.checked.contains(ConfigChanges::WORKAROUNDS));
+    assert!(report
+        .mismatches()
+        .all(|mismatch| mismatch.item != ConfigChanges::WORKAROUNDS));
+}
+
+#[test]
+fn workarounds_are_undone_by_a_configuration_not_requiring_them() {
+    let radio = reset_radio();
+    let mut device = Device::new(radio.clone());
+
+    device
+        .apply_config(&lora_with(LoRaBandwidth::Bw500, true))
+        .unwrap();
+    assert_eq!(radio.register(TX_MODULATION), 0x01);
+    assert_eq!(radio.register(IQ_POLARITY), 0x09);
+    assert_workarounds_read_back(&mut device);
+
+    // Back to 125 kHz and standard IQ, both registers are restored
+    device
+        .apply_config(&lora_with(LoRaBandwidth::Bw125, false))
+        .unwrap();
+    assert_eq!(radio.register(TX_MODULATION), 0x05);
+    assert_eq!(radio.register(IQ_POLARITY), 0x0D);
+    assert_workarounds_read_back(&mut device);
+}
+
+#[test]
+fn config_diff_undoes_workarounds_no_longer_required() {
+    let radio = reset_radio();
+    let mut device = Device::new(radio.clone());
+    device
+        .apply_config_diff(&lora_with(LoRaBandwidth::Bw500, true))
+        .unwrap();
+    assert_eq!(radio.register(TX_MODULATION), 0x01);
+    assert_eq!(radio.register(IQ_POLARITY), 0x09);
+
+    // Only the modulation and packet change, both registers are restored
+    let changes = device
+        .apply_config_diff(&lora_with(LoRaBandwidth::Bw125, false))
+        .unwrap();
+    assert_eq!(
+        changes,
+        ConfigChanges::MODULATION | ConfigChanges::PACKET | ConfigChanges::WORKAROUNDS
+    );
+    assert_eq!(radio.register(TX_MODULATION), 0x05);
+    assert_eq!(radio.register(IQ_POLARITY), 0x0D);
+    assert_workarounds_read_back(&mut device);
+}
+
+#[test]
+fn snapshot_restores_the_same_command_stream() {
+    let radio = reset_radio();
+    let mut device = Device::new(radio.clone());
+    assert_eq!(device.snapshot_config(), None);
+
+    let mut config = lora_with(LoRaBandwidth::Bw500, true);
+    config.tcxo = Some(TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    });
+    device.apply_config(&config).unwrap();
+    let snapshot = device.snapshot_config().unwrap();
+    assert_eq!(snapshot, config);
+
+    // Restored on a freshly reset radio, workarounds and calibration included
+    let restored = reset_radio();
+    let mut fresh = Device::new(restored.clone());
+    fresh.apply_config(&snapshot).unwrap();
+    assert_eq!(restored.ops(), radio.ops());
+    assert_eq!(restored.register(TX_MODULATION), 0x01);
+    assert_eq!(restored.register(IQ_POLARITY), 0x09);
+    assert_eq!(fresh.snapshot_config(), Some(config));
+}
+
+#[test]
+fn config_diff_sends_what_changed() {
+    let radio = Radio::new();
+    let sent = || {
+        let opcodes = radio.opcodes();
+        radio.clear();
+        opcodes
+    };
+    let mut device = Device::new(radio.clone());
+
+    // The first configuration is applied in full
+    let mut config = lora(868_100_000);
+    let changes = device.apply_config_diff(&config).unwrap();
+    assert!(changes.contains(ConfigChanges::PACKET_TYPE | ConfigChanges::FREQUENCY));
+    assert!(sent().contains(&0x8A));
+
+    // Nothing changed, nothing sent
+    assert_eq!(
+        device.apply_config_diff(&config).unwrap(),
+        ConfigChanges::empty()
+    );
+    assert!(sent().is_empty());
+
+    // A hop within the calibrated band only sends SetRfFrequency
+    config.frequency.frequency = 868_300_000;
+    assert_eq!(
+        device.apply_config_diff(&config).unwrap(),
+        ConfigChanges::FREQUENCY
+    );
+    assert_eq!(sent(), [0x86]);
+
+    // A hop and a data rate change are sent in the apply_config order, the LoRa 500 kHz
+    // workaround is re-evaluated
+    config.frequency.frequency = 868_500_000;
+    let ModulationParams::LoRa(params) = &mut config.modulation else {
+        unreachable!()
+    };
+    *params = LoRaModParams::new(SpreadingFactor::SF10, params.bandwidth);
+    assert_eq!(
+        device.apply_config_diff(&config).unwrap(),
+        ConfigChanges::FREQUENCY | ConfigChanges::MODULATION | ConfigChanges::WORKAROUNDS
+    );
+    assert_eq!(sent(), [0x86, 0x8B]);
+
+    // Switching to GFSK applies the whole configuration again
+    let gfsk = GfskInit::new(config.frequency).config().unwrap();
+    let changes = device.apply_config_diff(&gfsk).unwrap();
+    assert!(changes.contains(ConfigChanges::PACKET_TYPE));
+    let opcodes = sent();
+    assert_eq!(opcodes[..4], [0x80, 0x96, 0x9D, 0x8A]);
+    assert!(opcodes.contains(&0x8C));
+
+    // A detour through GFSK by hand, ending with the LoRa parameters sent again
+    let lora = LoRaInit::new(config.frequency).config().unwrap();
+    device.apply_config_diff(&lora).unwrap();
+    for (packet_type, config) in [(PacketType::Gfsk, &gfsk), (PacketType::LoRa, &lora)] {
+        device
+            .execute_command(SetPacketType { packet_type })
+            .unwrap();
+        device
+            .execute_command(SetModulationParams {
+                params: config.modulation,
+            })
+            .unwrap();
+        device
+            .execute_command(SetPacketParams {
+                params: config.packet,
+            })
+            .unwrap();
+    }
+    sent();
+
+    // The radio dropped its parameters on each switch, so none of them is skipped
+    let changes = device.apply_config_diff(&lora).unwrap();
+    assert!(changes.contains(ConfigChanges::PACKET_TYPE));
+    let opcodes = sent();
+    assert!([0x8A, 0x8B, 0x8C]
+        .iter()
+        .all(|opcode| opcodes.contains(opcode)));
+}
+
+#[test]
+fn tcxo_control_and_dio3_routes_exclude_each_other() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let tcxo = TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    };
+
+    // CAD_DONE routed to DIO3 keeps the TCXO off
+    device
+        .modify_irq_routing(|routing| {
+            routing.irq_mask |= IrqMask::CAD_DONE;
+            routing.dio3_mask |= IrqMask::CAD_DONE;
+        })
+        .unwrap();
+    let err = device.set_dio3_as_tcxo_ctrl(tcxo).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PinInUse {
+            pin: DioPin::Dio3,
+            owner: PinOwner::IrqMapping
+        }
+    );
+    assert_eq!(radio.opcodes(), [0x08]);
+
+    // Forced: SetDio3AsTcxoCtrl, ClearDeviceErrors and Calibrate
+    device.force_dio3_as_tcxo_ctrl(tcxo).unwrap();
+    assert_eq!(radio.opcodes(), [0x08, 0x97, 0x07, 0x89]);
+
+    // DIO3 now powers the TCXO, new routes to it are refused unless forced
+    let route_tx = |routing: &mut DioIrqConfig| {
+        routing.irq_mask |= IrqMask::TX_DONE;
+        routing.dio3_mask |= IrqMask::TX_DONE;
+    };
+    let err = device.modify_irq_routing(route_tx).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PinInUse {
+            pin: DioPin::Dio3,
+            owner: PinOwner::TcxoControl
+        }
+    );
+    device
+        .modify_irq_routing(|routing| routing.dio1_mask |= IrqMask::CAD_DONE)
+        .unwrap();
+    device.force_irq_routing(route_tx).unwrap();
+    assert_eq!(radio.count(0x08), 3);
+}
+
+#[test]
+fn retention_list_is_read_back() {
+    let list = RetentionList::from_registers(&[RxGain::id()]).unwrap();
+
+    let mut device = Device::new(Radio::new());
+    device.apply_retention(&list).unwrap();
+
+    // A stuck radio keeps the retention count at 0
+    let radio = Radio::new();
+    radio.state().read_only.insert(RetentionList::id());
+    let mut device = Device::new(radio);
+    assert!(device.apply_retention(&list).is_err());
+}
+
+#[test]
+fn image_calibration_covers_a_span() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    // One calibration over the 863-870 and 902-928MHz bands
+    device
+        .calibrate_image(ImageCalibConfig {
+            freq1: 0xD7,
+            freq2: 0xE9,
+        })
+        .unwrap();
+    assert_eq!(device.image_calibration_span(), Some(860..=932));
+
+    for frequency in [868_100_000, 915_000_000] {
+        device.apply_config(&lora(frequency)).unwrap();
+    }
+    assert_eq!(radio.count(0x98), 1);
+
+    // Outside the span the image is calibrated again
+    device.apply_config(&lora(433_920_000)).unwrap();
+    assert_eq!(radio.count(0x98), 2);
+    assert_eq!(device.image_calibration_span(), Some(428..=444));
+}
+
+#[test]
+fn calibration_waits_for_busy_or_the_worst_case() {
+    // With BUSY, the wait after the command allows for twice the worst case
+    let busy = Busy::default();
+    let mut device = Device::new(Radio::new()).with_busy(busy.clone());
+    device.set_busy_timeout_us(1_000);
+    device
+        .execute_command(Calibrate {
+            config: CalibrationConfig::all(),
+        })
+        .unwrap();
+    assert_eq!(busy.waits(), [1_000, 7_000]);
+
+    // Without BUSY, the owned delay waits for the worst case
+    let delay = Delay::default();
+    let mut device = Device::new(Radio::new()).with_delay(delay.clone());
+    device
+        .calibrate(CalibrationConfig::PLL | CalibrationConfig::IMAGE)
+        .unwrap();
+    assert_eq!(delay.sleeps_ns(), [1_700_000]);
+}
+
+/// Records the number of sleeps of `delay` preceding every command sent to `radio`
+fn sleeps_before_commands(radio: &Radio, delay: &Delay) -> Rc<RefCell<Vec<(u8, usize)>>> {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let (record, delay) = (seen.clone(), delay.clone());
+    radio.set_hook(move |_, opcode, _, _| {
+        record.borrow_mut().push((opcode, delay.count()));
+        false
+    });
+    seen
+}
+
+#[test]
+fn every_calibration_is_waited_out_without_busy() {
+    let lora = |tcxo| {
+        let mut init = LoRaInit::new(RfFrequencyConfig {
+            frequency: 868_100_000,
+        });
+        init.tcxo = tcxo;
+        init
+    };
+    let tcxo = Some(TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    });
+    // The worst case of a full calibration is slept right after Calibrate
+    let slept_after_calibrate = |seen: &[(u8, usize)]| {
+        let calibrate = seen.iter().position(|&(opcode, _)| opcode == 0x89).unwrap();
+        seen[calibrate].1 == 0 && seen[calibrate + 1].1 == 1
+    };
+
+    // Bring-up with and without a TCXO
+    for tcxo in [None, tcxo] {
+        let (radio, delay) = (Radio::new(), Delay::default());
+        let seen = sleeps_before_commands(&radio, &delay);
+        let mut device = Device::new(radio).with_delay(delay.clone());
+        device.init_lora(lora(tcxo)).unwrap();
+        assert!(slept_after_calibrate(&seen.borrow()));
+        assert_eq!(delay.sleeps_ns(), [3_500_000]);
+    }
+
+    // A configuration powering a TCXO
+    let (radio, delay) = (Radio::new(), Delay::default());
+    let seen = sleeps_before_commands(&radio, &delay);
+    let mut device = Device::new(radio).with_delay(delay.clone());
+    device.apply_config(&lora(tcxo).config().unwrap()).unwrap();
+    assert!(slept_after_calibrate(&seen.borrow()));
+    assert_eq!(delay.sleeps_ns(), [3_500_000]);
+}
+
+#[test]
+fn regulator_mode_is_only_set_in_standby() {
+    let radio = Radio::new();
+    // Follows the standby mode, starting in RX
+    radio
+        .set_status(0x54)
+        .set_hook(|state, opcode, request, _| {
+            if opcode == 0x80 {
+                state.status = if request[0] == 0 { 0x24 } else { 0x34 };
+            }
+            false
+        });
+    let sent = || -> Vec<(u8, Vec<u8>)> {
+        let commands = radio.commands();
+        radio.clear();
+        commands
+            .into_iter()
+            .filter(|(opcode, _)| *opcode != 0xC0)
+            .collect()
+    };
+    let mut device = Device::new(radio.clone());
+
+    // Rejected in RX
+    let err = device
+        .set_regulator_mode(RegulatorMode::DcDcLdo)
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::WrongMode {
+            found: Some(OperatingMode::Receive)
+        }
+    );
+    assert_eq!(err.step(), Some(0));
+    assert!(sent().is_empty());
+
+    // Sent in STDBY_RC
+    radio.set_status(0x24);
+    device.set_regulator_mode(RegulatorMode::DcDcLdo).unwrap();
+    assert_eq!(sent(), [(0x96, vec![0x01])]);
+
+    // Routed through STDBY_RC when idling in STDBY_XOSC
+    device
+        .set_turnaround_profile(TurnaroundProfile::LowLatency)
+        .unwrap();
+    sent();
+    device.set_regulator_mode(RegulatorMode::DcDcLdo).unwrap();
+    assert_eq!(
+        sent(),
+        [(0x80, vec![0x00]), (0x96, vec![0x01]), (0x80, vec![0x01])]
+    );
+    assert_eq!(radio.state().status >> 4, 0x3);
+}
+
+#[test]
+fn rx_gain_is_optionally_retained() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    // The gain register alone
+    device.set_rx_gain(RxGain::Boosted, false).unwrap();
+    assert_eq!(radio.register_writes(), [(0x08AC, vec![0x96])]);
+    radio.clear();
+
+    // Retained: 0x01 at 0x029F, then the RX gain address 0x08AC at 0x02A0 and 0x02A1
+    device.set_rx_gain(RxGain::Boosted, true).unwrap();
+    assert_eq!(
+        radio.register_writes(),
+        [
+            (0x08AC, vec![0x96]),
+            (0x029F, vec![0x01, 0x08, 0xAC, 0, 0, 0, 0, 0, 0])
+        ]
+    );
+}
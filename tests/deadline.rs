@@ -0,0 +1,49 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use core::time::Duration;
+
+use common::{Delay, Radio};
+use sx1262::{
+    error::{RxError, TxError},
+    Device, IrqMask,
+};
+
+#[test]
+fn deadlines_are_enforced_by_the_radio() {
+    let radio = Radio::new();
+    radio
+        .raise_on(0x83, IrqMask::TIMEOUT.bits())
+        .raise_on(0x82, IrqMask::TIMEOUT.bits());
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+
+    // 1 s and 100 ms in steps of 15.625 µs
+    let result = device.transmit_timeout(&[1, 2], Duration::from_secs(1));
+    assert!(matches!(result, Err(TxError::Timeout)));
+    assert_eq!(radio.last(0x83).unwrap(), [0x00, 0xFA, 0x00]);
+
+    let mut payload = [0; 8];
+    let result = device.receive_timeout(&mut payload, Duration::from_millis(100));
+    assert!(matches!(result, Err(RxError::Timeout)));
+    assert_eq!(radio.last(0x82).unwrap(), [0x00, 0x19, 0x00]);
+}
+
+#[test]
+fn sleep_wakes_the_radio_after_the_duration() {
+    let radio = Radio::new();
+    let delay = Delay::default();
+    let mut device = Device::new(radio.clone()).with_delay(delay.clone());
+
+    // Warm start, then woken and polled
+    device.sleep_for(Duration::from_millis(10)).unwrap();
+    assert_eq!(radio.opcodes(), [0x84, 0xC0, 0xC0]);
+    assert_eq!(radio.last(0x84).unwrap(), [0x04]);
+    assert_eq!(delay.sleeps_ns(), [10_000_000]);
+
+    // The radio is given time to save its configuration
+    delay.clear();
+    device.sleep_for(Duration::ZERO).unwrap();
+    assert_eq!(delay.sleeps_ns(), [500_000]);
+}
@@ -0,0 +1,44 @@
+use regiface::ToByteArray;
+use sx1262::{
+    CrcInitialValue, CrcPolynomial, DioIrqConfig, FallbackMode, ImageCalibConfig, IqPolaritySetup,
+    IrqMask, LoraSyncWord, OcpConfiguration, PacketType, RegulatorMode, RxGain, StandbyConfig,
+    TxClampConfig, TxModulation, WhiteningInitialValue, XtaTrim, XtbTrim,
+};
+
+/// Serializes the default value of a type
+fn default_bytes<T: Default + ToByteArray>() -> T::Array
+where
+    T::Error: core::fmt::Debug,
+{
+    T::default().to_bytes().unwrap()
+}
+
+#[test]
+fn register_defaults_are_the_reset_values() {
+    assert_eq!(default_bytes::<TxModulation>(), [0x05]);
+    assert_eq!(default_bytes::<TxClampConfig>(), [0xC8]);
+    assert_eq!(default_bytes::<IqPolaritySetup>(), [0x0D]);
+    assert_eq!(default_bytes::<RxGain>(), [0x94]);
+    assert_eq!(default_bytes::<WhiteningInitialValue>(), [0x01, 0x00]);
+    assert_eq!(default_bytes::<CrcInitialValue>(), [0x1D, 0x0F]);
+    assert_eq!(default_bytes::<CrcPolynomial>(), [0x10, 0x21]);
+    assert_eq!(default_bytes::<LoraSyncWord>(), [0x14, 0x24]);
+    assert_eq!(default_bytes::<XtaTrim>(), [0x05]);
+    assert_eq!(default_bytes::<XtbTrim>(), [0x05]);
+    // SX1261 value, SetPaConfig raises it to 0x38 for the SX1262
+    assert_eq!(OcpConfiguration::default().threshold, 0x18);
+}
+
+#[test]
+fn command_defaults_are_the_power_up_state() {
+    assert_eq!(PacketType::default(), PacketType::Gfsk);
+    assert_eq!(RegulatorMode::default(), RegulatorMode::LdoOnly);
+    assert_eq!(StandbyConfig::default(), StandbyConfig::Rc);
+    assert_eq!(default_bytes::<FallbackMode>(), [0x20]);
+    assert_eq!(default_bytes::<ImageCalibConfig>(), [0xE1, 0xE9]);
+
+    let irq = DioIrqConfig::default();
+    for mask in [irq.irq_mask, irq.dio1_mask, irq.dio2_mask, irq.dio3_mask] {
+        assert_eq!(mask, IrqMask::empty());
+    }
+}
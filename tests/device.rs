@@ -0,0 +1,293 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use common::{Busy, Chip, Delay, Op, Pin, Radio, Transaction};
+use regiface::Command;
+use sx1262::{
+    error::TxError,
+    pins::{NoPin, Pins},
+    CommandStatus, Device, Error, ErrorKind, IrqMask, OperatingMode, PreparedCommand,
+    RfFrequencyConfig, SetRfFrequency, SetStandby, SetTx, StandbyConfig, Timeout,
+};
+
+fn set_rf_frequency() -> SetRfFrequency {
+    SetRfFrequency {
+        config: RfFrequencyConfig {
+            frequency: 868_000_000,
+        },
+    }
+}
+
+#[test]
+fn transfers_are_split_beyond_the_max_transfer_size() {
+    let payload: Vec<u8> = (0..=254).collect();
+    let radio = Radio::new();
+    radio.set_registers(0x0700, &payload[..64]);
+    radio.state().max_transaction = Some(16);
+    let mut device = Device::new(radio.clone());
+
+    // Unlimited, the single transaction is refused
+    assert!(device.write_buffer(0x80, &payload).is_err());
+
+    // Split into transactions of 14 bytes written, 13 bytes read, wrapping around the buffer
+    device.set_max_transfer_size(Some(16));
+    device.write_buffer(0x80, &payload).unwrap();
+    let mut read = [0; 255];
+    device.read_buffer(0x80, &mut read).unwrap();
+    assert_eq!(read[..], payload[..]);
+
+    // Register span reads are split too, in 12 bytes
+    let mut span = [0; 40];
+    device.read_register_raw(0x0704, &mut span).unwrap();
+    assert_eq!(span[..], payload[4..44]);
+
+    // The refused transaction, then 19 writes, 20 buffer reads and 4 register reads
+    assert_eq!(radio.state().transactions, 1 + 19 + 20 + 4);
+}
+
+#[test]
+fn empty_payload_is_only_sent_when_allowed() {
+    let radio = Radio::new();
+    radio.raise_on(0x83, IrqMask::TX_DONE.bits());
+    let mut device = Device::new(radio);
+    device.assume_configured();
+
+    let rejected = device.transmit(&[], Timeout::default());
+    assert!(matches!(rejected, Err(TxError::EmptyPayload)));
+
+    // A wake-up frame
+    device.set_allow_empty_payload(true);
+    assert!(device.transmit(&[], Timeout::default()).is_ok());
+}
+
+#[test]
+fn paranoid_checks_report_failed_commands() {
+    // A failure to execute in every status byte
+    let radio = Radio::new();
+    radio.set_status(0x2A);
+    let mut device = Device::new(radio);
+    assert!(device.execute_command(set_rf_frequency()).is_ok());
+
+    device.set_paranoid_checks(true);
+    let err = device.execute_command(set_rf_frequency()).unwrap_err();
+    assert_eq!(
+        err,
+        Error::Command {
+            opcode: SetRfFrequency::id(),
+            step: None,
+            source: ErrorKind::CommandFailed {
+                status: CommandStatus::ExecutionFailure
+            },
+        }
+    );
+}
+
+#[test]
+fn paranoid_checks_detect_a_chip_reset() {
+    // A radio reset by a brown-out while in STDBY_XOSC
+    let radio = Radio::new();
+    radio.set_status(0x32);
+    let mut device = Device::new(radio.clone());
+    device.set_paranoid_checks(true);
+    device
+        .execute_command(SetStandby {
+            config: StandbyConfig::Xosc,
+        })
+        .unwrap();
+    device.execute_command(set_rf_frequency()).unwrap();
+
+    // STDBY_RC with a reserved command status
+    radio.set_status(0x22);
+    let err = device.execute_command(set_rf_frequency()).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PossibleChipReset {
+            expected: OperatingMode::StandbyXosc
+        }
+    );
+
+    // The radio is now known to be in STDBY_RC
+    device.execute_command(set_rf_frequency()).unwrap();
+}
+
+static CALLS: AtomicU32 = AtomicU32::new(0);
+
+fn clock() -> u32 {
+    CALLS.fetch_add(1, Ordering::Relaxed);
+    1_000
+}
+
+#[test]
+fn clock_timestamps_the_events() {
+    let radio = Radio::new();
+    radio.respond(0x12, &IrqMask::TX_DONE.bits().to_be_bytes());
+    let mut device = Device::new(radio);
+    device.assume_configured();
+    assert_eq!(device.transmit(&[1], Timeout::default()).unwrap().at, None);
+
+    device.set_clock(Some(clock));
+    assert_eq!(
+        device.transmit(&[1], Timeout::default()).unwrap().at,
+        Some(1_000)
+    );
+    assert_eq!(
+        device.transmit(&[1], Timeout::default()).unwrap().at,
+        Some(1_000)
+    );
+    if cfg!(not(feature = "metrics")) {
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+    }
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn raw_responses_are_captured_on_request() {
+    use sx1262::{GetStats, GetStatus};
+
+    // Clocks out a status that changes on every byte
+    let radio = Radio::new();
+    radio.set_hook(|_, _, _, response| {
+        for (byte, i) in response.iter_mut().zip(0..) {
+            *byte = 0x24 + i;
+        }
+        true
+    });
+    let mut device = Device::new(radio);
+    device.execute_command(GetStats).unwrap();
+    assert!(device.last_raw_response().is_none());
+
+    device.set_response_capture(true);
+    device.execute_command(GetStats).unwrap();
+    let raw = device.last_raw_response().unwrap();
+    assert_eq!(raw.opcode, 0x10);
+    assert_eq!(raw.bytes(), [0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2A]);
+
+    device.execute_command(GetStatus).unwrap();
+    assert_eq!(device.last_raw_response().unwrap().bytes(), [0x24]);
+}
+
+#[test]
+fn register_span_is_read_in_one_transaction() {
+    // Register bytes count up
+    let mut chip = Chip::new();
+    chip.read = |i| i as u8;
+    let mut device = Device::new(chip.clone());
+
+    // CrcInitialValue (0x06BC) to the end of SyncWord (0x06C7)
+    let mut span = [0; 12];
+    device.read_register_raw(0x06BC, &mut span).unwrap();
+    assert_eq!(span, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+
+    // The address is followed by a single NOP before the register bytes are clocked out
+    assert_eq!(
+        chip.transactions(),
+        [Transaction {
+            cs: 0,
+            written: vec![0x1D, 0x06, 0xBC, 0x00],
+            read: 12
+        }]
+    );
+}
+
+#[test]
+fn buffer_transfers_are_bounded() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let overrun = Error::Buffer {
+        offset: 0,
+        step: None,
+        source: ErrorKind::BufferOverrun { length: 256 },
+    };
+
+    // Empty transfers are skipped
+    device.write_buffer(0, &[]).unwrap();
+    device.read_buffer(0, &mut []).unwrap();
+
+    // 1 and 255 bytes are sent in one transaction each
+    device.write_buffer(0, &[0xAA]).unwrap();
+    device.read_buffer(0, &mut [0]).unwrap();
+    device.write_buffer(0, &[0xAA; 255]).unwrap();
+    device.read_buffer(0, &mut [0; 255]).unwrap();
+
+    // 256 bytes are refused without a transaction
+    assert_eq!(device.write_buffer(0, &[0xAA; 256]), Err(overrun));
+    assert_eq!(device.read_buffer(0, &mut [0; 256]), Err(overrun));
+
+    let lengths: Vec<_> = radio
+        .ops()
+        .into_iter()
+        .map(|op| match op {
+            Op::WriteBuffer(_, bytes) => bytes.len(),
+            Op::ReadBuffer(_, len) => len,
+            op => panic!("unexpected {op:?}"),
+        })
+        .collect();
+    assert_eq!(lengths, [1, 1, 255, 255]);
+}
+
+#[test]
+fn busy_is_waited_for_explicitly() {
+    let radio = Radio::new();
+    let busy = Busy::default();
+    let mut device = Device::new(radio.clone()).with_busy(busy.clone());
+    device.set_busy_timeout_us(1_000);
+    let tx = PreparedCommand::new(SetTx {
+        timeout: Timeout::default(),
+    });
+
+    // Hop, let the radio settle, then start TX at the slot boundary
+    let config = RfFrequencyConfig {
+        frequency: 915_200_000,
+    };
+    device.execute_command(SetRfFrequency { config }).unwrap();
+    device.wait_busy(500).unwrap();
+    device.execute_prepared(&tx).unwrap();
+    assert_eq!(radio.opcodes(), [0x86, 0x83]);
+    assert_eq!(busy.waits(), [1_000, 500, 1_000]);
+
+    busy.set_stuck(true);
+    assert_eq!(
+        device.wait_busy(0),
+        Err(Error::Control {
+            step: None,
+            source: ErrorKind::BusyStuck { waited_us: 0 }
+        })
+    );
+}
+
+#[test]
+fn owned_delay_is_used_unless_one_is_given() {
+    let radio = Radio::new();
+    radio.state().irq = IrqMask::TX_DONE.bits();
+    let dio1 = Pin::scripted([false, false, true]);
+    let delay = Delay::default();
+    let pins = Pins {
+        busy: NoPin,
+        reset: NoPin,
+        dio1: dio1.clone(),
+    };
+    let mut device = Device::new_with_parts(radio.clone(), pins, delay.clone());
+
+    // The owned DIO1 is read until high, with the owned delay between reads
+    let event = device.wait_irq_polled(100).unwrap();
+    assert_eq!(event.irq_mask, IrqMask::TX_DONE);
+    assert_eq!(dio1.line().reads, 3);
+    assert_eq!(delay.sleeps_ns(), [100_000, 100_000]);
+
+    // A delay given for one call is used instead
+    let mut lent = Delay::default();
+    dio1.line().script.extend([false, true]);
+    radio.state().irq = IrqMask::RX_DONE.bits();
+    let event = device.wait_irq_polled_with_delay(50, &mut lent).unwrap();
+    assert_eq!(event.irq_mask, IrqMask::RX_DONE);
+    assert_eq!(lent.sleeps_ns(), [50_000]);
+    assert_eq!(delay.count(), 2);
+
+    // Handed back with the other parts
+    let (_, _, pins, owned) = device.release_all();
+    assert_eq!(pins.dio1.line().reads, 5);
+    assert_eq!(owned.count(), 2);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{Device, PacketType, RxGain};
+
+#[test]
+fn discovers_a_configuration_left_by_a_bootloader() {
+    let radio = Radio::new();
+    // GFSK sync word, node and broadcast addresses
+    radio.set_registers(0x06C0, &[0xC1, 0x94, 0xC1]);
+    radio.set_registers(0x06CD, &[0x12, 0xFF]);
+    // Whitening seed, CRC initial value and polynomial
+    radio.set_registers(0x06B8, &[0x01, 0xFF]);
+    radio.set_registers(0x06BC, &[0x1D, 0x0F, 0x10, 0x21]);
+    // Public LoRa sync word, IQ polarity, RX gain and OCP threshold
+    radio.set_registers(0x0740, &[0x34, 0x44]);
+    radio.set_registers(0x0736, &[0x0D]);
+    radio.set_registers(0x08AC, &[0x96]);
+    radio.set_registers(0x08E7, &[0x38]);
+    // RX gain retained
+    radio.set_registers(0x029F, &[0x01, 0x08, 0xAC]);
+    let mut device = Device::new(radio.clone());
+
+    let config = device.discover_config().unwrap();
+    assert_eq!(config.packet_type, PacketType::Gfsk);
+    assert_eq!(
+        config.gfsk_sync_word.value,
+        [0xC1, 0x94, 0xC1, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        (
+            config.node_address.address,
+            config.broadcast_address.address
+        ),
+        (0x12, 0xFF)
+    );
+    assert_eq!(config.whitening.value, 0x01FF);
+    assert_eq!(config.crc_initial_value.value, 0x1D0F);
+    assert_eq!(config.crc_polynomial.value, 0x1021);
+    assert_eq!(config.lora_sync_word.value, 0x3444);
+    assert_eq!(config.iq_polarity.data, 0x0D);
+    assert_eq!(config.rx_gain, Some(RxGain::Boosted));
+    assert_eq!(config.ocp.threshold, 0x38);
+    assert!(config.retention_list.contains::<RxGain>());
+
+    // Set by the bootloader, not readable
+    assert_eq!(
+        (config.frequency, config.modulation, config.packet),
+        (None, None, None)
+    );
+    // Nothing is written
+    assert!(radio.register_writes().is_empty());
+}
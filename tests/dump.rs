@@ -0,0 +1,27 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Op, Radio};
+use sx1262::{registers::REGISTERS, Device};
+
+#[test]
+fn register_dump_reads_consecutive_registers_together() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let mut names = Vec::new();
+    device.dump_registers(|info, value| {
+        assert_eq!(value.unwrap().len(), info.len);
+        names.push(info.name);
+    });
+    assert_eq!(names.len(), REGISTERS.len());
+
+    let reads: Vec<_> = radio
+        .ops()
+        .into_iter()
+        .filter(|op| matches!(op, Op::ReadRegister(..)))
+        .collect();
+    assert!(reads.len() < REGISTERS.len());
+    // CrcInitialValue, CrcPolynomial and SyncWord
+    assert!(reads.contains(&Op::ReadRegister(0x06BC, 12)));
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{error::ErrorKind, Device, ImageCalibConfig, RfFrequencyConfig};
+
+#[test]
+fn frequency_change_calibrates_the_image_when_needed() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    device
+        .calibrate_image(ImageCalibConfig::BAND_863_870)
+        .unwrap();
+    radio.clear();
+
+    // Within the calibrated band only the frequency is sent
+    device
+        .set_rf_frequency(RfFrequencyConfig {
+            frequency: 869_525_000,
+        })
+        .unwrap();
+    assert_eq!(radio.commands(), [(0x86, vec![0x36, 0x58, 0x66, 0x66])]);
+    radio.clear();
+
+    // Another band is calibrated first
+    device
+        .set_rf_frequency(RfFrequencyConfig {
+            frequency: 915_000_000,
+        })
+        .unwrap();
+    assert_eq!(
+        radio.commands(),
+        [
+            (0x80, vec![0x00]),
+            (0x98, vec![0xE1, 0xE9]),
+            (0x86, vec![0x39, 0x30, 0x00, 0x00]),
+        ]
+    );
+    radio.clear();
+
+    // Out of range frequencies are rejected before any command
+    let out_of_range = RfFrequencyConfig {
+        frequency: 100_000_000,
+    };
+    assert!(device.set_rf_frequency(out_of_range).is_err());
+    assert!(radio.commands().is_empty());
+}
+
+#[test]
+fn pll_lock_error_is_reported_and_cleared() {
+    let radio = Radio::new();
+    // Enters FS on SetFs, ClearDeviceErrors clears the lock error
+    radio.respond(0x17, &[0, 0]);
+    radio.set_hook(|state, opcode, _, _| {
+        match opcode {
+            0x80 => state.status = 0x24,
+            0xC1 => state.status = 0x44,
+            0x07 => {
+                state.responses.insert(0x17, vec![0, 0]);
+            }
+            _ => {}
+        }
+        false
+    });
+    let mut device = Device::new(radio.clone());
+    let frequency = RfFrequencyConfig {
+        frequency: 868_100_000,
+    };
+
+    device.lock_pll(frequency).unwrap();
+    device.unlock_pll().unwrap();
+
+    radio.respond(0x17, &[0, 0x40]);
+    let err = device.lock_pll(frequency).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::PllLockFailed {
+            frequency: 868_100_000
+        }
+    );
+    assert_eq!(radio.state().responses[&0x17], [0, 0]);
+}
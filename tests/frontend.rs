@@ -0,0 +1,90 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Busy, Frontend, Radio, Switch};
+use sx1262::{
+    error::ErrorKind, Device, RxMode, SetCad, SetRx, SetStandby, SetTx, StandbyConfig, Timeout,
+};
+
+#[test]
+fn frontend_is_switched_around_mode_commands() {
+    let radio = Radio::new();
+    let frontend = Frontend::new(&radio);
+    let mut device = Device::new(radio.clone()).with_frontend(frontend.clone());
+
+    device
+        .execute_command(SetTx {
+            timeout: Timeout::default(),
+        })
+        .unwrap();
+    device
+        .execute_command(SetRx {
+            mode: RxMode::Continuous,
+        })
+        .unwrap();
+    device.execute_command(SetCad).unwrap();
+    device
+        .execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .unwrap();
+
+    // TX and RX before their command, idle after SetStandby
+    assert_eq!(radio.opcodes(), [0x83, 0x82, 0xC5, 0x80]);
+    assert_eq!(
+        frontend.switches(),
+        [
+            (Switch::Tx, 0),
+            (Switch::Rx, 1),
+            (Switch::Rx, 2),
+            (Switch::Idle, 4)
+        ]
+    );
+}
+
+#[test]
+fn frontend_returns_to_idle_when_the_command_fails() {
+    let tx = SetTx {
+        timeout: Timeout::default(),
+    };
+
+    // The command fails on the bus
+    let radio = Radio::new();
+    radio.set_failing(0x83, true);
+    let frontend = Frontend::new(&radio);
+    let mut device = Device::new(radio.clone()).with_frontend(frontend.clone());
+    let err = device.execute_command(tx.clone()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Bus);
+    assert_eq!(frontend.switches(), [(Switch::Tx, 0), (Switch::Idle, 1)]);
+
+    // BUSY never goes low, the command is not sent
+    let radio = Radio::new();
+    let busy = Busy::default();
+    busy.set_stuck(true);
+    let frontend = Frontend::new(&radio);
+    let mut device = Device::new(radio.clone())
+        .with_frontend(frontend.clone())
+        .with_busy(busy);
+    let err = device
+        .execute_command(SetRx {
+            mode: RxMode::Single,
+        })
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::BusyStuck { .. }));
+    assert!(radio.ops().is_empty());
+    assert_eq!(frontend.switches(), [(Switch::Rx, 0), (Switch::Idle, 0)]);
+}
+
+#[test]
+fn failed_switch_sends_nothing() {
+    let radio = Radio::new();
+    let frontend = Frontend::new(&radio);
+    frontend.set_failing(true);
+    let mut device = Device::new(radio.clone()).with_frontend(frontend.clone());
+
+    let err = device.execute_command(SetCad).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Frontend);
+    assert!(radio.ops().is_empty());
+    assert_eq!(frontend.switches(), [(Switch::Rx, 0)]);
+}
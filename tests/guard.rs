@@ -0,0 +1,85 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{bus::READ_BUFFER, ClearIrqStatus, Device, Error, GetIrqStatus, IrqMask, RxMode};
+
+#[test]
+fn mode_guard_restores_the_prior_mode() {
+    let radio = Radio::new();
+    // The radio is in STDBY_XOSC, buffer reads fail
+    radio.set_status(0x34).set_failing(READ_BUFFER, true);
+    let mut device = Device::new(radio.clone());
+
+    // Explicit finish: GetStatus, ClearIrqStatus, SetRx, then SetStandby(XOSC) and
+    // ClearIrqStatus
+    let rx = device.rx_guard(RxMode::Continuous).unwrap();
+    rx.finish().unwrap();
+    assert_eq!(radio.opcodes(), [0xC0, 0x02, 0x82, 0x80, 0x02]);
+    assert_eq!(radio.last(0x80).unwrap(), [0x01]);
+    radio.clear();
+
+    // Early return with `?`, the guard cleans up on drop
+    fn listen(device: &mut Device<Radio>) -> Result<(), Error> {
+        let mut cad = device.cad_guard()?;
+        cad.read_buffer(0, &mut [0; 4])?;
+        cad.finish()
+    }
+    assert!(listen(&mut device).is_err());
+    assert_eq!(radio.opcodes(), [0xC0, 0x02, 0xC5, 0x80, 0x02]);
+    assert_eq!(device.take_deferred_error(), None);
+    radio.clear();
+
+    // Continuous wave, dropped at the end of the scope
+    {
+        let _cw = device.tx_cw_guard().unwrap();
+    }
+    assert_eq!(radio.opcodes(), [0xC0, 0xD1, 0x80, 0x02]);
+}
+
+#[test]
+fn suspended_irqs_keep_their_enable_and_routing() {
+    let radio = Radio::new();
+    let routings = || {
+        radio
+            .commands()
+            .into_iter()
+            .filter(|(opcode, _)| *opcode == 0x08)
+    };
+    let mut device = Device::new(radio.clone());
+    device
+        .modify_irq_routing(|routing| {
+            routing.irq_mask = IrqMask::RX_DONE | IrqMask::TIMEOUT;
+            routing.dio1_mask = IrqMask::RX_DONE;
+            routing.dio2_mask = IrqMask::TIMEOUT;
+        })
+        .unwrap();
+    let routing = device.irq_routing().unwrap();
+    let original = routings().next().unwrap().1;
+
+    // The DIO masks are cleared, the IRQ enable is kept
+    let suspended = device.suspend_irqs().unwrap();
+    let [enable0, enable1] = routing.irq_mask.bits().to_be_bytes();
+    assert_eq!(
+        routings().nth(1).unwrap().1,
+        [enable0, enable1, 0, 0, 0, 0, 0, 0]
+    );
+    radio.state().irq = IrqMask::RX_DONE.bits();
+    suspended.resume().unwrap();
+
+    // The routing is restored byte for byte and the IRQ raised meanwhile is still pending
+    assert_eq!(routings().nth(2).unwrap().1, original);
+    assert_eq!(device.irq_routing(), Some(routing));
+    let irq_mask = device.execute_command(GetIrqStatus).unwrap().irq_mask;
+    assert_eq!(irq_mask, IrqMask::RX_DONE);
+    device.execute_command(ClearIrqStatus { irq_mask }).unwrap();
+
+    // Dropping the guard restores the routing too
+    {
+        let _suspended = device.suspend_irqs().unwrap();
+    }
+    assert_eq!(routings().count(), 5);
+    assert_eq!(routings().nth(4).unwrap().1, original);
+    assert_eq!(device.take_deferred_error(), None);
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{
+    Device, GfskInit, InitError, InitStep, LoRaInit, RfFrequencyConfig, TcxoConfig, TcxoVoltage,
+};
+
+const FREQUENCY: RfFrequencyConfig = RfFrequencyConfig {
+    frequency: 868_100_000,
+};
+
+const TCXO: TcxoConfig = TcxoConfig {
+    voltage: TcxoVoltage::V1_8,
+    delay: 320,
+};
+
+/// Commands of the bring-up from the packet type on, the same for both modes
+const CONFIGURATION: [u8; 11] = [
+    0x8A, 0x11, 0x00, 0x86, 0x95, 0x8E, 0x8B, 0x8C, 0x8F, 0x93, 0x08,
+];
+
+fn lora(tcxo: Option<TcxoConfig>) -> LoRaInit {
+    let mut init = LoRaInit::new(FREQUENCY);
+    init.tcxo = tcxo;
+    init
+}
+
+fn gfsk(tcxo: Option<TcxoConfig>) -> GfskInit {
+    let mut init = GfskInit::new(FREQUENCY);
+    init.tcxo = tcxo;
+    init
+}
+
+/// Returns the commands expected before the configuration, then the configuration.
+fn bring_up(tcxo: bool) -> Vec<u8> {
+    let mut opcodes = if tcxo {
+        // Calibrated by apply_config once the TCXO is powered
+        vec![0x80, 0x96, 0x80, 0x96, 0x9D, 0x97, 0x07, 0x89, 0x98]
+    } else {
+        vec![0x80, 0x96, 0x89, 0x80, 0x96, 0x9D, 0x98]
+    };
+    opcodes.extend(CONFIGURATION);
+    opcodes
+}
+
+#[test]
+fn bring_up_sends_the_documented_sequence() {
+    for tcxo in [None, Some(TCXO)] {
+        let radio = Radio::new();
+        let mut device = Device::new(radio.clone());
+        device.init_lora(lora(tcxo)).unwrap();
+        assert_eq!(radio.opcodes(), bring_up(tcxo.is_some()));
+        assert_eq!(radio.last(0x8A).unwrap(), [0x01]);
+
+        let radio = Radio::new();
+        let mut device = Device::new(radio.clone());
+        device.init_gfsk(gfsk(tcxo)).unwrap();
+        assert_eq!(radio.opcodes(), bring_up(tcxo.is_some()));
+        assert_eq!(radio.last(0x8A).unwrap(), [0x00]);
+    }
+}
+
+#[test]
+fn failures_name_the_bring_up_step() {
+    let failing_step = |tcxo, opcode| {
+        let radio = Radio::new();
+        radio.set_failing(opcode, true);
+        let mut device = Device::new(radio);
+        match device.init_lora(lora(tcxo)) {
+            Err(InitError::Device { step, .. }) => step,
+            result => panic!("{result:?}"),
+        }
+    };
+
+    // Calibrated before apply_config without a TCXO, within it with one
+    assert_eq!(failing_step(None, 0x89), InitStep::Calibrate);
+    assert_eq!(failing_step(Some(TCXO), 0x89), InitStep::Calibrate);
+    assert_eq!(failing_step(Some(TCXO), 0x97), InitStep::Tcxo);
+    assert_eq!(failing_step(Some(TCXO), 0x07), InitStep::Tcxo);
+    // Step 2 of apply_config without a TCXO command
+    assert_eq!(failing_step(None, 0x98), InitStep::ImageCalibration);
+    assert_eq!(failing_step(None, 0x80), InitStep::Standby);
+    assert_eq!(failing_step(None, 0x8B), InitStep::ModulationParams);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::{Chip, Radio, Transaction};
+use sx1262::{Device, DioIrqConfig, IrqMask, Timeout};
+
+#[test]
+fn irq_handle_commands_never_interleave_with_the_device() {
+    let bus = Rc::new(RefCell::new(Vec::new()));
+    let chip = Chip::on(bus.clone(), 0);
+    let (mut device, mut irq) = Device::new(chip.clone()).split_irq(chip);
+
+    device.write_buffer(0, &[0xAA, 0xBB]).unwrap();
+    // DIO1 interrupt
+    irq.take_irq().unwrap();
+    device.write_buffer(2, &[0xCC]).unwrap();
+
+    let written: Vec<_> = bus
+        .take()
+        .into_iter()
+        .map(|Transaction { written, .. }| written)
+        .collect();
+    assert_eq!(
+        written,
+        [
+            vec![0x0E, 0x00, 0xAA, 0xBB],
+            vec![0x12],
+            vec![0x02, 0x00, 0x24],
+            vec![0x0E, 0x02, 0xCC],
+        ]
+    );
+}
+
+#[test]
+fn irq_routes_are_only_sent_when_they_change() {
+    let radio = Radio::new();
+    radio.raise_on(0x83, IrqMask::TX_DONE.bits());
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+
+    // Route RX_DONE to DIO1, then again, which is skipped
+    let route_rx = |routing: &mut DioIrqConfig| {
+        routing.irq_mask |= IrqMask::RX_DONE;
+        routing.dio1_mask |= IrqMask::RX_DONE;
+    };
+    device.modify_irq_routing(route_rx).unwrap();
+    device.modify_irq_routing(route_rx).unwrap();
+    assert_eq!(radio.opcodes(), [0x08]);
+
+    // Transmitting adds its routes to the ones of the application
+    device.transmit(&[1], Timeout::default()).unwrap();
+    let routing = device.irq_routing().unwrap();
+    assert_eq!(
+        routing.dio1_mask,
+        IrqMask::RX_DONE | IrqMask::TX_DONE | IrqMask::TIMEOUT
+    );
+    assert_eq!(radio.count(0x08), 2);
+
+    // Once routed, transmitting sends no SetDioIrqParams
+    device.transmit(&[1], Timeout::default()).unwrap();
+    assert_eq!(radio.count(0x08), 2);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "linux")]
+//! Tests against a real radio on a Linux host, ignored by default
+//!
+//! The wiring is read from the environment, with the spidev device and GPIO chip defaulting to
+//! `/dev/spidev0.0` and `/dev/gpiochip0`:
+//!
+//! ```text
+//! SX1262_BUSY=20 SX1262_RESET=18 SX1262_DIO1=16 \
+//!     cargo test --features linux --test linux -- --ignored
+//! ```
+
+use std::{env, time::Duration};
+
+use sx1262::{
+    linux::{open, Wiring},
+    GetStatus, LoRaInit, LoraSyncWord, OperatingMode, RfFrequencyConfig, RxError,
+};
+
+/// Returns an environment variable, or the default when unset.
+fn var(name: &str, default: Option<&str>) -> String {
+    env::var(name)
+        .ok()
+        .or(default.map(String::from))
+        .unwrap_or_else(|| panic!("{name} is not set"))
+}
+
+/// Returns a GPIO line offset from the environment.
+fn line(name: &str) -> u32 {
+    var(name, None)
+        .parse()
+        .unwrap_or_else(|_| panic!("{name} is not a line offset"))
+}
+
+#[test]
+#[ignore = "needs a radio, see the top of the file"]
+fn hat_resets_configures_and_times_out() {
+    let spidev = var("SX1262_SPIDEV", Some("/dev/spidev0.0"));
+    let gpiochip = var("SX1262_GPIOCHIP", Some("/dev/gpiochip0"));
+    let mut device = open(&Wiring {
+        spidev: &spidev,
+        gpiochip: &gpiochip,
+        busy: line("SX1262_BUSY"),
+        reset: line("SX1262_RESET"),
+        dio1: line("SX1262_DIO1"),
+        spi_hz: 2_000_000,
+    })
+    .unwrap();
+
+    // Reset through NRESET, waiting for BUSY
+    device.recover().unwrap();
+    let status = device.execute_command(GetStatus).unwrap();
+    assert_eq!(status.mode, OperatingMode::StandbyRc);
+    assert_eq!(
+        device.read_register::<LoraSyncWord>().unwrap().value,
+        0x1424
+    );
+
+    let config = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    })
+    .config()
+    .unwrap();
+    device.apply_config(&config).unwrap();
+    assert!(device.validate_current_config().unwrap().is_consistent());
+
+    // Nothing is expected on the air, the RX timeout of the radio ends the receive
+    let mut payload = [0; 255];
+    let result = device.receive_timeout(&mut payload, Duration::from_millis(100));
+    assert!(matches!(result, Err(RxError::Timeout)));
+}
@@ -0,0 +1,58 @@
+#![cfg(all(feature = "sync", feature = "log"))]
+
+mod common;
+
+use std::sync::Mutex;
+
+use common::Radio;
+use log::{Log, Metadata, Record};
+use sx1262::{bus::WRITE_REGISTER, CrcPolynomial, Device, IrqMask, Timeout, WhiteningInitialValue};
+
+/// Keeps every record
+struct Capture(Mutex<Vec<String>>);
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let line = format!("{} {}", record.level(), record.args());
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn commands_irqs_and_failures_are_logged() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let radio = Radio::new();
+    radio.raise_on(0x83, IrqMask::TX_DONE.bits());
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+    device.transmit(&[1, 2, 3], Timeout::default()).unwrap();
+    device
+        .write_register(WhiteningInitialValue::default())
+        .unwrap();
+    radio.set_failing(WRITE_REGISTER, true);
+    device.write_register(CrcPolynomial::default()).unwrap_err();
+
+    let records = LOGGER.0.lock().unwrap();
+    assert!(records
+        .iter()
+        .any(|r| r.starts_with("DEBUG sx126x command 0x83")));
+    assert!(records
+        .iter()
+        .any(|r| r == "DEBUG sx126x irq IrqMask(TX_DONE)"));
+    assert!(records
+        .iter()
+        .any(|r| r == "TRACE sx126x register 0x06B8 <- [01, 00]"));
+    assert!(records
+        .iter()
+        .any(|r| r == "DEBUG sx126x register 0x06BE access failed: bus error"));
+}
@@ -0,0 +1,511 @@
+//! Two devices exchanging packets through a simulated channel
+//!
+//! Each [`Radio`] models the parts of an SX1262 the transmit and receive helpers rely on: the
+//! data buffer, the IRQ status, the packet parameters and the packet engine registers. SetTx
+//! puts a [`Frame`] on the shared [`Channel`], built from the buffer and the packet parameters
+//! with the software whitening and CRC of [`sx1262::gfsk::software`]. SetRx takes it back and
+//! raises the IRQs a receiver with its own settings would, so a TX and RX configuration that
+//! drifted apart fails here the way it does on the air.
+#![cfg(feature = "sync")]
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use sx1262::{
+    bus::RadioBus,
+    gfsk::software::{crc, whiten, CrcOptions},
+    CrcType, Device, GFSKPacketParams, GfskInit, LoRaHeaderMode, LoRaInit, LoRaPacketParams,
+    LoraSyncWord, RadioConfig, RfFrequencyConfig, RxError, RxMode, SyncWord, Timeout,
+};
+
+const FREQUENCY: RfFrequencyConfig = RfFrequencyConfig {
+    frequency: 868_100_000,
+};
+
+/// RX timeout of the receives, which the channel ends early when nothing was sent
+const RX_TIMEOUT: RxMode = RxMode::Timed(64_000);
+
+const GFSK: u8 = 0x00;
+const LORA: u8 = 0x01;
+
+const TX_DONE: u16 = 1 << 0;
+const RX_DONE: u16 = 1 << 1;
+const HEADER_ERROR: u16 = 1 << 4;
+const CRC_ERROR: u16 = 1 << 5;
+const TIMEOUT: u16 = 1 << 8;
+
+/// GFSK RxStatus bits
+const PACKET_RECEIVED: u8 = 1 << 1;
+const RX_CRC_ERROR: u8 = 1 << 4;
+
+/// Initial value and polynomial of the LoRa payload CRC
+///
+/// The modem's CRC is not documented, this stand-in only has to be the same on both ends.
+const LORA_CRC: (u16, u16) = (0x0000, 0x1021);
+
+/// What a receiver must share with the transmitter to demodulate a frame at all
+#[derive(Debug, Clone, PartialEq)]
+struct Link {
+    packet_type: u8,
+    frequency: Vec<u8>,
+    modulation: Vec<u8>,
+    /// LoRa sync word register, or the GFSK sync word bytes
+    sync_word: Vec<u8>,
+    /// LoRa IQ inversion
+    iq_inverted: bool,
+}
+
+/// A frame on the air
+#[derive(Debug, Clone)]
+struct Frame {
+    link: Link,
+    /// LoRa explicit header, with the payload length and CRC flag it carries
+    header: Option<(u8, bool)>,
+    /// Bytes following the sync word or header, whitened when the transmitter whitens
+    data: Vec<u8>,
+}
+
+type Channel = Rc<RefCell<Option<Frame>>>;
+
+/// A simulated radio on the channel
+struct Radio {
+    channel: Channel,
+    registers: HashMap<u16, u8>,
+    buffer: [u8; 256],
+    packet_type: u8,
+    frequency: Vec<u8>,
+    modulation: Vec<u8>,
+    packet: Vec<u8>,
+    tx_base: u8,
+    rx_base: u8,
+    irq: u16,
+    rx_len: u8,
+    packet_status: [u8; 3],
+}
+
+impl Radio {
+    fn new(channel: Channel) -> Self {
+        // Reset values of the registers the packet engine uses
+        let registers = [
+            // Whitening initial value
+            (0x06B8, 0x01),
+            (0x06B9, 0x00),
+            // CRC initial value
+            (0x06BC, 0x1D),
+            (0x06BD, 0x0F),
+            // CRC polynomial
+            (0x06BE, 0x10),
+            (0x06BF, 0x21),
+            // LoRa sync word
+            (0x0740, 0x14),
+            (0x0741, 0x24),
+        ];
+        Self {
+            channel,
+            registers: registers.into_iter().collect(),
+            buffer: [0; 256],
+            packet_type: GFSK,
+            frequency: Vec::new(),
+            modulation: Vec::new(),
+            packet: vec![0; 9],
+            tx_base: 0,
+            rx_base: 0,
+            irq: 0,
+            rx_len: 0,
+            packet_status: [0; 3],
+        }
+    }
+
+    fn register(&self, address: u16) -> u8 {
+        self.registers.get(&address).copied().unwrap_or(0)
+    }
+
+    fn register_u16(&self, address: u16) -> u16 {
+        u16::from_be_bytes([self.register(address), self.register(address + 1)])
+    }
+
+    fn lora(&self) -> bool {
+        self.packet_type == LORA
+    }
+
+    fn link(&self) -> Link {
+        let sync_word = if self.lora() {
+            vec![self.register(0x0740), self.register(0x0741)]
+        } else {
+            (0..u16::from(self.packet[3] / 8))
+                .map(|i| self.register(0x06C0 + i))
+                .collect()
+        };
+        Link {
+            packet_type: self.packet_type,
+            frequency: self.frequency.clone(),
+            modulation: self.modulation.clone(),
+            sync_word,
+            iq_inverted: self.lora() && self.packet[5] != 0,
+        }
+    }
+
+    /// Returns the GFSK CRC of `data` as sent, 0 to 2 bytes depending on the CRC type.
+    ///
+    /// A 1-byte CRC is approximated by the low byte of the 16-bit one.
+    fn gfsk_crc(&self, data: &[u8]) -> Vec<u8> {
+        let crc_type = self.packet[7];
+        let len = match crc_type {
+            0x01 => 0,
+            0x00 | 0x04 => 1,
+            _ => 2,
+        };
+        let options = CrcOptions {
+            reflect: false,
+            invert: crc_type & 0x04 != 0,
+        };
+        let value = crc(
+            self.register_u16(0x06BC),
+            self.register_u16(0x06BE),
+            options,
+            data,
+        );
+        value.to_be_bytes()[2 - len..].to_vec()
+    }
+
+    fn whitening_seed(&self) -> Option<u16> {
+        (self.packet[8] != 0).then(|| self.register_u16(0x06B8))
+    }
+
+    /// Builds the frame sent by SetTx.
+    fn transmit(&self) -> Frame {
+        let len = self.packet[if self.lora() { 3 } else { 6 }];
+        let payload: Vec<u8> = (0..len)
+            .map(|i| self.buffer[usize::from(self.tx_base.wrapping_add(i))])
+            .collect();
+
+        if self.lora() {
+            let crc_on = self.packet[4] != 0;
+            let mut data = payload.clone();
+            if crc_on {
+                let (init, poly) = LORA_CRC;
+                data.extend(crc(init, poly, CrcOptions::default(), &payload).to_be_bytes());
+            }
+            let explicit = self.packet[2] == 0x00;
+            return Frame {
+                link: self.link(),
+                header: explicit.then_some((len, crc_on)),
+                data,
+            };
+        }
+
+        let mut data = Vec::new();
+        if self.packet[5] == 0x01 {
+            data.push(len);
+        }
+        data.extend(&payload);
+        let check = self.gfsk_crc(&data);
+        data.extend(check);
+        if let Some(seed) = self.whitening_seed() {
+            whiten(seed, &mut data);
+        }
+        Frame {
+            link: self.link(),
+            header: None,
+            data,
+        }
+    }
+
+    /// Demodulates a frame after SetRx.
+    ///
+    /// # Returns
+    /// The IRQs raised, `None` when the frame is not heard at all
+    fn receive(&mut self, frame: &Frame) -> Option<u16> {
+        if frame.link != self.link() {
+            return None;
+        }
+        if self.lora() {
+            Some(self.receive_lora(frame))
+        } else {
+            Some(self.receive_gfsk(frame))
+        }
+    }
+
+    fn receive_lora(&mut self, frame: &Frame) -> u16 {
+        let explicit = self.packet[2] == 0x00;
+        let (len, crc_on, mut data) = match (explicit, frame.header) {
+            (true, Some((len, crc_on))) => (len, crc_on, frame.data.clone()),
+            (true, None) => return HEADER_ERROR,
+            // Without a header to expect, one is taken for payload
+            (false, header) => {
+                let mut data = header.map_or(Vec::new(), |(len, crc_on)| vec![len, crc_on as u8]);
+                data.extend(&frame.data);
+                (self.packet[3], self.packet[4] != 0, data)
+            }
+        };
+
+        // A frame shorter than expected is followed by noise
+        data.resize(usize::from(len) + if crc_on { 2 } else { 0 }, 0);
+        let (payload, check) = data.split_at(usize::from(len));
+        let (init, poly) = LORA_CRC;
+        let crc_ok =
+            !crc_on || crc(init, poly, CrcOptions::default(), payload).to_be_bytes() == check;
+
+        self.deliver(payload, [0x50, 0x20, 0x52]);
+        if crc_ok {
+            RX_DONE
+        } else {
+            RX_DONE | CRC_ERROR
+        }
+    }
+
+    fn receive_gfsk(&mut self, frame: &Frame) -> u16 {
+        let mut data = frame.data.clone();
+        if let Some(seed) = self.whitening_seed() {
+            whiten(seed, &mut data);
+        }
+
+        let variable = self.packet[5] == 0x01;
+        let header_len = usize::from(variable);
+        let len = if variable {
+            data.first().copied().unwrap_or(0)
+        } else {
+            self.packet[6]
+        };
+        let crc_len = self.gfsk_crc(&[]).len();
+
+        // A frame shorter than expected is followed by noise
+        data.resize(header_len + usize::from(len) + crc_len, 0);
+        let (body, check) = data.split_at(header_len + usize::from(len));
+        let crc_ok = self.gfsk_crc(body) == check;
+
+        let rx_status = if crc_ok {
+            PACKET_RECEIVED
+        } else {
+            PACKET_RECEIVED | RX_CRC_ERROR
+        };
+        self.deliver(&body[header_len..], [rx_status, 0x50, 0x52]);
+        if crc_ok {
+            RX_DONE
+        } else {
+            RX_DONE | CRC_ERROR
+        }
+    }
+
+    /// Stores a received payload at the RX base address.
+    fn deliver(&mut self, payload: &[u8], packet_status: [u8; 3]) {
+        for (i, &byte) in (0..).zip(payload) {
+            self.buffer[usize::from(self.rx_base.wrapping_add(i))] = byte;
+        }
+        self.rx_len = payload.len() as u8;
+        self.packet_status = packet_status;
+    }
+}
+
+impl RadioBus for Radio {
+    type Error = ();
+
+    fn command(&mut self, opcode: u8, request: &[u8], response: &mut [u8]) -> Result<(), ()> {
+        response.fill(0x24);
+        match opcode {
+            0x02 => self.irq &= !u16::from_be_bytes([request[0], request[1]]),
+            0x11 => response[1] = self.packet_type,
+            0x12 => response[1..].copy_from_slice(&self.irq.to_be_bytes()),
+            0x13 => response[1..].copy_from_slice(&[self.rx_len, self.rx_base]),
+            0x14 => response[1..].copy_from_slice(&self.packet_status),
+            0x82 => {
+                let frame = self.channel.borrow_mut().take();
+                match frame.and_then(|frame| self.receive(&frame)) {
+                    Some(irq) => self.irq |= irq,
+                    None => {
+                        assert!(
+                            request.iter().any(|&byte| byte != 0),
+                            "single RX with nothing to receive never ends"
+                        );
+                        self.irq |= TIMEOUT;
+                    }
+                }
+            }
+            0x83 => {
+                *self.channel.borrow_mut() = Some(self.transmit());
+                self.irq |= TX_DONE;
+            }
+            0x86 => self.frequency = request.to_vec(),
+            0x8A => self.packet_type = request[0],
+            0x8B => self.modulation = request.to_vec(),
+            0x8C => self.packet = request.to_vec(),
+            0x8F => {
+                self.tx_base = request[0];
+                self.rx_base = request[1];
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn read_register(&mut self, address: u16, value: &mut [u8]) -> Result<(), ()> {
+        for (address, byte) in (address..).zip(value) {
+            *byte = self.register(address);
+        }
+        Ok(())
+    }
+
+    fn write_register(&mut self, address: u16, value: &[u8]) -> Result<(), ()> {
+        self.registers
+            .extend((address..).zip(value.iter().copied()));
+        Ok(())
+    }
+
+    fn read_buffer(&mut self, offset: u8, bytes: &mut [u8]) -> Result<(), ()> {
+        for (i, byte) in (0..).zip(bytes) {
+            *byte = self.buffer[usize::from(offset.wrapping_add(i))];
+        }
+        Ok(())
+    }
+
+    fn write_buffer(&mut self, offset: u8, bytes: &[u8]) -> Result<(), ()> {
+        for (i, &byte) in (0..).zip(bytes) {
+            self.buffer[usize::from(offset.wrapping_add(i))] = byte;
+        }
+        Ok(())
+    }
+}
+
+fn lora(customize: impl FnOnce(&mut LoRaInit)) -> RadioConfig {
+    let mut init = LoRaInit::new(FREQUENCY);
+    customize(&mut init);
+    init.config().unwrap()
+}
+
+/// GFSK with whitening, a 2-byte inverted CRC and a 3-byte sync word
+fn gfsk(customize: impl FnOnce(&mut GfskInit)) -> RadioConfig {
+    let mut init = GfskInit::new(FREQUENCY);
+    init.packet = gfsk_packet(CrcType::Crc2ByteInv, true);
+    init.sync_word = Some(SyncWord {
+        value: [0xC1, 0x94, 0xC1, 0, 0, 0, 0, 0],
+    });
+    customize(&mut init);
+    init.config().unwrap()
+}
+
+fn gfsk_packet(crc_type: CrcType, whitening: bool) -> GFSKPacketParams {
+    GFSKPacketParams::builder()
+        .sync_word_bytes(3)
+        .variable_length()
+        .crc(crc_type)
+        .whitening(whitening)
+        .build()
+        .unwrap()
+}
+
+fn implicit_header(payload_length: u8) -> LoRaPacketParams {
+    LoRaPacketParams::builder()
+        .header_mode(LoRaHeaderMode::Implicit { payload_length })
+        .build()
+        .unwrap()
+}
+
+/// Sends `payload` from a device configured with `tx` to one configured with `rx`.
+///
+/// # Returns
+/// The payload received
+fn exchange(tx: &RadioConfig, rx: &RadioConfig, payload: &[u8]) -> Result<Vec<u8>, RxError> {
+    let channel = Channel::default();
+    let mut transmitter = Device::new(Radio::new(channel.clone()));
+    let mut receiver = Device::new(Radio::new(channel));
+    transmitter.apply_config(tx).unwrap();
+    receiver.apply_config(rx).unwrap();
+
+    transmitter.transmit(payload, Timeout::default()).unwrap();
+    let mut bytes = [0; 255];
+    let packet = receiver.receive(&mut bytes, RX_TIMEOUT)?;
+    assert!(packet.crc_ok);
+    Ok(bytes[..packet.len].to_vec())
+}
+
+#[test]
+fn lora_round_trip() {
+    let config = lora(|_| {});
+    assert_eq!(
+        exchange(&config, &config, b"hello lora"),
+        Ok(b"hello lora".to_vec())
+    );
+
+    let config = lora(|init| init.packet = implicit_header(6));
+    assert_eq!(
+        exchange(&config, &config, b"sensor"),
+        Ok(b"sensor".to_vec())
+    );
+}
+
+#[test]
+fn gfsk_round_trip() {
+    let config = gfsk(|_| {});
+    assert_eq!(
+        exchange(&config, &config, b"hello gfsk"),
+        Ok(b"hello gfsk".to_vec())
+    );
+
+    let config = gfsk(|init| {
+        init.packet = GFSKPacketParams::builder()
+            .sync_word_bytes(3)
+            .payload_length(12)
+            .fixed_length()
+            .build()
+            .unwrap();
+    });
+    assert_eq!(
+        exchange(&config, &config, b"fixed length"),
+        Ok(b"fixed length".to_vec())
+    );
+}
+
+#[test]
+fn mismatched_configs_fail() {
+    let cases = [
+        (
+            "frequency",
+            lora(|_| {}),
+            lora(|init| init.frequency.frequency = 869_525_000),
+            RxError::Timeout,
+        ),
+        (
+            "IQ inversion",
+            lora(|init| init.packet = LoRaPacketParams::builder().invert_iq(true).build().unwrap()),
+            lora(|_| {}),
+            RxError::Timeout,
+        ),
+        (
+            "LoRa sync word",
+            lora(|init| init.sync_word = Some(LoraSyncWord { value: 0x3444 })),
+            lora(|_| {}),
+            RxError::Timeout,
+        ),
+        (
+            "LoRa header",
+            lora(|init| init.packet = implicit_header(5)),
+            lora(|_| {}),
+            RxError::Header,
+        ),
+        (
+            "GFSK sync word",
+            gfsk(|_| {}),
+            gfsk(|init| {
+                init.sync_word = Some(SyncWord {
+                    value: [0xC1, 0x94, 0xC2, 0, 0, 0, 0, 0],
+                })
+            }),
+            RxError::Timeout,
+        ),
+        (
+            "GFSK CRC",
+            gfsk(|_| {}),
+            gfsk(|init| init.packet = gfsk_packet(CrcType::Crc2Byte, true)),
+            RxError::Crc,
+        ),
+        (
+            "GFSK whitening",
+            gfsk(|_| {}),
+            gfsk(|init| init.packet = gfsk_packet(CrcType::Crc2ByteInv, false)),
+            RxError::Crc,
+        ),
+    ];
+
+    for (name, tx, rx, expected) in cases {
+        assert_eq!(exchange(&tx, &rx, b"hello"), Err(expected), "{name}");
+    }
+}
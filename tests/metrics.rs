@@ -0,0 +1,105 @@
+#![cfg(all(feature = "sync", feature = "metrics"))]
+
+mod common;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use common::Radio;
+use sx1262::{
+    Device, FallbackMode, IrqMask, ModeDwellTimes, RxWatchdog, SetRxTxFallbackMode, SetStandby,
+    StandbyConfig, Timeout,
+};
+
+#[test]
+fn irq_storm_is_detected_and_unrouted() {
+    let radio = Radio::new();
+    // A burst of PREAMBLE_DETECTED
+    radio.respond(0x12, &IrqMask::PREAMBLE_DETECTED.bits().to_be_bytes());
+    let mut device = Device::new(radio);
+    let mut watchdog = RxWatchdog::new(60_000);
+    assert!(device.detect_irq_storm(500, 0).is_empty());
+
+    // 100 interrupts in 100 ms
+    for now in 0..100 {
+        device.poll_rx(&mut watchdog, now).unwrap();
+    }
+    assert_eq!(device.irq_counts().get(IrqMask::PREAMBLE_DETECTED), 100);
+
+    let storm = device.detect_irq_storm(500, 100);
+    assert_eq!(storm, IrqMask::PREAMBLE_DETECTED);
+    device
+        .modify_irq_routing(|routing| routing.dio1_mask.remove(storm))
+        .unwrap();
+
+    // 5 interrupts in the next second are within the rate
+    for now in 100..105 {
+        device.poll_rx(&mut watchdog, now).unwrap();
+    }
+    assert!(device.detect_irq_storm(500, 1_100).is_empty());
+    assert_eq!(
+        device.irq_counts().iter().collect::<Vec<_>>(),
+        [(IrqMask::PREAMBLE_DETECTED, 105)]
+    );
+
+    device.reset_irq_counts();
+    assert_eq!(device.irq_counts().get(IrqMask::all()), 0);
+}
+
+static NOW: AtomicU32 = AtomicU32::new(0);
+
+fn clock() -> u32 {
+    NOW.load(Ordering::Relaxed)
+}
+
+fn advance(us: u32) {
+    NOW.fetch_add(us, Ordering::Relaxed);
+}
+
+#[test]
+fn mode_dwell_times_follow_the_commands() {
+    let radio = Radio::new();
+    // TX_DONE, read 1 ms after SetTx
+    radio.respond(0x12, &IrqMask::TX_DONE.bits().to_be_bytes());
+    radio.set_hook(|_, opcode, _, _| {
+        if opcode == 0x12 {
+            advance(1_000);
+        }
+        false
+    });
+    let mut device = Device::new(radio);
+    device.set_clock(Some(clock));
+    device.assume_configured();
+
+    // Unknown mode, not counted
+    advance(500);
+    device
+        .execute_command(SetStandby {
+            config: StandbyConfig::Rc,
+        })
+        .unwrap();
+    advance(2_000);
+    device
+        .execute_command(SetRxTxFallbackMode {
+            mode: FallbackMode::StdbyXosc,
+        })
+        .unwrap();
+
+    // 1 ms in TX, then the fallback mode until now
+    device.transmit(&[1, 2], Timeout::default()).unwrap();
+    advance(300);
+
+    assert_eq!(
+        device.mode_dwell_times(),
+        ModeDwellTimes {
+            standby_rc: 2_000,
+            transmit: 1_000,
+            standby_xosc: 300,
+            ..ModeDwellTimes::default()
+        }
+    );
+
+    device.reset_mode_dwell_times();
+    advance(50);
+    assert_eq!(device.mode_dwell_times().standby_xosc, 50);
+    assert_eq!(device.mode_dwell_times().transmit, 0);
+}
@@ -0,0 +1,248 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Op, Radio};
+use sx1262::{
+    error::{Error, ErrorKind},
+    Device, GetRxBufferStatus, IrqMask, LoRaInit, LoraPacketHeaderType, PayloadInfo,
+    RfFrequencyConfig, RxMode,
+};
+
+/// A radio holding each byte's address in the data buffer
+fn addressed_buffer() -> Radio {
+    let radio = Radio::new();
+    for (address, byte) in radio.state().buffer.iter_mut().enumerate() {
+        *byte = address as u8;
+    }
+    radio
+}
+
+fn buffer_reads(radio: &Radio) -> Vec<(u8, usize)> {
+    radio
+        .ops()
+        .into_iter()
+        .filter_map(|op| match op {
+            Op::ReadBuffer(offset, len) => Some((offset, len)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn each_packet_is_read_once() {
+    let radio = Radio::new();
+    radio.respond(0x12, &IrqMask::RX_DONE.bits().to_be_bytes());
+    // Receives packet `n` of `n` bytes on the nth SetRx, each byte being `n`
+    let mut packet = 0;
+    radio.set_hook(move |state, opcode, _, _| {
+        if opcode == 0x82 {
+            packet += 1;
+            state.responses.insert(0x13, vec![packet, 0x00]);
+            state.buffer[..usize::from(packet)].fill(packet);
+        }
+        false
+    });
+    let mut device = Device::new(radio);
+    device.assume_configured();
+    let mut payload = [0; 8];
+    let mode = RxMode::Single;
+
+    // The first packet is read by receive, reading it again is refused
+    assert_eq!(device.receive(&mut payload, mode).unwrap().len, 1);
+    let err = device.read_received_payload(&mut payload).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NoPacketPending);
+
+    // The raw command is still available
+    let status = device.execute_command(GetRxBufferStatus).unwrap();
+    assert_eq!(status.buffer_status.payload_length, 1);
+
+    // The second packet is read in full
+    assert_eq!(device.receive(&mut payload, mode).unwrap().len, 2);
+    assert_eq!(payload[..2], [2, 2]);
+
+    // RX_DONE read and cleared elsewhere
+    device.mark_packet_pending();
+    assert_eq!(device.read_received_payload(&mut payload).unwrap(), 2);
+}
+
+#[test]
+fn truncated_read_copies_what_fits() {
+    let radio = addressed_buffer();
+    radio.respond(0x13, &[10, 0x80]);
+    let mut device = Device::new(radio);
+
+    // Only the header
+    let mut header = [0; 4];
+    let info = device.read_received_payload_truncated(&mut header).unwrap();
+    assert_eq!(
+        info,
+        PayloadInfo {
+            copied: 4,
+            total: 10,
+            truncated: true
+        }
+    );
+    assert_eq!(header, [0x80, 0x81, 0x82, 0x83]);
+
+    // An exact fit
+    let mut payload = [0; 10];
+    let info = device
+        .read_received_payload_truncated(&mut payload)
+        .unwrap();
+    assert_eq!(
+        info,
+        PayloadInfo {
+            copied: 10,
+            total: 10,
+            truncated: false
+        }
+    );
+    assert_eq!(payload[9], 0x89);
+}
+
+#[test]
+fn chunked_read_wraps_around_the_buffer() {
+    let radio = addressed_buffer();
+    // A 10 byte payload wrapping around the end of the data buffer
+    radio.respond(0x13, &[10, 0xFA]);
+    let mut device = Device::new(radio.clone());
+    let expected = [0xFA, 0xFB, 0xFC, 0xFD, 0xFE, 0xFF, 0x00, 0x01, 0x02, 0x03];
+
+    // An exact multiple of the chunk size
+    let mut payload = Vec::new();
+    let len = device
+        .read_received_payload_chunked(&mut [0; 5], |chunk| {
+            payload.extend_from_slice(chunk);
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    assert_eq!(len, 10);
+    assert_eq!(payload, expected);
+    assert_eq!(buffer_reads(&radio), [(0xFA, 5), (0xFF, 5)]);
+
+    // A shorter last chunk
+    radio.clear();
+    payload.clear();
+    device
+        .read_received_payload_chunked(&mut [0; 4], |chunk| {
+            payload.extend_from_slice(chunk);
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    assert_eq!(payload, expected);
+    assert_eq!(buffer_reads(&radio), [(0xFA, 4), (0xFE, 4), (0x02, 2)]);
+}
+
+#[test]
+fn chunked_read_stops_on_a_failed_chunk() {
+    /// Fails to decrypt a chunk
+    #[derive(Debug, PartialEq)]
+    enum AppError {
+        Radio,
+        Decrypt,
+    }
+
+    impl From<Error> for AppError {
+        fn from(_: Error) -> Self {
+            AppError::Radio
+        }
+    }
+
+    let radio = addressed_buffer();
+    radio.respond(0x13, &[10, 0xFA]);
+    let mut device = Device::new(radio.clone());
+
+    let mut chunks = 0;
+    let result = device.read_received_payload_chunked(&mut [0; 4], |_| {
+        chunks += 1;
+        if chunks == 2 {
+            Err(AppError::Decrypt)
+        } else {
+            Ok(())
+        }
+    });
+    assert_eq!(result, Err(AppError::Decrypt));
+    assert_eq!(buffer_reads(&radio).len(), 2);
+}
+
+#[test]
+fn fast_read_skips_the_buffer_status_of_fixed_length_packets() {
+    let radio = addressed_buffer();
+    let mut device = Device::new(radio.clone());
+    let mut init = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    });
+    init.packet.header_type = LoraPacketHeaderType::Fixed;
+    init.packet.payload_length = 6;
+    let mut config = init.config().unwrap();
+    config.buffer_base.rx_base_addr = 0x80;
+    device.apply_config(&config).unwrap();
+
+    // A single ReadBuffer at the RX base address
+    radio.clear();
+    device.mark_packet_pending();
+    let mut payload = [0; 8];
+    assert_eq!(device.read_received_payload_fast(&mut payload).unwrap(), 6);
+    assert_eq!(payload[..6], [0x80, 0x81, 0x82, 0x83, 0x84, 0x85]);
+    assert_eq!(radio.ops(), [Op::ReadBuffer(0x80, 6)]);
+
+    // The configured length must fit
+    device.mark_packet_pending();
+    let err = device.read_received_payload_fast(&mut [0; 4]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BufferTooSmall { required: 6 });
+}
+
+#[test]
+fn fast_read_falls_back_without_a_fixed_length() {
+    let radio = addressed_buffer();
+    radio.respond(0x13, &[3, 0x10]);
+    let mut device = Device::new(radio.clone());
+
+    // No configuration applied, the buffer status is queried
+    let mut payload = [0; 8];
+    assert_eq!(device.read_received_payload_fast(&mut payload).unwrap(), 3);
+    assert_eq!(payload[..3], [0x10, 0x11, 0x12]);
+    assert_eq!(radio.count(0x13), 1);
+    assert_eq!(buffer_reads(&radio), [(0x10, 3)]);
+
+    // Nor with variable-length packets
+    radio.clear();
+    device
+        .apply_config(
+            &LoRaInit::new(RfFrequencyConfig {
+                frequency: 868_100_000,
+            })
+            .config()
+            .unwrap(),
+        )
+        .unwrap();
+    device.mark_packet_pending();
+    assert_eq!(device.read_received_payload_fast(&mut payload).unwrap(), 3);
+    assert_eq!(radio.count(0x13), 1);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn vec_read_is_sized_from_the_buffer_status() {
+    let radio = addressed_buffer();
+    radio.respond(0x13, &[4, 0x20]);
+    let mut device = Device::new(radio.clone());
+
+    // An exact fit
+    let payload = device.read_received_payload_vec::<4>().unwrap();
+    assert_eq!(payload, [0x20, 0x21, 0x22, 0x23]);
+
+    // Room to spare, only the payload is read
+    device.mark_packet_pending();
+    let payload = device.read_received_payload_vec::<255>().unwrap();
+    assert_eq!(payload.len(), 4);
+    assert_eq!(buffer_reads(&radio).last(), Some(&(0x20, 4)));
+
+    // Too small, with the actual length
+    radio.clear();
+    device.mark_packet_pending();
+    let err = device.read_received_payload_vec::<3>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BufferTooSmall { required: 4 });
+    assert!(buffer_reads(&radio).is_empty());
+}
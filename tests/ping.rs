@@ -0,0 +1,126 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use common::{Op, Radio, State};
+use sx1262::{Device, IrqMask, LoRaInit, PingError, RfFrequencyConfig, RxMode, Timeout};
+
+/// Microseconds elapsed
+static NOW: AtomicU32 = AtomicU32::new(0);
+
+fn clock() -> u32 {
+    NOW.load(Ordering::Relaxed)
+}
+
+/// Returns the bytes last written to the data buffer.
+fn last_written(state: &State) -> Vec<u8> {
+    state
+        .ops
+        .iter()
+        .rev()
+        .find_map(|op| match op {
+            Op::WriteBuffer(_, bytes) => Some(bytes.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Puts `packet` in the data buffer as received.
+fn deliver(state: &mut State, packet: &[u8]) {
+    state.buffer[..packet.len()].copy_from_slice(packet);
+    state.responses.insert(0x13, vec![packet.len() as u8, 0]);
+}
+
+/// A radio sending and receiving packets instantly, received at `rssi`
+fn radio(rssi: i8) -> Radio {
+    let raw = (-2 * i16::from(rssi)) as u8;
+    let radio = Radio::new();
+    radio
+        .raise_on(0x82, IrqMask::RX_DONE.bits())
+        .raise_on(0x83, IrqMask::TX_DONE.bits())
+        .respond(0x14, &[raw, 0x20, raw]);
+    radio
+}
+
+#[test]
+fn ping_subtracts_the_time_on_air() {
+    // Answers every ping heard at -60 dBm after 100 ms on air each way, replying to a
+    // sequence number off by `skew`
+    let skew = Rc::new(Cell::new(0u8));
+    let radio = radio(-72);
+    let peer_skew = skew.clone();
+    radio.set_hook(move |state, opcode, _, _| {
+        match opcode {
+            0x82 => {
+                let ping = last_written(state);
+                let [0x50, sequence, ref payload @ ..] = ping[..] else {
+                    panic!("not a ping: {ping:?}");
+                };
+                let sequence = sequence.wrapping_add(peer_skew.get());
+                deliver(state, &[&[0x70, sequence, -60i8 as u8], payload].concat());
+                NOW.fetch_add(100_000, Ordering::Relaxed);
+            }
+            0x83 => {
+                NOW.fetch_add(100_000, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+        false
+    });
+    let mut device = Device::new(radio);
+    let frequency = RfFrequencyConfig {
+        frequency: 868_100_000,
+    };
+    device
+        .apply_config(&LoRaInit::new(frequency).config().unwrap())
+        .unwrap();
+
+    // SF7 at 125 kHz: 41.216 ms on air for the ping and for its reply
+    let result = device.ping(b"12345678", Timeout::default(), clock).unwrap();
+    assert_eq!(result.airtime_us, Some(2 * 41_216));
+    assert_eq!(result.rtt_us, 200_000 - 2 * 41_216);
+    assert_eq!(result.rssi_here, -72);
+    assert_eq!(result.rssi_reported_by_peer, Some(-60));
+
+    // A reply to another ping is rejected
+    skew.set(1);
+    let result = device.ping(b"12345678", Timeout::default(), clock);
+    assert_eq!(result, Err(PingError::UnexpectedReply));
+}
+
+#[test]
+fn only_pings_are_answered() {
+    // Two pings around a packet of another protocol
+    let mut inbox = vec![vec![0x50, 7, 0xAA], vec![0x01, 0x02], vec![0x50, 8]].into_iter();
+    let radio = radio(-80);
+    radio.set_hook(move |state, opcode, _, _| {
+        if opcode == 0x82 {
+            deliver(state, &inbox.next().unwrap());
+        }
+        false
+    });
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+
+    device
+        .respond_to_pings(2, RxMode::Single, Timeout::default())
+        .unwrap();
+    let sent: Vec<_> = radio
+        .ops()
+        .into_iter()
+        .filter_map(|op| match op {
+            Op::WriteBuffer(_, bytes) => Some(bytes),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        sent,
+        [vec![0x70, 7, -80i8 as u8, 0xAA], vec![0x70, 8, -80i8 as u8]]
+    );
+}
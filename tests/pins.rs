@@ -0,0 +1,43 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Delay, Pin};
+use sx1262::pins::{BusyError, BusyPin, BusyWait};
+
+#[test]
+fn busy_is_polled_at_the_interval() {
+    let pin = Pin::high();
+    let delay = Delay::default();
+    let mut busy = BusyPin::new(pin.clone(), delay.clone()).with_poll_interval_us(100);
+    assert_eq!(
+        busy.wait_ready(1_000),
+        Err(BusyError::Stuck { waited_us: 1_000 })
+    );
+    assert_eq!(delay.count(), 10);
+    assert_eq!(pin.line().reads, 11);
+
+    // Low after three reads
+    let mut busy = BusyPin::new(Pin::scripted([true, true, false]), Delay::default());
+    assert_eq!(busy.wait_ready(1_000), Ok(()));
+}
+
+#[test]
+fn busy_wait_does_not_overflow() {
+    let interval = u32::MAX / 2 + 1;
+    let mut busy = BusyPin::new(Pin::high(), Delay::default()).with_poll_interval_us(interval);
+    assert_eq!(
+        busy.wait_ready(u32::MAX),
+        Err(BusyError::Stuck {
+            waited_us: u32::MAX
+        })
+    );
+}
+
+#[test]
+fn unreadable_busy_is_reported() {
+    let pin = Pin::high();
+    pin.line().faulty = true;
+    let mut busy = BusyPin::new(pin, Delay::default());
+    assert_eq!(busy.wait_ready(1_000), Err(BusyError::Pin));
+}
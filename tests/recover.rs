@@ -0,0 +1,120 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Delay, Pin, Radio};
+use sx1262::{
+    error::ErrorKind,
+    pins::{NoPin, Pins},
+    Device, LoRaInit, RfFrequencyConfig, TcxoConfig, TcxoVoltage,
+};
+
+#[test]
+fn recovery_restores_the_board_setup_before_calibrating() {
+    // A board with a TCXO and an antenna switch on DIO2, both lost with the reset
+    let radio = Radio::new();
+    let reset = Pin::high();
+    let pins = Pins {
+        busy: NoPin,
+        reset: reset.clone(),
+        dio1: NoPin,
+    };
+    let mut device = Device::new_with_parts(radio.clone(), pins, Delay::default());
+
+    let mut init = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    });
+    init.tcxo = Some(TcxoConfig {
+        voltage: TcxoVoltage::V1_8,
+        delay: 320,
+    });
+    init.dio2_rf_switch = true;
+    device.init_lora(init).unwrap();
+    let config = device.snapshot_config().unwrap();
+    assert!(config.dio2_rf_switch && config.tcxo.is_some());
+
+    radio.clear();
+    reset.line().driven.clear();
+    device.recover().unwrap();
+    assert_eq!(reset.line().driven, [false, true]);
+    let opcodes = radio.opcodes();
+    let position = |opcode| opcodes.iter().position(|&op| op == opcode).unwrap();
+
+    // The RF switch is claimed in STDBY_RC, the TCXO is powered before every calibration
+    assert!(position(0x80) < position(0x9D));
+    assert!(position(0x97) < position(0x89));
+    assert!(position(0x89) < position(0x98));
+    assert!(position(0x98) < position(0x8A));
+}
+
+/// A radio whose status byte reads as garbage for the first `polls` GetStatus
+fn waking_radio(polls: usize) -> Radio {
+    let radio = Radio::new();
+    let mut garbage = polls;
+    radio.set_hook(move |_, opcode, _, response| {
+        if opcode != 0xC0 || garbage == 0 {
+            return false;
+        }
+        garbage -= 1;
+        response.fill(0xFF);
+        true
+    });
+    radio
+}
+
+fn no_pins() -> Pins {
+    Pins {
+        busy: NoPin,
+        reset: NoPin,
+        dio1: NoPin,
+    }
+}
+
+#[test]
+fn status_is_polled_with_exponential_backoff() {
+    let radio = waking_radio(4);
+    let delay = Delay::default();
+    let mut device = Device::new_with_parts(radio.clone(), no_pins(), delay.clone());
+
+    let responsive = device.wait_until_responsive(10_000).unwrap();
+    assert_eq!(responsive.waited_us, 480);
+    assert_eq!(radio.count(0xC0), 5);
+    assert_eq!(delay.sleeps_ns(), [32_000, 64_000, 128_000, 256_000]);
+}
+
+#[test]
+fn unresponsive_radio_times_out() {
+    let radio = waking_radio(usize::MAX);
+    let delay = Delay::default();
+    let mut device = Device::new_with_parts(radio.clone(), no_pins(), delay.clone());
+
+    // The last interval is cut short by the time left
+    let err = device.wait_until_responsive(1_000).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unresponsive { waited_us: 1_000 });
+    assert_eq!(delay.total_us(), 1_000);
+    assert_eq!(delay.sleeps_ns().last(), Some(&8_000));
+
+    // A bus error is not waited out
+    radio.set_failing(0xC0, true);
+    delay.clear();
+    let err = device.wait_until_responsive(1_000).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Bus);
+    assert_eq!(delay.count(), 0);
+}
+
+#[test]
+fn recovery_waits_until_the_radio_answers() {
+    let radio = waking_radio(2);
+    let delay = Delay::default();
+    let pins = Pins {
+        busy: NoPin,
+        reset: Pin::high(),
+        dio1: NoPin,
+    };
+    let mut device = Device::new_with_parts(radio.clone(), pins, delay.clone());
+
+    // The reset pulse, then two polls backing off
+    device.recover().unwrap();
+    assert_eq!(delay.sleeps_ns(), [200_000, 32_000, 64_000]);
+    assert_eq!(radio.count(0xC0), 3);
+}
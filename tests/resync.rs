@@ -0,0 +1,47 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Waker},
+};
+
+use common::{block_on, Radio, Yielding};
+use sx1262::{Device, IrqMask, RxMode, Timeout};
+
+#[test]
+fn cancelled_transmit_is_resynced_by_the_next_operation() {
+    let radio = Radio::new();
+    radio
+        .raise_on(0x82, IrqMask::RX_DONE.bits())
+        .respond(0x13, &[0, 0])
+        .respond(0x14, &[0, 0, 0]);
+    // Still in TX after the transmit is dropped
+    radio.set_hook(|_, opcode, _, response| {
+        if opcode == 0xC0 {
+            response[0] = 0x62;
+        }
+        opcode == 0xC0
+    });
+    let mut device = Device::new(Yielding(radio.clone()));
+    device.assume_configured();
+
+    // Poll the transmit until it waits for TX_DONE, then drop it
+    {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut transmit = pin!(device.transmit_async(&[1, 2], Timeout::default()));
+        for _ in 0..8 {
+            assert!(transmit.as_mut().poll(&mut cx).is_pending());
+        }
+    }
+
+    // The next receive aborts the TX and clears its IRQs before entering RX
+    block_on(device.receive_async(&mut [0; 4], RxMode::Continuous)).unwrap();
+
+    let opcodes = radio.opcodes();
+    let resync = opcodes.iter().position(|&opcode| opcode == 0xC0).unwrap();
+    assert!(opcodes[..resync].starts_with(&[0x02, 0x83, 0x12]));
+    assert_eq!(opcodes[resync..resync + 5], [0xC0, 0x80, 0x02, 0x02, 0x82]);
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Delay, Radio};
+use sx1262::{Device, DeviceSelect, SelfTestOutcome, SelfTestPlan};
+
+/// A radio following the operating mode, whose PLL does not lock on the `unlocked` SetFs
+fn radio(unlocked: Option<usize>) -> Radio {
+    let radio = Radio::new();
+    // GetRssiInst, -100 dBm
+    radio.respond(0x15, &[200]);
+    let mut mode = 0x2;
+    let mut set_fs = 0;
+    radio.set_hook(move |state, opcode, _, _| {
+        let lock_error = Some(set_fs) == unlocked;
+        match opcode {
+            0x80 => mode = 0x2,
+            0xC1 => {
+                set_fs += 1;
+                mode = if Some(set_fs) == unlocked { 0x2 } else { 0x4 };
+            }
+            0xD1 => mode = 0x6,
+            0x82 => mode = 0x5,
+            _ => {}
+        }
+        state.status = mode << 4 | 0x04;
+        // GetDeviceErrors, with PLL_LOCK_ERR after the failed SetFs
+        state
+            .responses
+            .insert(0x17, vec![0, if lock_error { 0x40 } else { 0 }]);
+        false
+    });
+    radio
+}
+
+#[test]
+fn self_test_passes_on_a_healthy_radio() {
+    let plan = SelfTestPlan::new(DeviceSelect::Sx1262, 863_000_000..=928_000_000);
+    let mut device = Device::new(radio(None));
+    let report = device.self_test(plan, &mut Delay::default()).unwrap();
+    assert!(report.passed());
+    assert_eq!(report.noise_floor_dbm, Some(-100));
+}
+
+#[test]
+fn self_test_reports_a_pll_failing_to_lock_at_the_band_edge() {
+    let plan = SelfTestPlan::new(DeviceSelect::Sx1262, 863_000_000..=928_000_000);
+    let mut device = Device::new(radio(Some(3)));
+    let report = device.self_test(plan, &mut Delay::default()).unwrap();
+    assert!(!report.passed());
+    assert_eq!(
+        report.pll_lock,
+        [
+            SelfTestOutcome::Passed,
+            SelfTestOutcome::Passed,
+            SelfTestOutcome::Failed
+        ]
+    );
+    assert_eq!(report.calibration, SelfTestOutcome::Passed);
+    assert_eq!(report.cw, SelfTestOutcome::Passed);
+    assert_eq!(report.rssi, SelfTestOutcome::Passed);
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{
+    error::ErrorKind, AddressFiltering, CrcType, Device, GFSKPacketHeaderType, GfskBandwidth,
+    GfskInit, GfskModParams, GfskPulseShape, GfskRxStatus, IrqMask, PacketParams, PacketType,
+    RfFrequencyConfig,
+};
+
+/// FSK sync word register
+const SYNC_WORD: u16 = 0x06C0;
+
+const MODULATION: GfskModParams = GfskModParams {
+    bit_rate: 38_400,
+    pulse_shape: GfskPulseShape::Bt05,
+    bandwidth: GfskBandwidth::Bw1173,
+    freq_deviation: 20_000,
+};
+
+#[test]
+fn promiscuous_mode_disables_filtering_and_keeps_the_crc() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let mut init = GfskInit::new(RfFrequencyConfig {
+        frequency: 868_300_000,
+    });
+    init.packet.address_filtering = AddressFiltering::Node;
+    init.packet.packet_type = GFSKPacketHeaderType::Fixed;
+    init.packet.payload_length = 16;
+    init.packet.crc_type = CrcType::CrcOff;
+    device.apply_config(&init.config().unwrap()).unwrap();
+
+    radio.clear();
+    device
+        .enter_promiscuous_gfsk(MODULATION, Some(&[0xC1, 0x94, 0xC1]))
+        .unwrap();
+    assert_eq!(device.packet_type(), Some(PacketType::Gfsk));
+
+    // Variable-length, no address filtering, a 2 byte CRC reported rather than none
+    let packet = radio.last(0x8C).unwrap();
+    assert_eq!(packet[3], 24);
+    assert_eq!(packet[4..8], [0x00, 0x01, 0xFF, CrcType::Crc2Byte as u8]);
+    assert_eq!(
+        radio.register_writes(),
+        [(SYNC_WORD, vec![0xC1, 0x94, 0xC1, 0, 0, 0, 0, 0])]
+    );
+
+    // The IRQs reporting frames are enabled, then continuous RX is started
+    let irq = u16::from_be_bytes(radio.last(0x08).unwrap()[..2].try_into().unwrap());
+    let sniffed = IrqMask::RX_DONE | IrqMask::CRC_ERROR | IrqMask::HEADER_ERROR;
+    assert!(IrqMask::from_bits_retain(irq).contains(sniffed));
+    assert_eq!(radio.opcodes().last(), Some(&0x82));
+    assert_eq!(radio.last(0x82).unwrap(), [0xFF, 0xFF, 0xFF]);
+
+    // The cached configuration follows
+    let config = device.snapshot_config().unwrap();
+    let PacketParams::GFSK(packet) = config.packet else {
+        panic!("not a GFSK configuration");
+    };
+    assert_eq!(packet.address_filtering, AddressFiltering::Disable);
+    assert_eq!(packet.packet_type, GFSKPacketHeaderType::Variable);
+    assert_eq!(
+        config.gfsk_sync_word.unwrap().value[..3],
+        [0xC1, 0x94, 0xC1]
+    );
+}
+
+#[test]
+fn promiscuous_mode_rejects_a_long_sync_word() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    let err = device
+        .enter_promiscuous_gfsk(MODULATION, Some(&[0x55; 9]))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Serialization);
+    assert!(radio.ops().is_empty());
+}
+
+#[test]
+fn sniffed_frames_report_crc_failures() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    device.enter_promiscuous_gfsk(MODULATION, None).unwrap();
+    assert!(radio.register_writes().is_empty());
+
+    // Nothing, then a frame failing the CRC check
+    radio
+        .script_irqs([0, (IrqMask::RX_DONE | IrqMask::CRC_ERROR).bits()])
+        .respond(0x13, &[3, 0x40])
+        .respond(0x14, &[0x12, 0x50, 0x60]);
+    radio.state().buffer[0x40..0x43].copy_from_slice(&[1, 2, 3]);
+    radio.clear();
+
+    let mut payload = [0; 8];
+    let frame = device.next_sniffed_frame(&mut payload).unwrap();
+    assert_eq!(frame.len, 3);
+    assert_eq!(payload[..3], [1, 2, 3]);
+    assert!(frame.status.rx_status.contains(GfskRxStatus::CRC_ERROR));
+    assert_eq!(frame.status.rssi_sync, -40.0);
+    assert_eq!(frame.status.rssi_avg, -48.0);
+
+    // Polled until the frame, whose IRQs are cleared
+    assert_eq!(radio.count(0x12), 2);
+    assert_eq!(radio.last(0x02).unwrap(), [0x00, 0x22]);
+}
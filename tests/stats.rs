@@ -0,0 +1,32 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{error::ErrorKind, Device, GetRssiInst, OperatingMode};
+
+#[test]
+fn rssi_is_only_read_in_rx_and_corrected_by_the_offset() {
+    // -80 dBm
+    let radio = Radio::new();
+    radio.respond(0x15, &[0xA0]);
+    let mut device = Device::new(radio.clone());
+    device.set_rssi_offset_db(-3);
+
+    // In STDBY_RC
+    let err = device.rssi_inst().unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::WrongMode {
+            found: Some(OperatingMode::StandbyRc)
+        }
+    );
+    assert_eq!(device.rssi_inst_unchecked().unwrap(), -83);
+
+    // In RX
+    radio.set_status(0x54);
+    assert_eq!(device.rssi_inst().unwrap(), -83);
+
+    // The raw response is left uncorrected
+    assert_eq!(device.execute_command(GetRssiInst).unwrap().rssi, 0xA0);
+}
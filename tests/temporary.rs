@@ -0,0 +1,86 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{error::ErrorKind, ConfigItems, Device, GetStatus, LoRaInit, RfFrequencyConfig};
+
+fn configured() -> (Radio, Device<Radio>) {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    let config = LoRaInit::new(RfFrequencyConfig {
+        frequency: 868_100_000,
+    })
+    .config()
+    .unwrap();
+    device.apply_config(&config).unwrap();
+    (radio, device)
+}
+
+#[test]
+fn frequency_is_restored_when_the_body_fails() {
+    let (radio, mut device) = configured();
+    let original = radio.last(0x86).unwrap();
+    radio.clear();
+    radio.set_failing(0xC0, true);
+
+    let err = device
+        .with_frequency(
+            RfFrequencyConfig {
+                frequency: 869_525_000,
+            },
+            |device| device.execute_command(GetStatus).map(drop),
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Bus);
+
+    // Changed, the body failed, then restored
+    let commands = radio.commands();
+    assert_eq!(radio.opcodes(), [0x86, 0xC0, 0x86]);
+    assert_ne!(commands[0].1, original);
+    assert_eq!(commands[2].1, original);
+}
+
+#[test]
+fn tx_power_is_restored_after_the_body() {
+    let (radio, mut device) = configured();
+    let original = radio.last(0x8E).unwrap();
+    radio.clear();
+
+    // The ramp time is kept, the value of the body returned
+    let value = device
+        .with_tx_power(-9, |device| {
+            assert_eq!(device.snapshot_config().unwrap().tx_params.power, 14);
+            Ok(7)
+        })
+        .unwrap();
+    assert_eq!(value, 7);
+    let commands = radio.commands();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0], (0x8E, vec![0xF7, original[1]]));
+    assert_eq!(commands[1], (0x8E, original.clone()));
+
+    // Restored on the error path too
+    radio.clear();
+    radio.set_failing(0xC0, true);
+    let err = device
+        .with_tx_power(22, |device| device.execute_command(GetStatus).map(drop))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Bus);
+    assert_eq!(radio.last(0x8E), Some(original));
+}
+
+#[test]
+fn temporary_changes_need_a_configuration() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    let err = device.with_tx_power(10, |_| Ok(())).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::NotConfigured {
+            missing: ConfigItems::all()
+        }
+    );
+    assert!(radio.ops().is_empty());
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "sync", feature = "trace"))]
+
+mod common;
+
+use common::Radio;
+use sx1262::Device;
+
+#[cfg(feature = "oplog")]
+#[test]
+fn operation_log_keeps_the_last_operations() {
+    use sx1262::{
+        bus::READ_REGISTER,
+        trace::{TraceDirection, TraceTarget},
+        ErrorKind, SetStandby, StandbyConfig,
+    };
+
+    let radio = Radio::new();
+    radio.set_failing(READ_REGISTER, true);
+    let mut device = Device::new(radio);
+    device
+        .execute_command(SetStandby {
+            config: StandbyConfig::Xosc,
+        })
+        .unwrap();
+    device.write_buffer(0x80, &[1, 2, 3, 4, 5]).unwrap();
+    assert!(device.read_register_raw(0x0740, &mut [0; 2]).is_err());
+
+    let log: Vec<_> = device.operation_log().copied().collect();
+    assert_eq!(log.len(), 3);
+    assert_eq!(log[0].target, TraceTarget::Command(0x80));
+    assert_eq!(log[0].params(), [0x01]);
+    assert_eq!(log[1].target, TraceTarget::Buffer(0x80));
+    assert_eq!(log[1].params(), [1, 2, 3, 4]);
+    assert_eq!(log[2].target, TraceTarget::Register(0x0740));
+    assert_eq!(log[2].direction, TraceDirection::Read);
+    assert_eq!(log[2].result, Err(ErrorKind::Bus));
+    assert_eq!(
+        device.last_command().unwrap().target,
+        TraceTarget::Command(0x80)
+    );
+
+    device.clear_operation_log();
+    assert_eq!(device.operation_log().count(), 0);
+}
@@ -0,0 +1,296 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::{Delay, Op, Radio};
+use sx1262::{
+    error::{RxError, TxError},
+    ConfigItems, Device, ErrorKind, HeaderErrorPolicy, IqMode, IrqMask, LoRaInit, LoRaModParams,
+    ModulationParams, PacketType, RepeatedTxError, RfFrequencyConfig, RxMode, RxOptions,
+    SetModulationParams, SetPacketType, SetRfFrequency, Timeout, TxOptions,
+};
+
+const FREQUENCY: RfFrequencyConfig = RfFrequencyConfig {
+    frequency: 868_100_000,
+};
+
+/// IQ polarity register
+const IQ_POLARITY: u16 = 0x0736;
+
+#[test]
+fn crc_failures_are_rejected_or_delivered() {
+    for (packet_type, gfsk) in [(PacketType::LoRa, false), (PacketType::Gfsk, true)] {
+        // A received 2 byte packet, CRC_ERROR raised in the IRQs (LoRa) or the RxStatus (GFSK)
+        let irq = if gfsk {
+            IrqMask::RX_DONE
+        } else {
+            IrqMask::RX_DONE | IrqMask::CRC_ERROR
+        };
+        let rx_status = if gfsk { 0x12 } else { 0x00 };
+        let radio = Radio::new();
+        radio
+            .respond(0x12, &irq.bits().to_be_bytes())
+            .respond(0x13, &[2, 0x80])
+            .respond(0x14, &[rx_status, 0x50, 0x50]);
+        radio.state().buffer[0x80..0x82].copy_from_slice(&[0xAB, 0xCD]);
+        let mut device = Device::new(radio);
+        device
+            .execute_command(SetPacketType { packet_type })
+            .unwrap();
+        device.assume_configured();
+        let mut bytes = [0; 255];
+
+        let rejected = device.receive(&mut bytes, RxMode::Single);
+        assert!(matches!(rejected, Err(RxError::Crc)));
+
+        let options = RxOptions {
+            deliver_crc_failures: true,
+            ..RxOptions::default()
+        };
+        let packet = device
+            .receive_with_options(&mut bytes, RxMode::Single, options)
+            .unwrap();
+        assert!(!packet.crc_ok);
+        assert_eq!(&bytes[..packet.len], [0xAB, 0xCD]);
+    }
+}
+
+#[test]
+fn unconfigured_radio_is_refused_and_stale_irqs_are_cleared() {
+    // SetTx and SetRx time out
+    let radio = Radio::new();
+    radio
+        .raise_on(0x82, IrqMask::TIMEOUT.bits())
+        .raise_on(0x83, IrqMask::TIMEOUT.bits());
+    let mut device = Device::new(radio.clone());
+    let stale = (IrqMask::TX_DONE | IrqMask::RX_DONE).bits();
+
+    // Nothing was configured since reset
+    let rejected = device.transmit(&[1, 2], Timeout::default());
+    let missing = ConfigItems::all();
+    assert!(matches!(
+        rejected,
+        Err(TxError::Device(err)) if err.kind() == ErrorKind::NotConfigured { missing }
+    ));
+
+    // Only the frequency and modulation were set
+    device
+        .execute_command(SetRfFrequency { config: FREQUENCY })
+        .unwrap();
+    let params = ModulationParams::LoRa(LoRaModParams::default());
+    device
+        .execute_command(SetModulationParams { params })
+        .unwrap();
+    let rejected = device.receive(&mut [0; 4], RxMode::Single);
+    let missing = ConfigItems::PACKET;
+    assert!(matches!(
+        rejected,
+        Err(RxError::Device(err)) if err.kind() == ErrorKind::NotConfigured { missing }
+    ));
+
+    // A radio configured elsewhere
+    device.assume_configured();
+
+    radio.state().irq = stale;
+    let sent = device.transmit(&[1, 2], Timeout::default());
+    assert!(matches!(sent, Err(TxError::Timeout)));
+
+    radio.state().irq = stale;
+    let received = device.receive(&mut [0; 4], RxMode::Single);
+    assert!(matches!(received, Err(RxError::Timeout)));
+}
+
+/// Returns the IQ bit of the packet parameters sent last in `ops`, and the IQ polarity
+/// register writes following them.
+fn last_iq(ops: &[Op]) -> (u8, Vec<u8>) {
+    let sent = ops
+        .iter()
+        .rposition(|op| matches!(op, Op::Command(0x8C, _)))
+        .unwrap();
+    let Op::Command(_, params) = &ops[sent] else {
+        unreachable!()
+    };
+    let writes = ops[sent + 1..]
+        .iter()
+        .take_while(|op| !matches!(op, Op::Command(..)))
+        .filter_map(|op| match op {
+            Op::WriteRegister(IQ_POLARITY, value) => Some(value[0]),
+            _ => None,
+        })
+        .collect();
+    (params[5], writes)
+}
+
+#[test]
+fn iq_polarity_is_overridden_for_one_operation() {
+    // A repeater receiving with standard IQ and retransmitting with inverted IQ
+    let radio = Radio::new();
+    radio
+        .raise_on(0x82, IrqMask::RX_DONE.bits())
+        .raise_on(0x83, IrqMask::TX_DONE.bits())
+        .respond(0x13, &[2, 0x00])
+        .set_registers(IQ_POLARITY, &[0x0D]);
+    let mut device = Device::new(radio.clone());
+    device
+        .apply_config(&LoRaInit::new(FREQUENCY).config().unwrap())
+        .unwrap();
+
+    // Receive the uplink with standard IQ
+    radio.clear();
+    let options = RxOptions {
+        iq: Some(IqMode::Standard),
+        ..RxOptions::default()
+    };
+    let mut bytes = [0; 255];
+    let packet = device
+        .receive_with_options(&mut bytes, RxMode::Single, options)
+        .unwrap();
+    let rx_ops = radio.ops();
+    let set_rx = rx_ops
+        .iter()
+        .position(|op| matches!(op, Op::Command(0x82, _)))
+        .unwrap();
+    assert_eq!(last_iq(&rx_ops[..set_rx]), (0x00, vec![0x0D]));
+
+    // Retransmit it with inverted IQ, the configured standard IQ is restored afterwards
+    radio.clear();
+    let options = TxOptions {
+        iq: Some(IqMode::Inverted),
+    };
+    device
+        .transmit_with_options(&bytes[..packet.len], Timeout::default(), options)
+        .unwrap();
+    let tx_ops = radio.ops();
+    let set_tx = tx_ops
+        .iter()
+        .position(|op| matches!(op, Op::Command(0x83, _)))
+        .unwrap();
+    assert_eq!(last_iq(&tx_ops[..set_tx]), (0x01, vec![0x09]));
+    assert_eq!(last_iq(&tx_ops[set_tx..]), (0x00, vec![0x0D]));
+    assert_eq!(radio.register(IQ_POLARITY), 0x0D);
+}
+
+/// A radio raising TX_DONE after SetTx, or TIMEOUT after `fail_at` of them
+fn beacon_radio(fail_at: usize) -> Radio {
+    let radio = Radio::new();
+    let mut set_tx = 0;
+    radio.set_hook(move |state, opcode, _, _| {
+        if opcode == 0x83 {
+            set_tx += 1;
+            state.irq |= if set_tx == fail_at {
+                IrqMask::TIMEOUT.bits()
+            } else {
+                IrqMask::TX_DONE.bits()
+            };
+        }
+        false
+    });
+    radio
+}
+
+#[test]
+fn repeated_transmit_writes_the_payload_once() {
+    let radio = beacon_radio(0);
+    let delay = Delay::default();
+    let mut device = Device::new(radio.clone()).with_delay(delay.clone());
+    device.assume_configured();
+
+    // Written once, sent three times, with two intervals
+    device
+        .transmit_repeated(b"beacon", 3, 100, Timeout::default())
+        .unwrap();
+    let writes = radio
+        .ops()
+        .iter()
+        .filter(|op| matches!(op, Op::WriteBuffer(..)))
+        .count();
+    assert_eq!((writes, radio.count(0x83)), (1, 3));
+    assert_eq!(delay.sleeps_ms(), [100, 100]);
+}
+
+#[test]
+fn repeated_transmit_reports_the_transmissions_sent() {
+    // The third transmission times out
+    let mut device = Device::new(beacon_radio(3));
+    device.assume_configured();
+    let result =
+        device.transmit_repeated_with_delay(b"beacon", 5, 100, Timeout(640), &mut Delay::default());
+    assert_eq!(
+        result,
+        Err(RepeatedTxError {
+            sent: 2,
+            source: TxError::Timeout
+        })
+    );
+}
+
+#[test]
+fn header_errors_follow_the_policy() {
+    for policy in [
+        HeaderErrorPolicy::Return,
+        HeaderErrorPolicy::RestartRx,
+        HeaderErrorPolicy::Count,
+    ] {
+        let radio = Radio::new();
+        radio
+            .script_irqs([IrqMask::HEADER_ERROR.bits(), IrqMask::RX_DONE.bits()])
+            .respond(0x13, &[1, 0x80]);
+        let mut device = Device::new(radio.clone());
+        device.assume_configured();
+        let options = RxOptions {
+            on_header_error: policy,
+            ..RxOptions::default()
+        };
+
+        let result = device.receive_with_options(&mut [0; 4], RxMode::Single, options);
+        let set_rx = radio.count(0x82);
+        match policy {
+            HeaderErrorPolicy::Return => {
+                assert!(matches!(result, Err(RxError::Header)));
+                assert_eq!(device.link_stats().header_errors_skipped, 0);
+                assert_eq!(set_rx, 1);
+            }
+            _ => {
+                assert_eq!(result.unwrap().len, 1);
+                assert_eq!(device.link_stats().header_errors_skipped, 1);
+                let restarted = policy == HeaderErrorPolicy::RestartRx;
+                assert_eq!(set_rx, if restarted { 2 } else { 1 });
+            }
+        }
+
+        // The header error is cleared before RX is re-armed
+        if policy == HeaderErrorPolicy::RestartRx {
+            let opcodes = radio.opcodes();
+            let second_rx = opcodes.iter().rposition(|&op| op == 0x82).unwrap();
+            assert_eq!(opcodes[second_rx - 1], 0x02);
+        }
+    }
+}
+
+#[test]
+fn polls_give_up_when_no_irq_is_raised() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+    device.assume_configured();
+    device.set_busy_timeout_us(100);
+    let mut bytes = [0; 255];
+
+    // The busy timeout without a radio timeout, added to the 1 ms timeout otherwise
+    let sent = device.transmit(&[1, 2], Timeout::default());
+    let Err(TxError::Device(err)) = sent else {
+        panic!("{sent:?}");
+    };
+    assert_eq!(err.kind(), ErrorKind::IrqTimeout { waited_us: 100 });
+    assert_eq!(radio.count(0x12), 100);
+    // Put back in STDBY_RC instead of being left in TX
+    assert_eq!(radio.opcodes().last(), Some(&0x80));
+
+    for (mode, waited_us) in [(RxMode::Continuous, 100), (RxMode::Timed(64), 1_100)] {
+        let received = device.receive(&mut bytes, mode);
+        let Err(RxError::Device(err)) = received else {
+            panic!("{received:?}");
+        };
+        assert_eq!(err.kind(), ErrorKind::IrqTimeout { waited_us });
+        assert_eq!(radio.opcodes().last(), Some(&0x80));
+    }
+}
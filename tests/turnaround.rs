@@ -0,0 +1,26 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{Device, TurnaroundProfile};
+
+#[test]
+fn turnaround_profile_sets_standby_and_fallback_modes() {
+    let radio = Radio::new();
+    let mut device = Device::new(radio.clone());
+
+    for (profile, standby, fallback) in [
+        (TurnaroundProfile::LowPower, 0x00, 0x20),
+        (TurnaroundProfile::Balanced, 0x00, 0x30),
+        (TurnaroundProfile::LowLatency, 0x01, 0x40),
+    ] {
+        radio.clear();
+        device.set_turnaround_profile(profile).unwrap();
+        assert_eq!(
+            radio.commands(),
+            [(0x80, vec![standby]), (0x93, vec![fallback])]
+        );
+        assert_eq!(device.turnaround_profile(), profile);
+    }
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{
+    config::GfskInit, error::RxError, ConfigChanges, Device, FixedLengthWarning, GFSKPacketParams,
+    IrqMask, LoRaInit, LoraSyncWord, RadioConfig, ReadBackSource, RfFrequencyConfig, RxGain,
+    RxMode, Timeout,
+};
+
+const FREQUENCY: RfFrequencyConfig = RfFrequencyConfig {
+    frequency: 868_100_000,
+};
+
+fn fixed(len: u8) -> RadioConfig {
+    let mut init = GfskInit::new(FREQUENCY);
+    init.packet = GFSKPacketParams::builder()
+        .payload_length(len)
+        .fixed_length()
+        .build()
+        .unwrap();
+    init.config().unwrap()
+}
+
+#[test]
+fn fixed_length_differing_from_the_last_transmit_is_flagged() {
+    // A loopback test transmitting 16 bytes, then receiving with 17
+    let radio = Radio::new();
+    radio.respond(0x12, &IrqMask::TX_DONE.bits().to_be_bytes());
+    let mut device = Device::new(radio);
+    device.apply_config(&fixed(16)).unwrap();
+    device.transmit(&[0x55; 16], Timeout::default()).unwrap();
+    assert_eq!(
+        device
+            .validate_current_config()
+            .unwrap()
+            .fixed_length_warning,
+        None
+    );
+
+    device.apply_config(&fixed(17)).unwrap();
+    let report = device.validate_current_config().unwrap();
+    assert_eq!(
+        report.fixed_length_warning,
+        Some(FixedLengthWarning {
+            configured: 17,
+            transmitted: 16
+        })
+    );
+
+    // A buffer sized for the transmitted packet is rejected before RX is armed
+    let result = device.receive(&mut [0; 16], RxMode::Single);
+    assert!(matches!(
+        result,
+        Err(RxError::PayloadLengthMismatch {
+            configured: Some(17),
+            requested: 16
+        })
+    ));
+}
+
+#[test]
+fn registers_changed_behind_the_driver_are_reported() {
+    // SetPaConfig resets the OCP threshold
+    let radio = Radio::new();
+    radio.set_hook(|state, opcode, _, _| {
+        if opcode == 0x95 {
+            state.registers.insert(0x08E7, 0x38);
+        }
+        false
+    });
+    let mut device = Device::new(radio.clone());
+    let mut config = LoRaInit::new(FREQUENCY).config().unwrap();
+    config.lora_sync_word = Some(LoraSyncWord { value: 0x3444 });
+    config.rx_gain = RxGain::Boosted;
+    device.apply_config(&config).unwrap();
+
+    let report = device.validate_current_config().unwrap();
+    assert!(report.is_consistent());
+    assert!(report
+        .checked
+        .contains(ConfigChanges::LORA_SYNC_WORD | ConfigChanges::WORKAROUNDS));
+    assert!(report.unverifiable.contains(ConfigChanges::FREQUENCY));
+
+    // Another application switched to a private network and lowered the OCP threshold
+    radio
+        .set_registers(0x0740, &[0x14, 0x24])
+        .set_registers(0x08E7, &[0x18]);
+    let report = device.validate_current_config().unwrap();
+    let mismatches: Vec<_> = report
+        .mismatches()
+        .map(|mismatch| (mismatch.source, mismatch.expected, mismatch.actual))
+        .collect();
+    assert_eq!(
+        mismatches,
+        [
+            (ReadBackSource::Register(0x0740), 0x3444, 0x1424),
+            (ReadBackSource::Register(0x08E7), 0x38, 0x18),
+        ]
+    );
+}
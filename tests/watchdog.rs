@@ -0,0 +1,41 @@
+#![cfg(feature = "sync")]
+
+mod common;
+
+use common::Radio;
+use sx1262::{Device, HeaderErrorPolicy, IrqMask, RxOptions, RxWatchdog};
+
+#[test]
+fn pending_header_error_follows_the_policy() {
+    let radio = Radio::new();
+    radio.respond(0x12, &IrqMask::HEADER_ERROR.bits().to_be_bytes());
+    let mut device = Device::new(radio.clone());
+    let mut watchdog = RxWatchdog::new(60_000);
+
+    // By default RX is re-armed once the IRQ is cleared
+    let event = device.poll_rx(&mut watchdog, 0).unwrap();
+    assert!(event.irq_mask.is_empty());
+    assert_eq!(radio.opcodes(), [0x12, 0x02, 0x82]);
+    radio.clear();
+
+    // Counted only
+    let count = RxOptions {
+        on_header_error: HeaderErrorPolicy::Count,
+        ..RxOptions::default()
+    };
+    let event = device
+        .poll_rx_with_options(&mut watchdog, 1, count)
+        .unwrap();
+    assert!(event.irq_mask.is_empty());
+    assert_eq!(radio.opcodes(), [0x12, 0x02]);
+    assert_eq!(device.link_stats().header_errors_skipped, 2);
+    radio.clear();
+
+    // Reported to the caller
+    let event = device
+        .poll_rx_with_options(&mut watchdog, 2, RxOptions::default())
+        .unwrap();
+    assert_eq!(event.irq_mask, IrqMask::HEADER_ERROR);
+    assert_eq!(radio.opcodes(), [0x12, 0x02]);
+    assert_eq!(device.link_stats().header_errors_skipped, 2);
+}